@@ -10,6 +10,7 @@
 //! - Text input and editing
 //! - Field validation
 
+#[cfg(feature = "form")]
 use ncurses::*;
 
 #[cfg(feature = "form")]
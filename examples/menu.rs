@@ -9,6 +9,7 @@
 //! - Navigating with arrow keys
 //! - Selecting items
 
+#[cfg(feature = "menu")]
 use ncurses::*;
 
 #[cfg(feature = "menu")]
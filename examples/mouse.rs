@@ -110,25 +110,17 @@ fn main() -> Result<()> {
                 last_button = "Button 3 (Right)";
                 click_count += 1;
             }
-            k if k == key::KEY_UP => {
-                if last_y > 0 {
-                    last_y -= 1;
-                }
+            k if k == key::KEY_UP && last_y > 0 => {
+                last_y -= 1;
             }
-            k if k == key::KEY_DOWN => {
-                if last_y < max_y - 1 {
-                    last_y += 1;
-                }
+            k if k == key::KEY_DOWN && last_y < max_y - 1 => {
+                last_y += 1;
             }
-            k if k == key::KEY_LEFT => {
-                if last_x > 0 {
-                    last_x -= 1;
-                }
+            k if k == key::KEY_LEFT && last_x > 0 => {
+                last_x -= 1;
             }
-            k if k == key::KEY_RIGHT => {
-                if last_x < max_x - 1 {
-                    last_x += 1;
-                }
+            k if k == key::KEY_RIGHT && last_x < max_x - 1 => {
+                last_x += 1;
             }
             _ => {}
         }
@@ -9,6 +9,7 @@
 //! - Showing and hiding panels
 //! - Updating panels for display
 
+#[cfg(feature = "panels")]
 use ncurses::*;
 
 #[cfg(feature = "panels")]
@@ -79,29 +79,21 @@ fn main() -> Result<()> {
         let mut moved = false;
 
         match ch {
-            k if k == key::KEY_UP => {
-                if box_y > 6 {
-                    box_y -= 1;
-                    moved = true;
-                }
+            k if k == key::KEY_UP && box_y > 6 => {
+                box_y -= 1;
+                moved = true;
             }
-            k if k == key::KEY_DOWN => {
-                if box_y < max_y - box_height - 6 {
-                    box_y += 1;
-                    moved = true;
-                }
+            k if k == key::KEY_DOWN && box_y < max_y - box_height - 6 => {
+                box_y += 1;
+                moved = true;
             }
-            k if k == key::KEY_LEFT => {
-                if box_x > 3 {
-                    box_x -= 1;
-                    moved = true;
-                }
+            k if k == key::KEY_LEFT && box_x > 3 => {
+                box_x -= 1;
+                moved = true;
             }
-            k if k == key::KEY_RIGHT => {
-                if box_x < max_x - box_width - 3 {
-                    box_x += 1;
-                    moved = true;
-                }
+            k if k == key::KEY_RIGHT && box_x < max_x - box_width - 3 => {
+                box_x += 1;
+                moved = true;
             }
             k if k == b'q' as i32 || k == b'Q' as i32 => {
                 break;
@@ -152,6 +152,97 @@ pub const ACS_ROUND_URCORNER: char = '╮';
 /// Rounded lower right corner: ╯
 pub const ACS_ROUND_LRCORNER: char = '╯';
 
+// ============================================================================
+// Output modes
+// ============================================================================
+
+/// How `doupdate` sends line-drawing characters to the terminal.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum AcsMode {
+    /// Write Unicode box-drawing characters as UTF-8.
+    #[default]
+    Unicode,
+    /// Switch to the terminal's VT100 line-drawing set (`smacs`/`rmacs`)
+    /// and write the corresponding ASCII codes. Use this on terminals or
+    /// locales that cannot display UTF-8, such as the Linux console.
+    Vt100,
+}
+
+/// Get the VT100 line-drawing code for a box-drawing character.
+///
+/// Double, heavy and rounded variants map to the plain VT100 character.
+/// Returns `None` for characters the VT100 set cannot show.
+pub const fn vt100_code(c: char) -> Option<u8> {
+    let code = match c {
+        '┌' | '╔' | '┏' | '╭' => b'l',
+        '└' | '╚' | '┗' | '╰' => b'm',
+        '┐' | '╗' | '┓' | '╮' => b'k',
+        '┘' | '╝' | '┛' | '╯' => b'j',
+        '├' | '╠' | '┣' => b't',
+        '┤' | '╣' | '┫' => b'u',
+        '┬' | '╦' | '┳' => b'w',
+        '┴' | '╩' | '┻' => b'v',
+        '─' | '═' | '━' => b'q',
+        '│' | '║' | '┃' => b'x',
+        '┼' | '╬' | '╋' => b'n',
+        '◆' => b'`',
+        '▒' => b'a',
+        '°' => b'f',
+        '±' => b'g',
+        '·' => b'~',
+        '←' => b',',
+        '→' => b'+',
+        '↓' => b'.',
+        '↑' => b'-',
+        '▓' => b'h',
+        '█' => b'0',
+        '≤' => b'y',
+        '≥' => b'z',
+        'π' => b'{',
+        '≠' => b'|',
+        '£' => b'}',
+        _ => return None,
+    };
+    Some(code)
+}
+
+/// Get the Unicode character for a VT100 line-drawing code.
+///
+/// This is the inverse of [`vt100_code`] for the plain line characters.
+pub const fn vt100_unicode(code: u8) -> Option<char> {
+    let c = match code {
+        b'l' => ACS_ULCORNER,
+        b'm' => ACS_LLCORNER,
+        b'k' => ACS_URCORNER,
+        b'j' => ACS_LRCORNER,
+        b't' => ACS_LTEE,
+        b'u' => ACS_RTEE,
+        b'w' => ACS_TTEE,
+        b'v' => ACS_BTEE,
+        b'q' => ACS_HLINE,
+        b'x' => ACS_VLINE,
+        b'n' => ACS_PLUS,
+        b'`' => ACS_DIAMOND,
+        b'a' => ACS_CKBOARD,
+        b'f' => ACS_DEGREE,
+        b'g' => ACS_PLMINUS,
+        b'~' => ACS_BULLET,
+        b',' => ACS_LARROW,
+        b'+' => ACS_RARROW,
+        b'.' => ACS_DARROW,
+        b'-' => ACS_UARROW,
+        b'h' => ACS_BOARD,
+        b'0' => ACS_BLOCK,
+        b'y' => ACS_LEQUAL,
+        b'z' => ACS_GEQUAL,
+        b'{' => ACS_PI,
+        b'|' => ACS_NEQUAL,
+        b'}' => ACS_STERLING,
+        _ => return None,
+    };
+    Some(c)
+}
+
 // ============================================================================
 // Helper functions
 // ============================================================================
@@ -173,38 +264,9 @@ pub fn acs_char(c: char) -> ChType {
     use crate::attr::A_ALTCHARSET;
 
     // Map Unicode characters to ASCII fallbacks with A_ALTCHARSET
-    let ascii = match c {
-        '┌' | '╔' | '┏' | '╭' => 'l',
-        '└' | '╚' | '┗' | '╰' => 'm',
-        '┐' | '╗' | '┓' | '╮' => 'k',
-        '┘' | '╝' | '┛' | '╯' => 'j',
-        '├' | '╠' | '┣' => 't',
-        '┤' | '╣' | '┫' => 'u',
-        '┬' | '╦' | '┳' => 'w',
-        '┴' | '╩' | '┻' => 'v',
-        '─' | '═' | '━' => 'q',
-        '│' | '║' | '┃' => 'x',
-        '┼' | '╬' | '╋' => 'n',
-        '◆' => '`',
-        '▒' => 'a',
-        '°' => 'f',
-        '±' => 'g',
-        '·' => '~',
-        '←' => ',',
-        '→' => '+',
-        '↓' => '.',
-        '↑' => '-',
-        '▓' => 'h',
-        '█' => '0',
-        '≤' => 'y',
-        '≥' => 'z',
-        'π' => '{',
-        '≠' => '|',
-        '£' => '}',
-        _ => c,
-    };
+    let ascii = vt100_code(c).unwrap_or(c as u8);
 
-    (ascii as u8 as ChType) | A_ALTCHARSET
+    (ascii as ChType) | A_ALTCHARSET
 }
 
 /// Get the ACS map for the current terminal.
@@ -296,6 +358,17 @@ mod tests {
         assert!(ch != 0);
     }
 
+    #[test]
+    fn test_vt100_code() {
+        assert_eq!(vt100_code(ACS_HLINE), Some(b'q'));
+        assert_eq!(vt100_code(ACS_D_ULCORNER), Some(b'l'));
+        assert_eq!(vt100_code('x'), None);
+        for code in b"lmkjtuwvqxn`afg~,+.-h0yz{|}" {
+            let c = vt100_unicode(*code).unwrap();
+            assert_eq!(vt100_code(c), Some(*code));
+        }
+    }
+
     #[test]
     fn test_acs_map() {
         let map = acs_map();
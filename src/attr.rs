@@ -142,14 +142,22 @@ pub const WA_ITALIC: AttrT = A_ITALIC;
 // Color pair helpers
 // ============================================================================
 
+/// Largest color pair number that fits in the `A_COLOR` bits of an attribute.
+///
+/// Higher pair numbers (with ext-colors) must be passed separately, e.g. via
+/// `color_set` or the `ext_color` field of a `CCharT`.
+pub const MAX_ATTR_COLOR_PAIR: i16 = (A_COLOR >> NCURSES_ATTR_SHIFT) as i16;
+
 /// Create a color attribute from a color pair number.
 ///
 /// This function encodes a color pair number into the attribute bits
-/// that can be OR'd with other attributes.
+/// that can be OR'd with other attributes. Pair numbers outside
+/// `0..=MAX_ATTR_COLOR_PAIR` are saturated so they can never spill into
+/// the video attribute bits; use [`try_color_pair`] to detect them.
 ///
 /// # Arguments
 ///
-/// * `n` - The color pair number (1-255 for standard, higher with ext-colors)
+/// * `n` - The color pair number (0-255)
 ///
 /// # Example
 ///
@@ -160,9 +168,37 @@ pub const WA_ITALIC: AttrT = A_ITALIC;
 /// ```
 #[inline]
 pub const fn color_pair(n: i16) -> AttrT {
+    let n = if n < 0 {
+        0
+    } else if n > MAX_ATTR_COLOR_PAIR {
+        MAX_ATTR_COLOR_PAIR
+    } else {
+        n
+    };
     ncurses_bits(n as u32, 0) & A_COLOR
 }
 
+/// Create a color attribute from a color pair number, checking its range.
+///
+/// Returns `None` if `n` is negative or larger than [`MAX_ATTR_COLOR_PAIR`].
+///
+/// # Example
+///
+/// ```rust
+/// use ncurses::attr::{color_pair, try_color_pair};
+///
+/// assert_eq!(try_color_pair(3), Some(color_pair(3)));
+/// assert_eq!(try_color_pair(300), None);
+/// ```
+#[inline]
+pub const fn try_color_pair(n: i16) -> Option<AttrT> {
+    if n < 0 || n > MAX_ATTR_COLOR_PAIR {
+        None
+    } else {
+        Some(color_pair(n))
+    }
+}
+
 /// Extract the color pair number from an attribute value.
 ///
 /// # Arguments
@@ -245,6 +281,138 @@ impl From<Attribute> for AttrT {
     }
 }
 
+// ============================================================================
+// Attribute builder
+// ============================================================================
+
+/// Builder for an attribute value with a color pair.
+///
+/// This avoids or-ing raw `AttrT` constants together by hand:
+///
+/// ```rust
+/// use ncurses::attr::{color_pair, AttrSpec, A_BOLD, A_UNDERLINE};
+///
+/// let attr = AttrSpec::new().bold().underline().color(2).build();
+/// assert_eq!(attr, A_BOLD | A_UNDERLINE | color_pair(2));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[must_use]
+pub struct AttrSpec {
+    attrs: Attribute,
+    pair: i16,
+}
+
+impl AttrSpec {
+    /// Create a builder with no attributes and color pair 0.
+    pub const fn new() -> Self {
+        Self {
+            attrs: Attribute::NORMAL,
+            pair: 0,
+        }
+    }
+
+    /// Split an existing attribute value into video attributes and color pair.
+    ///
+    /// The character bits of a `chtype` are ignored.
+    pub const fn from_attr(attr: AttrT) -> Self {
+        Self {
+            attrs: Attribute::from_bits_truncate(attr & !A_COLOR & !A_CHARTEXT),
+            pair: pair_number(attr),
+        }
+    }
+
+    /// Add video attributes.
+    pub const fn attr(self, attrs: Attribute) -> Self {
+        Self {
+            attrs: self.attrs.union(attrs),
+            pair: self.pair,
+        }
+    }
+
+    /// Add bold.
+    pub const fn bold(self) -> Self {
+        self.attr(Attribute::BOLD)
+    }
+
+    /// Add dim.
+    pub const fn dim(self) -> Self {
+        self.attr(Attribute::DIM)
+    }
+
+    /// Add italic.
+    pub const fn italic(self) -> Self {
+        self.attr(Attribute::ITALIC)
+    }
+
+    /// Add underline.
+    pub const fn underline(self) -> Self {
+        self.attr(Attribute::UNDERLINE)
+    }
+
+    /// Add reverse video.
+    pub const fn reverse(self) -> Self {
+        self.attr(Attribute::REVERSE)
+    }
+
+    /// Add blinking.
+    pub const fn blink(self) -> Self {
+        self.attr(Attribute::BLINK)
+    }
+
+    /// Add standout.
+    pub const fn standout(self) -> Self {
+        self.attr(Attribute::STANDOUT)
+    }
+
+    /// Add invisible.
+    pub const fn invis(self) -> Self {
+        self.attr(Attribute::INVIS)
+    }
+
+    /// Add the alternate character set.
+    pub const fn altcharset(self) -> Self {
+        self.attr(Attribute::ALTCHARSET)
+    }
+
+    /// Add protected.
+    pub const fn protect(self) -> Self {
+        self.attr(Attribute::PROTECT)
+    }
+
+    /// Set the color pair, replacing any previous one.
+    ///
+    /// Pairs outside `0..=MAX_ATTR_COLOR_PAIR` are saturated by `build`, as
+    /// with [`color_pair`].
+    pub const fn color(self, pair: i16) -> Self {
+        Self {
+            attrs: self.attrs,
+            pair,
+        }
+    }
+
+    /// Get the video attributes.
+    pub const fn attributes(&self) -> Attribute {
+        self.attrs
+    }
+
+    /// Get the color pair.
+    pub const fn pair(&self) -> i16 {
+        self.pair
+    }
+
+    /// Combine everything into an attribute value.
+    #[must_use]
+    pub const fn build(self) -> AttrT {
+        self.attrs.bits() | color_pair(self.pair)
+    }
+}
+
+impl From<AttrSpec> for AttrT {
+    fn from(spec: AttrSpec) -> Self {
+        spec.build()
+    }
+}
+
 // ============================================================================
 // Character extraction helpers
 // ============================================================================
@@ -275,8 +443,8 @@ mod tests {
     fn test_attribute_bits() {
         assert_eq!(A_NORMAL, 0);
         assert_eq!(A_CHARTEXT, 0xFF);
-        assert!(A_STANDOUT > A_CHARTEXT);
-        assert!(A_BOLD > A_STANDOUT);
+        const { assert!(A_STANDOUT > A_CHARTEXT) };
+        const { assert!(A_BOLD > A_STANDOUT) };
     }
 
     #[test]
@@ -289,6 +457,26 @@ mod tests {
         assert_eq!(pair_number(pair255), 255);
     }
 
+    #[test]
+    fn test_color_pair_round_trip() {
+        for n in 0..=MAX_ATTR_COLOR_PAIR {
+            assert_eq!(pair_number(color_pair(n)), n);
+            assert_eq!(try_color_pair(n), Some(color_pair(n)));
+        }
+    }
+
+    #[test]
+    fn test_color_pair_out_of_range() {
+        assert_eq!(try_color_pair(-1), None);
+        assert_eq!(try_color_pair(MAX_ATTR_COLOR_PAIR + 1), None);
+        assert_eq!(try_color_pair(300), None);
+
+        // Saturates instead of corrupting other attribute bits
+        assert_eq!(color_pair(300) & !A_COLOR, 0);
+        assert_eq!(pair_number(color_pair(300)), MAX_ATTR_COLOR_PAIR);
+        assert_eq!(color_pair(-5), 0);
+    }
+
     #[test]
     fn test_chtype_helpers() {
         let ch = make_chtype(b'A', A_BOLD | color_pair(1));
@@ -303,4 +491,32 @@ mod tests {
         assert!(attr.contains(Attribute::UNDERLINE));
         assert!(!attr.contains(Attribute::REVERSE));
     }
+
+    #[test]
+    fn test_attr_spec_matches_manual() {
+        assert_eq!(AttrSpec::new().build(), A_NORMAL);
+        assert_eq!(
+            AttrSpec::new().bold().underline().color(3).build(),
+            A_BOLD | A_UNDERLINE | color_pair(3)
+        );
+        assert_eq!(
+            AttrSpec::new().reverse().attr(Attribute::ITALIC).build(),
+            A_REVERSE | A_ITALIC
+        );
+        // A later color replaces the earlier one instead of mixing bits
+        assert_eq!(AttrSpec::new().color(1).color(6).build(), color_pair(6));
+    }
+
+    #[test]
+    fn test_attr_spec_round_trip() {
+        let attr = A_DIM | A_BLINK | color_pair(42);
+        let spec = AttrSpec::from_attr(attr);
+        assert_eq!(spec.pair(), 42);
+        assert_eq!(spec.attributes(), Attribute::DIM | Attribute::BLINK);
+        assert_eq!(spec.build(), attr);
+        assert_eq!(
+            AttrSpec::from_attr(make_chtype(b'x', A_BOLD)).build(),
+            A_BOLD
+        );
+    }
 }
@@ -5,7 +5,7 @@
 //! that can be applied to text output.
 
 use crate::error::{Error, Result};
-use crate::types::ColorT;
+use crate::types::{AttrT, ColorT};
 
 // ============================================================================
 // Standard Colors
@@ -95,6 +95,77 @@ pub const DEFAULT_COLOR_PAIRS: i32 = 64;
 /// Maximum RGB value for color definition.
 pub const RGB_MAX: i16 = 1000;
 
+// ============================================================================
+// xterm 256-color palette
+// ============================================================================
+
+/// The 16 system colors as xterm defines them, in 8-bit RGB.
+const XTERM_SYSTEM_COLORS: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/// Channel levels of the 6x6x6 color cube (indices 16-231).
+const XTERM_CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// Get the standard xterm RGB value of a 256-color palette index.
+///
+/// Indices 0-15 are the system colors, 16-231 the 6x6x6 color cube and
+/// 232-255 the grayscale ramp. Components are scaled to 0-[`RGB_MAX`],
+/// the same range `init_color` accepts.
+pub const fn xterm_256_rgb(index: u8) -> (u16, u16, u16) {
+    let (r, g, b) = match index {
+        0..=15 => XTERM_SYSTEM_COLORS[index as usize],
+        16..=231 => {
+            let i = (index - 16) as usize;
+            (
+                XTERM_CUBE_LEVELS[i / 36],
+                XTERM_CUBE_LEVELS[(i / 6) % 6],
+                XTERM_CUBE_LEVELS[i % 6],
+            )
+        }
+        232..=255 => {
+            let level = 8 + 10 * (index - 232);
+            (level, level, level)
+        }
+    };
+    (scale_8bit(r), scale_8bit(g), scale_8bit(b))
+}
+
+/// Find the xterm palette index closest to an RGB value.
+///
+/// Components use the 0-[`RGB_MAX`] range; only the first `count` palette
+/// entries (at most 256) are considered.
+pub fn nearest_xterm_color(r: i16, g: i16, b: i16, count: i32) -> i16 {
+    let distance = |index: i32| {
+        let (pr, pg, pb) = xterm_256_rgb(index as u8);
+        let d = |p: u16, c: i16| (p as i32 - c as i32).pow(2);
+        d(pr, r) + d(pg, g) + d(pb, b)
+    };
+    (0..count.clamp(1, 256))
+        .min_by_key(|&i| distance(i))
+        .unwrap_or(0) as i16
+}
+
+/// Scale an 8-bit channel to 0-`RGB_MAX`, rounding to nearest.
+const fn scale_8bit(v: u8) -> u16 {
+    ((v as u32 * RGB_MAX as u32 + 127) / 255) as u16
+}
+
 // ============================================================================
 // Color definition storage
 // ============================================================================
@@ -134,6 +205,9 @@ impl ColorDef {
     }
 }
 
+/// A 24-bit RGB color, one byte per channel.
+pub type Rgb = (u8, u8, u8);
+
 /// Color pair definition (foreground and background).
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub struct ColorPair {
@@ -143,6 +217,11 @@ pub struct ColorPair {
     pub background: ColorT,
     /// Whether this pair has been initialized.
     pub initialized: bool,
+    /// Exact foreground and background for truecolor terminals.
+    ///
+    /// When set, `foreground`/`background` hold the nearest palette indices
+    /// used on terminals without truecolor support.
+    pub rgb: Option<(Rgb, Rgb)>,
 }
 
 impl ColorPair {
@@ -152,6 +231,7 @@ impl ColorPair {
             foreground,
             background,
             initialized: true,
+            rgb: None,
         }
     }
 
@@ -161,6 +241,7 @@ impl ColorPair {
             foreground: COLOR_WHITE,
             background: COLOR_BLACK,
             initialized: false,
+            rgb: None,
         }
     }
 }
@@ -169,6 +250,13 @@ impl ColorPair {
 // Color Manager
 // ============================================================================
 
+/// Error for an attempt to redefine pair 0 through `init_pair`.
+fn pair_zero_error() -> Error {
+    Error::InvalidArgument(
+        "color pair 0 holds the default colors; set it with assume_default_colors".into(),
+    )
+}
+
 /// Manages the color palette and color pairs.
 pub struct ColorManager {
     /// Whether color support has been started.
@@ -185,11 +273,24 @@ pub struct ColorManager {
     pairs: Vec<ColorPair>,
     /// Whether to use default colors (-1 for default).
     use_default_colors: bool,
+    /// Whether the terminal has no colors, so pairs are only recorded for
+    /// the monochrome fallback.
+    monochrome: bool,
 }
 
 impl ColorManager {
     /// Create a new color manager.
+    ///
+    /// Without colors or pairs the manager still keeps a 16-color palette
+    /// and 256 pairs, so that colored attributes can be shown with a
+    /// [`MonoFallback`]; `has_colors()` and the counts report none.
     pub fn new(num_colors: i32, num_pairs: i32, can_change: bool) -> Self {
+        let monochrome = num_colors <= 0 || num_pairs <= 0;
+        let (num_colors, num_pairs) = if monochrome {
+            (MONO_COLORS, MONO_PAIRS)
+        } else {
+            (num_colors, num_pairs)
+        };
         let mut colors = vec![ColorDef::default_color(); num_colors as usize];
         let pairs = vec![ColorPair::default_pair(); num_pairs as usize];
 
@@ -213,14 +314,15 @@ impl ColorManager {
             colors,
             pairs,
             use_default_colors: false,
+            monochrome,
         }
     }
 
     /// Start color mode.
+    ///
+    /// This succeeds on a monochrome terminal too, so pairs can be defined
+    /// for the monochrome fallback.
     pub fn start(&mut self) -> Result<()> {
-        if self.num_colors <= 0 || self.num_pairs <= 0 {
-            return Err(Error::ColorNotAvailable);
-        }
         self.started = true;
         Ok(())
     }
@@ -232,7 +334,7 @@ impl ColorManager {
 
     /// Check if the terminal has color support.
     pub fn has_colors(&self) -> bool {
-        self.num_colors > 0 && self.num_pairs > 0
+        !self.monochrome
     }
 
     /// Check if the terminal can change color definitions.
@@ -242,21 +344,28 @@ impl ColorManager {
 
     /// Get the number of available colors.
     pub fn num_colors(&self) -> i32 {
-        self.num_colors
+        if self.monochrome {
+            0
+        } else {
+            self.num_colors
+        }
     }
 
     /// Get the number of available color pairs.
     pub fn num_pairs(&self) -> i32 {
-        self.num_pairs
+        if self.monochrome {
+            0
+        } else {
+            self.num_pairs
+        }
     }
 
     /// Enable the use of default colors (-1 represents terminal default).
+    ///
+    /// This is `assume_default_colors(-1, -1)`: pair 0 becomes the
+    /// terminal's own colors.
     pub fn use_default_colors(&mut self) -> Result<()> {
-        if !self.started {
-            return Err(Error::ColorNotAvailable);
-        }
-        self.use_default_colors = true;
-        Ok(())
+        self.assume_default_colors(-1, -1)
     }
 
     /// Initialize a color pair.
@@ -268,8 +377,7 @@ impl ColorManager {
             return Err(Error::InvalidColorPair(pair));
         }
         if pair == 0 {
-            // Color pair 0 is typically reserved
-            return Err(Error::InvalidColorPair(0));
+            return Err(pair_zero_error());
         }
 
         // Validate colors (-1 is allowed if use_default_colors is enabled)
@@ -286,6 +394,28 @@ impl ColorManager {
         Ok(())
     }
 
+    /// Initialize a color pair with 24-bit RGB colors.
+    ///
+    /// The pair also gets the nearest palette colors, which are what
+    /// `pair_content` reports and what terminals without truecolor use.
+    pub fn init_pair_rgb(&mut self, pair: i16, fg: Rgb, bg: Rgb) -> Result<()> {
+        let nearest = |(r, g, b): Rgb| {
+            let scale = |v: u8| (v as i32 * RGB_MAX as i32 / 255) as i16;
+            nearest_xterm_color(scale(r), scale(g), scale(b), self.num_colors)
+        };
+        self.init_pair(pair, nearest(fg), nearest(bg))?;
+        self.pairs[pair as usize].rgb = Some((fg, bg));
+        Ok(())
+    }
+
+    /// Get the RGB colors of a pair set up with `init_pair_rgb`.
+    pub fn pair_rgb(&self, pair: i16) -> Option<(Rgb, Rgb)> {
+        if pair < 0 {
+            return None;
+        }
+        self.pairs.get(pair as usize).and_then(|p| p.rgb)
+    }
+
     /// Get the definition of a color pair.
     pub fn pair_content(&self, pair: i16) -> Result<(ColorT, ColorT)> {
         if !self.started {
@@ -332,21 +462,32 @@ impl ColorManager {
         Ok((def.red, def.green, def.blue))
     }
 
-    /// Reset all color pairs.
+    /// Reset all color pairs except pair 0, which keeps the default colors.
     pub fn reset_color_pairs(&mut self) {
-        for pair in self.pairs.iter_mut() {
+        for pair in self.pairs.iter_mut().skip(1) {
             *pair = ColorPair::default_pair();
         }
     }
 
     /// Set default foreground and background colors for pair 0.
+    ///
+    /// Either color may be -1 for the terminal's default, which also allows
+    /// -1 in later `init_pair` calls.
     pub fn assume_default_colors(&mut self, fg: ColorT, bg: ColorT) -> Result<()> {
         if !self.started {
             return Err(Error::ColorNotAvailable);
         }
+        for color in [fg, bg] {
+            if !(-1..self.num_colors).contains(&(color as i32)) {
+                return Err(Error::InvalidColor(color));
+            }
+        }
 
         // Unlike init_pair, this can modify pair 0
         self.pairs[0] = ColorPair::new(fg, bg);
+        if fg == -1 || bg == -1 {
+            self.use_default_colors = true;
+        }
         Ok(())
     }
 
@@ -373,7 +514,7 @@ impl ColorManager {
             return Err(Error::InvalidColorPair(pair as i16));
         }
         if pair == 0 {
-            return Err(Error::InvalidColorPair(0));
+            return Err(pair_zero_error());
         }
 
         let min_color = if self.use_default_colors { -1 } else { 0 };
@@ -509,6 +650,170 @@ impl Default for ColorManager {
     }
 }
 
+// ============================================================================
+// Monochrome fallback
+// ============================================================================
+
+/// Palette size kept for pairs on a terminal without colors.
+const MONO_COLORS: i32 = 16;
+
+/// Number of pairs kept on a terminal without colors.
+const MONO_PAIRS: i32 = 256;
+
+/// How color pairs are shown on a terminal without colors.
+///
+/// Each pair is replaced by video attributes derived from its colors, so a
+/// UI that relies on color to set things apart stays legible. The default
+/// shows bright foregrounds in bold and colored backgrounds in reverse video.
+///
+/// # Example
+///
+/// ```rust
+/// use ncurses::attr::{A_BOLD, A_REVERSE, A_UNDERLINE};
+/// use ncurses::color::{MonoFallback, COLOR_BLACK, COLOR_BLUE, COLOR_RED, COLOR_WHITE};
+///
+/// let fallback = MonoFallback::default();
+/// assert_eq!(fallback.attrs_for(COLOR_RED + 8, COLOR_BLACK), A_BOLD);
+/// assert_eq!(fallback.attrs_for(COLOR_WHITE, COLOR_BLUE), A_REVERSE);
+///
+/// let underline = MonoFallback { bright_foreground: A_UNDERLINE, ..fallback };
+/// assert_eq!(underline.attrs_for(COLOR_RED + 8, -1), A_UNDERLINE);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MonoFallback {
+    /// Attributes for a pair with a bright foreground (colors 8-15).
+    pub bright_foreground: AttrT,
+    /// Attributes for a pair with a background other than black or the
+    /// terminal default.
+    pub background: AttrT,
+}
+
+impl MonoFallback {
+    /// Attributes standing in for a pair with colors `fg` and `bg`.
+    #[must_use]
+    pub fn attrs_for(&self, fg: ColorT, bg: ColorT) -> AttrT {
+        let mut attrs = 0;
+        if (8..16).contains(&fg) {
+            attrs |= self.bright_foreground;
+        }
+        if bg > COLOR_BLACK {
+            attrs |= self.background;
+        }
+        attrs
+    }
+}
+
+impl Default for MonoFallback {
+    fn default() -> Self {
+        Self {
+            bright_foreground: crate::attr::A_BOLD,
+            background: crate::attr::A_REVERSE,
+        }
+    }
+}
+
+// ============================================================================
+// Pair registry
+// ============================================================================
+
+/// Hands out color pair numbers for foreground/background combinations.
+///
+/// Modules that share one registry get the same pair for the same colors and
+/// never collide on pair numbers. Pairs are numbered from 1 in the order they
+/// are first requested; install them with `Screen::apply_registry`.
+///
+/// # Example
+///
+/// ```rust
+/// use ncurses::color::{PairRegistry, COLOR_BLUE, COLOR_RED, COLOR_WHITE};
+///
+/// let mut registry = PairRegistry::new().with_limit(64);
+/// let title = registry.get_or_create(COLOR_WHITE, COLOR_BLUE);
+/// let error = registry.get_or_create(COLOR_RED, COLOR_BLUE);
+/// assert_eq!(registry.get_or_create(COLOR_WHITE, COLOR_BLUE), title);
+/// assert_ne!(title, error);
+/// ```
+#[derive(Clone, Debug)]
+pub struct PairRegistry {
+    /// Registered combinations; pair `n` is at index `n - 1`.
+    pairs: Vec<(ColorT, ColorT)>,
+    /// Number of pairs available, including pair 0.
+    limit: i32,
+}
+
+impl PairRegistry {
+    /// Create an empty registry with no limit beyond the `i16` pair range.
+    pub fn new() -> Self {
+        Self {
+            pairs: Vec::new(),
+            limit: i16::MAX as i32 + 1,
+        }
+    }
+
+    /// Limit the registry to `num_pairs` pairs, as reported by `COLOR_PAIRS`.
+    ///
+    /// Pair 0 is reserved, so at most `num_pairs - 1` combinations fit.
+    #[must_use]
+    pub fn with_limit(mut self, num_pairs: i32) -> Self {
+        self.limit = num_pairs.clamp(0, i16::MAX as i32 + 1);
+        self
+    }
+
+    /// Get the pair for `fg`/`bg`, allocating the next number if needed.
+    ///
+    /// Returns 0 (the default pair) if the registry is full; use
+    /// [`try_get_or_create`](Self::try_get_or_create) to detect that.
+    pub fn get_or_create(&mut self, fg: ColorT, bg: ColorT) -> i16 {
+        self.try_get_or_create(fg, bg).unwrap_or(0)
+    }
+
+    /// Get the pair for `fg`/`bg`, or `None` if it is new and the registry
+    /// is full.
+    pub fn try_get_or_create(&mut self, fg: ColorT, bg: ColorT) -> Option<i16> {
+        if let Some(pair) = self.get(fg, bg) {
+            return Some(pair);
+        }
+        let pair = self.pairs.len() as i32 + 1;
+        if pair >= self.limit {
+            return None;
+        }
+        self.pairs.push((fg, bg));
+        Some(pair as i16)
+    }
+
+    /// Get the pair already registered for `fg`/`bg`.
+    pub fn get(&self, fg: ColorT, bg: ColorT) -> Option<i16> {
+        self.pairs
+            .iter()
+            .position(|&p| p == (fg, bg))
+            .map(|i| (i + 1) as i16)
+    }
+
+    /// Iterate over the registered pairs as `(pair, fg, bg)`.
+    pub fn iter(&self) -> impl Iterator<Item = (i16, ColorT, ColorT)> + '_ {
+        self.pairs
+            .iter()
+            .enumerate()
+            .map(|(i, &(fg, bg))| ((i + 1) as i16, fg, bg))
+    }
+
+    /// Get the number of registered pairs.
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    /// Check if no pairs have been registered.
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+}
+
+impl Default for PairRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -521,6 +826,39 @@ mod tests {
         assert_eq!(Color::from_index(100), None);
     }
 
+    #[test]
+    fn test_xterm_256_rgb() {
+        assert_eq!(xterm_256_rgb(0), (0, 0, 0));
+        assert_eq!(xterm_256_rgb(15), (1000, 1000, 1000));
+        assert_eq!(xterm_256_rgb(196), (1000, 0, 0));
+        assert_eq!(xterm_256_rgb(16 + 36 + 6 + 1), (373, 373, 373));
+
+        let ramp: Vec<u16> = (232..=255).map(|i| xterm_256_rgb(i).0).collect();
+        assert!(ramp.windows(2).all(|w| w[0] < w[1]));
+        assert!((232..=255).all(|i| {
+            let (r, g, b) = xterm_256_rgb(i);
+            r == g && g == b
+        }));
+    }
+
+    #[test]
+    fn test_pair_registry() {
+        let mut registry = PairRegistry::new().with_limit(4);
+        assert_eq!(registry.get_or_create(COLOR_RED, COLOR_BLACK), 1);
+        assert_eq!(registry.get_or_create(COLOR_GREEN, COLOR_BLACK), 2);
+        assert_eq!(registry.get_or_create(COLOR_RED, COLOR_BLACK), 1);
+        assert_eq!(registry.get_or_create(COLOR_BLACK, COLOR_RED), 3);
+
+        // Pairs 1-3 are used up; known combos still resolve
+        assert_eq!(registry.try_get_or_create(COLOR_BLUE, COLOR_BLACK), None);
+        assert_eq!(registry.get_or_create(COLOR_BLUE, COLOR_BLACK), 0);
+        assert_eq!(registry.get_or_create(COLOR_GREEN, COLOR_BLACK), 2);
+        assert_eq!(registry.len(), 3);
+
+        let pairs: Vec<_> = registry.iter().collect();
+        assert_eq!(pairs[2], (3, COLOR_BLACK, COLOR_RED));
+    }
+
     #[test]
     fn test_color_manager() {
         let mut cm = ColorManager::new(8, 64, true);
@@ -542,4 +880,80 @@ mod tests {
         let (r, g, b) = cm.color_content(1).unwrap();
         assert_eq!((r, g, b), (500, 500, 500));
     }
+
+    #[test]
+    fn test_init_pair_rejects_pair_zero() {
+        let mut cm = ColorManager::new(8, 64, true);
+        cm.start().unwrap();
+        assert!(matches!(
+            cm.init_pair(0, COLOR_RED, COLOR_BLUE),
+            Err(Error::InvalidArgument(_))
+        ));
+        assert_eq!(cm.pair_content(0).unwrap(), (COLOR_WHITE, COLOR_BLACK));
+        #[cfg(feature = "ext-colors")]
+        assert!(matches!(
+            cm.init_extended_pair(0, 1, 2),
+            Err(Error::InvalidArgument(_))
+        ));
+
+        assert!(matches!(
+            cm.init_pair(64, COLOR_RED, COLOR_BLUE),
+            Err(Error::InvalidColorPair(64))
+        ));
+        assert!(matches!(
+            cm.init_pair(-1, COLOR_RED, COLOR_BLUE),
+            Err(Error::InvalidColorPair(-1))
+        ));
+        cm.init_pair(63, COLOR_RED, COLOR_BLUE).unwrap();
+    }
+
+    #[test]
+    fn test_pair_zero_defaults() {
+        let mut cm = ColorManager::new(8, 64, true);
+        cm.start().unwrap();
+        assert_eq!(cm.pair_content(0).unwrap(), (COLOR_WHITE, COLOR_BLACK));
+
+        cm.assume_default_colors(COLOR_YELLOW, COLOR_BLUE).unwrap();
+        assert_eq!(cm.pair_content(0).unwrap(), (COLOR_YELLOW, COLOR_BLUE));
+        assert!(cm.init_pair(1, -1, COLOR_BLACK).is_err());
+        assert!(cm.assume_default_colors(COLOR_RED, 8).is_err());
+
+        cm.use_default_colors().unwrap();
+        assert_eq!(cm.pair_content(0).unwrap(), (-1, -1));
+        cm.init_pair(1, -1, COLOR_BLACK).unwrap();
+
+        cm.assume_default_colors(-1, COLOR_BLUE).unwrap();
+        cm.reset_color_pairs();
+        assert_eq!(cm.pair_content(0).unwrap(), (-1, COLOR_BLUE));
+        assert_eq!(cm.pair_content(1).unwrap(), (COLOR_WHITE, COLOR_BLACK));
+    }
+
+    #[test]
+    fn test_init_color_validation() {
+        let mut cm = ColorManager::new(8, 64, true);
+        cm.start().unwrap();
+        cm.init_color(2, 0, 1000, 250).unwrap();
+        assert_eq!(cm.color_content(2).unwrap(), (0, 1000, 250));
+
+        for (r, g, b) in [(1001, 0, 0), (0, -1, 0), (0, 0, 2000)] {
+            assert!(matches!(
+                cm.init_color(2, r, g, b),
+                Err(Error::InvalidArgument(_))
+            ));
+        }
+        assert!(matches!(
+            cm.init_color(8, 0, 0, 0),
+            Err(Error::InvalidColor(8))
+        ));
+        assert_eq!(cm.color_content(2).unwrap(), (0, 1000, 250));
+
+        let mut fixed = ColorManager::new(8, 64, false);
+        fixed.start().unwrap();
+        let before = fixed.color_content(1).unwrap();
+        assert!(matches!(
+            fixed.init_color(1, 500, 500, 500),
+            Err(Error::NotSupported(_))
+        ));
+        assert_eq!(fixed.color_content(1).unwrap(), before);
+    }
 }
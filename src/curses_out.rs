@@ -0,0 +1,322 @@
+//! Drawing abstraction for testing UI code.
+//!
+//! Code that takes `&mut Screen` needs a terminal to run. Writing it against
+//! the [`CursesOut`] trait instead lets the same function draw on a real
+//! [`Screen`] and on a [`RecordingCurses`], which keeps a log of every call
+//! for assertions in unit tests.
+//!
+//! # Example
+//!
+//! ```rust
+//! use ncurses::curses_out::{CursesCall, CursesOut, RecordingCurses};
+//! use ncurses::{Result, A_BOLD};
+//!
+//! fn draw_title(out: &mut impl CursesOut, title: &str) -> Result<()> {
+//!     let (_, cols) = out.getmaxyx();
+//!     out.attron(A_BOLD)?;
+//!     out.mvaddstr(0, (cols - title.len() as i32) / 2, title)?;
+//!     out.attroff(A_BOLD)?;
+//!     out.refresh()
+//! }
+//!
+//! let mut rec = RecordingCurses::new(24, 80);
+//! draw_title(&mut rec, "Inbox").unwrap();
+//! assert!(rec.calls().contains(&CursesCall::Move { y: 0, x: 37 }));
+//! assert_eq!(rec.text(), "Inbox");
+//! ```
+
+use crate::error::{Error, Result};
+use crate::screen::Screen;
+use crate::types::{AttrT, ChType};
+
+/// The common stdscr drawing operations.
+///
+/// Implemented by [`Screen`], which forwards to its methods of the same
+/// name, and by [`RecordingCurses`]. The `mv*` methods default to a move
+/// followed by the plain operation.
+pub trait CursesOut {
+    /// Move the cursor to (y, x).
+    fn mv(&mut self, y: i32, x: i32) -> Result<()>;
+
+    /// Add a character at the cursor.
+    fn addch(&mut self, ch: ChType) -> Result<()>;
+
+    /// Add a string at the cursor.
+    fn addstr(&mut self, s: &str) -> Result<()>;
+
+    /// Move to (y, x) and add a character.
+    fn mvaddch(&mut self, y: i32, x: i32, ch: ChType) -> Result<()> {
+        self.mv(y, x)?;
+        self.addch(ch)
+    }
+
+    /// Move to (y, x) and add a string.
+    fn mvaddstr(&mut self, y: i32, x: i32, s: &str) -> Result<()> {
+        self.mv(y, x)?;
+        self.addstr(s)
+    }
+
+    /// Turn on attributes.
+    fn attron(&mut self, attr: AttrT) -> Result<()>;
+
+    /// Turn off attributes.
+    fn attroff(&mut self, attr: AttrT) -> Result<()>;
+
+    /// Set attributes.
+    fn attrset(&mut self, attr: AttrT) -> Result<()>;
+
+    /// Clear the screen on the next refresh.
+    fn clear(&mut self) -> Result<()>;
+
+    /// Blank the screen.
+    fn erase(&mut self) -> Result<()>;
+
+    /// Clear from the cursor to the end of the line.
+    fn clrtoeol(&mut self) -> Result<()>;
+
+    /// Show the changes on the terminal.
+    fn refresh(&mut self) -> Result<()>;
+
+    /// Size of the drawing area (lines, columns).
+    fn getmaxyx(&self) -> (i32, i32);
+}
+
+impl CursesOut for Screen {
+    fn mv(&mut self, y: i32, x: i32) -> Result<()> {
+        Screen::mv(self, y, x)
+    }
+
+    fn addch(&mut self, ch: ChType) -> Result<()> {
+        Screen::addch(self, ch)
+    }
+
+    fn addstr(&mut self, s: &str) -> Result<()> {
+        Screen::addstr(self, s)
+    }
+
+    fn mvaddch(&mut self, y: i32, x: i32, ch: ChType) -> Result<()> {
+        Screen::mvaddch(self, y, x, ch)
+    }
+
+    fn mvaddstr(&mut self, y: i32, x: i32, s: &str) -> Result<()> {
+        Screen::mvaddstr(self, y, x, s)
+    }
+
+    fn attron(&mut self, attr: AttrT) -> Result<()> {
+        Screen::attron(self, attr)
+    }
+
+    fn attroff(&mut self, attr: AttrT) -> Result<()> {
+        Screen::attroff(self, attr)
+    }
+
+    fn attrset(&mut self, attr: AttrT) -> Result<()> {
+        Screen::attrset(self, attr)
+    }
+
+    fn clear(&mut self) -> Result<()> {
+        Screen::clear(self)
+    }
+
+    fn erase(&mut self) -> Result<()> {
+        Screen::erase(self)
+    }
+
+    fn clrtoeol(&mut self) -> Result<()> {
+        Screen::clrtoeol(self)
+    }
+
+    fn refresh(&mut self) -> Result<()> {
+        Screen::refresh(self)
+    }
+
+    fn getmaxyx(&self) -> (i32, i32) {
+        Screen::getmaxyx(self)
+    }
+}
+
+/// One call made on a [`RecordingCurses`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CursesCall {
+    /// `mv(y, x)`, also recorded for the `mv*` methods.
+    Move {
+        /// Target row.
+        y: i32,
+        /// Target column.
+        x: i32,
+    },
+    /// `addch(ch)`.
+    AddCh(ChType),
+    /// `addstr(s)`.
+    AddStr(String),
+    /// `attron(attr)`.
+    AttrOn(AttrT),
+    /// `attroff(attr)`.
+    AttrOff(AttrT),
+    /// `attrset(attr)`.
+    AttrSet(AttrT),
+    /// `clear()`.
+    Clear,
+    /// `erase()`.
+    Erase,
+    /// `clrtoeol()`.
+    ClrToEol,
+    /// `refresh()`.
+    Refresh,
+}
+
+/// A [`CursesOut`] that records calls instead of drawing.
+///
+/// Moves outside the configured size fail with [`Error::OutOfBounds`] like
+/// they do on a real window, so off-by-one layout bugs show up in tests.
+#[derive(Clone, Debug)]
+pub struct RecordingCurses {
+    lines: i32,
+    cols: i32,
+    calls: Vec<CursesCall>,
+}
+
+impl RecordingCurses {
+    /// Create a recorder reporting a `lines` x `cols` drawing area.
+    pub fn new(lines: i32, cols: i32) -> Self {
+        RecordingCurses {
+            lines,
+            cols,
+            calls: Vec::new(),
+        }
+    }
+
+    /// The calls made so far, oldest first.
+    #[must_use]
+    pub fn calls(&self) -> &[CursesCall] {
+        &self.calls
+    }
+
+    /// Return the recorded calls and start a new log.
+    pub fn take_calls(&mut self) -> Vec<CursesCall> {
+        std::mem::take(&mut self.calls)
+    }
+
+    /// All text passed to `addstr` and `addch`, concatenated in call order.
+    #[must_use]
+    pub fn text(&self) -> String {
+        self.calls
+            .iter()
+            .filter_map(|call| match call {
+                CursesCall::AddStr(s) => Some(s.clone()),
+                CursesCall::AddCh(ch) => {
+                    Some(((ch & crate::attr::A_CHARTEXT) as u8 as char).to_string())
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn record(&mut self, call: CursesCall) -> Result<()> {
+        self.calls.push(call);
+        Ok(())
+    }
+}
+
+impl CursesOut for RecordingCurses {
+    fn mv(&mut self, y: i32, x: i32) -> Result<()> {
+        if y < 0 || y >= self.lines || x < 0 || x >= self.cols {
+            return Err(Error::OutOfBounds {
+                y,
+                x,
+                max_y: self.lines - 1,
+                max_x: self.cols - 1,
+            });
+        }
+        self.record(CursesCall::Move { y, x })
+    }
+
+    fn addch(&mut self, ch: ChType) -> Result<()> {
+        self.record(CursesCall::AddCh(ch))
+    }
+
+    fn addstr(&mut self, s: &str) -> Result<()> {
+        self.record(CursesCall::AddStr(s.to_string()))
+    }
+
+    fn attron(&mut self, attr: AttrT) -> Result<()> {
+        self.record(CursesCall::AttrOn(attr))
+    }
+
+    fn attroff(&mut self, attr: AttrT) -> Result<()> {
+        self.record(CursesCall::AttrOff(attr))
+    }
+
+    fn attrset(&mut self, attr: AttrT) -> Result<()> {
+        self.record(CursesCall::AttrSet(attr))
+    }
+
+    fn clear(&mut self) -> Result<()> {
+        self.record(CursesCall::Clear)
+    }
+
+    fn erase(&mut self) -> Result<()> {
+        self.record(CursesCall::Erase)
+    }
+
+    fn clrtoeol(&mut self) -> Result<()> {
+        self.record(CursesCall::ClrToEol)
+    }
+
+    fn refresh(&mut self) -> Result<()> {
+        self.record(CursesCall::Refresh)
+    }
+
+    fn getmaxyx(&self) -> (i32, i32) {
+        (self.lines, self.cols)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attr::{A_BOLD, A_REVERSE};
+
+    /// A status bar as an application might draw it.
+    fn draw_status(out: &mut impl CursesOut, left: &str, right: &str) -> Result<()> {
+        let (lines, cols) = out.getmaxyx();
+        out.attrset(A_REVERSE)?;
+        out.mv(lines - 1, 0)?;
+        out.clrtoeol()?;
+        out.addstr(left)?;
+        out.mvaddstr(lines - 1, cols - right.len() as i32, right)?;
+        out.attrset(A_BOLD)?;
+        out.refresh()
+    }
+
+    #[test]
+    fn test_recorder_logs_draw_calls() {
+        let mut rec = RecordingCurses::new(10, 20);
+        draw_status(&mut rec, "main", "3/7").unwrap();
+        assert_eq!(
+            rec.calls(),
+            [
+                CursesCall::AttrSet(A_REVERSE),
+                CursesCall::Move { y: 9, x: 0 },
+                CursesCall::ClrToEol,
+                CursesCall::AddStr("main".into()),
+                CursesCall::Move { y: 9, x: 17 },
+                CursesCall::AddStr("3/7".into()),
+                CursesCall::AttrSet(A_BOLD),
+                CursesCall::Refresh,
+            ]
+        );
+        assert_eq!(rec.text(), "main3/7");
+
+        assert_eq!(rec.take_calls().len(), 8);
+        assert!(rec.calls().is_empty());
+    }
+
+    #[test]
+    fn test_recorder_rejects_out_of_bounds_moves() {
+        let mut rec = RecordingCurses::new(10, 20);
+        assert!(draw_status(&mut rec, "main", "a label wider than the screen").is_err());
+        assert!(rec.mvaddch(10, 0, b'x' as ChType).is_err());
+        assert!(!rec.calls().contains(&CursesCall::Refresh));
+    }
+}
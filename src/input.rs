@@ -6,8 +6,20 @@
 use crate::key::Key;
 use std::collections::VecDeque;
 
-/// Size of the input FIFO buffer.
-const FIFO_SIZE: usize = 256;
+/// Default size of the input FIFO buffer.
+pub const FIFO_SIZE: usize = 256;
+
+/// What an [`InputBuffer`] does when an entry does not fit.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum InputOverflow {
+    /// Refuse the new entry; `ungetch()` fails with `Error::BufferFull`.
+    #[default]
+    Error,
+    /// Discard the oldest entries to make room. Pushed-back keys drop the
+    /// keys pushed back earliest, which would be read last; typeahead drops
+    /// the input that would be read next.
+    DropOldest,
+}
 
 /// Input buffer for handling typeahead and escape sequences.
 pub struct InputBuffer {
@@ -16,17 +28,52 @@ pub struct InputBuffer {
     /// Whether there's pending input to be processed.
     #[allow(dead_code)]
     pending: bool,
+    /// Maximum number of entries.
+    capacity: usize,
+    /// Behavior when the buffer is full.
+    overflow: InputOverflow,
 }
 
 impl InputBuffer {
-    /// Create a new input buffer.
+    /// Create a new input buffer holding [`FIFO_SIZE`] entries.
     pub fn new() -> Self {
+        Self::with_capacity(FIFO_SIZE)
+    }
+
+    /// Create an input buffer holding at most `capacity` entries.
+    pub fn with_capacity(capacity: usize) -> Self {
         Self {
-            fifo: VecDeque::with_capacity(FIFO_SIZE),
+            fifo: VecDeque::with_capacity(capacity),
             pending: false,
+            capacity,
+            overflow: InputOverflow::Error,
         }
     }
 
+    /// Maximum number of entries the buffer holds.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Change the capacity.
+    ///
+    /// When shrinking below the current length, the entries that would be
+    /// read last are discarded.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.fifo.truncate(capacity);
+    }
+
+    /// Behavior when the buffer is full.
+    pub fn overflow(&self) -> InputOverflow {
+        self.overflow
+    }
+
+    /// Set the behavior when the buffer is full.
+    pub fn set_overflow(&mut self, overflow: InputOverflow) {
+        self.overflow = overflow;
+    }
+
     /// Check if there's input available.
     pub fn has_input(&self) -> bool {
         !self.fifo.is_empty()
@@ -43,20 +90,47 @@ impl InputBuffer {
     }
 
     /// Push a character back to the front of the buffer.
+    ///
+    /// Returns false if the buffer is full and the overflow policy is
+    /// [`InputOverflow::Error`].
     pub fn unget(&mut self, ch: i32) -> bool {
-        if self.fifo.len() < FIFO_SIZE {
+        self.unget_seq(&[ch])
+    }
+
+    /// Push a sequence back to the front of the buffer so that it is read
+    /// in order, before anything already buffered.
+    ///
+    /// Nothing is pushed if the whole sequence does not fit, either because
+    /// the buffer is full under [`InputOverflow::Error`] or because the
+    /// sequence is longer than the capacity.
+    pub fn unget_seq(&mut self, seq: &[i32]) -> bool {
+        if seq.len() > self.capacity {
+            return false;
+        }
+        let excess = (self.fifo.len() + seq.len()).saturating_sub(self.capacity);
+        if excess > 0 {
+            if self.overflow == InputOverflow::Error {
+                return false;
+            }
+            self.fifo.truncate(self.fifo.len() - excess);
+        }
+        for &ch in seq.iter().rev() {
             self.fifo.push_front(ch);
-            true
-        } else {
-            false
         }
+        true
     }
 
     /// Add a character to the end of the buffer.
+    ///
+    /// When the buffer is full the character is dropped, or under
+    /// [`InputOverflow::DropOldest`] the front entry is.
     pub fn push(&mut self, ch: i32) {
-        if self.fifo.len() < FIFO_SIZE {
-            self.fifo.push_back(ch);
+        if self.fifo.len() >= self.capacity
+            && (self.overflow == InputOverflow::Error || self.fifo.pop_front().is_none())
+        {
+            return;
         }
+        self.fifo.push_back(ch);
     }
 
     /// Clear the input buffer.
@@ -87,8 +161,10 @@ pub struct InputMode {
     /// Raw mode - no processing of input.
     pub raw: bool,
     /// cbreak mode - no line buffering but signals processed.
-    /// Value >1 indicates halfdelay mode with timeout.
     pub cbreak: i32,
+    /// Halfdelay timeout in tenths of a second, 0 when not in halfdelay
+    /// mode. Halfdelay implies cbreak.
+    pub halfdelay: i32,
     /// Echo mode - echo typed characters.
     pub echo: bool,
     /// Newline translation mode.
@@ -105,6 +181,7 @@ impl InputMode {
         Self {
             raw: false,
             cbreak: 0,
+            halfdelay: 0,
             echo: true,
             nl: true,
             meta: false,
@@ -114,16 +191,12 @@ impl InputMode {
 
     /// Check if halfdelay mode is active.
     pub fn is_halfdelay(&self) -> bool {
-        self.cbreak > 1
+        self.halfdelay > 0
     }
 
     /// Get the halfdelay timeout in tenths of a second.
     pub fn halfdelay_tenths(&self) -> i32 {
-        if self.cbreak > 1 {
-            self.cbreak - 1
-        } else {
-            0
-        }
+        self.halfdelay.max(0)
     }
 }
 
@@ -329,6 +402,17 @@ impl EscapeParser {
         self.add(b"\x1b[23~", key_f(11));
         self.add(b"\x1b[24~", key_f(12));
 
+        // F1-F4 with modifiers (xterm): Shift, Ctrl, Ctrl+Shift, Alt and
+        // Alt+Shift count on from F13 in steps of 12, as in terminfo
+        for (modifier, offset) in [(2, 12), (5, 24), (6, 36), (3, 48), (4, 60)] {
+            for (n, last) in (1..=4).zip([b'P', b'Q', b'R', b'S']) {
+                self.add(
+                    &[0x1b, b'[', b'1', b';', b'0' + modifier, last],
+                    key_f(n + offset),
+                );
+            }
+        }
+
         // Alternative F1-F4
         self.add(b"\x1b[11~", key_f(1));
         self.add(b"\x1b[12~", key_f(2));
@@ -426,6 +510,23 @@ impl EscapeParser {
         &self.current
     }
 
+    /// Decode a cursor position report, `ESC [ row ; col R`.
+    ///
+    /// This is the terminal's reply to a `ESC [ 6 n` query rather than a
+    /// key, so it has no entry in the key table. Returns the 0-based
+    /// `(y, x)` position, or `None` if `seq` is not exactly one report.
+    pub fn cursor_report(seq: &[u8]) -> Option<(i32, i32)> {
+        let params = seq.strip_prefix(b"\x1b[")?.strip_suffix(b"R")?;
+        let (row, col) = std::str::from_utf8(params).ok()?.split_once(';')?;
+        let number = |s: &str| -> Option<i32> {
+            if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            s.parse::<i32>().ok().filter(|&n| n >= 1)
+        };
+        Some((number(row)? - 1, number(col)? - 1))
+    }
+
     /// Define a custom key escape sequence.
     ///
     /// This associates the given escape sequence with a keycode. If the sequence
@@ -613,6 +714,56 @@ mod tests {
         assert_eq!(buf.get(), Some(65));
     }
 
+    #[test]
+    fn test_unget_seq() {
+        let mut buf = InputBuffer::new();
+        buf.push(65);
+        assert!(buf.unget_seq(&[1, 2, 3]));
+        assert_eq!(buf.len(), 4);
+        assert_eq!(buf.get(), Some(1));
+
+        // All or nothing when the buffer is nearly full
+        while buf.unget(0) {}
+        buf.get();
+        assert!(!buf.unget_seq(&[1, 2]));
+        assert_eq!(buf.len(), FIFO_SIZE - 1);
+    }
+
+    #[test]
+    fn test_overflow_policy() {
+        let mut buf = InputBuffer::with_capacity(3);
+        assert_eq!(buf.overflow(), InputOverflow::Error);
+        assert!(buf.unget(1) && buf.unget(2) && buf.unget(3));
+        assert!(!buf.unget(4));
+        buf.push(5);
+        assert_eq!(buf.len(), 3);
+        assert_eq!(buf.peek(), Some(3));
+
+        buf.set_overflow(InputOverflow::DropOldest);
+        assert!(buf.unget(4));
+        assert!(buf.unget_seq(&[6, 7]));
+        assert_eq!(
+            (buf.get(), buf.get(), buf.get()),
+            (Some(6), Some(7), Some(4))
+        );
+        assert!(!buf.unget_seq(&[1, 2, 3, 4]));
+
+        buf.push(8);
+        buf.push(9);
+        buf.push(10);
+        buf.push(11);
+        assert_eq!(
+            (buf.get(), buf.get(), buf.get()),
+            (Some(9), Some(10), Some(11))
+        );
+
+        buf.push(1);
+        buf.push(2);
+        buf.set_capacity(1);
+        assert_eq!(buf.get(), Some(1));
+        assert!(buf.is_empty());
+    }
+
     #[test]
     fn test_escape_parser() {
         let mut parser = EscapeParser::new();
@@ -631,8 +782,20 @@ mod tests {
         assert!(mode.echo);
         assert!(!mode.is_halfdelay());
 
-        mode.cbreak = 5;
+        mode.cbreak = 1;
+        assert!(!mode.is_halfdelay());
+        mode.halfdelay = 4;
         assert!(mode.is_halfdelay());
         assert_eq!(mode.halfdelay_tenths(), 4);
     }
+
+    #[test]
+    fn test_cursor_report() {
+        assert_eq!(EscapeParser::cursor_report(b"\x1b[12;34R"), Some((11, 33)));
+        assert_eq!(EscapeParser::cursor_report(b"\x1b[1;1R"), Some((0, 0)));
+        assert_eq!(EscapeParser::cursor_report(b"\x1b[0;1R"), None);
+        assert_eq!(EscapeParser::cursor_report(b"\x1b[12R"), None);
+        assert_eq!(EscapeParser::cursor_report(b"\x1b[1;+2R"), None);
+        assert_eq!(EscapeParser::cursor_report(b"\x1b[1;2A"), None);
+    }
 }
@@ -289,9 +289,43 @@ pub const KEY_MOUSE: i32 = 0o631;
 /// Terminal resize event (ncurses extension).
 pub const KEY_RESIZE: i32 = 0o632;
 
+/// Bracketed paste event (ncurses-pure extension).
+///
+/// Returned once the whole pasted text has been read; retrieve it with
+/// `Screen::get_paste()`.
+pub const KEY_PASTE: i32 = 0o634;
+
+/// Terminal gained focus (ncurses-pure extension).
+///
+/// Only reported after `Screen::set_focus_events(true)`.
+pub const KEY_FOCUS_IN: i32 = 0o635;
+
+/// Terminal lost focus (ncurses-pure extension).
+///
+/// Only reported after `Screen::set_focus_events(true)`.
+pub const KEY_FOCUS_OUT: i32 = 0o636;
+
+/// Unrecognized CSI escape sequence (ncurses-pure extension).
+///
+/// Only reported after `Screen::set_unknown_csi_coalesce(true)`; retrieve
+/// the raw bytes with `Screen::get_unknown_csi()`.
+pub const KEY_UNKNOWN_CSI: i32 = 0o637;
+
 /// Maximum key value.
 pub const KEY_MAX: i32 = 0o777;
 
+/// Flag marking a character typed with Alt (ncurses-pure extension).
+///
+/// Only reported after `Screen::set_meta_sends_escape(true)`: a printable
+/// ASCII character arriving right after ESC is returned as `KEY_ALT | ch`.
+/// The flag lies above every key code and Unicode scalar value.
+pub const KEY_ALT: i32 = 1 << 24;
+
+/// Get the code `getch()` returns for `ch` typed with Alt.
+pub const fn key_alt(ch: char) -> i32 {
+    KEY_ALT | ch as i32
+}
+
 /// Key enumeration for type-safe key handling.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Key {
@@ -333,6 +367,16 @@ pub enum Key {
     Mouse,
     /// Terminal resize.
     Resize,
+    /// Bracketed paste.
+    Paste,
+    /// Terminal gained focus.
+    FocusIn,
+    /// Terminal lost focus.
+    FocusOut,
+    /// Unrecognized CSI sequence.
+    UnknownCsi,
+    /// A character typed with Alt.
+    Alt(char),
     /// Unknown key code.
     Unknown(i32),
 }
@@ -367,10 +411,20 @@ impl Key {
             KEY_BTAB => Key::BackTab,
             KEY_MOUSE => Key::Mouse,
             KEY_RESIZE => Key::Resize,
+            KEY_PASTE => Key::Paste,
+            KEY_FOCUS_IN => Key::FocusIn,
+            KEY_FOCUS_OUT => Key::FocusOut,
+            KEY_UNKNOWN_CSI => Key::UnknownCsi,
 
             // Function keys
             k if (KEY_F0..=KEY_F0 + 64).contains(&k) => Key::F((k - KEY_F0) as u8),
 
+            // Alt-modified characters
+            k if k & KEY_ALT != 0 => match char::from_u32((k & !KEY_ALT) as u32) {
+                Some(c) => Key::Alt(c),
+                None => Key::Unknown(code),
+            },
+
             // Unknown
             _ => Key::Unknown(code),
         }
@@ -398,6 +452,11 @@ impl Key {
             Key::F(n) => KEY_F0 + n as i32,
             Key::Mouse => KEY_MOUSE,
             Key::Resize => KEY_RESIZE,
+            Key::Paste => KEY_PASTE,
+            Key::FocusIn => KEY_FOCUS_IN,
+            Key::FocusOut => KEY_FOCUS_OUT,
+            Key::UnknownCsi => KEY_UNKNOWN_CSI,
+            Key::Alt(c) => key_alt(c),
             Key::Unknown(code) => code,
         }
     }
@@ -448,6 +507,11 @@ impl std::fmt::Display for Key {
             Key::F(n) => write!(f, "<F{}>", n),
             Key::Mouse => write!(f, "<Mouse>"),
             Key::Resize => write!(f, "<Resize>"),
+            Key::Paste => write!(f, "<Paste>"),
+            Key::FocusIn => write!(f, "<FocusIn>"),
+            Key::FocusOut => write!(f, "<FocusOut>"),
+            Key::UnknownCsi => write!(f, "<UnknownCsi>"),
+            Key::Alt(c) => write!(f, "<Alt-{}>", c),
             Key::Unknown(code) => write!(f, "<Unknown:{}>", code),
         }
     }
@@ -508,6 +572,10 @@ pub fn keyname(code: i32) -> &'static str {
         KEY_BTAB => "KEY_BTAB",
         KEY_MOUSE => "KEY_MOUSE",
         KEY_RESIZE => "KEY_RESIZE",
+        KEY_PASTE => "KEY_PASTE",
+        KEY_FOCUS_IN => "KEY_FOCUS_IN",
+        KEY_FOCUS_OUT => "KEY_FOCUS_OUT",
+        KEY_UNKNOWN_CSI => "KEY_UNKNOWN_CSI",
         k if (KEY_F0..=KEY_F0 + 12).contains(&k) => match k - KEY_F0 {
             0 => "KEY_F(0)",
             1 => "KEY_F(1)",
@@ -528,30 +596,54 @@ pub fn keyname(code: i32) -> &'static str {
     }
 }
 
+/// Options controlling how [`unctrl_with`] renders characters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UnctrlOptions {
+    /// Show NUL as `"^@"`; otherwise it renders as an empty string.
+    pub show_nul: bool,
+    /// Show characters with the high bit set in `M-` notation; otherwise
+    /// 160-255 are shown as the Latin-1 character itself.
+    pub meta_prefix: bool,
+}
+
+impl Default for UnctrlOptions {
+    fn default() -> Self {
+        Self {
+            show_nul: true,
+            meta_prefix: true,
+        }
+    }
+}
+
 /// Get a printable representation of a character.
 ///
 /// This function returns a printable string representation of any character:
 /// - Control characters (0-31) return "^X" notation (e.g., "^A" for 0x01)
 /// - DEL (127) returns "^?"
 /// - Printable ASCII (32-126) returns the character itself
-/// - Characters >= 128 return "M-X" notation or the character if printable
+/// - Characters >= 128 return "M-" followed by the representation of the
+///   low seven bits (e.g., "M-^A" for 0x81, "M-a" for 0xE1)
 ///
-/// This is the ncurses `unctrl()` function.
+/// Attributes in `ch` are ignored. This is the ncurses `unctrl()` function.
 pub fn unctrl(ch: u32) -> String {
+    unctrl_with(ch, UnctrlOptions::default())
+}
+
+/// Get a printable representation of a character, as [`unctrl`] does,
+/// using `options`.
+pub fn unctrl_with(ch: u32, options: UnctrlOptions) -> String {
     let c = (ch & 0xFF) as u8;
     match c {
-        0 => "^@".to_string(),
-        1..=26 => format!("^{}", (b'A' + c - 1) as char),
-        27 => "^[".to_string(),
-        28 => "^\\".to_string(),
-        29 => "^]".to_string(),
-        30 => "^^".to_string(),
-        31 => "^_".to_string(),
+        0 if !options.show_nul => String::new(),
+        0..=31 => format!("^{}", (c + 64) as char),
         32..=126 => (c as char).to_string(),
         127 => "^?".to_string(),
-        128..=159 => format!("M-^{}", unctrl((c - 128) as u32)),
-        160..=254 => format!("M-{}", (c - 128) as char),
-        255 => "M-^?".to_string(),
+        160..=255 if !options.meta_prefix => (c as char).to_string(),
+        128..=255 => {
+            // NUL keeps its "^@" under the meta prefix
+            let low = unctrl_with(u32::from(c & 0x7F), UnctrlOptions::default());
+            format!("M-{low}")
+        }
     }
 }
 
@@ -590,6 +682,13 @@ mod tests {
 
         assert_eq!(Key::Up.to_code(), KEY_UP);
         assert_eq!(Key::F(1).to_code(), KEY_F0 + 1);
+
+        assert_eq!(Key::from_code(KEY_PASTE), Key::Paste);
+        assert_eq!(Key::Paste.to_code(), KEY_PASTE);
+
+        assert_eq!(Key::from_code(key_alt('x')), Key::Alt('x'));
+        assert_eq!(Key::Alt('x').to_code(), KEY_ALT | 'x' as i32);
+        assert_eq!(Key::Alt('x').to_string(), "<Alt-x>");
     }
 
     #[test]
@@ -598,4 +697,24 @@ mod tests {
         assert_eq!(keyname(KEY_F0 + 1), "KEY_F(1)");
         assert_eq!(keyname(27), "^[");
     }
+
+    #[test]
+    fn test_unctrl() {
+        assert_eq!(unctrl(0x01), "^A");
+        assert_eq!(unctrl(0x1b), "^[");
+        assert_eq!(unctrl(0x7f), "^?");
+        assert_eq!(unctrl(0x81), "M-^A");
+        assert_eq!(unctrl(0xe1), "M-a");
+        assert_eq!(unctrl(0xff), "M-^?");
+        assert_eq!(unctrl(b'x' as u32 | crate::attr::A_BOLD), "x");
+
+        let options = UnctrlOptions {
+            show_nul: false,
+            meta_prefix: false,
+        };
+        assert_eq!(unctrl(0), "^@");
+        assert_eq!(unctrl_with(0, options), "");
+        assert_eq!(unctrl_with(0x80, options), "M-^@");
+        assert_eq!(unctrl_with(0xe9, options), "é");
+    }
 }
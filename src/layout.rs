@@ -0,0 +1,123 @@
+//! Layout helpers for ncurses-pure.
+//!
+//! This module provides pure functions for dividing a screen region into
+//! adjacent rectangles, so that several windows can be placed side by side
+//! or stacked without hand-computing coordinates. Combine the results with
+//! [`Screen::newwin_rect`](crate::Screen::newwin_rect) to create the windows.
+//!
+//! # Example
+//!
+//! ```rust
+//! use ncurses::layout::split_horizontal;
+//! use ncurses::types::Rect;
+//!
+//! // Three equal columns across an 80x24 screen
+//! let columns = split_horizontal(Rect::new(0, 0, 24, 80), &[1, 1, 1]);
+//! let widths: Vec<i32> = columns.iter().map(|r| r.size.width).collect();
+//! assert_eq!(widths, [27, 27, 26]);
+//! ```
+
+use crate::types::{Coord, Rect};
+
+/// Split `parent` into side-by-side regions whose widths follow `ratios`.
+///
+/// Every region spans the full height of `parent`. Widths are proportional
+/// to the ratios; cells left over after rounding down are handed out one at
+/// a time to the first regions with a non-zero ratio, so the widths always
+/// sum to the parent's width (unless every ratio is zero).
+#[must_use]
+pub fn split_horizontal(parent: Rect, ratios: &[u16]) -> Vec<Rect> {
+    let mut x = parent.left();
+    distribute(parent.size.width, ratios)
+        .into_iter()
+        .map(|width| {
+            let rect = Rect::new(parent.top(), x, parent.size.height, width);
+            x += width;
+            rect
+        })
+        .collect()
+}
+
+/// Split `parent` into stacked regions whose heights follow `ratios`.
+///
+/// Every region spans the full width of `parent`. Leftover rows are handed
+/// out the same way as in [`split_horizontal`].
+#[must_use]
+pub fn split_vertical(parent: Rect, ratios: &[u16]) -> Vec<Rect> {
+    let mut y = parent.top();
+    distribute(parent.size.height, ratios)
+        .into_iter()
+        .map(|height| {
+            let rect = Rect::new(y, parent.left(), height, parent.size.width);
+            y += height;
+            rect
+        })
+        .collect()
+}
+
+/// Divide `total` cells among `ratios`, giving the remainder to the first
+/// regions with a non-zero ratio.
+fn distribute(total: Coord, ratios: &[u16]) -> Vec<Coord> {
+    let total = total.max(0) as i64;
+    let sum: i64 = ratios.iter().map(|&r| r as i64).sum();
+    if sum == 0 {
+        return vec![0; ratios.len()];
+    }
+
+    let mut sizes: Vec<i64> = ratios.iter().map(|&r| total * r as i64 / sum).collect();
+    let mut remainder = total - sizes.iter().sum::<i64>();
+    for (size, &ratio) in sizes.iter_mut().zip(ratios) {
+        if remainder == 0 {
+            break;
+        }
+        if ratio > 0 {
+            *size += 1;
+            remainder -= 1;
+        }
+    }
+
+    sizes.into_iter().map(|s| s as Coord).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_horizontal_equal() {
+        let parent = Rect::new(2, 0, 10, 80);
+        let parts = split_horizontal(parent, &[1, 1, 1]);
+        assert_eq!(
+            parts,
+            [
+                Rect::new(2, 0, 10, 27),
+                Rect::new(2, 27, 10, 27),
+                Rect::new(2, 54, 10, 26),
+            ]
+        );
+        assert_eq!(parts.iter().map(|r| r.size.width).sum::<i32>(), 80);
+    }
+
+    #[test]
+    fn test_split_vertical_weighted() {
+        let parent = Rect::new(1, 5, 24, 40);
+        let parts = split_vertical(parent, &[1, 2, 0]);
+        assert_eq!(
+            parts,
+            [
+                Rect::new(1, 5, 8, 40),
+                Rect::new(9, 5, 16, 40),
+                Rect::new(25, 5, 0, 40),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split_degenerate() {
+        let parent = Rect::new(0, 0, 5, 5);
+        assert!(split_horizontal(parent, &[]).is_empty());
+        assert!(split_vertical(parent, &[0, 0])
+            .iter()
+            .all(|r| r.size.height == 0));
+    }
+}
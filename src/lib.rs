@@ -12,6 +12,7 @@
 //! - **panels**: Panels library for window stacking
 //! - **menu**: Menu library for selection interfaces
 //! - **form**: Form library for data entry
+//! - **serde**: `Serialize`/`Deserialize` for cells and [`WindowSnapshot`]
 //!
 //! ## Example
 //!
@@ -45,13 +46,18 @@
 pub mod acs;
 pub mod attr;
 pub mod color;
+pub mod curses_out;
 pub mod error;
 pub mod input;
 pub mod key;
+pub mod layout;
 pub mod line;
+pub mod line_editor;
+pub mod pad;
 pub mod screen;
 pub mod terminal;
 pub mod types;
+pub mod widgets;
 pub mod window;
 
 #[cfg(feature = "mouse")]
@@ -82,13 +88,16 @@ pub mod threadsafe;
 pub use acs::*;
 pub use attr::*;
 pub use color::*;
+pub use curses_out::CursesOut;
 pub use error::{Error, Result};
 pub use input::*;
 pub use key::*;
+pub use line_editor::LineEditor;
+pub use pad::{PadView, ScrollView};
 pub use screen::globals::{COLS, LINES};
-pub use screen::Screen;
+pub use screen::{CellChange, Event, Screen};
 pub use types::*;
-pub use window::Window;
+pub use window::{AddResult, Window, WindowSnapshot};
 
 #[cfg(feature = "mouse")]
 pub use mouse::*;
@@ -42,6 +42,16 @@ pub struct LineData {
     /// Used for scroll optimization. A value of NEWINDEX (-1) indicates
     /// that this is a new line (inserted or created by scrolling).
     oldindex: NcursesSize,
+
+    /// Columns whose content was written since [`take_written()`] was last
+    /// called, as `(first, last)`.
+    ///
+    /// Unlike `firstchar`/`lastchar` this is not reset by a refresh and is
+    /// not set by merely touching the line, so it tells a window which of
+    /// its cells to pass on to linked subwindows.
+    ///
+    /// [`take_written()`]: Self::take_written
+    written: Option<(NcursesSize, NcursesSize)>,
 }
 
 impl LineData {
@@ -55,6 +65,7 @@ impl LineData {
             firstchar: NOCHANGE,
             lastchar: NOCHANGE,
             oldindex: NEWINDEX,
+            written: None,
         }
     }
 
@@ -93,9 +104,35 @@ impl LineData {
         self.lastchar = NOCHANGE;
     }
 
+    /// Take the range of columns written since the last call.
+    pub fn take_written(&mut self) -> Option<(usize, usize)> {
+        self.written
+            .take()
+            .map(|(first, last)| (first as usize, last as usize))
+    }
+
+    /// Record columns `first..=last` as written.
+    #[inline]
+    fn mark_written(&mut self, first: usize, last: usize) {
+        let (first, last) = (first as NcursesSize, last as NcursesSize);
+        self.written = Some(match self.written {
+            Some((f, l)) => (f.min(first), l.max(last)),
+            None => (first, last),
+        });
+    }
+
+    /// Record the whole line as written and changed.
+    fn mark_all_changed(&mut self) {
+        if !self.text.is_empty() {
+            self.mark_written(0, self.text.len() - 1);
+        }
+        self.touch();
+    }
+
     /// Mark a specific position as changed.
     #[inline]
     pub fn mark_changed(&mut self, x: usize) {
+        self.mark_written(x, x);
         let x = x as NcursesSize;
         if self.firstchar == NOCHANGE {
             self.firstchar = x;
@@ -137,20 +174,24 @@ impl LineData {
     }
 
     /// Set a character at the specified position.
+    ///
+    /// The changed range only grows if the cell actually changes.
     #[cfg(not(feature = "wide"))]
     #[inline]
     pub fn set(&mut self, x: usize, ch: ChType) {
-        if x < self.text.len() {
+        if x < self.text.len() && self.text[x] != ch {
             self.text[x] = ch;
             self.mark_changed(x);
         }
     }
 
     /// Set a character at the specified position (wide character version).
+    ///
+    /// The changed range only grows if the cell actually changes.
     #[cfg(feature = "wide")]
     #[inline]
     pub fn set(&mut self, x: usize, ch: CCharT) {
-        if x < self.text.len() {
+        if x < self.text.len() && self.text[x] != ch {
             self.text[x] = ch;
             self.mark_changed(x);
         }
@@ -169,14 +210,24 @@ impl LineData {
     }
 
     /// Get a mutable slice of the text data.
+    ///
+    /// The whole line counts as written.
     #[cfg(not(feature = "wide"))]
     pub fn text_mut(&mut self) -> &mut [ChType] {
+        if !self.text.is_empty() {
+            self.mark_written(0, self.text.len() - 1);
+        }
         &mut self.text
     }
 
     /// Get a mutable slice of the text data (wide character version).
+    ///
+    /// The whole line counts as written.
     #[cfg(feature = "wide")]
     pub fn text_mut(&mut self) -> &mut [CCharT] {
+        if !self.text.is_empty() {
+            self.mark_written(0, self.text.len() - 1);
+        }
         &mut self.text
     }
 
@@ -184,14 +235,14 @@ impl LineData {
     #[cfg(not(feature = "wide"))]
     pub fn fill(&mut self, ch: ChType) {
         self.text.fill(ch);
-        self.touch();
+        self.mark_all_changed();
     }
 
     /// Fill the line with a character (wide character version).
     #[cfg(feature = "wide")]
     pub fn fill(&mut self, ch: CCharT) {
         self.text.fill(ch);
-        self.touch();
+        self.mark_all_changed();
     }
 
     /// Fill a range of the line with a character.
@@ -220,25 +271,53 @@ impl LineData {
         }
     }
 
+    /// Blank half of a double-width character split at column `x`.
+    ///
+    /// Checks the boundary between columns `x - 1` and `x`: a double-width
+    /// character at `x - 1` must be followed by its placeholder, and a
+    /// placeholder at `x` must follow a double-width character (so one at
+    /// column 0 is always alone). Whichever
+    /// half is left alone is replaced by a space with the same attributes.
+    #[cfg(feature = "wide")]
+    pub fn fix_wide_boundary(&mut self, x: usize) {
+        let Some(&right) = self.text.get(x) else {
+            return;
+        };
+        match x.checked_sub(1).map(|l| self.text[l]) {
+            Some(left) if left.is_wide() => {
+                if !right.is_null() {
+                    self.set(x - 1, CCharT::from_char_attr(' ', left.attrs()));
+                }
+            }
+            _ => {
+                if right.is_null() {
+                    self.set(x, CCharT::from_char_attr(' ', right.attrs()));
+                }
+            }
+        }
+    }
+
     /// Copy content from another line.
     pub fn copy_from(&mut self, other: &LineData) {
         let len = self.text.len().min(other.text.len());
         self.text[..len].copy_from_slice(&other.text[..len]);
-        self.touch();
+        self.mark_all_changed();
     }
 
     /// Resize the line to a new width.
     #[cfg(not(feature = "wide"))]
     pub fn resize(&mut self, new_width: usize, fill: ChType) {
         self.text.resize(new_width, fill);
-        self.touch();
+        self.written = None;
+        self.mark_all_changed();
     }
 
     /// Resize the line to a new width (wide character version).
     #[cfg(feature = "wide")]
     pub fn resize(&mut self, new_width: usize, fill: CCharT) {
         self.text.resize(new_width, fill);
-        self.touch();
+        self.written = None;
+        self.mark_all_changed();
     }
 
     /// Insert characters at a position, shifting content right.
@@ -260,6 +339,9 @@ impl LineData {
     }
 
     /// Insert characters at a position, shifting content right (wide character version).
+    ///
+    /// A wide character split by the insertion point, or whose placeholder
+    /// is pushed off the right edge, is blanked.
     #[cfg(feature = "wide")]
     pub fn insert(&mut self, x: usize, ch: CCharT, count: usize) {
         if x >= self.text.len() {
@@ -267,16 +349,59 @@ impl LineData {
         }
         let width = self.text.len();
         let count = count.min(width - x);
+        self.blank_wide_at(x);
         // Shift content right using copy_within (more efficient than manual loop)
         self.text.copy_within(x..width - count, x + count);
         // Insert the character
         for i in x..(x + count) {
             self.text[i] = ch;
         }
+        self.blank_cut_off_wide();
+        self.mark_changed(x);
+        self.mark_changed(width - 1);
+    }
+
+    /// Insert one character at a position, shifting content right by its width.
+    ///
+    /// A double-width character is stored with its placeholder cell. A wide
+    /// character split by the insertion point or pushed half off the right
+    /// edge is blanked. Nothing is inserted if the character itself would not
+    /// fit before the end of the line.
+    #[cfg(feature = "wide")]
+    pub fn insert_char(&mut self, x: usize, ch: CCharT) {
+        let width = self.text.len();
+        let count = ch.width().max(1);
+        if x + count > width {
+            return;
+        }
+        self.blank_wide_at(x);
+        self.text.copy_within(x..width - count, x + count);
+        self.text[x] = ch;
+        self.text[x + 1..x + count].fill(CCharT::new());
+        self.blank_cut_off_wide();
         self.mark_changed(x);
         self.mark_changed(width - 1);
     }
 
+    /// Blank both halves of a wide character whose placeholder is at `x`,
+    /// before an insertion separates them.
+    #[cfg(feature = "wide")]
+    fn blank_wide_at(&mut self, x: usize) {
+        if x > 0 && self.text[x].is_null() && self.text[x - 1].is_wide() {
+            let attrs = self.text[x - 1].attrs();
+            self.text[x - 1] = CCharT::from_char_attr(' ', attrs);
+            self.text[x] = CCharT::from_char_attr(' ', attrs);
+        }
+    }
+
+    /// Blank a wide character left in the last column without its placeholder.
+    #[cfg(feature = "wide")]
+    fn blank_cut_off_wide(&mut self) {
+        if let Some(last) = self.text.last_mut().filter(|c| c.is_wide()) {
+            *last = CCharT::from_char_attr(' ', last.attrs());
+        }
+    }
+
     /// Delete characters at a position, shifting content left.
     #[cfg(not(feature = "wide"))]
     pub fn delete(&mut self, x: usize, count: usize, fill: ChType) {
@@ -359,6 +484,20 @@ mod tests {
         assert_eq!(line.changed_range(), Some((0, 79)));
     }
 
+    #[test]
+    fn test_written_range() {
+        let mut line = LineData::new(80);
+        line.touch();
+        assert_eq!(line.take_written(), None);
+
+        // Refreshing does not forget what was written
+        line.mark_changed(30);
+        line.mark_changed(12);
+        line.untouch();
+        assert_eq!(line.take_written(), Some((12, 30)));
+        assert_eq!(line.take_written(), None);
+    }
+
     #[cfg(not(feature = "wide"))]
     #[test]
     fn test_set_get() {
@@ -367,4 +506,71 @@ mod tests {
         assert_eq!(line.get(10), b'A' as ChType);
         assert!(line.is_touched());
     }
+
+    #[test]
+    fn test_set_identical_keeps_range() {
+        #[cfg(not(feature = "wide"))]
+        let cell = |c: u8| c as ChType;
+        #[cfg(feature = "wide")]
+        let cell = |c: u8| CCharT::from_char(c as char);
+
+        let mut line = LineData::new(80);
+        line.set(2, cell(b'A'));
+        line.set(40, line.get(40));
+        assert_eq!(line.changed_range(), Some((2, 2)));
+
+        line.untouch();
+        line.set(2, cell(b'A'));
+        assert!(!line.is_touched());
+        line.set(40, cell(b'B'));
+        assert_eq!(line.changed_range(), Some((40, 40)));
+    }
+
+    #[cfg(feature = "wide")]
+    #[test]
+    fn test_insert_char_wide() {
+        let mut line = LineData::new(6);
+        for (i, c) in "abcde".chars().enumerate() {
+            line.set(i, CCharT::from_char(c));
+        }
+        line.insert_char(1, CCharT::from_char('中'));
+        let text: Vec<char> = line.text().iter().map(|c| c.spacing_char()).collect();
+        assert_eq!(text, ['a', '中', '\0', 'b', 'c', 'd']);
+
+        // A wide character pushed half off the edge is blanked
+        for c in "wxyz".chars() {
+            line.insert_char(0, CCharT::from_char(c));
+        }
+        assert_eq!(line.text()[4].spacing_char(), 'a');
+        assert_eq!(line.text()[5].spacing_char(), ' ');
+
+        // No room for both halves at the last column
+        line.insert_char(5, CCharT::from_char('文'));
+        assert_eq!(line.text()[5].spacing_char(), ' ');
+    }
+
+    #[test]
+    #[cfg(feature = "wide")]
+    fn test_insert_drops_wide_char_at_edge() {
+        let mut line = LineData::new(5);
+        for (i, c) in "abc".chars().enumerate() {
+            line.set(i, CCharT::from_char(c));
+        }
+        let wide = CCharT::from_char_attr('中', crate::attr::A_BOLD);
+        line.set(3, wide);
+        line.set(4, CCharT::new());
+
+        line.insert(0, CCharT::from_char('x'), 1);
+        let text: Vec<char> = line.text().iter().map(|c| c.spacing_char()).collect();
+        assert_eq!(text, ['x', 'a', 'b', 'c', ' ']);
+        assert_eq!(line.text()[4].attrs(), crate::attr::A_BOLD);
+
+        // Inserting into the middle of a wide character blanks its left half
+        line.set(3, wide);
+        line.set(4, CCharT::new());
+        line.insert(4, CCharT::from_char('y'), 1);
+        let text: Vec<char> = line.text().iter().map(|c| c.spacing_char()).collect();
+        assert_eq!(text, ['x', 'a', 'b', ' ', 'y']);
+        assert!(line.text().iter().all(|c| !c.is_null()));
+    }
 }
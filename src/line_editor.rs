@@ -0,0 +1,288 @@
+//! Single-line editing with history for ncurses-pure.
+//!
+//! [`LineEditor`] holds the text being edited, the cursor within it and a
+//! list of previously entered lines. It only interprets key codes; use
+//! [`Screen::edit_line`](crate::Screen::edit_line) to read and display a
+//! line on the terminal, or drive it with [`LineEditor::handle_key`] from
+//! your own input loop.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use ncurses::line_editor::LineEditor;
+//! use ncurses::{Result, Screen};
+//!
+//! # fn main() -> Result<()> {
+//! let mut screen = Screen::init()?;
+//! let mut editor = LineEditor::new();
+//! loop {
+//!     let line = screen.edit_line(&mut editor, "> ")?;
+//!     if line == "quit" {
+//!         break;
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{Error, Result};
+use crate::key::{KEY_BACKSPACE, KEY_DC, KEY_DOWN, KEY_END, KEY_ENTER, KEY_HOME, KEY_LEFT};
+use crate::key::{KEY_RIGHT, KEY_UP};
+
+/// Number of history entries kept by default.
+const DEFAULT_MAX_HISTORY: usize = 100;
+
+/// What a key did to the line being edited.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EditStatus {
+    /// The line is still being edited.
+    Editing,
+    /// Enter was pressed; the line is complete.
+    Done,
+}
+
+/// A single-line editor with cursor movement and history recall.
+#[derive(Clone, Debug)]
+pub struct LineEditor {
+    /// The line being edited.
+    buffer: Vec<char>,
+    /// Cursor position as an index into `buffer`.
+    cursor: usize,
+    /// Previously entered lines, oldest first.
+    history: Vec<String>,
+    /// Most history entries kept.
+    max_history: usize,
+    /// History entry being shown, or `None` for the line being typed.
+    recall: Option<usize>,
+    /// The line being typed, kept while browsing history.
+    draft: Vec<char>,
+}
+
+impl Default for LineEditor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LineEditor {
+    /// Create an editor with an empty line and no history.
+    pub fn new() -> Self {
+        Self::with_history(Vec::new())
+    }
+
+    /// Create an editor that recalls `history`, oldest entry first.
+    pub fn with_history(history: Vec<String>) -> Self {
+        let mut editor = Self {
+            buffer: Vec::new(),
+            cursor: 0,
+            history,
+            max_history: DEFAULT_MAX_HISTORY,
+            recall: None,
+            draft: Vec::new(),
+        };
+        editor.trim_history();
+        editor
+    }
+
+    /// Get the history, oldest entry first.
+    pub fn history(&self) -> &[String] {
+        &self.history
+    }
+
+    /// Set how many history entries are kept, dropping the oldest.
+    pub fn set_max_history(&mut self, max: usize) {
+        self.max_history = max;
+        self.trim_history();
+    }
+
+    /// Get the line being edited.
+    pub fn line(&self) -> String {
+        self.buffer.iter().collect()
+    }
+
+    /// Get the cursor position in characters from the start of the line.
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Replace the line being edited and put the cursor at its end.
+    pub fn set_line(&mut self, line: &str) {
+        self.buffer = line.chars().collect();
+        self.cursor = self.buffer.len();
+    }
+
+    /// Apply a key code to the line.
+    ///
+    /// Printable ASCII characters are inserted at the cursor; use
+    /// [`handle_char()`](Self::handle_char) for other characters, since key
+    /// codes and bytes of UTF-8 input overlap them. Left/Right, Home/End
+    /// (or Ctrl-A/Ctrl-E) move the cursor, Backspace and Delete remove a
+    /// character, and Up/Down step through the history. Enter finishes the
+    /// line: it is added to the history and the editor is cleared for the
+    /// next one. Returns [`Error::Interrupted`] for Ctrl-C and Ctrl-D, like
+    /// `getstr()`; other keys are ignored.
+    pub fn handle_key(&mut self, key: i32) -> Result<EditStatus> {
+        match key {
+            0x0a | 0x0d | KEY_ENTER => return Ok(EditStatus::Done),
+            0x03 | 0x04 => return Err(Error::Interrupted),
+            KEY_LEFT => self.cursor = self.cursor.saturating_sub(1),
+            KEY_RIGHT => self.cursor = (self.cursor + 1).min(self.buffer.len()),
+            KEY_HOME | 0x01 => self.cursor = 0,
+            KEY_END | 0x05 => self.cursor = self.buffer.len(),
+            KEY_BACKSPACE | 0x08 | 0x7f if self.cursor > 0 => {
+                self.cursor -= 1;
+                self.buffer.remove(self.cursor);
+            }
+            KEY_DC if self.cursor < self.buffer.len() => {
+                self.buffer.remove(self.cursor);
+            }
+            KEY_UP => self.recall_older(),
+            KEY_DOWN => self.recall_newer(),
+            0x20..=0x7e => self.insert(key as u8 as char),
+            _ => {}
+        }
+        Ok(EditStatus::Editing)
+    }
+
+    /// Apply a decoded character to the line.
+    ///
+    /// Any character other than a control character is inserted at the
+    /// cursor. Control characters are editing keys, as in
+    /// [`handle_key()`](Self::handle_key).
+    pub fn handle_char(&mut self, c: char) -> Result<EditStatus> {
+        if c.is_ascii_control() {
+            return self.handle_key(c as i32);
+        }
+        if !c.is_control() {
+            self.insert(c);
+        }
+        Ok(EditStatus::Editing)
+    }
+
+    /// Insert `c` at the cursor and move past it.
+    fn insert(&mut self, c: char) {
+        self.buffer.insert(self.cursor, c);
+        self.cursor += 1;
+    }
+
+    /// Take the finished line, adding it to the history.
+    ///
+    /// Empty lines and repeats of the newest entry are not recorded.
+    pub fn finish(&mut self) -> String {
+        let line = self.line();
+        if !line.is_empty() && self.history.last() != Some(&line) {
+            self.history.push(line.clone());
+            self.trim_history();
+        }
+        self.buffer.clear();
+        self.draft.clear();
+        self.cursor = 0;
+        self.recall = None;
+        line
+    }
+
+    /// Show the previous history entry.
+    fn recall_older(&mut self) {
+        let index = match self.recall {
+            Some(0) => return,
+            Some(i) => i - 1,
+            None if self.history.is_empty() => return,
+            None => {
+                self.draft = std::mem::take(&mut self.buffer);
+                self.history.len() - 1
+            }
+        };
+        self.recall = Some(index);
+        self.set_line(&self.history[index].clone());
+    }
+
+    /// Show the next history entry, or the draft after the newest one.
+    fn recall_newer(&mut self) {
+        let Some(i) = self.recall else { return };
+        if i + 1 < self.history.len() {
+            self.recall = Some(i + 1);
+            self.set_line(&self.history[i + 1].clone());
+        } else {
+            self.recall = None;
+            self.buffer = std::mem::take(&mut self.draft);
+            self.cursor = self.buffer.len();
+        }
+    }
+
+    /// Drop the oldest entries beyond `max_history`.
+    fn trim_history(&mut self) {
+        let excess = self.history.len().saturating_sub(self.max_history);
+        self.history.drain(..excess);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn type_str(editor: &mut LineEditor, s: &str) {
+        for b in s.bytes() {
+            editor.handle_key(b as i32).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_cursor_editing() {
+        let mut editor = LineEditor::new();
+        type_str(&mut editor, "helo");
+        editor.handle_key(KEY_LEFT).unwrap();
+        type_str(&mut editor, "l");
+        assert_eq!((editor.line().as_str(), editor.cursor()), ("hello", 4));
+
+        editor.handle_key(KEY_HOME).unwrap();
+        editor.handle_key(KEY_DC).unwrap();
+        type_str(&mut editor, "j");
+        editor.handle_key(KEY_END).unwrap();
+        editor.handle_key(KEY_BACKSPACE).unwrap();
+        assert_eq!(editor.line(), "jell");
+        assert_eq!(editor.handle_key(0x0d).unwrap(), EditStatus::Done);
+        assert!(matches!(editor.handle_key(0x03), Err(Error::Interrupted)));
+    }
+
+    #[test]
+    fn test_history_recall() {
+        let mut editor = LineEditor::with_history(vec!["one".into(), "two".into()]);
+        type_str(&mut editor, "dra");
+
+        editor.handle_key(KEY_UP).unwrap();
+        assert_eq!(editor.line(), "two");
+        editor.handle_key(KEY_UP).unwrap();
+        editor.handle_key(KEY_UP).unwrap();
+        assert_eq!(editor.line(), "one");
+        editor.handle_key(KEY_DOWN).unwrap();
+        editor.handle_key(KEY_DOWN).unwrap();
+        assert_eq!((editor.line().as_str(), editor.cursor()), ("dra", 3));
+
+        editor.handle_key(KEY_UP).unwrap();
+        assert_eq!(editor.finish(), "two");
+        assert_eq!(editor.history(), ["one", "two"]);
+
+        editor.set_max_history(1);
+        type_str(&mut editor, "three");
+        editor.finish();
+        assert_eq!(editor.history(), ["three"]);
+    }
+
+    #[test]
+    fn test_non_ascii_chars() {
+        let mut editor = LineEditor::new();
+        for c in "héllo €日本".chars() {
+            editor.handle_char(c).unwrap();
+        }
+        assert_eq!(
+            (editor.line().as_str(), editor.cursor()),
+            ("héllo €日本", 9)
+        );
+
+        // Control characters still edit rather than insert
+        editor.handle_char('\u{8}').unwrap();
+        editor.handle_char('\u{85}').unwrap();
+        assert_eq!(editor.line(), "héllo €日");
+        assert_eq!(editor.handle_char('\r').unwrap(), EditStatus::Done);
+    }
+}
@@ -4,7 +4,8 @@
 //! must be enabled with the `mouse` feature flag.
 
 use crate::types::MmaskT;
-use std::time::Instant;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
 
 // ============================================================================
 // Mouse button masks
@@ -43,7 +44,7 @@ pub const BUTTON3_DOUBLE_CLICKED: MmaskT = 0x00002000;
 /// Mouse button 3 triple-clicked.
 pub const BUTTON3_TRIPLE_CLICKED: MmaskT = 0x00004000;
 
-/// Mouse button 4 pressed (scroll up).
+/// Mouse button 4 pressed (wheel up).
 pub const BUTTON4_PRESSED: MmaskT = 0x00010000;
 /// Mouse button 4 released.
 pub const BUTTON4_RELEASED: MmaskT = 0x00008000;
@@ -54,7 +55,7 @@ pub const BUTTON4_DOUBLE_CLICKED: MmaskT = 0x00040000;
 /// Mouse button 4 triple-clicked.
 pub const BUTTON4_TRIPLE_CLICKED: MmaskT = 0x00080000;
 
-/// Mouse button 5 pressed (scroll down).
+/// Mouse button 5 pressed (wheel down).
 pub const BUTTON5_PRESSED: MmaskT = 0x00200000;
 /// Mouse button 5 released.
 pub const BUTTON5_RELEASED: MmaskT = 0x00100000;
@@ -65,6 +66,11 @@ pub const BUTTON5_DOUBLE_CLICKED: MmaskT = 0x00800000;
 /// Mouse button 5 triple-clicked.
 pub const BUTTON5_TRIPLE_CLICKED: MmaskT = 0x01000000;
 
+/// Mouse button 6 pressed (wheel left).
+pub const BUTTON6_PRESSED: MmaskT = 0x02000000;
+/// Mouse button 7 pressed (wheel right).
+pub const BUTTON7_PRESSED: MmaskT = 0x40000000;
+
 /// Shift was held during the mouse event.
 pub const BUTTON_SHIFT: MmaskT = 0x04000000;
 /// Ctrl was held during the mouse event.
@@ -72,12 +78,19 @@ pub const BUTTON_CTRL: MmaskT = 0x08000000;
 /// Alt was held during the mouse event.
 pub const BUTTON_ALT: MmaskT = 0x10000000;
 
+/// Modifier keys that may accompany any button event.
+const BUTTON_MODIFIERS: MmaskT = BUTTON_SHIFT | BUTTON_CTRL | BUTTON_ALT;
+
 /// Report mouse position changes.
 pub const REPORT_MOUSE_POSITION: MmaskT = 0x20000000;
 
 /// All mouse events.
 pub const ALL_MOUSE_EVENTS: MmaskT = 0x1fffffff;
 
+/// All mouse events including wheel right, which lies outside
+/// [`ALL_MOUSE_EVENTS`]. Position reports are still not included.
+pub const ALL_MOUSE_EVENTS_HWHEEL: MmaskT = ALL_MOUSE_EVENTS | BUTTON7_PRESSED;
+
 // ============================================================================
 // Helper constants for button event lookup
 // ============================================================================
@@ -118,6 +131,15 @@ const BUTTON_DOUBLE_CLICKED: [MmaskT; 5] = [
     BUTTON5_DOUBLE_CLICKED,
 ];
 
+/// Wheel masks indexed by the low button bits of a wheel report: up, down,
+/// left and right.
+const WHEEL_PRESSED: [MmaskT; 4] = [
+    BUTTON4_PRESSED,
+    BUTTON5_PRESSED,
+    BUTTON6_PRESSED,
+    BUTTON7_PRESSED,
+];
+
 /// Button triple-clicked masks indexed by button number.
 const BUTTON_TRIPLE_CLICKED: [MmaskT; 5] = [
     BUTTON1_TRIPLE_CLICKED,
@@ -209,11 +231,18 @@ impl ClickTracker {
     ///
     /// Returns the mask that should be added to the event's bstate.
     pub fn record_release(&mut self, button: usize, x: i32, y: i32) -> MmaskT {
+        self.record_release_at(button, x, y, Instant::now())
+    }
+
+    /// Record a button release that happened at `now`.
+    ///
+    /// This is [`record_release`](Self::record_release) with an explicit
+    /// timestamp.
+    pub fn record_release_at(&mut self, button: usize, x: i32, y: i32, now: Instant) -> MmaskT {
         if button >= 5 {
             return 0;
         }
 
-        let now = Instant::now();
         let state = &mut self.buttons[button];
 
         // Check if this is within the click interval and at the same position
@@ -274,14 +303,14 @@ pub struct MouseState {
     enabled: bool,
     /// Last mouse event.
     last_event: MouseEvent,
-    /// Mouse event queue.
-    event_queue: Vec<MouseEvent>,
+    /// Mouse event queue, oldest first.
+    event_queue: VecDeque<MouseEvent>,
     /// Click interval in milliseconds.
     click_interval: i32,
     /// Click tracker for double/triple click detection.
     click_tracker: ClickTracker,
-    /// Track which buttons are currently pressed.
-    buttons_pressed: [bool; 5],
+    /// Time and cell of the press for each button currently held down.
+    pressed_at: [Option<(Instant, (i32, i32))>; 5],
 }
 
 impl MouseState {
@@ -293,10 +322,10 @@ impl MouseState {
             old_mask: 0,
             enabled: false,
             last_event: MouseEvent::new(),
-            event_queue: Vec::new(),
+            event_queue: VecDeque::new(),
             click_interval,
             click_tracker: ClickTracker::new(click_interval),
-            buttons_pressed: [false; 5],
+            pressed_at: [None; 5],
         }
     }
 
@@ -319,47 +348,107 @@ impl MouseState {
     }
 
     /// Get the next mouse event from the queue.
+    ///
+    /// The queue is first in, first out: events come out in the order they
+    /// were pushed, oldest first, as in ncurses. Earlier versions of this
+    /// crate returned the newest first.
     pub fn getmouse(&mut self) -> Option<MouseEvent> {
-        self.event_queue.pop()
+        self.event_queue.pop_front()
     }
 
-    /// Push a mouse event to the queue.
+    /// Look at the event `getmouse()` would return next, without removing it.
+    pub fn peek_event(&self) -> Option<&MouseEvent> {
+        self.event_queue.front()
+    }
+
+    /// Push a mouse event to the back of the queue.
+    ///
+    /// Events the mask does not ask for are dropped.
     pub fn push_event(&mut self, event: MouseEvent) {
+        self.enqueue(event);
+    }
+
+    /// Push a mouse event unless the mask filters it out, reporting whether
+    /// it was queued.
+    fn enqueue(&mut self, event: MouseEvent) -> bool {
         // Filter by mask
-        if (event.bstate & self.mask) != 0 {
-            self.last_event = event;
-            self.event_queue.push(event);
+        if (event.bstate & self.mask) == 0 {
+            return false;
         }
+        self.last_event = event;
+        self.event_queue.push_back(event);
+        true
     }
 
     /// Process a raw mouse event and push it with click detection.
     ///
     /// This handles converting press/release events into click events
     /// with proper double/triple click detection.
-    pub fn process_event(&mut self, mut event: MouseEvent) {
-        // Check for button press/release and track clicks
+    pub fn process_event(&mut self, event: MouseEvent) {
+        self.process_event_at(event, Instant::now());
+    }
+
+    /// Process a raw mouse event that happened at `now`.
+    ///
+    /// A release at the same cell as its press, within the click interval,
+    /// becomes a click; consecutive clicks within the interval become double
+    /// and triple clicks. The release is reported as the most specific click
+    /// class the mask asks for, and stays a release otherwise. Bits outside
+    /// the mask (other than modifiers) are dropped from the reported event.
+    /// Returns `false` if the mask filtered the event out.
+    pub fn process_event_at(&mut self, mut event: MouseEvent, now: Instant) -> bool {
+        let interval = Duration::from_millis(self.click_interval.max(0) as u64);
+        let pos = (event.x, event.y);
+
         for button in 0..5 {
-            let pressed_mask = BUTTON_PRESSED[button];
-            let released_mask = BUTTON_RELEASED[button];
+            if event.has_button(BUTTON_PRESSED[button]) {
+                self.pressed_at[button] = Some((now, pos));
+            }
 
-            if event.has_button(pressed_mask) {
-                self.buttons_pressed[button] = true;
+            if !event.has_button(BUTTON_RELEASED[button]) {
+                continue;
+            }
+            let is_click = match self.pressed_at[button].take() {
+                Some((pressed, press_pos)) => {
+                    press_pos == pos && now.duration_since(pressed) <= interval
+                }
+                None => false,
+            };
+            if !is_click {
+                self.click_tracker.reset(button);
+                continue;
             }
 
-            if event.has_button(released_mask) && self.buttons_pressed[button] {
-                self.buttons_pressed[button] = false;
-                // Record the release and get click type
-                let click_mask = self.click_tracker.record_release(button, event.x, event.y);
-                event.bstate |= click_mask;
+            let click = self
+                .click_tracker
+                .record_release_at(button, pos.0, pos.1, now);
+            if let Some(class) = self.click_class(button, click) {
+                event.bstate = (event.bstate & !BUTTON_RELEASED[button]) | class;
             }
         }
 
-        self.push_event(event);
+        event.bstate &= self.mask | BUTTON_MODIFIERS;
+        self.enqueue(event)
+    }
+
+    /// Pick the reported class for a click, falling back from triple to
+    /// double to single clicks when the mask does not ask for the former.
+    fn click_class(&self, button: usize, click: MmaskT) -> Option<MmaskT> {
+        let classes = [
+            BUTTON_TRIPLE_CLICKED[button],
+            BUTTON_DOUBLE_CLICKED[button],
+            BUTTON_CLICKED[button],
+        ];
+        let start = classes.iter().position(|&c| c == click)?;
+        classes[start..]
+            .iter()
+            .copied()
+            .find(|&c| self.mask & c != 0)
     }
 
     /// Push an event back to the front of the queue.
     pub fn ungetmouse(&mut self, event: MouseEvent) -> bool {
-        self.event_queue.insert(0, event);
+        self.event_queue.push_front(event);
         true
     }
 
@@ -499,12 +588,8 @@ pub fn parse_sgr_mouse(data: &[u8]) -> Option<MouseEvent> {
     let mut bstate: MmaskT = 0;
 
     if scroll {
-        // Scroll events
-        if button == 0 {
-            bstate |= BUTTON4_PRESSED; // Scroll up
-        } else if button == 1 {
-            bstate |= BUTTON5_PRESSED; // Scroll down
-        }
+        // Wheel events: 64/65 vertical, 66/67 horizontal
+        bstate |= WHEEL_PRESSED[button as usize];
     } else if motion {
         bstate |= REPORT_MOUSE_POSITION;
     } else {
@@ -631,12 +716,8 @@ pub fn parse_normal_mouse(data: &[u8]) -> Option<MouseEvent> {
     let mut bstate: MmaskT = 0;
 
     if scroll {
-        // Scroll events (button 4/5)
-        if button == 0 {
-            bstate |= BUTTON4_PRESSED; // Scroll up
-        } else if button == 1 {
-            bstate |= BUTTON5_PRESSED; // Scroll down
-        }
+        // Wheel events: 64/65 vertical, 66/67 horizontal
+        bstate |= WHEEL_PRESSED[button as usize];
     } else if motion {
         // Motion event
         bstate |= REPORT_MOUSE_POSITION;
@@ -812,6 +893,12 @@ mod tests {
         let got = state.getmouse().unwrap();
         assert_eq!(got.x, 10);
         assert_eq!(got.y, 20);
+
+        // Oldest first
+        state.push_event(event);
+        state.push_event(MouseEvent { x: 11, ..event });
+        assert_eq!(state.getmouse().map(|e| e.x), Some(10));
+        assert_eq!(state.getmouse().map(|e| e.x), Some(11));
     }
 
     #[test]
@@ -837,6 +924,31 @@ mod tests {
         assert!(event.has_button(BUTTON_SHIFT));
     }
 
+    #[test]
+    fn test_parse_sgr_wheel() {
+        let wheel = [
+            (&b"\x1b[<64;3;4M"[..], BUTTON4_PRESSED),
+            (&b"\x1b[<65;3;4M"[..], BUTTON5_PRESSED),
+            (&b"\x1b[<66;3;4M"[..], BUTTON6_PRESSED),
+            (&b"\x1b[<67;3;4M"[..], BUTTON7_PRESSED),
+        ];
+        for (packet, expected) in wheel {
+            let event = parse_sgr_mouse(packet).unwrap();
+            assert_eq!((event.y, event.x, event.bstate), (3, 2, expected));
+
+            let mut state = MouseState::new();
+            state.mousemask(ALL_MOUSE_EVENTS_HWHEEL);
+            state.process_event(event);
+            assert_eq!(state.getmouse().unwrap().bstate, expected);
+        }
+
+        // Wheel up with Ctrl held
+        let event = parse_sgr_mouse(b"\x1b[<80;1;1M").unwrap();
+        assert_eq!(event.bstate, BUTTON4_PRESSED | BUTTON_CTRL);
+        assert_eq!(ALL_MOUSE_EVENTS_HWHEEL & REPORT_MOUSE_POSITION, 0);
+        assert_eq!(ALL_MOUSE_EVENTS, 0x1fffffff);
+    }
+
     #[test]
     fn test_parse_x10_mouse() {
         // Button 1 press at (0,0) - bytes are 32+0, 33+0, 33+0
@@ -919,6 +1031,71 @@ mod tests {
         assert_eq!(mask, BUTTON1_CLICKED);
     }
 
+    #[test]
+    fn test_click_synthesis() {
+        let mut state = MouseState::new();
+        state.mousemask(BUTTON1_PRESSED | BUTTON1_CLICKED | BUTTON1_DOUBLE_CLICKED);
+        state.mouseinterval(200);
+
+        let t0 = Instant::now();
+        let at = |ms| t0 + Duration::from_millis(ms);
+        let button = |bstate| MouseEvent {
+            x: 3,
+            y: 4,
+            bstate,
+            ..MouseEvent::new()
+        };
+        let mut feed = |bstate, ms| {
+            state.process_event_at(button(bstate), at(ms));
+            state.getmouse().map(|e| e.bstate)
+        };
+
+        assert_eq!(feed(BUTTON1_PRESSED, 0), Some(BUTTON1_PRESSED));
+        assert_eq!(feed(BUTTON1_RELEASED, 50), Some(BUTTON1_CLICKED));
+        feed(BUTTON1_PRESSED, 100);
+        assert_eq!(feed(BUTTON1_RELEASED, 150), Some(BUTTON1_DOUBLE_CLICKED));
+
+        // Triple clicks are not in the mask, so a third is reported as a
+        // double click
+        feed(BUTTON1_PRESSED, 200);
+        assert_eq!(feed(BUTTON1_RELEASED, 250), Some(BUTTON1_DOUBLE_CLICKED));
+
+        // A press held longer than the interval is not a click, and the
+        // release is not in the mask
+        feed(BUTTON1_PRESSED, 1000);
+        assert_eq!(feed(BUTTON1_RELEASED, 1500), None);
+
+        // Releasing on a different cell is not a click either
+        feed(BUTTON1_PRESSED, 2000);
+        let mut moved = button(BUTTON1_RELEASED);
+        moved.x = 9;
+        state.process_event_at(moved, at(2050));
+        assert!(state.getmouse().is_none());
+    }
+
+    #[test]
+    fn test_event_queue_order() {
+        let mut state = MouseState::new();
+        state.mousemask(ALL_MOUSE_EVENTS);
+        state.process_event(MouseEvent {
+            bstate: BUTTON1_PRESSED,
+            ..MouseEvent::new()
+        });
+        state.process_event(MouseEvent {
+            bstate: BUTTON2_PRESSED,
+            ..MouseEvent::new()
+        });
+        state.ungetmouse(MouseEvent {
+            bstate: BUTTON3_PRESSED,
+            ..MouseEvent::new()
+        });
+
+        let order: Vec<MmaskT> = std::iter::from_fn(|| state.getmouse())
+            .map(|e| e.bstate)
+            .collect();
+        assert_eq!(order, [BUTTON3_PRESSED, BUTTON1_PRESSED, BUTTON2_PRESSED]);
+    }
+
     #[test]
     fn test_wenclose() {
         assert!(wenclose(0, 0, 10, 20, 5, 10));
@@ -0,0 +1,336 @@
+//! Scrollable pad views for ncurses-pure.
+//!
+//! A pad is a window larger than the area it is shown in. [`PadView`] keeps
+//! a pad together with the screen rectangle it is displayed in and the
+//! current scroll offset, so that scrolling and refreshing do not require
+//! passing the six `prefresh` coordinates around by hand. [`ScrollView`]
+//! is the same for the common case of a tall list or log shown in part of
+//! stdscr.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use ncurses::pad::PadView;
+//! use ncurses::types::Rect;
+//! use ncurses::{Result, Screen};
+//!
+//! # fn main() -> Result<()> {
+//! let mut screen = Screen::init()?;
+//! let pad = screen.newpad(100, 80)?;
+//! let mut view = PadView::new(pad, Rect::new(0, 0, 10, 80))?;
+//!
+//! view.scroll_by(5, 0);
+//! view.refresh(&mut screen)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{Error, Result};
+use crate::screen::Screen;
+use crate::types::{Coord, Rect};
+use crate::window::Window;
+
+/// A pad shown through a fixed screen rectangle at a scroll offset.
+pub struct PadView {
+    /// The pad holding the content.
+    pad: Window,
+    /// Screen area the pad is displayed in.
+    rect: Rect,
+    /// First pad row shown.
+    top: Coord,
+    /// First pad column shown.
+    left: Coord,
+}
+
+impl PadView {
+    /// Create a view of `pad` displayed in `rect`, scrolled to the top left.
+    ///
+    /// Returns an error if `pad` is not a pad or `rect` is empty.
+    pub fn new(pad: Window, rect: Rect) -> Result<Self> {
+        if !pad.is_pad() {
+            return Err(Error::InvalidArgument("window is not a pad".into()));
+        }
+        if rect.size.height <= 0 || rect.size.width <= 0 {
+            return Err(Error::InvalidArgument("pad view must not be empty".into()));
+        }
+        Ok(Self {
+            pad,
+            rect,
+            top: 0,
+            left: 0,
+        })
+    }
+
+    /// Create a pad just large enough for `lines` and a view of it.
+    ///
+    /// The pad is at least as large as `rect`, so short content still fills
+    /// the view with the pad's background.
+    pub fn from_lines(lines: &[&str], rect: Rect) -> Result<Self> {
+        #[cfg(feature = "wide")]
+        let width_of = crate::wide::string_width;
+        #[cfg(not(feature = "wide"))]
+        let width_of = |s: &str| s.chars().count();
+
+        let content_width = lines.iter().map(|l| width_of(l)).max().unwrap_or(0) as Coord;
+        let height = (lines.len() as Coord).max(rect.size.height);
+        let width = content_width.max(rect.size.width);
+
+        let mut pad = Window::new_pad(height, width)?;
+        for (y, line) in lines.iter().enumerate() {
+            pad.mvaddstr(y as Coord, 0, line)?;
+        }
+        Self::new(pad, rect)
+    }
+
+    /// Get the pad.
+    pub fn pad(&self) -> &Window {
+        &self.pad
+    }
+
+    /// Get the pad for drawing.
+    ///
+    /// Call [`clamp`](Self::clamp) afterwards if the pad was resized.
+    pub fn pad_mut(&mut self) -> &mut Window {
+        &mut self.pad
+    }
+
+    /// Take the pad out of the view.
+    pub fn into_pad(self) -> Window {
+        self.pad
+    }
+
+    /// Get the screen area the pad is displayed in.
+    pub fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    /// Move or resize the display area, keeping the offset within bounds.
+    pub fn set_rect(&mut self, rect: Rect) -> Result<()> {
+        if rect.size.height <= 0 || rect.size.width <= 0 {
+            return Err(Error::InvalidArgument("pad view must not be empty".into()));
+        }
+        self.rect = rect;
+        self.clamp();
+        Ok(())
+    }
+
+    /// Get the current scroll offset as `(top, left)`.
+    pub fn offset(&self) -> (Coord, Coord) {
+        (self.top, self.left)
+    }
+
+    /// Get the largest possible scroll offset as `(top, left)`.
+    pub fn max_offset(&self) -> (Coord, Coord) {
+        (
+            (self.pad.getmaxy() - self.rect.size.height).max(0),
+            (self.pad.getmaxx() - self.rect.size.width).max(0),
+        )
+    }
+
+    /// Scroll to an absolute offset, clamped to the pad content.
+    pub fn scroll_to(&mut self, top: Coord, left: Coord) {
+        self.top = top;
+        self.left = left;
+        self.clamp();
+    }
+
+    /// Scroll by a relative amount, clamped to the pad content.
+    ///
+    /// Positive `dy` scrolls down and positive `dx` scrolls right.
+    pub fn scroll_by(&mut self, dy: Coord, dx: Coord) {
+        self.scroll_to(self.top.saturating_add(dy), self.left.saturating_add(dx));
+    }
+
+    /// Scroll just far enough that pad cell (y, x) is inside the view.
+    ///
+    /// Does nothing if the cell is already shown. Use this to follow the
+    /// selected row of a list or the last line of a log.
+    pub fn ensure_visible(&mut self, y: Coord, x: Coord) {
+        let (height, width) = (self.rect.size.height, self.rect.size.width);
+        let top = if y < self.top {
+            y
+        } else if y >= self.top + height {
+            y - height + 1
+        } else {
+            self.top
+        };
+        let left = if x < self.left {
+            x
+        } else if x >= self.left + width {
+            x - width + 1
+        } else {
+            self.left
+        };
+        self.scroll_to(top, left);
+    }
+
+    /// Bring the offset back within bounds after the pad or area changed.
+    pub fn clamp(&mut self) {
+        let (max_top, max_left) = self.max_offset();
+        self.top = self.top.clamp(0, max_top);
+        self.left = self.left.clamp(0, max_left);
+    }
+
+    /// Copy the visible part of the pad to the virtual screen.
+    pub fn noutrefresh(&mut self, screen: &mut Screen) -> Result<()> {
+        screen.pnoutrefresh(
+            &mut self.pad,
+            self.top,
+            self.left,
+            self.rect.top(),
+            self.rect.left(),
+            self.rect.bottom() - 1,
+            self.rect.right() - 1,
+        )
+    }
+
+    /// Show the visible part of the pad on the terminal.
+    pub fn refresh(&mut self, screen: &mut Screen) -> Result<()> {
+        self.noutrefresh(screen)?;
+        screen.doupdate()
+    }
+}
+
+/// A tall pad shown in a viewport of stdscr, for lists and logs.
+///
+/// This is a thin wrapper over [`PadView`]: draw into [`pad_mut()`](Self::pad_mut),
+/// call [`ensure_visible()`](Self::ensure_visible) to follow the line of
+/// interest and [`render()`](Self::render) before `doupdate()`.
+pub struct ScrollView {
+    view: PadView,
+}
+
+impl ScrollView {
+    /// Create a pad of `lines` by `cols` shown in `viewport`, scrolled to
+    /// the top left.
+    ///
+    /// Returns an error if the size is invalid or `viewport` is empty.
+    pub fn new(lines: Coord, cols: Coord, viewport: Rect) -> Result<Self> {
+        let pad = Window::new_pad(lines, cols)?;
+        Ok(Self {
+            view: PadView::new(pad, viewport)?,
+        })
+    }
+
+    /// Get the pad.
+    pub fn pad(&self) -> &Window {
+        self.view.pad()
+    }
+
+    /// Get the pad for drawing.
+    pub fn pad_mut(&mut self) -> &mut Window {
+        self.view.pad_mut()
+    }
+
+    /// Get the area of stdscr the pad is shown in.
+    pub fn viewport(&self) -> Rect {
+        self.view.rect()
+    }
+
+    /// Get the current scroll offset as `(top, left)`.
+    pub fn offset(&self) -> (Coord, Coord) {
+        self.view.offset()
+    }
+
+    /// Scroll by a relative amount, clamped to the pad content.
+    pub fn scroll_by(&mut self, dy: Coord, dx: Coord) {
+        self.view.scroll_by(dy, dx);
+    }
+
+    /// Scroll just far enough that pad cell (y, x) is shown.
+    ///
+    /// See [`PadView::ensure_visible()`].
+    pub fn ensure_visible(&mut self, y: Coord, x: Coord) {
+        self.view.ensure_visible(y, x);
+    }
+
+    /// Copy the visible part of the pad to the virtual screen with
+    /// `pnoutrefresh()`.
+    pub fn render(&mut self, screen: &mut Screen) -> Result<()> {
+        self.view.noutrefresh(screen)
+    }
+
+    /// Get the underlying view.
+    pub fn view(&self) -> &PadView {
+        &self.view
+    }
+
+    /// Get the underlying view for changes not covered here.
+    pub fn view_mut(&mut self) -> &mut PadView {
+        &mut self.view
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scroll_clamps() {
+        let pad = Window::new_pad(100, 40).unwrap();
+        let mut view = PadView::new(pad, Rect::new(2, 0, 10, 40)).unwrap();
+        assert_eq!(view.max_offset(), (90, 0));
+
+        view.scroll_by(-5, 0);
+        assert_eq!(view.offset(), (0, 0));
+        view.scroll_by(25, 3);
+        assert_eq!(view.offset(), (25, 0));
+        view.scroll_by(1000, 0);
+        assert_eq!(view.offset(), (90, 0));
+
+        // Growing the area pulls the offset back in
+        view.set_rect(Rect::new(0, 0, 20, 40)).unwrap();
+        assert_eq!(view.offset(), (80, 0));
+    }
+
+    #[test]
+    fn test_from_lines() {
+        let view = PadView::from_lines(&["short", "a longer line"], Rect::new(0, 0, 5, 8)).unwrap();
+        assert_eq!(view.pad().getmaxy(), 5);
+        assert_eq!(view.pad().getmaxx(), 13);
+        assert_eq!(view.max_offset(), (0, 5));
+
+        assert!(PadView::new(Window::new(5, 5, 0, 0).unwrap(), Rect::new(0, 0, 5, 5)).is_err());
+    }
+
+    #[test]
+    fn test_ensure_visible() {
+        let pad = Window::new_pad(50, 20).unwrap();
+        let mut view = PadView::new(pad, Rect::new(0, 0, 5, 10)).unwrap();
+        for y in 0..12 {
+            view.pad_mut().mvaddstr(y, 0, &format!("line {y}")).unwrap();
+        }
+
+        view.ensure_visible(11, 0);
+        assert_eq!(view.offset(), (7, 0));
+        // Already shown, so nothing moves
+        view.ensure_visible(9, 3);
+        assert_eq!(view.offset(), (7, 0));
+
+        view.ensure_visible(2, 15);
+        assert_eq!(view.offset(), (2, 6));
+        view.ensure_visible(100, 0);
+        assert_eq!(view.offset(), (45, 0));
+    }
+
+    #[test]
+    fn test_scroll_view_follows_last_line() {
+        let mut log = ScrollView::new(200, 30, Rect::new(3, 0, 4, 30)).unwrap();
+        for y in 0..4 {
+            log.pad_mut().mvaddstr(y, 0, &format!("entry {y}")).unwrap();
+            log.ensure_visible(y, 0);
+        }
+        assert_eq!(log.offset(), (0, 0));
+
+        // Lines past the viewport scroll it
+        for y in 4..10 {
+            log.pad_mut().mvaddstr(y, 0, &format!("entry {y}")).unwrap();
+            log.ensure_visible(y, 0);
+        }
+        assert_eq!(log.offset(), (6, 0));
+        assert_eq!(log.pad_mut().mvinnstr(9, 0, 7).unwrap(), "entry 9");
+        assert_eq!(log.viewport(), Rect::new(3, 0, 4, 30));
+
+        assert!(ScrollView::new(10, 10, Rect::new(0, 0, 0, 10)).is_err());
+    }
+}
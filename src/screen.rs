@@ -3,25 +3,145 @@
 //! This module implements the `Screen` structure, which is the main entry point
 //! for using ncurses. It manages the terminal, windows, colors, and input/output.
 
-use crate::attr::{self, A_NORMAL};
-use crate::color::ColorManager;
+use crate::acs::{self, AcsMode};
+#[cfg(not(feature = "wide"))]
+use crate::attr::A_CHARTEXT;
+use crate::attr::{self, A_COLOR, A_NORMAL};
+use crate::color::{ColorManager, MonoFallback};
 use crate::error::{Error, Result};
-use crate::input::{EscapeMatch, EscapeParser, InputBuffer, InputMode};
-use crate::key::KEY_MOUSE;
+use crate::input::{EscapeMatch, EscapeParser, InputBuffer, InputMode, InputOverflow};
+use crate::key::{Key, KEY_UNKNOWN_CSI};
+use crate::key::{KEY_ALT, KEY_FOCUS_IN, KEY_FOCUS_OUT, KEY_MOUSE, KEY_PASTE, KEY_RESIZE};
+use crate::line::LineData;
+use crate::line_editor::{EditStatus, LineEditor};
 #[cfg(feature = "mouse")]
 use crate::mouse::{is_mouse_prefix, parse_sgr_mouse, MouseEvent, MouseProtocol, MouseState};
 #[cfg(feature = "slk")]
 use crate::slk::{SlkFormat, SlkState};
-use crate::terminal::{TermState, Terminal};
+use crate::terminal::{sgr_attributes, sgr_color, sgr_rgb, TermCapabilities, TermState, Terminal};
 use crate::types::ColorT;
-use crate::types::CursorVisibility;
 #[cfg(feature = "mouse")]
 use crate::types::MmaskT;
-use crate::types::{AttrT, ChType, Delay};
+use crate::types::{AttrT, ChType, Delay, Rect};
+use crate::types::{CursorStyle, CursorVisibility};
 use crate::window::Window;
 
+use std::collections::{BTreeSet, VecDeque};
+use std::os::unix::io::RawFd;
 use std::time::{Duration, Instant};
 
+/// Length of the `el` (clear to end of line) sequence emitted by `doupdate`.
+const CLEAR_EOL_LEN: usize = "\x1b[K".len();
+
+/// Length of the `ich1`/`dch1` sequences emitted by `doupdate`.
+const SHIFT_CHAR_LEN: usize = "\x1b[@".len();
+
+/// Sequence a terminal sends before pasted text in bracketed paste mode.
+const PASTE_START: &[u8] = b"\x1b[200~";
+
+/// Sequence a terminal sends after pasted text in bracketed paste mode.
+const PASTE_END: &[u8] = b"\x1b[201~";
+
+/// How long to wait for more pasted bytes before giving up on `PASTE_END`.
+const PASTE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Longest unrecognized CSI sequence coalesced into `KEY_UNKNOWN_CSI`.
+const MAX_CSI_LEN: usize = 32;
+
+/// How long to wait for the terminal to answer a query.
+const QUERY_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// How long `init()` waits for the terminal to report its capabilities.
+const INIT_QUERY_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// How long after a cursor position or device attributes query timed out a
+/// late reply is still expected and dropped instead of being read as a key.
+const LATE_REPLY_WINDOW: Duration = Duration::from_secs(5);
+
+/// How long the software visual bell keeps the screen inverted, in ms.
+const FLASH_MS: i32 = 100;
+
+/// Most characters a string input function accepts when given no limit.
+const MAX_GETSTR_LEN: usize = 1024;
+
+/// Translate an ncurses `n` argument into a character limit.
+///
+/// A negative `n` means no limit beyond [`MAX_GETSTR_LEN`]; any other value
+/// is used as is.
+fn getstr_limit(n: i32) -> usize {
+    if n < 0 {
+        MAX_GETSTR_LEN
+    } else {
+        n as usize
+    }
+}
+
+/// Describe attribute bits as flag names plus color pair, for `dump_state`.
+fn describe_attrs(attrs: AttrT) -> String {
+    let mut names: Vec<String> = attr::Attribute::from(attrs)
+        .iter_names()
+        .map(|(name, _)| name.to_string())
+        .collect();
+    let pair = attr::pair_number(attrs);
+    if pair != 0 {
+        names.push(format!("pair {}", pair));
+    }
+    names.join("|")
+}
+
+/// Contents of a single screen cell as stored in the virtual screens.
+#[cfg(not(feature = "wide"))]
+type CellData = ChType;
+#[cfg(feature = "wide")]
+type CellData = crate::wide::CCharT;
+
+/// A changed screen cell reported by [`Screen::take_render_diff`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CellChange {
+    /// Screen row.
+    pub y: i32,
+    /// Screen column.
+    pub x: i32,
+    /// Character displayed in the cell (`'\0'` for the right half of a wide character).
+    pub ch: char,
+    /// Everything displayed in the cell: `ch` followed by any combining
+    /// characters, or empty for the right half of a wide character.
+    #[cfg(feature = "wide")]
+    pub text: String,
+    /// Video attributes, including the color pair bits.
+    pub attr: AttrT,
+}
+
+/// Input delivered to the callback of [`Screen::run`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event {
+    /// A key or character from `getch()`.
+    Key(Key),
+    /// The terminal was resized; stdscr already has the new size.
+    Resize {
+        /// New number of lines.
+        lines: i32,
+        /// New number of columns.
+        cols: i32,
+    },
+    /// A mouse event that passed the mouse mask.
+    #[cfg(feature = "mouse")]
+    Mouse(MouseEvent),
+    /// Text pasted while bracketed paste was enabled.
+    Paste(String),
+    /// No input arrived within the input timeout set with `timeout()` or
+    /// `halfdelay()`.
+    Timeout,
+}
+
+/// A single pending terminal update collected by `doupdate`.
+enum Update<C> {
+    /// Write a cell at (y, x).
+    Cell(usize, usize, C),
+    /// Erase from (y, x) to the end of the line using the given attributes.
+    ClearEol(usize, usize, AttrT),
+}
+
 /// The main ncurses screen structure.
 ///
 /// This structure owns the terminal, windows, and all state necessary for
@@ -61,6 +181,9 @@ pub struct Screen {
     /// Color manager.
     colors: ColorManager,
 
+    /// Attributes standing in for color pairs on a monochrome terminal.
+    mono_fallback: MonoFallback,
+
     /// Input mode settings.
     input_mode: InputMode,
 
@@ -73,6 +196,12 @@ pub struct Screen {
     /// Current cursor visibility.
     cursor_visibility: CursorVisibility,
 
+    /// Cursor shape set by the application, if any.
+    cursor_style: Option<CursorStyle>,
+
+    /// Number of window titles pushed and not yet popped.
+    titles_pushed: usize,
+
     /// Whether the screen has been initialized.
     initialized: bool,
 
@@ -86,9 +215,60 @@ pub struct Screen {
     #[cfg(feature = "mouse")]
     mouse: MouseState,
 
-    /// Current mouse protocol.
+    /// Mouse DEC modes written to the terminal, in the order enabled.
+    #[cfg(feature = "mouse")]
+    mouse_modes: Vec<MouseProtocol>,
+
+    /// Whether `getmouse_in` leaves events outside the window queued.
     #[cfg(feature = "mouse")]
-    mouse_protocol: MouseProtocol,
+    mouse_clip_to_window: bool,
+
+    /// Whether bracketed paste mode is enabled.
+    bracketed_paste: bool,
+
+    /// Pasted text waiting to be retrieved with `get_paste()`.
+    pastes: VecDeque<String>,
+
+    /// Whether focus in/out reporting is enabled.
+    focus_events: bool,
+
+    /// Whether `doupdate` brackets its output as a synchronized update.
+    synchronized_output: bool,
+
+    /// What the terminal reported at `init()` or `detect_capabilities()`.
+    capabilities: Option<TermCapabilities>,
+
+    /// Until when a reply to a timed-out cursor position query may arrive.
+    late_cursor_report: Option<Instant>,
+
+    /// Until when a reply to a timed-out device attributes query may arrive.
+    late_capabilities_report: Option<Instant>,
+
+    /// Whether `doupdate` may shift lines with insert/delete character.
+    idc_update: bool,
+    /// Rows of newscr changed since the last update by a window with
+    /// `idcok` off; these are always redrawn rather than shifted.
+    no_idc_rows: BTreeSet<usize>,
+
+    /// How line-drawing characters are written to the terminal.
+    acs_mode: AcsMode,
+
+    /// Whether unrecognized CSI sequences are returned as `KEY_UNKNOWN_CSI`.
+    unknown_csi_coalesce: bool,
+
+    /// Whether a `KEY_RESIZE` is waiting to be read.
+    resize_pending: bool,
+
+    /// Raw unrecognized CSI sequences waiting for `get_unknown_csi()`.
+    unknown_csis: VecDeque<Vec<u8>>,
+
+    /// Whether ESC followed at once by a printable character means Alt.
+    meta_sends_escape: bool,
+
+    /// Nesting depth of `begin_batch()` calls.
+    batch_depth: u32,
+    /// Whether `flash()` was called inside the current batch.
+    flash_pending: bool,
 
     /// Whether filter mode is enabled (single-line mode).
     filtered: bool,
@@ -96,6 +276,16 @@ pub struct Screen {
     /// Soft label key state (when slk feature is enabled).
     #[cfg(feature = "slk")]
     slk: Option<SlkState>,
+
+    /// Lines ripped off with `ripoffline()` before this screen was created.
+    ripoffs: Vec<RipoffSpec>,
+
+    /// Windows for the ripped-off lines, in the order they were requested.
+    ripoff_windows: Vec<Window>,
+
+    /// Number of window lines visited while copying to the virtual screen.
+    #[cfg(test)]
+    lines_copied: usize,
 }
 
 impl Screen {
@@ -106,9 +296,38 @@ impl Screen {
     /// - Enters program mode (raw, no echo)
     /// - Creates the standard screen window
     /// - Initializes color support if available
+    ///
+    /// After [`query_capabilities_at_init(true)`](globals::query_capabilities_at_init)
+    /// it also asks the terminal for its capabilities, waiting up to 200 ms.
     pub fn init() -> Result<Self> {
-        let mut terminal = Terminal::from_stdio()?;
+        let mut screen = Self::with_terminal(Terminal::from_stdio()?)?;
+        if globals::get_query_capabilities_at_init() && !screen.terminal.is_no_tty() {
+            // A terminal that stays silent just keeps the $TERM defaults
+            let _ = screen.query_capabilities(b"\x1b[c", INIT_QUERY_TIMEOUT);
+        }
+        Ok(screen)
+    }
+
+    /// Initialize curses mode reading from `input_fd` and rendering to
+    /// `output`.
+    ///
+    /// Use this to drive a PTY master or capture the output in memory. The
+    /// screen size cannot be detected from an arbitrary writer, so it must be
+    /// given explicitly; see [`Terminal::with_output()`].
+    pub fn with_output(
+        input_fd: RawFd,
+        output: Box<dyn std::io::Write + Send>,
+        size: crate::types::Size,
+    ) -> Result<Self> {
+        Self::with_terminal(Terminal::with_output(input_fd, output, size)?)
+    }
 
+    /// Initialize a screen on top of an already opened terminal.
+    ///
+    /// This performs the same setup as [`init()`](Self::init) but lets the
+    /// caller choose the file descriptors the terminal is bound to, or use a
+    /// terminal replaying recorded input from [`Terminal::replay_from()`].
+    pub fn with_terminal(mut terminal: Terminal) -> Result<Self> {
         // Get terminal dimensions
         let lines = terminal.lines();
         let cols = terminal.columns();
@@ -116,8 +335,14 @@ impl Screen {
         // Update global dimensions
         globals::set_dimensions(lines, cols);
 
+        // Ripped-off lines belong to this screen; later screens start afresh
+        let ripoffs = RIPOFF_LINES.with(|rips| std::mem::take(&mut *rips.borrow_mut()));
+        let (top, bottom) = ripoff_counts(&ripoffs);
+        let stdscr_lines = (lines - top - bottom).max(1);
+        globals::set_dimensions(stdscr_lines, cols);
+
         // Create the three main windows
-        let stdscr = Window::new(lines, cols, 0, 0)?;
+        let stdscr = Window::new(stdscr_lines, cols, top, 0)?;
         let curscr = Window::new(lines, cols, 0, 0)?;
         let newscr = Window::new(lines, cols, 0, 0)?;
 
@@ -137,31 +362,69 @@ impl Screen {
             curscr,
             newscr,
             colors,
+            mono_fallback: MonoFallback::default(),
             input_mode: InputMode::new(),
             input_buffer: InputBuffer::new(),
             escape_parser: EscapeParser::new(),
             cursor_visibility: CursorVisibility::Normal,
+            cursor_style: None,
+            titles_pushed: 0,
             initialized: true,
             escdelay: 100,
             tabsize: 8,
             #[cfg(feature = "mouse")]
             mouse: MouseState::new(),
             #[cfg(feature = "mouse")]
-            mouse_protocol: MouseProtocol::None,
+            mouse_modes: Vec::new(),
+            #[cfg(feature = "mouse")]
+            mouse_clip_to_window: false,
+            bracketed_paste: false,
+            pastes: VecDeque::new(),
+            focus_events: false,
+            synchronized_output: false,
+            capabilities: None,
+            late_cursor_report: None,
+            late_capabilities_report: None,
+            idc_update: false,
+            no_idc_rows: BTreeSet::new(),
+            acs_mode: AcsMode::Unicode,
+            unknown_csi_coalesce: false,
+            resize_pending: false,
+            unknown_csis: VecDeque::new(),
+            meta_sends_escape: false,
+            batch_depth: 0,
+            flash_pending: false,
             filtered: false,
             #[cfg(feature = "slk")]
             slk: None,
+            ripoffs,
+            ripoff_windows: Vec::new(),
+            #[cfg(test)]
+            lines_copied: 0,
         };
 
+        // The terminal is in program mode from here on, so undo that before
+        // reporting a failure
+        if let Err(err) = screen
+            .layout_ripoffs(lines, cols)
+            .and_then(|()| screen.start())
+        {
+            let _ = screen.endwin();
+            return Err(err);
+        }
+
+        Ok(screen)
+    }
+
+    /// Apply the initial input mode and clear the screen.
+    fn start(&mut self) -> Result<()> {
         // Set default input mode (cbreak, noecho)
-        screen.cbreak()?;
-        screen.noecho()?;
+        self.cbreak()?;
+        self.noecho()?;
 
         // Clear the screen
-        screen.terminal.clear_screen()?;
-        screen.terminal.flush()?;
-
-        Ok(screen)
+        self.terminal.clear_screen()?;
+        self.terminal.flush()
     }
 
     /// End curses mode (equivalent to `endwin()`).
@@ -170,16 +433,35 @@ impl Screen {
     /// you can call `refresh()` to re-enter curses mode if needed.
     pub fn endwin(&mut self) -> Result<()> {
         if self.initialized {
-            // Disable mouse if enabled
+            // Disable every mouse mode we turned on
             #[cfg(feature = "mouse")]
-            if self.mouse_protocol != MouseProtocol::None {
-                let _ = self
-                    .terminal
-                    .write(self.mouse_protocol.disable_sequence().as_bytes());
-                let _ = self
-                    .terminal
-                    .write(MouseProtocol::ButtonEvent.disable_sequence().as_bytes());
-                self.mouse_protocol = MouseProtocol::None;
+            self.disable_mouse_modes();
+
+            // Turn off bracketed paste
+            if self.bracketed_paste {
+                self.terminal.write(b"\x1b[?2004l")?;
+                self.bracketed_paste = false;
+            }
+
+            // Turn off focus reporting
+            if self.focus_events {
+                self.terminal.write(b"\x1b[?1004l")?;
+                self.focus_events = false;
+            }
+
+            // Restore the default cursor shape
+            if self.cursor_style.take().is_some() {
+                self.terminal.cursor_style(0)?;
+            }
+
+            // Restore the title from before the first push_title()
+            while self.titles_pushed > 0 {
+                self.pop_title()?;
+            }
+
+            // Make sure no synchronized update is left holding the display
+            if self.synchronized_output {
+                self.terminal.end_sync()?;
             }
 
             // Show cursor
@@ -198,6 +480,24 @@ impl Screen {
         !self.initialized || self.terminal.state() == TermState::Suspend
     }
 
+    /// Restore the terminal if the program panics while this screen is alive.
+    ///
+    /// `Drop` already runs `endwin()` when a panic unwinds past the screen,
+    /// but not when the panic aborts or the screen is leaked. The hook leaves
+    /// the alternate screen, shows the cursor and restores the tty settings
+    /// before the existing panic hook prints its message. Calling this again
+    /// is harmless; the hook is removed when the screen is dropped. See
+    /// [`Terminal::install_panic_hook()`] for details.
+    pub fn install_panic_hook(&self) {
+        self.terminal.install_panic_hook();
+    }
+
+    /// Stop restoring the terminal on panic, putting back the hook that was
+    /// installed before [`install_panic_hook()`](Self::install_panic_hook).
+    pub fn remove_panic_hook(&self) {
+        self.terminal.remove_panic_hook();
+    }
+
     /// Get a reference to the standard screen window.
     pub fn stdscr(&self) -> &Window {
         &self.stdscr
@@ -243,6 +543,9 @@ impl Screen {
     }
 
     /// Update the terminal size (call after SIGWINCH).
+    ///
+    /// The next `getch()` returns `KEY_RESIZE`. Several updates before it is
+    /// read still deliver only one.
     pub fn update_term_size(&mut self) -> Result<()> {
         self.terminal.update_size()?;
 
@@ -251,32 +554,97 @@ impl Screen {
 
         // Resize windows
         self.resize_term(lines, cols)?;
+        self.resize_pending = true;
 
         Ok(())
     }
 
     /// Resize the terminal to the specified size.
+    ///
+    /// stdscr, curscr and newscr are resized in place, so their contents
+    /// survive wherever they still fit and a redraw after `SIGWINCH` does
+    /// not blank the screen. A window is only recreated from scratch if it
+    /// cannot be resized to the requested dimensions. curscr is marked with
+    /// `clearok()`, so the next update clears the terminal and repaints it in
+    /// full from the kept contents. Lines ripped off with
+    /// `ripoffline()` are laid out again for the new size and their
+    /// callbacks run with the resized windows.
     pub fn resize_term(&mut self, lines: i32, cols: i32) -> Result<()> {
+        let (top, bottom) = ripoff_counts(&self.ripoffs);
+        let stdscr_lines = (lines - top - bottom).max(1);
+
         // Update global dimensions
-        globals::set_dimensions(lines, cols);
+        globals::set_dimensions(stdscr_lines, cols);
 
-        // Recreate windows with new size
-        self.stdscr = Window::new(lines, cols, 0, 0)?;
-        self.curscr = Window::new(lines, cols, 0, 0)?;
-        self.newscr = Window::new(lines, cols, 0, 0)?;
+        for win in [&mut self.curscr, &mut self.newscr] {
+            if win.resize(lines, cols).is_err() {
+                *win = Window::new(lines, cols, 0, 0)?;
+            }
+        }
+        if self.stdscr.resize(stdscr_lines, cols).is_err() {
+            self.stdscr = Window::new(stdscr_lines, cols, top, 0)?;
+        }
+        self.stdscr.mvwin(top, 0)?;
+        self.layout_ripoffs(lines, cols)?;
 
-        // Mark everything as needing update
+        // What the terminal shows after a resize is unknown, so repaint it all
+        self.curscr.clearok(true);
         self.stdscr.touchwin();
 
         Ok(())
     }
 
+    /// Position the windows of ripped-off lines on a `lines` by `cols`
+    /// screen and run their callbacks.
+    ///
+    /// Top lines are taken from the top down and bottom lines from the
+    /// bottom up, in the order they were requested.
+    fn layout_ripoffs(&mut self, lines: i32, cols: i32) -> Result<()> {
+        let (mut top, mut bottom) = (0, lines - 1);
+        for (i, spec) in self.ripoffs.iter().enumerate() {
+            let y = if spec.line > 0 {
+                top += 1;
+                top - 1
+            } else {
+                bottom -= 1;
+                bottom + 1
+            };
+            let y = y.clamp(0, lines - 1);
+            match self.ripoff_windows.get_mut(i) {
+                Some(win) => {
+                    win.resize(1, cols)?;
+                    win.mvwin(y, 0)?;
+                }
+                None => self.ripoff_windows.push(Window::new(1, cols, y, 0)?),
+            }
+            if let Some(init) = spec.callback {
+                init(&mut self.ripoff_windows[i], cols);
+            }
+        }
+        Ok(())
+    }
+
+    /// Get the window of a line ripped off with `ripoffline()`.
+    ///
+    /// `index` counts the calls to `ripoffline()` made before this screen
+    /// was created, starting at 0.
+    pub fn ripoff_window(&self, index: usize) -> Option<&Window> {
+        self.ripoff_windows.get(index)
+    }
+
+    /// Get the window of a ripped-off line for drawing.
+    ///
+    /// Changes are shown by the next `refresh()` together with stdscr.
+    pub fn ripoff_window_mut(&mut self, index: usize) -> Option<&mut Window> {
+        self.ripoff_windows.get_mut(index)
+    }
+
     /// Check if the terminal has been resized.
     ///
-    /// Compares the current terminal size with the stored dimensions
-    /// and returns `true` if they differ (meaning a resize is needed).
+    /// Compares the given size with the current size of the screen and
+    /// returns `true` if they differ (meaning a resize is needed).
     pub fn is_term_resized(&self, lines: i32, cols: i32) -> bool {
-        self.terminal.lines() != lines || self.terminal.columns() != cols
+        self.newscr.getmaxy() != lines || self.newscr.getmaxx() != cols
     }
 
     /// Resize the terminal (alias for `resize_term`).
@@ -308,12 +676,30 @@ impl Screen {
             ncols
         };
 
-        Window::new(nlines, ncols, begy, begx)
+        let mut win = Window::new(nlines, ncols, begy, begx)?;
+        win.set_tabsize(self.tabsize);
+        Ok(win)
+    }
+
+    /// Create a new window covering `rect`.
+    ///
+    /// This pairs with the splitters in [`crate::layout`]. Unlike `newwin`,
+    /// a zero height or width is rejected rather than meaning "to the edge
+    /// of the screen".
+    pub fn newwin_rect(&self, rect: Rect) -> Result<Window> {
+        if rect.size.height <= 0 || rect.size.width <= 0 {
+            return Err(Error::InvalidArgument(
+                "window rectangle must not be empty".into(),
+            ));
+        }
+        self.newwin(rect.size.height, rect.size.width, rect.top(), rect.left())
     }
 
     /// Create a new pad.
     pub fn newpad(&self, nlines: i32, ncols: i32) -> Result<Window> {
-        Window::new_pad(nlines, ncols)
+        let mut pad = Window::new_pad(nlines, ncols)?;
+        pad.set_tabsize(self.tabsize);
+        Ok(pad)
     }
 
     // ========================================================================
@@ -423,19 +809,9 @@ impl Screen {
             }
 
             if let Some(pad_line) = pad.line(pad_y) {
-                for dx in 0..copy_width {
-                    let pad_x = (pmincol + dx) as usize;
-                    let screen_x = (smincol + dx) as usize;
-
-                    if pad_x >= pad_width as usize || screen_x >= screen_width as usize {
-                        break;
-                    }
-
-                    let ch = pad_line.get(pad_x);
-                    if let Some(newscr_line) = self.newscr.line_mut(screen_y) {
-                        newscr_line.set(screen_x, ch);
-                    }
-                }
+                let first = pmincol as usize;
+                let last = first + copy_width as usize - 1;
+                Self::copy_span(&mut self.newscr, pad_line, first, last, screen_y, smincol);
             }
         }
 
@@ -445,6 +821,9 @@ impl Screen {
             if let Some(newscr_line) = self.newscr.line_mut(screen_y) {
                 newscr_line.touch();
             }
+            if !pad.is_idcok() {
+                self.no_idc_rows.insert(screen_y);
+            }
         }
 
         // Clear touch flags on the pad since we've processed it
@@ -502,26 +881,64 @@ impl Screen {
     }
 
     /// Start color mode.
+    ///
+    /// On a terminal without colors this still succeeds and pairs can be
+    /// defined, but they are drawn with the attributes chosen by
+    /// [`set_mono_fallback()`](Self::set_mono_fallback) instead of colors.
     pub fn start_color(&mut self) -> Result<()> {
         self.colors.start()
     }
 
+    /// Choose how color pairs are shown when the terminal has no colors.
+    ///
+    /// Like a change to a pair, this affects cells drawn from now on; touch
+    /// the windows to redraw what is already on the screen.
+    pub fn set_mono_fallback(&mut self, fallback: MonoFallback) {
+        self.mono_fallback = fallback;
+    }
+
+    /// Get the monochrome fallback in use.
+    pub fn mono_fallback(&self) -> MonoFallback {
+        self.mono_fallback
+    }
+
     /// Initialize a color pair.
+    ///
+    /// `pair` must be between 1 and `num_color_pairs() - 1`. Pair 0 holds
+    /// the default colors and is changed with
+    /// [`assume_default_colors()`](Self::assume_default_colors) instead.
     pub fn init_pair(&mut self, pair: i16, fg: ColorT, bg: ColorT) -> Result<()> {
         self.colors.init_pair(pair, fg, bg)
     }
 
+    /// Initialize every pair in a [`PairRegistry`](crate::color::PairRegistry).
+    ///
+    /// Stops at the first pair the terminal rejects, for example one beyond
+    /// `COLOR_PAIRS`; create the registry with `with_limit(num_color_pairs())`
+    /// to avoid that.
+    pub fn apply_registry(&mut self, registry: &crate::color::PairRegistry) -> Result<()> {
+        for (pair, fg, bg) in registry.iter() {
+            self.colors.init_pair(pair, fg, bg)?;
+        }
+        Ok(())
+    }
+
     /// Get the foreground and background of a color pair.
     pub fn pair_content(&self, pair: i16) -> Result<(ColorT, ColorT)> {
         self.colors.pair_content(pair)
     }
 
     /// Initialize a color with RGB values.
+    ///
+    /// Components range from 0 to 1000. Fails with `Error::NotSupported` if
+    /// [`can_change_color()`](Self::can_change_color) is false, and with
+    /// `Error::InvalidArgument` for a component out of range; the color is
+    /// left unchanged in either case.
     pub fn init_color(&mut self, color: ColorT, r: i16, g: i16, b: i16) -> Result<()> {
         self.colors.init_color(color, r, g, b)
     }
 
-    /// Get the RGB content of a color.
+    /// Get the RGB content of a color, as last set by `init_color()`.
     pub fn color_content(&self, color: ColorT) -> Result<(i16, i16, i16)> {
         self.colors.color_content(color)
     }
@@ -551,6 +968,43 @@ impl Screen {
         self.colors.assume_default_colors(fg, bg)
     }
 
+    /// Find the palette index closest to an RGB value.
+    ///
+    /// Components use the 0-1000 range of `init_color`. The search uses the
+    /// standard xterm palette (see [`color::xterm_256_rgb`](crate::color::xterm_256_rgb))
+    /// limited to the colors this terminal supports, not any colors redefined
+    /// with `init_color`.
+    pub fn nearest_color(&self, r: i16, g: i16, b: i16) -> i16 {
+        crate::color::nearest_xterm_color(r, g, b, self.colors.num_colors())
+    }
+
+    /// Initialize a color pair with 24-bit RGB colors.
+    ///
+    /// On truecolor terminals (see [`has_truecolor()`](Self::has_truecolor))
+    /// the exact colors are drawn; elsewhere the nearest palette colors are
+    /// used.
+    pub fn init_pair_rgb(
+        &mut self,
+        pair: i16,
+        fg_rgb: crate::color::Rgb,
+        bg_rgb: crate::color::Rgb,
+    ) -> Result<()> {
+        self.colors.init_pair_rgb(pair, fg_rgb, bg_rgb)
+    }
+
+    /// Check if the terminal accepts 24-bit RGB colors.
+    ///
+    /// This is detected from `COLORTERM=truecolor` (or `24bit`).
+    pub fn has_truecolor(&self) -> bool {
+        self.terminal.has_truecolor()
+    }
+
+    /// Override truecolor detection, for terminals that support it without
+    /// advertising it in `COLORTERM`.
+    pub fn set_truecolor(&mut self, truecolor: bool) {
+        self.terminal.set_truecolor(truecolor);
+    }
+
     // ========================================================================
     // Input mode control
     // ========================================================================
@@ -560,6 +1014,7 @@ impl Screen {
         self.terminal.raw(true)?;
         self.input_mode.raw = true;
         self.input_mode.cbreak = 0;
+        self.input_mode.halfdelay = 0;
         Ok(())
     }
 
@@ -571,17 +1026,22 @@ impl Screen {
     }
 
     /// Enable cbreak mode (no line buffering).
+    ///
+    /// This also leaves halfdelay mode, so a blocking `getch()` waits for
+    /// input again.
     pub fn cbreak(&mut self) -> Result<()> {
         self.terminal.cbreak(true)?;
         self.input_mode.cbreak = 1;
+        self.input_mode.halfdelay = 0;
         self.input_mode.raw = false;
         Ok(())
     }
 
-    /// Disable cbreak mode.
+    /// Disable cbreak mode, and halfdelay mode with it.
     pub fn nocbreak(&mut self) -> Result<()> {
         self.terminal.cbreak(false)?;
         self.input_mode.cbreak = 0;
+        self.input_mode.halfdelay = 0;
         Ok(())
     }
 
@@ -612,17 +1072,43 @@ impl Screen {
     }
 
     /// Enable halfdelay mode (timeout in tenths of seconds).
+    ///
+    /// Like `cbreak()`, but a blocking `getch()` gives up with
+    /// `Error::Timeout` after `tenths` tenths of a second. Calling it again
+    /// changes the timeout; `cbreak()`, `nocbreak()` or `raw()` leave the
+    /// mode.
     pub fn halfdelay(&mut self, tenths: i32) -> Result<()> {
         if !(1..=255).contains(&tenths) {
             return Err(Error::InvalidArgument(
                 "halfdelay must be 1-255 tenths of a second".into(),
             ));
         }
-        self.input_mode.cbreak = tenths + 1;
+        self.cbreak()?;
+        self.input_mode.halfdelay = tenths;
         Ok(())
     }
 
+    /// Get the halfdelay timeout in tenths of a second, or `None` outside
+    /// halfdelay mode.
+    pub fn halfdelay_tenths(&self) -> Option<i32> {
+        self.input_mode
+            .is_halfdelay()
+            .then(|| self.input_mode.halfdelay_tenths())
+    }
+
+    /// How long a blocking read waits: the halfdelay timeout, or forever.
+    fn blocking_timeout(&self) -> Option<Duration> {
+        self.halfdelay_tenths()
+            .map(|tenths| Duration::from_millis(tenths as u64 * 100))
+    }
+
     /// Set the ESCDELAY value.
+    ///
+    /// This is how many milliseconds to wait after ESC for the rest of an
+    /// escape sequence. With 0 a lone ESC is returned at once; function keys
+    /// are then only recognised if their whole sequence has already arrived
+    /// when the ESC is read, which is usually but not always the case over
+    /// slow links.
     pub fn set_escdelay(&mut self, delay: i32) {
         self.escdelay = delay;
         self.escape_parser.set_escape_delay(delay);
@@ -633,9 +1119,40 @@ impl Screen {
         self.escdelay
     }
 
+    /// Treat ESC followed at once by a character as an Alt key.
+    ///
+    /// Terminals send Alt+X as ESC followed by X. With this enabled and
+    /// keypad mode on, a printable ASCII character arriving within the
+    /// escape delay after ESC is returned as `KEY_ALT | ch` (see
+    /// [`key_alt()`](crate::key::key_alt)) instead of ESC and then the
+    /// character. An ESC with nothing after it is still returned as `0x1b`
+    /// once the delay expires.
+    pub fn set_meta_sends_escape(&mut self, bf: bool) {
+        self.meta_sends_escape = bf;
+    }
+
+    /// Check if ESC followed by a character is reported as an Alt key.
+    pub fn is_meta_sends_escape(&self) -> bool {
+        self.meta_sends_escape
+    }
+
+    /// Decode ESC plus a single printable character as an Alt key.
+    fn alt_key(&self, seq: &[u8]) -> Option<i32> {
+        match *seq {
+            [0x1b, b] if self.meta_sends_escape && (0x20..0x7f).contains(&b) => {
+                Some(KEY_ALT | b as i32)
+            }
+            _ => None,
+        }
+    }
+
     /// Set the TABSIZE value.
+    ///
+    /// This applies to stdscr and to windows created afterwards with
+    /// `newwin`/`newpad`; use `Window::set_tabsize` for existing windows.
     pub fn set_tabsize(&mut self, size: i32) {
-        self.tabsize = size;
+        self.tabsize = size.max(1);
+        self.stdscr.set_tabsize(self.tabsize);
     }
 
     /// Get the TABSIZE value.
@@ -668,27 +1185,15 @@ impl Screen {
         let old = self.mouse.mousemask(newmask);
 
         // Enable/disable terminal mouse reporting
-        if newmask != 0 && self.mouse_protocol == MouseProtocol::None {
+        if newmask != 0 && self.mouse_modes.is_empty() {
             // Enable SGR mouse protocol (most modern and feature-rich)
-            self.mouse_protocol = MouseProtocol::Sgr;
-            let _ = self
-                .terminal
-                .write(MouseProtocol::Sgr.enable_sequence().as_bytes());
+            self.enable_mouse_mode(MouseProtocol::Sgr);
             // Also enable button event tracking
-            let _ = self
-                .terminal
-                .write(MouseProtocol::ButtonEvent.enable_sequence().as_bytes());
+            self.enable_mouse_mode(MouseProtocol::ButtonEvent);
             let _ = self.terminal.flush();
-        } else if newmask == 0 && self.mouse_protocol != MouseProtocol::None {
+        } else if newmask == 0 {
             // Disable mouse reporting
-            let _ = self
-                .terminal
-                .write(self.mouse_protocol.disable_sequence().as_bytes());
-            let _ = self
-                .terminal
-                .write(MouseProtocol::ButtonEvent.disable_sequence().as_bytes());
-            let _ = self.terminal.flush();
-            self.mouse_protocol = MouseProtocol::None;
+            self.disable_mouse_modes();
         }
 
         old
@@ -696,12 +1201,58 @@ impl Screen {
 
     /// Get the next mouse event.
     ///
-    /// Call this after receiving KEY_MOUSE from getch().
+    /// Call this after receiving KEY_MOUSE from getch(). Queued events come
+    /// out oldest first, as in ncurses; earlier versions of this crate
+    /// returned the newest first. The position is
+    /// clamped to the screen, `0..LINES` and `0..COLS`, since a report sent
+    /// just before a resize can lie outside it. Use
+    /// [`getmouse_unclamped()`](Self::getmouse_unclamped) for the raw report.
     #[cfg(feature = "mouse")]
     pub fn getmouse(&mut self) -> Option<MouseEvent> {
+        let event = self.mouse.getmouse()?;
+        Some(self.clamp_mouse(event))
+    }
+
+    /// Get the next mouse event with the position the terminal reported.
+    #[cfg(feature = "mouse")]
+    pub fn getmouse_unclamped(&mut self) -> Option<MouseEvent> {
         self.mouse.getmouse()
     }
 
+    /// Move a mouse event's position onto the screen.
+    #[cfg(feature = "mouse")]
+    fn clamp_mouse(&self, mut event: MouseEvent) -> MouseEvent {
+        let (lines, cols) = (self.lines(), self.cols());
+        event.y = event.y.clamp(0, (lines - 1).max(0));
+        event.x = event.x.clamp(0, (cols - 1).max(0));
+        event
+    }
+
+    /// Get the next mouse event in coordinates relative to `win`.
+    ///
+    /// The position is first clamped to the screen as by `getmouse()`.
+    /// Events inside the window come back with `y`/`x` translated as by
+    /// `wmouse_trafo`. Events outside it are returned unchanged, or, after
+    /// [`mouse_clip_to_window(true)`](Self::mouse_clip_to_window), left in
+    /// the queue and reported as `None` so another window can claim them.
+    #[cfg(feature = "mouse")]
+    pub fn getmouse_in(&mut self, win: &Window) -> Option<MouseEvent> {
+        let mut event = self.clamp_mouse(*self.mouse.peek_event()?);
+        match win.screen_to_local(event.y, event.x) {
+            Some((y, x)) => (event.y, event.x) = (y, x),
+            None if self.mouse_clip_to_window => return None,
+            None => {}
+        }
+        self.mouse.getmouse()?;
+        Some(event)
+    }
+
+    /// Choose whether `getmouse_in` skips events outside the window.
+    #[cfg(feature = "mouse")]
+    pub fn mouse_clip_to_window(&mut self, bf: bool) {
+        self.mouse_clip_to_window = bf;
+    }
+
     /// Push a mouse event back to the queue.
     #[cfg(feature = "mouse")]
     pub fn ungetmouse(&mut self, event: MouseEvent) -> bool {
@@ -723,2433 +1274,4228 @@ impl Screen {
         self.mouse.is_enabled()
     }
 
-    // ========================================================================
-    // Cursor control
-    // ========================================================================
-
-    /// Set cursor visibility.
-    ///
-    /// # Arguments
-    ///
-    /// * `visibility` - The desired cursor visibility. Can be:
-    ///   - `CursorVisibility::Invisible` (0) - cursor is hidden
-    ///   - `CursorVisibility::Normal` (1) - default cursor visibility  
-    ///   - `CursorVisibility::VeryVisible` (2) - very visible cursor (e.g., block)
-    ///
-    /// # Returns
-    ///
-    /// Returns the previous cursor visibility setting.
-    ///
-    /// # Example
-    ///
-    /// ```rust,ignore
-    /// use ncurses::types::CursorVisibility;
+    /// Turn on an additional mouse tracking mode.
     ///
-    /// // Hide the cursor
-    /// let old = screen.curs_set(CursorVisibility::Invisible)?;
-    ///
-    /// // Also accepts i32 for compatibility
-    /// let old = screen.curs_set(0)?;
-    /// ```
-    pub fn curs_set(
-        &mut self,
-        visibility: impl TryInto<CursorVisibility>,
-    ) -> Result<CursorVisibility> {
-        let visibility = visibility
-            .try_into()
-            .map_err(|_| Error::InvalidArgument("cursor visibility must be 0, 1, or 2".into()))?;
-
-        let old = self.cursor_visibility;
-        self.cursor_visibility = visibility;
-
-        match visibility {
-            CursorVisibility::Invisible => self.terminal.cursor_visible(false)?,
-            _ => self.terminal.cursor_visible(true)?,
+    /// [`mousemask()`](Self::mousemask) enables SGR reporting with button
+    /// event tracking; use this to request e.g. [`MouseProtocol::AnyEvent`]
+    /// for motion without a button held. Every mode enabled here is turned
+    /// off again by `mousemask(0)` and [`endwin()`](Self::endwin).
+    #[cfg(feature = "mouse")]
+    pub fn set_mouse_protocol(&mut self, protocol: MouseProtocol) -> Result<()> {
+        if protocol == MouseProtocol::None {
+            return Err(Error::InvalidArgument(
+                "use mousemask(0) to disable mouse reporting".into(),
+            ));
         }
+        self.enable_mouse_mode(protocol);
+        self.terminal.flush()
+    }
 
-        Ok(old)
+    /// Write the enable sequence for `mode` and remember it for teardown.
+    #[cfg(feature = "mouse")]
+    fn enable_mouse_mode(&mut self, mode: MouseProtocol) {
+        if self.mouse_modes.contains(&mode) {
+            return;
+        }
+        let _ = self.terminal.write(mode.enable_sequence().as_bytes());
+        self.mouse_modes.push(mode);
     }
 
-    /// Get the current cursor visibility setting.
-    #[must_use]
-    pub fn cursor_visibility(&self) -> CursorVisibility {
-        self.cursor_visibility
+    /// Disable every recorded mouse mode, most recent first.
+    #[cfg(feature = "mouse")]
+    fn disable_mouse_modes(&mut self) {
+        if self.mouse_modes.is_empty() {
+            return;
+        }
+        while let Some(mode) = self.mouse_modes.pop() {
+            let _ = self.terminal.write(mode.disable_sequence().as_bytes());
+        }
+        let _ = self.terminal.flush();
     }
 
     // ========================================================================
-    // Output functions
+    // Bracketed paste
     // ========================================================================
 
-    /// Ring the terminal bell.
-    pub fn beep(&mut self) -> Result<()> {
-        self.terminal.beep()?;
-        self.terminal.flush()
-    }
+    /// Enable or disable bracketed paste mode.
+    ///
+    /// While enabled, the terminal wraps pasted text in `\x1b[200~` and
+    /// `\x1b[201~`. `getch()` collects everything between the markers and
+    /// returns `KEY_PASTE` instead of the individual characters; the text is
+    /// then available from [`get_paste()`](Self::get_paste).
+    pub fn set_bracketed_paste(&mut self, bf: bool) -> Result<()> {
+        if bf {
+            self.escape_parser.define_key(PASTE_START, KEY_PASTE);
+        }
+        self.escape_parser.keyok(KEY_PASTE, bf);
 
-    /// Flash the screen (visual bell).
-    pub fn flash(&mut self) -> Result<()> {
-        self.terminal.flash()
+        let seq: &[u8] = if bf { b"\x1b[?2004h" } else { b"\x1b[?2004l" };
+        self.terminal.write(seq)?;
+        self.terminal.flush()?;
+        self.bracketed_paste = bf;
+        Ok(())
     }
 
-    // ========================================================================
-    // Refresh operations
-    // ========================================================================
-
-    /// Refresh the standard screen.
-    pub fn refresh(&mut self) -> Result<()> {
-        // Copy stdscr to newscr then update
-        self.stdscr_to_newscr()?;
-        self.doupdate()
+    /// Check if bracketed paste mode is enabled.
+    pub fn is_bracketed_paste(&self) -> bool {
+        self.bracketed_paste
     }
 
-    /// Copy stdscr to the new screen buffer.
-    fn stdscr_to_newscr(&mut self) -> Result<()> {
-        let maxy = self.stdscr.getmaxy();
-        let maxx = self.stdscr.getmaxx();
-
-        for y in 0..maxy {
-            if let Some(line) = self.stdscr.line(y as usize) {
-                if line.is_touched() {
-                    if let Some((first, last)) = line.changed_range() {
-                        for x in first..=last {
-                            if x >= maxx as usize {
-                                break;
-                            }
-                            let ch = line.get(x);
-                            if let Some(newscr_line) = self.newscr.line_mut(y as usize) {
-                                newscr_line.set(x, ch);
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        Ok(())
-    }
-
-    /// Refresh a window (copy to physical screen).
-    pub fn wrefresh(&mut self, win: &mut Window) -> Result<()> {
-        // Copy window to newscr
-        self.wnoutrefresh(win)?;
-        // Update the physical screen
-        self.doupdate()
+    /// Get the text of the next paste.
+    ///
+    /// Call this after receiving `KEY_PASTE` from `getch()`.
+    pub fn get_paste(&mut self) -> Option<String> {
+        self.pastes.pop_front()
     }
 
-    /// Copy a window to the virtual screen (but don't update physical screen).
-    pub fn wnoutrefresh(&mut self, win: &Window) -> Result<()> {
-        // Copy changed portions of win to newscr
-        let begy = win.getbegy();
-        let begx = win.getbegx();
-        let maxy = win.getmaxy();
-
-        for y in 0..maxy {
-            let screen_y = (begy + y) as usize;
-            if screen_y >= self.newscr.getmaxy() as usize {
+    /// Read pasted bytes up to the closing `PASTE_END` marker.
+    ///
+    /// Stops early on EOF or if no input arrives within `PASTE_TIMEOUT`, so a
+    /// lost end marker cannot hang the application.
+    fn read_paste(&mut self) -> Result<String> {
+        let mut buf = Vec::new();
+        while !buf.ends_with(PASTE_END) {
+            if !self.terminal.has_input() && !self.terminal.wait_for_input(Some(PASTE_TIMEOUT)) {
                 break;
             }
+            match self.terminal.read_byte()? {
+                Some(b) => buf.push(b),
+                None => break,
+            }
+        }
+        if buf.ends_with(PASTE_END) {
+            buf.truncate(buf.len() - PASTE_END.len());
+        }
+        Ok(String::from_utf8_lossy(&buf).into_owned())
+    }
 
-            if let Some(line) = win.line(y as usize) {
-                if line.is_touched() {
-                    if let Some((first, last)) = line.changed_range() {
-                        for x in first..=last {
-                            let screen_x = (begx as usize) + x;
-                            if screen_x >= self.newscr.getmaxx() as usize {
-                                break;
-                            }
+    // ========================================================================
+    // Focus events
+    // ========================================================================
 
-                            let ch = line.get(x);
-                            if let Some(newscr_line) = self.newscr.line_mut(screen_y) {
-                                newscr_line.set(screen_x, ch);
-                            }
-                        }
-                    }
-                }
-            }
+    /// Enable or disable focus in/out reporting.
+    ///
+    /// While enabled, the terminal sends `\x1b[I` and `\x1b[O` when its
+    /// window gains or loses focus, which `getch()` returns as `KEY_FOCUS_IN`
+    /// and `KEY_FOCUS_OUT`. Like other function keys they are only decoded
+    /// with keypad mode on.
+    pub fn set_focus_events(&mut self, bf: bool) -> Result<()> {
+        if bf {
+            self.escape_parser.define_key(b"\x1b[I", KEY_FOCUS_IN);
+            self.escape_parser.define_key(b"\x1b[O", KEY_FOCUS_OUT);
         }
+        self.escape_parser.keyok(KEY_FOCUS_IN, bf);
+        self.escape_parser.keyok(KEY_FOCUS_OUT, bf);
 
+        let seq: &[u8] = if bf { b"\x1b[?1004h" } else { b"\x1b[?1004l" };
+        self.terminal.write(seq)?;
+        self.terminal.flush()?;
+        self.focus_events = bf;
         Ok(())
     }
 
-    /// Update the physical screen from the virtual screen.
-    pub fn doupdate(&mut self) -> Result<()> {
-        // Check if we need to clear the screen first
-        let do_clear = self.stdscr.take_clear_flag();
-        if do_clear {
-            self.terminal.clear_screen()?;
-            self.curscr.erase()?;
-            self.curscr.touchwin();
-        }
-
-        let lines = self.newscr.getmaxy() as usize;
-        let cols = self.newscr.getmaxx() as usize;
+    /// Check if focus in/out reporting is enabled.
+    pub fn has_focus_events(&self) -> bool {
+        self.focus_events
+    }
 
-        // Collect changes first to avoid borrow issues
-        #[cfg(not(feature = "wide"))]
-        type CellData = (usize, usize, ChType);
-        #[cfg(feature = "wide")]
-        type CellData = (usize, usize, crate::wide::CCharT);
+    // ========================================================================
+    // Synchronized output
+    // ========================================================================
 
-        let mut changes: Vec<CellData> = Vec::new();
+    /// Have `doupdate()` send each frame as a synchronized update.
+    ///
+    /// The frame is bracketed by `ESC [ ? 2026 h` and `ESC [ ? 2026 l`, so
+    /// the terminal shows it all at once instead of drawing it as it
+    /// arrives, which avoids flicker on large updates.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::NotSupported` when enabling on a terminal not known
+    /// to support it, either by type or from
+    /// [`detect_capabilities()`](Self::detect_capabilities).
+    pub fn set_synchronized_output(&mut self, bf: bool) -> Result<()> {
+        if bf && !self.has_synchronized_output() {
+            return Err(Error::NotSupported(format!(
+                "synchronized output on terminal '{}'",
+                self.terminal.term_type()
+            )));
+        }
+        self.synchronized_output = bf;
+        Ok(())
+    }
 
-        for y in 0..lines {
-            let newscr_line = match self.newscr.line(y) {
-                Some(l) => l,
-                None => continue,
-            };
-            let curscr_line = match self.curscr.line(y) {
-                Some(l) => l,
-                None => continue,
-            };
+    /// Check if updates are sent as synchronized updates.
+    pub fn is_synchronized_output(&self) -> bool {
+        self.synchronized_output
+    }
 
-            // Check if line has changes
-            if !newscr_line.is_touched() {
-                continue;
-            }
+    /// Check whether the terminal supports synchronized updates.
+    pub fn has_synchronized_output(&self) -> bool {
+        self.terminal.has_synchronized_output()
+            || self
+                .capabilities
+                .as_ref()
+                .is_some_and(|caps| caps.has_synchronized_output())
+    }
 
-            if let Some((first, last)) = newscr_line.changed_range() {
-                for x in first..=last.min(cols - 1) {
-                    let new_cell = newscr_line.get(x);
-                    let cur_cell = curscr_line.get(x);
+    // ========================================================================
+    // Terminal queries
+    // ========================================================================
 
-                    if new_cell != cur_cell || do_clear {
-                        changes.push((y, x, new_cell));
-                    }
-                }
+    /// Ask the terminal where its cursor is.
+    ///
+    /// Sends the `ESC [ 6 n` status request and waits up to a second for the
+    /// `ESC [ row ; col R` reply, returning the 0-based `(y, x)` position.
+    /// Anything typed before the reply arrives is kept for the next
+    /// `getch()`. A reply that turns up within a few seconds after the
+    /// timeout is dropped by `getch()` in keypad mode. Returns
+    /// `Error::Timeout` if the terminal does not answer.
+    pub fn query_cursor_position(&mut self) -> Result<(i32, i32)> {
+        let result = self.query_terminal(b"\x1b[6n", QUERY_TIMEOUT, |pending| {
+            if pending.last() != Some(&b'R') {
+                return None;
             }
+            let csi = pending.windows(2).rposition(|w| w == b"\x1b[")?;
+            let pos = EscapeParser::cursor_report(&pending[csi..])?;
+            pending.truncate(csi);
+            Some(pos)
+        });
+        self.late_cursor_report = match result {
+            Err(Error::Timeout) => Some(Instant::now() + LATE_REPLY_WINDOW),
+            _ => None,
+        };
+        result
+    }
+
+    /// Check whether `seq` is the late reply to a timed-out cursor position
+    /// or device attributes query, and stop expecting one if so.
+    ///
+    /// Otherwise a sequence like `ESC [ 1 ; 2 R` is a key (Shift+F3). A late
+    /// device attributes reply is still recorded as the capabilities.
+    fn take_late_reply(&mut self, seq: &[u8]) -> bool {
+        let now = Instant::now();
+        let expected = self
+            .late_cursor_report
+            .is_some_and(|deadline| now < deadline);
+        if expected && EscapeParser::cursor_report(seq).is_some() {
+            self.late_cursor_report = None;
+            return true;
         }
 
-        // Now output the changes
-        let mut last_attr: AttrT = A_NORMAL;
-        let mut current_y: i32 = -1;
-        let mut current_x: i32 = -1;
-
-        for (y, x, cell) in changes {
-            // Move cursor if needed
-            if current_y != y as i32 || current_x != x as i32 {
-                self.terminal.move_cursor(y as i32, x as i32)?;
-                current_y = y as i32;
-                current_x = x as i32;
+        let expected = self
+            .late_capabilities_report
+            .is_some_and(|deadline| now < deadline);
+        if let Some(caps) = TermCapabilities::parse_da1(seq).filter(|_| expected) {
+            self.late_capabilities_report = None;
+            if caps.has_truecolor() {
+                self.terminal.set_truecolor(true);
             }
+            self.capabilities = Some(caps);
+            return true;
+        }
+        false
+    }
 
-            #[cfg(not(feature = "wide"))]
-            {
-                // Handle attributes
-                let new_attr = cell & !A_CHARTEXT;
-                if new_attr != last_attr {
-                    self.output_attr(new_attr)?;
-                    last_attr = new_attr;
-                }
+    /// Ask the terminal to identify itself.
+    ///
+    /// Sends the XTVERSION and primary device attributes (DA1) requests and
+    /// waits up to a second for the DA1 reply. Every terminal answers DA1,
+    /// so a terminal that ignores XTVERSION costs no extra wait. The result
+    /// is kept for [`capabilities()`](Self::capabilities) and takes the
+    /// place of the `$TERM` name in `tigetflag()`; a terminal that names
+    /// itself as a known 24-bit emulator also turns on truecolor output.
+    ///
+    /// `init()` only sends the DA1 request, waiting briefly, after
+    /// [`query_capabilities_at_init(true)`](globals::query_capabilities_at_init);
+    /// call this to also learn the XTVERSION name or to wait longer for a
+    /// slow link. Typeahead is kept for `getch()`. Returns `Error::Timeout` if
+    /// the terminal does not answer; a DA1 reply that turns up within a few
+    /// seconds after that is still recorded by `getch()` in keypad mode.
+    pub fn detect_capabilities(&mut self) -> Result<&TermCapabilities> {
+        self.query_capabilities(b"\x1b[>0q\x1b[c", QUERY_TIMEOUT)
+    }
 
-                // Output the character
-                let c = (cell & A_CHARTEXT) as u8;
-                if c >= 0x20 && c < 0x7f {
-                    self.terminal.write(&[c])?;
-                } else if c == 0 {
-                    self.terminal.write(b" ")?;
-                } else {
-                    // Control character or high byte - output as space
-                    self.terminal.write(b" ")?;
-                }
+    /// Send `request`, which ends with the DA1 request, and record the
+    /// capabilities from the reply, waiting at most `timeout` for it.
+    fn query_capabilities(
+        &mut self,
+        request: &[u8],
+        timeout: Duration,
+    ) -> Result<&TermCapabilities> {
+        let result = self.query_terminal(request, timeout, |pending| {
+            if pending.last() != Some(&b'c') {
+                return None;
             }
-
-            #[cfg(feature = "wide")]
-            {
-                // Handle attributes
-                let new_attr = cell.attrs();
-                if new_attr != last_attr {
-                    self.output_attr(new_attr)?;
-                    last_attr = new_attr;
-                }
-
-                // Output the character
-                let c = cell.spacing_char();
-                if c == '\0' {
-                    self.terminal.write(b" ")?;
-                } else {
-                    let mut buf = [0u8; 4];
-                    let s = c.encode_utf8(&mut buf);
-                    self.terminal.write(s.as_bytes())?;
+            let csi = pending.windows(3).rposition(|w| w == b"\x1b[?")?;
+            let mut caps = TermCapabilities::parse_da1(&pending[csi..])?;
+            pending.truncate(csi);
+
+            // The XTVERSION reply, if any, arrived first
+            if let Some(dcs) = pending.windows(4).position(|w| w == b"\x1bP>|") {
+                if let Some(len) = pending[dcs..].windows(2).position(|w| w == b"\x1b\\") {
+                    let end = dcs + len + 2;
+                    caps.version = TermCapabilities::parse_xtversion(&pending[dcs..end]);
+                    pending.drain(dcs..end);
                 }
             }
+            Some(caps)
+        });
+        self.late_capabilities_report = match result {
+            Err(Error::Timeout) => Some(Instant::now() + LATE_REPLY_WINDOW),
+            _ => None,
+        };
 
-            current_x += 1;
-        }
-
-        // Reset attributes
-        if last_attr != A_NORMAL {
-            self.terminal.set_attributes(A_NORMAL)?;
+        let caps = result?;
+        if caps.has_truecolor() {
+            self.terminal.set_truecolor(true);
         }
+        Ok(self.capabilities.insert(caps))
+    }
 
-        // Position cursor at stdscr's cursor position
-        let cursor_y = self.stdscr.getcury();
-        let cursor_x = self.stdscr.getcurx();
-        if !self.stdscr.is_leaveok() {
-            self.terminal.move_cursor(cursor_y, cursor_x)?;
-        }
+    /// Get what the terminal reported at `init()` or from
+    /// [`detect_capabilities()`](Self::detect_capabilities), if it answered.
+    pub fn capabilities(&self) -> Option<&TermCapabilities> {
+        self.capabilities.as_ref()
+    }
 
-        // Flush output
+    /// Send `request` and read input until `reply` recognizes the answer or
+    /// `timeout` passes.
+    ///
+    /// `reply` is called with the bytes read so far after each byte; when it
+    /// finds the answer it removes it from the buffer and returns it. The
+    /// other bytes read are kept for `getch()`.
+    fn query_terminal<T>(
+        &mut self,
+        request: &[u8],
+        timeout: Duration,
+        mut reply: impl FnMut(&mut Vec<u8>) -> Option<T>,
+    ) -> Result<T> {
+        self.terminal.write(request)?;
         self.terminal.flush()?;
 
-        // Copy newscr to curscr and clear touch flags
-        for y in 0..lines {
-            if let (Some(newscr_line), Some(curscr_line)) =
-                (self.newscr.line(y), self.curscr.line_mut(y))
-            {
-                curscr_line.copy_from(newscr_line);
-                curscr_line.untouch();
+        let start = Instant::now();
+        let mut pending: Vec<u8> = Vec::new();
+        let result = loop {
+            if let Some(answer) = reply(&mut pending) {
+                break Ok(answer);
             }
-            if let Some(newscr_line) = self.newscr.line_mut(y) {
-                newscr_line.untouch();
+            if !self.terminal.has_input() {
+                let remaining = timeout.saturating_sub(start.elapsed());
+                if remaining.is_zero() {
+                    break Err(Error::Timeout);
+                }
+                // Woken by input, the deadline or a signal; look again
+                self.terminal.wait_for_input(Some(remaining));
+                continue;
+            }
+            match self.terminal.read_byte()? {
+                Some(b) => pending.push(b),
+                None => break Err(Error::Eof),
             }
+        };
+
+        for b in pending {
+            self.input_buffer.push(b as i32);
         }
+        result
+    }
 
-        // Clear touch flags on stdscr
-        self.stdscr.untouchwin();
+    // ========================================================================
+    // Unknown CSI sequences
+    // ========================================================================
 
-        Ok(())
+    /// Return unrecognized CSI sequences as a single key.
+    ///
+    /// By default an escape sequence that matches no known key is returned
+    /// as ESC followed by its remaining bytes one at a time. With this
+    /// enabled, a complete but unrecognized CSI sequence (`ESC [` followed by
+    /// parameters and a final byte) is returned as `KEY_UNKNOWN_CSI`, and its
+    /// raw bytes are available from [`get_unknown_csi()`](Self::get_unknown_csi).
+    pub fn set_unknown_csi_coalesce(&mut self, bf: bool) {
+        self.unknown_csi_coalesce = bf;
     }
 
-    /// Output attribute changes to the terminal.
-    fn output_attr(&mut self, attr: AttrT) -> Result<()> {
-        // Set text attributes
-        self.terminal.set_attributes(attr)?;
+    /// Check if unrecognized CSI sequences are coalesced.
+    pub fn is_unknown_csi_coalesce(&self) -> bool {
+        self.unknown_csi_coalesce
+    }
 
-        // Handle color pair
-        let pair = attr::pair_number(attr);
-        if pair > 0 {
-            if let Ok((fg, bg)) = self.colors.pair_content(pair) {
-                self.terminal.set_fg_color(fg)?;
-                self.terminal.set_bg_color(bg)?;
+    /// Get the raw bytes of the next unrecognized CSI sequence.
+    ///
+    /// Call this after receiving `KEY_UNKNOWN_CSI` from `getch()`. The bytes
+    /// include the leading `ESC [`.
+    pub fn get_unknown_csi(&mut self) -> Option<Vec<u8>> {
+        self.unknown_csis.pop_front()
+    }
+
+    /// Read the rest of a CSI sequence into `seq` up to its final byte.
+    ///
+    /// Returns false if the sequence is malformed, too long, or not finished
+    /// within the escape delay; `seq` then holds everything read so far.
+    fn read_csi_tail(&mut self, seq: &mut Vec<u8>) -> Result<bool> {
+        let start = Instant::now();
+        let escape_timeout = Duration::from_millis(self.escdelay as u64);
+        loop {
+            match seq.last() {
+                Some(&b) if seq.len() > 2 && (0x40..=0x7e).contains(&b) => return Ok(true),
+                Some(&b) if seq.len() > 2 && !(0x20..=0x3f).contains(&b) => return Ok(false),
+                _ => {}
+            }
+            if seq.len() >= MAX_CSI_LEN {
+                return Ok(false);
+            }
+            if !self.terminal.has_input() {
+                let remaining = escape_timeout.saturating_sub(start.elapsed());
+                if remaining.is_zero() || !self.terminal.wait_for_input(Some(remaining)) {
+                    return Ok(false);
+                }
+            }
+            match self.terminal.read_byte()? {
+                Some(b) => seq.push(b),
+                None => return Ok(false),
             }
-        } else {
-            // Reset to default colors
-            self.terminal.set_fg_color(-1)?;
-            self.terminal.set_bg_color(-1)?;
         }
-
-        Ok(())
     }
 
     // ========================================================================
-    // Input operations
+    // Cursor control
     // ========================================================================
 
-    /// Read a character from the terminal (using stdscr settings).
-    pub fn getch(&mut self) -> Result<i32> {
-        // If immedok is set, refresh first
-        if self.stdscr.is_immedok() {
-            self.refresh()?;
-        }
-
-        // Get delay setting from stdscr
-        let delay = Delay::from_raw(self.stdscr.getdelay());
-        let use_keypad = self.stdscr.is_keypad();
+    /// Set cursor visibility.
+    ///
+    /// # Arguments
+    ///
+    /// * `visibility` - The desired cursor visibility. Can be:
+    ///   - `CursorVisibility::Invisible` (0) - cursor is hidden
+    ///   - `CursorVisibility::Normal` (1) - default cursor visibility  
+    ///   - `CursorVisibility::VeryVisible` (2) - very visible cursor (e.g., block)
+    ///
+    /// # Returns
+    ///
+    /// Returns the previous cursor visibility setting.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use ncurses::types::CursorVisibility;
+    ///
+    /// // Hide the cursor
+    /// let old = screen.curs_set(CursorVisibility::Invisible)?;
+    ///
+    /// // Also accepts i32 for compatibility
+    /// let old = screen.curs_set(0)?;
+    /// ```
+    pub fn curs_set(
+        &mut self,
+        visibility: impl TryInto<CursorVisibility>,
+    ) -> Result<CursorVisibility> {
+        let visibility = visibility
+            .try_into()
+            .map_err(|_| Error::InvalidArgument("cursor visibility must be 0, 1, or 2".into()))?;
 
-        self.getch_internal(delay, use_keypad)
-    }
+        let old = self.cursor_visibility;
+        self.cursor_visibility = visibility;
 
-    /// Read a character from a window.
-    pub fn wgetch(&mut self, win: &mut Window) -> Result<i32> {
-        // If immedok is set, refresh first
-        if win.is_immedok() {
-            self.wrefresh(win)?;
+        match visibility {
+            CursorVisibility::Invisible => self.terminal.cursor_visible(false)?,
+            _ => self.terminal.cursor_visible(true)?,
         }
 
-        // Get delay setting from window
-        let delay = Delay::from_raw(win.getdelay());
-        let use_keypad = win.is_keypad();
+        Ok(old)
+    }
 
-        self.getch_internal(delay, use_keypad)
+    /// Get the current cursor visibility setting.
+    #[must_use]
+    pub fn cursor_visibility(&self) -> CursorVisibility {
+        self.cursor_visibility
     }
 
-    /// Move cursor and read a character from stdscr.
-    pub fn mvgetch(&mut self, y: i32, x: i32) -> Result<i32> {
-        self.stdscr.mv(y, x)?;
-        self.getch()
+    /// Set the hardware cursor shape.
+    ///
+    /// This emits the DECSCUSR sequence for `style`. The terminal's default
+    /// shape is restored by `endwin()`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::NotSupported` if the terminal type is not known to
+    /// understand DECSCUSR.
+    pub fn set_cursor_style(&mut self, style: CursorStyle) -> Result<()> {
+        if !self.terminal.has_cursor_style() {
+            return Err(Error::NotSupported(format!(
+                "cursor styles on terminal '{}'",
+                self.terminal.term_type()
+            )));
+        }
+        self.terminal.cursor_style(style.to_raw())?;
+        self.cursor_style = Some(style);
+        Ok(())
     }
 
-    /// Move cursor and read a character from a window.
-    pub fn mvwgetch(&mut self, win: &mut Window, y: i32, x: i32) -> Result<i32> {
-        win.mv(y, x)?;
-        self.wgetch(win)
+    /// Get the cursor shape set with `set_cursor_style()`, if any.
+    #[must_use]
+    pub fn cursor_style(&self) -> Option<CursorStyle> {
+        self.cursor_style
     }
 
-    /// Read a wide character from the terminal (using stdscr settings).
+    /// Set the terminal window title.
     ///
-    /// Returns the wide character result, which can be a character or key code.
-    /// This is the Rust equivalent of `get_wch()`.
-    #[cfg(feature = "wide")]
-    pub fn get_wch(&mut self) -> Result<crate::wide::WideInput> {
-        // If immedok is set, refresh first
-        if self.stdscr.is_immedok() {
-            self.refresh()?;
+    /// The title is sent with the next refresh or [`flush()`](Self::flush).
+    /// Call [`push_title()`](Self::push_title) first to have `endwin()` put
+    /// back the title the user had.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::NotSupported` if the terminal type is not known to
+    /// accept titles.
+    pub fn set_title(&mut self, title: &str) -> Result<()> {
+        if !self.terminal.has_title() {
+            return Err(Error::NotSupported(format!(
+                "window titles on terminal '{}'",
+                self.terminal.term_type()
+            )));
         }
-
-        let delay = Delay::from_raw(self.stdscr.getdelay());
-        let use_keypad = self.stdscr.is_keypad();
-
-        self.get_wch_internal(delay, use_keypad)
+        self.terminal.set_title(title)
     }
 
-    /// Read a wide character from a window.
+    /// Save the current window title on the terminal's title stack.
     ///
-    /// Returns the wide character result, which can be a character or key code.
-    /// This is the Rust equivalent of `wget_wch()`.
-    #[cfg(feature = "wide")]
-    pub fn wget_wch(&mut self, win: &mut Window) -> Result<crate::wide::WideInput> {
-        // If immedok is set, refresh first
-        if win.is_immedok() {
-            self.wrefresh(win)?;
+    /// Each push is undone by a [`pop_title()`](Self::pop_title); any left
+    /// over are popped by `endwin()`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::NotSupported` if the terminal type is not known to
+    /// keep a title stack.
+    pub fn push_title(&mut self) -> Result<()> {
+        if !self.terminal.has_title_stack() {
+            return Err(Error::NotSupported(format!(
+                "title stack on terminal '{}'",
+                self.terminal.term_type()
+            )));
         }
-
-        let delay = Delay::from_raw(win.getdelay());
-        let use_keypad = win.is_keypad();
-
-        self.get_wch_internal(delay, use_keypad)
+        self.terminal.push_title()?;
+        self.titles_pushed += 1;
+        Ok(())
     }
 
-    /// Move cursor and read a wide character from stdscr.
-    #[cfg(feature = "wide")]
-    pub fn mvget_wch(&mut self, y: i32, x: i32) -> Result<crate::wide::WideInput> {
-        self.stdscr.mv(y, x)?;
-        self.get_wch()
+    /// Restore the window title saved by the last `push_title()`.
+    ///
+    /// Returns an error if no title has been pushed.
+    pub fn pop_title(&mut self) -> Result<()> {
+        if self.titles_pushed == 0 {
+            return Err(Error::InvalidArgument("no window title pushed".into()));
+        }
+        self.terminal.pop_title()?;
+        self.titles_pushed -= 1;
+        Ok(())
     }
 
-    /// Move cursor and read a wide character from a window.
-    #[cfg(feature = "wide")]
-    pub fn mvwget_wch(
-        &mut self,
-        win: &mut Window,
-        y: i32,
-        x: i32,
-    ) -> Result<crate::wide::WideInput> {
-        win.mv(y, x)?;
-        self.wget_wch(win)
-    }
+    // ========================================================================
+    // Output functions
+    // ========================================================================
 
-    /// Internal wide character reading logic.
-    #[cfg(feature = "wide")]
-    fn get_wch_internal(
-        &mut self,
-        delay: Delay,
-        use_keypad: bool,
-    ) -> Result<crate::wide::WideInput> {
-        use crate::wide::WideInput;
+    /// Send any buffered output to the terminal.
+    ///
+    /// Output is normally flushed by `refresh()`/`doupdate()`. Calls that
+    /// only buffer their escape sequences, such as `curs_set()`, take effect
+    /// at the next refresh; use this to send them right away without
+    /// redrawing the screen.
+    pub fn flush(&mut self) -> Result<()> {
+        self.terminal.flush()
+    }
 
-        // First get a character using the normal getch
-        let ch = match self.getch_internal(delay, use_keypad) {
-            Ok(c) => c,
-            Err(Error::Timeout) => return Ok(WideInput::None),
-            Err(Error::NoInput) => return Ok(WideInput::None),
-            Err(Error::Eof) => return Ok(WideInput::Eof),
-            Err(e) => return Err(e),
-        };
+    /// Ring the terminal bell.
+    ///
+    /// The bell is sent immediately, together with any other buffered
+    /// output.
+    pub fn beep(&mut self) -> Result<()> {
+        self.terminal.beep()?;
+        self.terminal.flush()
+    }
 
-        // Check if it's a key code (high values or negative)
-        if !(0..=255).contains(&ch) {
-            return Ok(WideInput::Key(ch));
+    /// Flash the screen (visual bell).
+    ///
+    /// Terminals without a `flash` capability get a software visual bell:
+    /// the screen is repainted in reverse video for a moment and then put
+    /// back. Changes copied with `wnoutrefresh()` but not yet shown stay
+    /// pending. Inside a batch the flash is held back until `end_batch()`
+    /// has drawn the batched changes.
+    pub fn flash(&mut self) -> Result<()> {
+        if self.batch_depth > 0 {
+            self.flash_pending = true;
+            return Ok(());
         }
-
-        // Handle as UTF-8
-        let first_byte = ch as u8;
-
-        // Single-byte ASCII
-        if first_byte < 0x80 {
-            return Ok(WideInput::Char(first_byte as char));
+        if self.tigetstr("flash").is_some() {
+            return self.terminal.flash();
         }
 
-        // Multi-byte UTF-8 sequence
-        let needed = if first_byte & 0xE0 == 0xC0 {
-            1 // 2-byte sequence
-        } else if first_byte & 0xF0 == 0xE0 {
-            2 // 3-byte sequence
-        } else if first_byte & 0xF8 == 0xF0 {
-            3 // 4-byte sequence
-        } else {
-            // Invalid UTF-8 start byte, return as-is
-            return Ok(WideInput::Char(first_byte as char));
-        };
-
-        let mut bytes = vec![first_byte];
+        let pending: Vec<LineData> = self.newscr.lines().to_vec();
+        let shown: Vec<LineData> = self.curscr.lines().to_vec();
+        self.paint_lines(&shown, attr::A_REVERSE)?;
+        self.napms(FLASH_MS);
+        self.paint_lines(&shown, A_NORMAL)?;
 
-        // Read continuation bytes
-        for _ in 0..needed {
-            // Use a short timeout for continuation bytes
-            let timeout = Delay::Timeout(50);
-            match self.getch_internal(timeout, false) {
-                Ok(b) if (b as u8) & 0xC0 == 0x80 => {
-                    bytes.push(b as u8);
-                }
-                Ok(b) => {
-                    // Not a continuation byte, push it back
-                    self.input_buffer.unget(b);
-                    break;
-                }
-                Err(_) => break,
+        for (y, line) in pending.into_iter().enumerate() {
+            if let Some(newscr_line) = self.newscr.line_mut(y) {
+                *newscr_line = line;
             }
         }
+        Ok(())
+    }
 
-        // Try to decode as UTF-8
-        match std::str::from_utf8(&bytes) {
-            Ok(s) => {
-                if let Some(c) = s.chars().next() {
-                    Ok(WideInput::Char(c))
-                } else {
-                    Ok(WideInput::Error)
-                }
-            }
-            Err(_) => {
-                // Invalid UTF-8, return first byte as char
-                Ok(WideInput::Char(first_byte as char))
+    /// Show `lines` on the terminal with the `toggle` attributes flipped.
+    fn paint_lines(&mut self, lines: &[LineData], toggle: AttrT) -> Result<()> {
+        for (y, line) in lines.iter().enumerate() {
+            let Some(newscr_line) = self.newscr.line_mut(y) else {
+                break;
+            };
+            for x in 0..line.width() {
+                #[cfg(not(feature = "wide"))]
+                let cell = line.get(x) ^ toggle;
+                #[cfg(feature = "wide")]
+                let cell = {
+                    let mut cell = line.get(x);
+                    cell.set_attrs(cell.attrs() ^ toggle);
+                    cell
+                };
+                newscr_line.set(x, cell);
             }
         }
+        self.doupdate()
     }
 
-    /// Internal character reading logic.
-    fn getch_internal(&mut self, delay: Delay, use_keypad: bool) -> Result<i32> {
-        // Check input buffer first
-        if let Some(ch) = self.input_buffer.get() {
-            return Ok(ch);
-        }
+    // ========================================================================
+    // Refresh operations
+    // ========================================================================
 
-        // Determine timeout
-        let timeout = match delay {
-            Delay::NoDelay => Some(Duration::ZERO),
-            Delay::Blocking => {
-                if self.input_mode.is_halfdelay() {
-                    Some(Duration::from_millis(
-                        (self.input_mode.halfdelay_tenths() * 100) as u64,
-                    ))
-                } else {
-                    None // Block indefinitely
-                }
-            }
-            Delay::Timeout(ms) => Some(Duration::from_millis(ms as u64)),
+    /// Refresh the standard screen.
+    pub fn refresh(&mut self) -> Result<()> {
+        // Copy stdscr to newscr then update
+        self.stdscr_to_newscr()?;
+        self.doupdate()
+    }
+
+    /// Copy cells `first..=last` of `line` into row `screen_y` of `newscr`,
+    /// starting at column `screen_x`.
+    ///
+    /// Cells past the end of either line are skipped. This is the copy step
+    /// shared by `wnoutrefresh()`, `pnoutrefresh()` and `refresh()`.
+    fn copy_span(
+        newscr: &mut Window,
+        line: &LineData,
+        first: usize,
+        last: usize,
+        screen_y: usize,
+        screen_x: i32,
+    ) {
+        let Some(newscr_line) = newscr.line_mut(screen_y) else {
+            return;
         };
+        let last = last.min(line.width().saturating_sub(1));
+        for x in first..=last {
+            let target = screen_x + (x - first) as i32;
+            if target < 0 {
+                continue;
+            }
+            if target as usize >= newscr_line.width() {
+                break;
+            }
+            newscr_line.set(target as usize, line.get(x));
+        }
+    }
 
-        let start = Instant::now();
+    /// Copy stdscr to the new screen buffer and reset its touch state.
+    fn stdscr_to_newscr(&mut self) -> Result<()> {
+        self.stdscr.apply_synced();
+        let (begy, begx) = self.stdscr.origin();
 
-        loop {
-            // Check if input is available
-            if !self.terminal.has_input() {
-                // For NoDelay mode, return immediately if no input
-                if timeout == Some(Duration::ZERO) {
-                    return Err(Error::NoInput);
-                }
-                // Check for timeout (only for non-zero timeouts)
-                if let Some(t) = timeout {
-                    if start.elapsed() >= t {
-                        return Err(Error::Timeout);
+        for y in self.stdscr.touched_lines() {
+            #[cfg(test)]
+            {
+                self.lines_copied += 1;
+            }
+            if let Some(line) = self.stdscr.line(y) {
+                if let Some((first, last)) = line.changed_range() {
+                    let screen_y = begy as usize + y;
+                    let screen_x = begx + first as i32;
+                    Self::copy_span(&mut self.newscr, line, first, last, screen_y, screen_x);
+                    if !self.stdscr.is_idcok() {
+                        self.no_idc_rows.insert(screen_y);
                     }
                 }
-                // Brief sleep to avoid busy waiting
-                std::thread::sleep(Duration::from_millis(1));
-                continue;
             }
+        }
+        self.stdscr.untouchwin();
 
-            // Read a byte
-            let byte = match self.terminal.read_byte()? {
-                Some(b) => b,
-                None => return Err(Error::Eof),
-            };
+        // Ripped-off lines are owned by the screen, so refresh them here
+        let mut ripoffs = std::mem::take(&mut self.ripoff_windows);
+        let result = ripoffs
+            .iter_mut()
+            .try_for_each(|win| self.wnoutrefresh_untouch(win));
+        self.ripoff_windows = ripoffs;
+        result
+    }
 
-            // Handle escape sequences if keypad mode is enabled
-            if use_keypad && byte == 0x1b {
-                // Start escape sequence parsing
-                self.escape_parser.reset();
-                let result = self.parse_escape_sequence()?;
-                return Ok(result);
-            }
+    /// Refresh a window (copy to physical screen).
+    pub fn wrefresh(&mut self, win: &mut Window) -> Result<()> {
+        // Copy window to newscr
+        self.wnoutrefresh_untouch(win)?;
+        // Update the physical screen
+        self.doupdate()
+    }
 
-            // Handle newline translation
-            if self.input_mode.nl && byte == b'\r' {
-                return Ok(b'\n' as i32);
-            }
+    /// Copy stdscr to the virtual screen (but don't update physical screen).
+    ///
+    /// This is the stdscr form of [`wnoutrefresh()`](Self::wnoutrefresh).
+    pub fn noutrefresh(&mut self) -> Result<()> {
+        self.stdscr_to_newscr()
+    }
 
-            return Ok(byte as i32);
-        }
+    /// Copy a window to the virtual screen, then reset its touch state.
+    ///
+    /// Like ncurses, the window is marked unchanged once its changes are in
+    /// the virtual screen, so a second call before `doupdate()` only copies
+    /// what changed since the first. [`wrefresh()`](Self::wrefresh) copies
+    /// this way.
+    pub fn wnoutrefresh_untouch(&mut self, win: &mut Window) -> Result<()> {
+        win.apply_synced();
+        self.wnoutrefresh(win)?;
+        win.untouchwin();
+        win.take_clear_flag();
+        Ok(())
     }
 
-    /// Parse an escape sequence after receiving ESC.
-    fn parse_escape_sequence(&mut self) -> Result<i32> {
-        self.escape_parser.reset();
-        self.escape_parser.feed(0x1b);
+    /// Copy a window to the virtual screen (but don't update physical screen).
+    ///
+    /// The window's changed ranges stay intact, so they can be copied again,
+    /// for example after another window has been drawn over the same area.
+    /// Use [`wnoutrefresh_untouch()`](Self::wnoutrefresh_untouch) to reset
+    /// them.
+    ///
+    /// Pads have no place on the screen of their own; this fails with
+    /// `Error::InvalidArgument` for them. Use
+    /// [`pnoutrefresh()`](Self::pnoutrefresh) instead.
+    pub fn wnoutrefresh(&mut self, win: &Window) -> Result<()> {
+        if win.is_pad() {
+            return Err(Error::InvalidArgument(
+                "wnoutrefresh cannot place a pad; use pnoutrefresh".into(),
+            ));
+        }
 
-        let start = Instant::now();
-        let escape_timeout = Duration::from_millis(self.escdelay as u64);
+        // Copy changed portions of win to newscr
+        let (begy, begx) = win.origin();
 
-        // Buffer to accumulate the sequence for mouse parsing
-        let mut sequence_buf: Vec<u8> = vec![0x1b];
+        for y in win.touched_lines() {
+            let screen_y = begy as usize + y;
+            if screen_y >= self.newscr.getmaxy() as usize {
+                break;
+            }
+            #[cfg(test)]
+            {
+                self.lines_copied += 1;
+            }
 
-        loop {
-            // Check timeout
-            if start.elapsed() >= escape_timeout {
-                // Timeout - return the accumulated input
-                let input = self.escape_parser.current_input();
-                if input.len() == 1 {
-                    // Just ESC
-                    return Ok(0x1b);
-                }
-                // Return current match if any, otherwise just ESC
-                if let Some(key) = self.escape_parser.current_match() {
-                    return Ok(key);
-                }
-                // Push remaining bytes back to buffer (except ESC which we return)
-                for &b in &input[1..] {
-                    self.input_buffer.push(b as i32);
+            if let Some(line) = win.line(y) {
+                if let Some((first, last)) = line.changed_range() {
+                    let screen_x = begx + first as i32;
+                    Self::copy_span(&mut self.newscr, line, first, last, screen_y, screen_x);
+                    if !win.is_idcok() {
+                        self.no_idc_rows.insert(screen_y);
+                    }
                 }
-                return Ok(0x1b);
             }
+        }
 
-            // Check for more input
-            if !self.terminal.has_input() {
-                std::thread::sleep(Duration::from_millis(1));
+        // Cells synced up from subwindows show before the window applies them
+        for (y, x, cell) in win.synced_cells() {
+            let screen_x = begx + x as i32;
+            if screen_x < 0 {
                 continue;
             }
-
-            let byte = match self.terminal.read_byte()? {
-                Some(b) => b,
-                None => {
-                    // EOF during escape - return what we have
-                    if let Some(key) = self.escape_parser.current_match() {
-                        return Ok(key);
-                    }
-                    return Ok(0x1b);
-                }
-            };
-
-            sequence_buf.push(byte);
-
-            // Check for SGR mouse sequence: \x1b[<...M or \x1b[<...m
-            #[cfg(feature = "mouse")]
-            if self.mouse.is_enabled() && is_mouse_prefix(&sequence_buf) {
-                // Check if we have a complete mouse sequence
-                if sequence_buf.len() >= 3 && &sequence_buf[0..3] == b"\x1b[<" {
-                    // Look for terminator
-                    if byte == b'M' || byte == b'm' {
-                        // Complete mouse sequence
-                        if let Some(event) = parse_sgr_mouse(&sequence_buf) {
-                            self.mouse.push_event(event);
-                            return Ok(KEY_MOUSE);
-                        }
-                    }
-                    // Continue accumulating if not complete
-                    if sequence_buf.len() < 20 {
-                        // Reasonable max length for mouse sequence
-                        continue;
-                    }
+            if let Some(line) = self.newscr.line_mut(begy as usize + y) {
+                line.set(screen_x as usize, cell);
+                if !win.is_idcok() {
+                    self.no_idc_rows.insert(begy as usize + y);
                 }
             }
+        }
 
-            match self.escape_parser.feed(byte) {
-                EscapeMatch::Complete(key) => {
-                    return Ok(key);
-                }
-                EscapeMatch::None => {
-                    // No match - return ESC and push rest to buffer
-                    let input = self.escape_parser.current_input();
-                    if input.len() > 1 {
-                        for &b in &input[1..] {
-                            self.input_buffer.push(b as i32);
-                        }
-                    }
-                    self.input_buffer.push(byte as i32);
-                    return Ok(0x1b);
-                }
-                EscapeMatch::Partial => {
-                    // Continue reading
-                }
-            }
+        // clearok on any window repaints the whole screen
+        if win.is_cleared() {
+            self.newscr.clearok(true);
         }
+
+        Ok(())
     }
 
-    /// Push a character back into the input buffer.
-    pub fn ungetch(&mut self, ch: i32) -> Result<()> {
-        if self.input_buffer.unget(ch) {
-            Ok(())
-        } else {
-            Err(Error::BufferFull)
+    /// Take a pending request to clear and repaint the whole screen.
+    ///
+    /// Set by `clearok(true)` on stdscr, curscr or a refreshed window. The
+    /// windows keep their contents; all of newscr is marked to be redrawn
+    /// once the terminal is cleared.
+    fn take_clear_request(&mut self) -> bool {
+        let do_clear = self.stdscr.take_clear_flag()
+            | self.newscr.take_clear_flag()
+            | self.curscr.take_clear_flag();
+        if do_clear {
+            self.newscr.touchwin();
         }
+        do_clear
     }
 
-    /// Push a wide character back into the input buffer.
+    /// Start batching screen updates.
     ///
-    /// This converts the wide character to its UTF-8 representation and
-    /// pushes each byte back in reverse order so they will be read in
-    /// the correct order.
-    #[cfg(feature = "wide")]
-    pub fn unget_wch(&mut self, wch: char) -> Result<()> {
-        let mut buf = [0u8; 4];
-        let encoded = wch.encode_utf8(&mut buf);
-        let bytes = encoded.as_bytes();
+    /// Until the matching [`end_batch()`](Self::end_batch), `refresh()`,
+    /// `wrefresh()` and `doupdate()` only update the virtual screen; the
+    /// terminal is written once when the outermost batch ends. Batches nest.
+    pub fn begin_batch(&mut self) {
+        self.batch_depth += 1;
+    }
 
-        // Push bytes in reverse order
-        for &byte in bytes.iter().rev() {
-            if !self.input_buffer.unget(byte as i32) {
-                return Err(Error::BufferFull);
+    /// End a batch started with [`begin_batch()`](Self::begin_batch).
+    ///
+    /// Ending the outermost batch runs a single `doupdate()`, followed by
+    /// the `flash()` if one was requested during the batch. Returns an
+    /// error if no batch is open.
+    pub fn end_batch(&mut self) -> Result<()> {
+        if self.batch_depth == 0 {
+            return Err(Error::InvalidArgument("no batch in progress".into()));
+        }
+        self.batch_depth -= 1;
+        if self.batch_depth == 0 {
+            self.doupdate()?;
+            if std::mem::take(&mut self.flash_pending) {
+                self.flash()?;
             }
         }
         Ok(())
     }
 
-    /// Check if there's typeahead input.
-    pub fn has_key(&self) -> bool {
-        self.input_buffer.has_input() || self.terminal.has_input()
+    /// Check if screen updates are currently being batched.
+    pub fn is_batching(&self) -> bool {
+        self.batch_depth > 0
     }
 
-    /// Flush the input buffer.
-    pub fn flushinp(&mut self) {
-        self.input_buffer.clear();
-        // Also try to drain the terminal input
-        while self.terminal.has_input() {
-            let _ = self.terminal.read_byte();
+    /// Update the physical screen from the virtual screen.
+    ///
+    /// Inside a batch this does nothing; the update happens at `end_batch()`.
+    pub fn doupdate(&mut self) -> Result<()> {
+        if self.batch_depth > 0 {
+            return Ok(());
         }
-    }
-
-    /// Get a string from the user with simple line editing (using stdscr).
-    pub fn getstr(&mut self, maxlen: usize) -> Result<String> {
-        let mut result = String::new();
-        let echo_enabled = self.input_mode.echo;
 
-        // Get delay and keypad settings from stdscr
-        let delay = Delay::from_raw(self.stdscr.getdelay());
-        let use_keypad = self.stdscr.is_keypad();
+        // Check if we need to clear the screen first
+        let do_clear = self.take_clear_request();
 
-        loop {
-            let ch = self.getch_internal(delay, use_keypad)?;
+        // Show the frame at once; skip the markers when nothing changed
+        let sync = self.synchronized_output && (do_clear || self.newscr.is_wintouched());
+        if sync {
+            self.terminal.begin_sync()?;
+        }
 
-            match ch {
-                // Enter/Return
-                0x0a | 0x0d => {
-                    if echo_enabled {
-                        self.stdscr.addch(b'\n' as ChType)?;
-                        self.refresh()?;
-                    }
-                    break;
-                }
-                // Backspace
-                0x08 | 0x7f => {
-                    if !result.is_empty() {
-                        result.pop();
-                        if echo_enabled {
-                            let (y, x) = (self.stdscr.getcury(), self.stdscr.getcurx());
-                            if x > 0 {
-                                self.stdscr.mv(y, x - 1)?;
-                                self.stdscr.addch(b' ' as ChType)?;
-                                self.stdscr.mv(y, x - 1)?;
-                                self.refresh()?;
-                            }
-                        }
-                    }
-                }
-                // Regular character
-                _ if (0x20..0x7f).contains(&ch) => {
-                    if result.len() < maxlen {
-                        result.push(ch as u8 as char);
-                        if echo_enabled {
-                            self.stdscr.addch(ch as ChType)?;
-                            self.refresh()?;
-                        }
-                    }
-                }
-                // Control-C, Control-D - cancel
-                0x03 | 0x04 => {
-                    return Err(Error::Interrupted);
-                }
-                _ => {}
-            }
+        // Always end the frame, or the terminal would stop showing output
+        let drawn = self.draw_update(do_clear);
+        if sync {
+            let ended = self.terminal.end_sync();
+            drawn?;
+            ended?;
+        } else {
+            drawn?;
         }
 
-        Ok(result)
+        // Flush output
+        self.terminal.flush()?;
+
+        self.commit_newscr();
+
+        Ok(())
     }
 
-    /// Get a string from a window with simple line editing.
-    pub fn wgetstr(&mut self, win: &mut Window, maxlen: usize) -> Result<String> {
-        let mut result = String::new();
-        let echo_enabled = self.input_mode.echo;
+    /// Write the changes from curscr to newscr to the terminal and place the
+    /// cursor, clearing the terminal first if `do_clear` is set.
+    fn draw_update(&mut self, do_clear: bool) -> Result<()> {
+        if do_clear {
+            self.terminal.clear_screen()?;
+            self.curscr.erase()?;
+            self.curscr.touchwin();
+        }
 
-        // Get delay and keypad settings from window
-        let delay = Delay::from_raw(win.getdelay());
-        let use_keypad = win.is_keypad();
+        // Where the cursor was left, or -1 if unknown
+        let (mut current_y, mut current_x) = if self.idc_update && !do_clear {
+            self.shift_chars()?
+        } else {
+            (-1, -1)
+        };
+        self.no_idc_rows.clear();
 
-        loop {
-            let ch = self.getch_internal(delay, use_keypad)?;
+        // With background color erase, a trailing run of blanks can be
+        // cleared with a single el once the run's color has been selected.
+        let bce = self.tigetflag("bce") == 1;
+        let changes = self.collect_updates(do_clear, Some(bce));
 
-            match ch {
-                // Enter/Return
-                0x0a | 0x0d => {
-                    if echo_enabled {
-                        win.addch(b'\n' as ChType)?;
-                        self.wrefresh(win)?;
+        // Now output the changes
+        let mut last_attr: AttrT = A_NORMAL;
+        let mut in_acs = false;
+
+        for update in changes {
+            let (y, x, cell) = match update {
+                Update::Cell(y, x, cell) => (y, x, cell),
+                Update::ClearEol(y, x, attr) => {
+                    self.terminal
+                        .move_cursor_from(current_y, current_x, y as i32, x as i32)?;
+                    current_y = y as i32;
+                    current_x = x as i32;
+                    if attr != last_attr {
+                        self.output_attr(attr)?;
+                        last_attr = attr;
                     }
-                    break;
+                    self.terminal.clear_to_eol()?;
+                    continue;
                 }
-                // Backspace
-                0x08 | 0x7f => {
-                    if !result.is_empty() {
-                        result.pop();
-                        if echo_enabled {
-                            let (y, x) = (win.getcury(), win.getcurx());
-                            if x > 0 {
-                                win.mv(y, x - 1)?;
-                                win.addch(b' ' as ChType)?;
-                                win.mv(y, x - 1)?;
-                                self.wrefresh(win)?;
-                            }
-                        }
-                    }
+            };
+
+            // The right half of a double-width character was drawn with its
+            // left half; writing anything here would break it
+            #[cfg(feature = "wide")]
+            if cell.is_null() && x > 0 {
+                let left = self.newscr.line(y).map(|line| line.get(x - 1));
+                if left.is_some_and(|left| left.is_wide()) {
+                    continue;
                 }
-                // Regular character
-                _ if (0x20..0x7f).contains(&ch) => {
-                    if result.len() < maxlen {
-                        result.push(ch as u8 as char);
-                        if echo_enabled {
-                            win.addch(ch as ChType)?;
-                            self.wrefresh(win)?;
-                        }
-                    }
+            }
+
+            // Move cursor if needed, rewriting a short unchanged gap on the
+            // same line when that is cheaper
+            let gap = (current_y == y as i32 && !in_acs)
+                .then(|| self.gap_text(y, current_x, x, last_attr))
+                .flatten();
+            match gap {
+                Some(text) => self.terminal.write(&text)?,
+                None => self
+                    .terminal
+                    .move_cursor_from(current_y, current_x, y as i32, x as i32)?,
+            }
+            current_y = y as i32;
+            current_x = x as i32;
+
+            #[cfg(not(feature = "wide"))]
+            {
+                // Handle attributes
+                let new_attr = cell & !A_CHARTEXT;
+                if new_attr != last_attr {
+                    self.output_attr(new_attr)?;
+                    last_attr = new_attr;
                 }
-                // Control-C, Control-D - cancel
-                0x03 | 0x04 => {
-                    return Err(Error::Interrupted);
+
+                // Output the character
+                let c = (cell & A_CHARTEXT) as u8;
+                if cell & attr::A_ALTCHARSET != 0 {
+                    match acs::vt100_unicode(c) {
+                        Some(line) => self.output_acs_char(line, &mut in_acs)?,
+                        None => self.terminal.write(&[c])?,
+                    }
+                } else {
+                    self.set_acs(false, &mut in_acs)?;
+                    if c >= 0x20 && c < 0x7f {
+                        self.terminal.write(&[c])?;
+                    } else if c == 0 {
+                        self.terminal.write(b" ")?;
+                    } else {
+                        // Control character or high byte - output as space
+                        self.terminal.write(b" ")?;
+                    }
                 }
-                _ => {}
             }
-        }
 
-        Ok(result)
-    }
+            #[cfg(not(feature = "wide"))]
+            let width = 1;
 
-    // ========================================================================
-    // Convenience methods for stdscr operations
-    // ========================================================================
+            #[cfg(feature = "wide")]
+            let width = {
+                // Handle attributes
+                let new_attr = cell.attrs();
+                if new_attr != last_attr {
+                    self.output_attr(new_attr)?;
+                    last_attr = new_attr;
+                }
 
-    /// Move cursor to position (y, x) in stdscr.
-    pub fn mv(&mut self, y: i32, x: i32) -> Result<()> {
-        self.stdscr.mv(y, x)
-    }
+                // Output the character
+                let c = cell.spacing_char();
+                if c == '\0' {
+                    self.set_acs(false, &mut in_acs)?;
+                    self.terminal.write(b" ")?;
+                } else {
+                    self.output_acs_char(c, &mut in_acs)?;
+                }
+                cell.width().max(1) as i32
+            };
 
-    /// Add a character at the current cursor position in stdscr.
-    pub fn addch(&mut self, ch: ChType) -> Result<()> {
-        self.stdscr.addch(ch)
-    }
+            current_x += width;
+        }
 
-    /// Add a string at the current cursor position in stdscr.
-    pub fn addstr(&mut self, s: &str) -> Result<()> {
-        self.stdscr.addstr(s)
-    }
+        self.set_acs(false, &mut in_acs)?;
 
-    /// Move to (y, x) and add a character in stdscr.
-    pub fn mvaddch(&mut self, y: i32, x: i32, ch: ChType) -> Result<()> {
-        self.stdscr.mv(y, x)?;
-        self.stdscr.addch(ch)
-    }
+        // Reset attributes
+        if last_attr != A_NORMAL {
+            self.terminal.set_attributes(A_NORMAL)?;
+        }
 
-    /// Move to (y, x) and add a string in stdscr.
-    pub fn mvaddstr(&mut self, y: i32, x: i32, s: &str) -> Result<()> {
-        self.stdscr.mv(y, x)?;
-        self.stdscr.addstr(s)
-    }
+        // Position cursor at stdscr's cursor position, which starts below
+        // any lines ripped off the top
+        let (begy, begx) = self.stdscr.origin();
+        let cursor_y = begy + self.stdscr.getcury();
+        let cursor_x = begx + self.stdscr.getcurx();
+        if !self.stdscr.is_leaveok() {
+            self.terminal
+                .move_cursor_from(current_y, current_x, cursor_y, cursor_x)?;
+        }
 
-    /// Add a wide string at the current cursor position in stdscr.
-    ///
-    /// This is the Rust equivalent of `addwstr()`.
-    #[cfg(feature = "wide")]
-    pub fn addwstr(&mut self, s: &str) -> Result<()> {
-        self.stdscr.addwstr(s)
+        Ok(())
     }
 
-    /// Add a wide string with a maximum length in stdscr.
+    /// Text that redraws the unchanged cells of row `y` from `from_x` up to
+    /// `x`, if that is shorter than a relative cursor move over them.
     ///
-    /// This is the Rust equivalent of `addnwstr()`.
-    #[cfg(feature = "wide")]
-    pub fn addnwstr(&mut self, s: &str, n: i32) -> Result<()> {
-        self.stdscr.addnwstr(s, n)
-    }
+    /// Only plain single-width ASCII cells drawn with `attr` qualify, since
+    /// they can be written without any other output.
+    fn gap_text(&self, y: usize, from_x: i32, x: usize, attr: AttrT) -> Option<Vec<u8>> {
+        // Rewriting is only shorter than "\x1b[nC" for up to three cells
+        let from_x = usize::try_from(from_x).ok()?;
+        if x <= from_x || x - from_x > 3 {
+            return None;
+        }
+        let line = self.newscr.line(y)?;
+        (from_x..x)
+            .map(|gx| {
+                let cell = line.get(gx);
+                #[cfg(not(feature = "wide"))]
+                let (same_attr, c) = (
+                    cell & !A_CHARTEXT == attr,
+                    (cell & A_CHARTEXT) as u8 as char,
+                );
+                #[cfg(feature = "wide")]
+                let (same_attr, c) = (
+                    cell.attrs() == attr && cell.combining().next().is_none(),
+                    cell.spacing_char(),
+                );
+                (same_attr && (' '..='~').contains(&c)).then_some(c as u8)
+            })
+            .collect()
+    }
+
+    /// Compute the pending changes without writing them to the terminal.
+    ///
+    /// This runs the same `curscr`/`newscr` comparison as [`doupdate()`](Self::doupdate)
+    /// and then marks the changes as applied, so embedders can drive a
+    /// non-terminal backend (a GUI grid, a test harness) from the usual window
+    /// code: update windows, call `wnoutrefresh()`/`noutrefresh()`, then take
+    /// the diff instead of calling `doupdate()`.
+    pub fn take_render_diff(&mut self) -> Vec<CellChange> {
+        let do_clear = self.take_clear_request();
+        if do_clear {
+            let _ = self.curscr.erase();
+            self.curscr.touchwin();
+        }
 
-    /// Move to (y, x) and add a wide string in stdscr.
-    #[cfg(feature = "wide")]
-    pub fn mvaddwstr(&mut self, y: i32, x: i32, s: &str) -> Result<()> {
-        self.stdscr.mvaddwstr(y, x, s)
-    }
+        let changes = self
+            .collect_updates(do_clear, None)
+            .into_iter()
+            .filter_map(|update| match update {
+                #[cfg(not(feature = "wide"))]
+                Update::Cell(y, x, cell) => Some(CellChange {
+                    y: y as i32,
+                    x: x as i32,
+                    ch: (cell & A_CHARTEXT) as u8 as char,
+                    attr: cell & !A_CHARTEXT,
+                }),
+                #[cfg(feature = "wide")]
+                Update::Cell(y, x, cell) => Some(CellChange {
+                    y: y as i32,
+                    x: x as i32,
+                    ch: cell.spacing_char(),
+                    text: if cell.is_null() {
+                        String::new()
+                    } else {
+                        std::iter::once(cell.spacing_char())
+                            .chain(cell.combining())
+                            .collect()
+                    },
+                    attr: cell.attrs(),
+                }),
+                Update::ClearEol(..) => None,
+            })
+            .collect();
+
+        self.commit_newscr();
+        changes
+    }
+
+    /// Collect the cells of newscr that differ from curscr.
+    ///
+    /// When `bce` is given, trailing blank runs are turned into
+    /// `Update::ClearEol` where that is cheaper; `None` always yields
+    /// individual cells.
+    fn collect_updates(&self, do_clear: bool, bce: Option<bool>) -> Vec<Update<CellData>> {
+        let lines = self.newscr.getmaxy() as usize;
+        let cols = self.newscr.getmaxx() as usize;
 
-    /// Move to (y, x) and add a wide string with a maximum length in stdscr.
-    #[cfg(feature = "wide")]
-    pub fn mvaddnwstr(&mut self, y: i32, x: i32, s: &str, n: i32) -> Result<()> {
-        self.stdscr.mvaddnwstr(y, x, s, n)
-    }
+        let mut changes: Vec<Update<CellData>> = Vec::new();
 
-    /// Add a wide string at the current cursor position in a window.
-    ///
-    /// This is the Rust equivalent of `waddwstr()`.
-    #[cfg(feature = "wide")]
-    pub fn waddwstr(&mut self, win: &mut Window, s: &str) -> Result<()> {
-        win.addwstr(s)
-    }
+        for y in 0..lines {
+            let newscr_line = match self.newscr.line(y) {
+                Some(l) => l,
+                None => continue,
+            };
+            let curscr_line = match self.curscr.line(y) {
+                Some(l) => l,
+                None => continue,
+            };
 
-    /// Add a wide string with a maximum length in a window.
-    ///
-    /// This is the Rust equivalent of `waddnwstr()`.
-    #[cfg(feature = "wide")]
-    pub fn waddnwstr(&mut self, win: &mut Window, s: &str, n: i32) -> Result<()> {
-        win.addnwstr(s, n)
-    }
+            // Check if line has changes
+            if !newscr_line.is_touched() {
+                continue;
+            }
 
-    /// Move cursor and add a wide string in a window.
-    #[cfg(feature = "wide")]
-    pub fn mvwaddwstr(&mut self, win: &mut Window, y: i32, x: i32, s: &str) -> Result<()> {
-        win.mvaddwstr(y, x, s)
-    }
+            if let Some((first, last)) = newscr_line.changed_range() {
+                let last = last.min(cols - 1);
+                let (clear_from, clear_attr) = match bce {
+                    Some(bce) => Self::erasable_tail(newscr_line, first, cols, bce),
+                    None => (cols, A_NORMAL),
+                };
+
+                // Only worth it when el is shorter than the blanks it replaces
+                let pending = (clear_from..=last)
+                    .filter(|&x| newscr_line.get(x) != curscr_line.get(x) || do_clear)
+                    .count();
+                let clear_from = if pending > CLEAR_EOL_LEN {
+                    clear_from
+                } else {
+                    cols
+                };
 
-    /// Move cursor and add a wide string with a maximum length in a window.
-    #[cfg(feature = "wide")]
-    pub fn mvwaddnwstr(&mut self, win: &mut Window, y: i32, x: i32, s: &str, n: i32) -> Result<()> {
-        win.mvaddnwstr(y, x, s, n)
-    }
+                for x in first..clear_from.min(last + 1) {
+                    let new_cell = newscr_line.get(x);
+                    let cur_cell = curscr_line.get(x);
 
-    /// Turn on attributes in stdscr.
-    pub fn attron(&mut self, attr: AttrT) -> Result<()> {
-        self.stdscr.attron(attr)
-    }
+                    if new_cell != cur_cell || do_clear {
+                        changes.push(Update::Cell(y, x, new_cell));
+                    }
+                }
+                if clear_from < cols {
+                    changes.push(Update::ClearEol(y, clear_from, clear_attr));
+                }
+            }
+        }
 
-    /// Turn off attributes in stdscr.
-    pub fn attroff(&mut self, attr: AttrT) -> Result<()> {
-        self.stdscr.attroff(attr)
+        changes
     }
 
-    /// Set attributes in stdscr.
-    pub fn attrset(&mut self, attr: AttrT) -> Result<()> {
-        self.stdscr.attrset(attr)
-    }
+    /// Copy newscr to curscr and clear the touch flags after an update.
+    fn commit_newscr(&mut self) {
+        let lines = self.newscr.getmaxy() as usize;
+        for y in 0..lines {
+            if let (Some(newscr_line), Some(curscr_line)) =
+                (self.newscr.line(y), self.curscr.line_mut(y))
+            {
+                curscr_line.copy_from(newscr_line);
+                curscr_line.untouch();
+            }
+            if let Some(newscr_line) = self.newscr.line_mut(y) {
+                newscr_line.untouch();
+            }
+        }
 
-    /// Clear the stdscr.
-    pub fn clear(&mut self) -> Result<()> {
-        self.stdscr.clear()
+        // Clear touch flags on stdscr
+        self.stdscr.untouchwin();
     }
 
-    /// Erase the stdscr.
-    pub fn erase(&mut self) -> Result<()> {
-        self.stdscr.erase()
-    }
+    /// Find the trailing run of blanks on a line that `el` can erase.
+    ///
+    /// Returns the column where the run starts (never before `first`) and its
+    /// attributes, or `cols` when the line does not end in such a run. Blanks
+    /// carrying a color pair only qualify when the terminal has `bce`, and
+    /// blanks with video attributes never do since `el` cannot reproduce them.
+    fn erasable_tail(line: &LineData, first: usize, cols: usize, bce: bool) -> (usize, AttrT) {
+        #[cfg(not(feature = "wide"))]
+        let blank_attr = |x: usize| {
+            let cell = line.get(x);
+            ((cell & A_CHARTEXT) == b' ' as ChType).then_some(cell & !A_CHARTEXT)
+        };
+        #[cfg(feature = "wide")]
+        let blank_attr = |x: usize| {
+            let cell = line.get(x);
+            (cell.chars[0] == ' ' && cell.chars[1] == '\0').then_some(cell.attrs())
+        };
 
-    /// Clear to end of line in stdscr.
-    pub fn clrtoeol(&mut self) -> Result<()> {
-        self.stdscr.clrtoeol()
-    }
+        let attr = match blank_attr(cols - 1) {
+            Some(attr) => attr,
+            None => return (cols, A_NORMAL),
+        };
+        if attr & !A_COLOR != 0 || (attr::pair_number(attr) != 0 && !bce) {
+            return (cols, A_NORMAL);
+        }
 
-    /// Clear to bottom of screen in stdscr.
-    pub fn clrtobot(&mut self) -> Result<()> {
-        self.stdscr.clrtobot()
+        let mut start = cols - 1;
+        while start > first && blank_attr(start - 1) == Some(attr) {
+            start -= 1;
+        }
+        (start, attr)
     }
 
-    /// Draw a border around stdscr.
-    #[allow(clippy::too_many_arguments)]
-    pub fn border(
-        &mut self,
-        ls: ChType,
-        rs: ChType,
-        ts: ChType,
-        bs: ChType,
-        tl: ChType,
-        tr: ChType,
-        bl: ChType,
-        br: ChType,
-    ) -> Result<()> {
-        self.stdscr.border(ls, rs, ts, bs, tl, tr, bl, br)
-    }
+    /// Shift changed lines on the terminal with insert/delete character.
+    ///
+    /// For each changed line that differs from `curscr` only by a run of
+    /// inserted or deleted cells, emit `ich1`/`dch1` at that column and apply
+    /// the same shift to `curscr`, so the normal diff that follows only has
+    /// to draw the inserted cells instead of the whole shifted tail. Returns
+    /// the resulting cursor position, or `(-1, -1)` if nothing was emitted.
+    fn shift_chars(&mut self) -> Result<(i32, i32)> {
+        #[cfg(not(feature = "wide"))]
+        let blank = b' ' as ChType;
+        #[cfg(feature = "wide")]
+        let blank = CellData::from_char(' ');
 
-    /// Draw a box around stdscr using default characters.
-    pub fn r#box(&mut self, verch: ChType, horch: ChType) -> Result<()> {
-        self.stdscr.box_(verch, horch)
-    }
+        let mut cursor = (-1, -1);
+        for y in 0..self.newscr.getmaxy() as usize {
+            if self.no_idc_rows.contains(&y) {
+                continue;
+            }
+            let shift = match (self.newscr.line(y), self.curscr.line(y)) {
+                (Some(new), Some(cur)) if new.is_touched() => {
+                    // Shifting cells would split double-width characters
+                    #[cfg(feature = "wide")]
+                    if new.text().iter().chain(cur.text()).any(|c| c.width() != 1) {
+                        continue;
+                    }
+                    Self::find_char_shift(cur.text(), new.text())
+                }
+                _ => None,
+            };
+            let Some((x, n)) = shift else {
+                continue;
+            };
 
-    /// Draw a horizontal line in stdscr.
-    pub fn hline(&mut self, ch: ChType, n: i32) -> Result<()> {
-        self.stdscr.hline(ch, n)
+            self.terminal.move_cursor(y as i32, x as i32)?;
+            cursor = (y as i32, x as i32);
+            for _ in 0..n.unsigned_abs() {
+                if n > 0 {
+                    self.terminal.insert_char()?;
+                } else {
+                    self.terminal.delete_char()?;
+                }
+            }
+            if let Some(cur) = self.curscr.line_mut(y) {
+                if n > 0 {
+                    cur.insert(x, blank, n as usize);
+                } else {
+                    cur.delete(x, n.unsigned_abs(), blank);
+                }
+            }
+        }
+        Ok(cursor)
     }
 
-    /// Draw a vertical line in stdscr.
-    pub fn vline(&mut self, ch: ChType, n: i32) -> Result<()> {
-        self.stdscr.vline(ch, n)
-    }
+    /// Find a run of cells inserted into or deleted from a line.
+    ///
+    /// Returns `(x, n)` when `new` is `old` with `n` cells inserted at column
+    /// `x` (`n > 0`) or `-n` cells deleted there (`n < 0`), and shifting the
+    /// tail costs fewer bytes than redrawing it.
+    fn find_char_shift<C: PartialEq>(old: &[C], new: &[C]) -> Option<(usize, isize)> {
+        let width = old.len().min(new.len());
+        let x = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+        if x == width {
+            return None;
+        }
 
-    /// Get current cursor Y position in stdscr.
-    pub fn getcury(&self) -> i32 {
-        self.stdscr.getcury()
+        let mut k = 1;
+        while k * SHIFT_CHAR_LEN < width - x - k {
+            if new[x + k..width] == old[x..width - k] {
+                return Some((x, k as isize));
+            }
+            if new[x..width - k] == old[x + k..width] {
+                return Some((x, -(k as isize)));
+            }
+            k += 1;
+        }
+        None
     }
 
-    /// Get current cursor X position in stdscr.
-    pub fn getcurx(&self) -> i32 {
-        self.stdscr.getcurx()
+    /// Write a character, using the line-drawing set for it in VT100 mode.
+    fn output_acs_char(&mut self, c: char, in_acs: &mut bool) -> Result<()> {
+        let code = match self.acs_mode {
+            AcsMode::Vt100 => acs::vt100_code(c),
+            AcsMode::Unicode => None,
+        };
+        self.set_acs(code.is_some(), in_acs)?;
+        match code {
+            Some(code) => self.terminal.write(&[code]),
+            None => {
+                let mut buf = [0u8; 4];
+                self.terminal.write(c.encode_utf8(&mut buf).as_bytes())
+            }
+        }
     }
 
-    /// Get current cursor position in stdscr (y, x).
-    pub fn getyx(&self) -> (i32, i32) {
-        (self.stdscr.getcury(), self.stdscr.getcurx())
+    /// Switch the terminal into or out of the line-drawing set if needed.
+    fn set_acs(&mut self, on: bool, in_acs: &mut bool) -> Result<()> {
+        if on != *in_acs {
+            if on {
+                self.terminal.enter_acs()?;
+            } else {
+                self.terminal.exit_acs()?;
+            }
+            *in_acs = on;
+        }
+        Ok(())
     }
 
-    /// Get maximum Y coordinate in stdscr.
-    pub fn getmaxy(&self) -> i32 {
-        self.stdscr.getmaxy()
+    /// Output attribute changes to the terminal.
+    fn output_attr(&mut self, attr: AttrT) -> Result<()> {
+        let seq = self.attr_sequence(attr);
+        self.terminal.write_str(&seq)
     }
 
-    /// Get maximum X coordinate in stdscr.
-    pub fn getmaxx(&self) -> i32 {
-        self.stdscr.getmaxx()
-    }
+    /// SGR sequences selecting `attr`, including its color pair.
+    fn attr_sequence(&self, attr: AttrT) -> String {
+        let pair = attr::pair_number(attr);
 
-    /// Enable scrolling in stdscr.
-    pub fn scrollok(&mut self, bf: bool) {
-        self.stdscr.scrollok(bf);
-    }
+        // Without colors the pair becomes video attributes
+        if !self.colors.has_colors() {
+            let fallback = match self.colors.pair_content(pair) {
+                Ok((fg, bg)) if pair > 0 => self.mono_fallback.attrs_for(fg, bg),
+                _ => A_NORMAL,
+            };
+            return sgr_attributes(attr | fallback);
+        }
 
-    /// Set scrolling region in stdscr.
-    pub fn setscrreg(&mut self, top: i32, bot: i32) -> Result<()> {
-        self.stdscr.setscrreg(top, bot)
-    }
+        // Set text attributes
+        let mut seq = sgr_attributes(attr);
 
-    /// Scroll stdscr by n lines.
-    pub fn scroll(&mut self, n: i32) -> Result<()> {
-        self.stdscr.scrl(n)
-    }
+        // Handle color pair
+        let rgb = self
+            .colors
+            .pair_rgb(pair)
+            .filter(|_| self.terminal.has_truecolor());
+        if let Some((fg, bg)) = rgb {
+            seq += &sgr_rgb(fg, false);
+            seq += &sgr_rgb(bg, true);
+        } else if pair > 0 {
+            if let Ok((fg, bg)) = self.colors.pair_content(pair) {
+                seq += &sgr_color(fg, false);
+                seq += &sgr_color(bg, true);
+            }
+        } else {
+            // Reset to default colors
+            seq += &sgr_color(-1, false);
+            seq += &sgr_color(-1, true);
+        }
 
-    /// Set keypad mode in stdscr.
-    pub fn keypad(&mut self, bf: bool) {
-        self.stdscr.keypad(bf);
+        seq
     }
 
-    /// Set nodelay mode in stdscr.
-    pub fn nodelay(&mut self, bf: bool) {
-        self.stdscr.nodelay(bf);
+    // ========================================================================
+    // Debugging
+    // ========================================================================
+
+    /// Write a human-readable rendering of stdscr, newscr and curscr.
+    ///
+    /// Each window is shown as a character grid framed by `|`, with `*` in
+    /// front of touched lines, followed by the runs of non-normal attributes
+    /// on each line. Comparing the three grids shows whether a change was
+    /// lost in the window, in the virtual screen or on the way to the
+    /// terminal.
+    pub fn dump_state(&self, w: &mut dyn std::io::Write) -> Result<()> {
+        for (name, win) in [
+            ("stdscr", &self.stdscr),
+            ("newscr", &self.newscr),
+            ("curscr", &self.curscr),
+        ] {
+            Self::dump_window(w, name, win)?;
+        }
+        Ok(())
     }
 
-    /// Set timeout for input in stdscr.
-    pub fn timeout(&mut self, delay: i32) {
-        self.stdscr.timeout(delay);
+    /// Render the virtual screen as text with embedded SGR sequences.
+    ///
+    /// Each row of `newscr` is one line, starting from a reset rendition and
+    /// switching attributes and colors as `doupdate()` would. Writing the
+    /// result to a terminal at the top-left corner reproduces the frame,
+    /// which makes it suitable for logs and golden-file tests. Call
+    /// [`refresh()`](Self::refresh) or [`wnoutrefresh()`](Self::wnoutrefresh)
+    /// first so `newscr` holds the frame.
+    #[must_use]
+    pub fn render_to_ansi(&self) -> String {
+        let mut out = String::new();
+        for y in 0..self.newscr.getmaxy() as usize {
+            if y > 0 {
+                out.push('\n');
+            }
+            let Some(line) = self.newscr.line(y) else {
+                break;
+            };
+            let mut last_attr = None;
+            for x in 0..line.width() {
+                let Some((ch, attr)) = display_cell(line, x) else {
+                    continue;
+                };
+                if last_attr != Some(attr) {
+                    out += &self.attr_sequence(attr);
+                    last_attr = Some(attr);
+                }
+                out.push(ch);
+            }
+            if last_attr.is_some_and(|attr| attr != A_NORMAL) {
+                out += &sgr_attributes(A_NORMAL);
+                out += &sgr_color(-1, false);
+                out += &sgr_color(-1, true);
+            }
+        }
+        out
     }
 
-    /// Insert a character at the current position in stdscr.
-    pub fn insch(&mut self, ch: ChType) -> Result<()> {
-        self.stdscr.insch(ch)
+    /// Render the virtual screen as plain text, one line per row.
+    ///
+    /// Like [`render_to_ansi()`](Self::render_to_ansi) without any escape
+    /// sequences. Rows keep their full width, including trailing blanks.
+    #[must_use]
+    pub fn render_to_text(&self) -> String {
+        let mut out = String::new();
+        for y in 0..self.newscr.getmaxy() as usize {
+            if y > 0 {
+                out.push('\n');
+            }
+            let Some(line) = self.newscr.line(y) else {
+                break;
+            };
+            out.extend((0..line.width()).filter_map(|x| display_cell(line, x).map(|(ch, _)| ch)));
+        }
+        out
+    }
+
+    /// Write one window for [`dump_state`](Self::dump_state).
+    fn dump_window(w: &mut dyn std::io::Write, name: &str, win: &Window) -> Result<()> {
+        let touched = win.touched_lines();
+        writeln!(
+            w,
+            "{} {}x{} cursor ({},{}) touched {:?}",
+            name,
+            win.getmaxy(),
+            win.getmaxx(),
+            win.getcury(),
+            win.getcurx(),
+            touched
+        )?;
+
+        let mut legend = Vec::new();
+        for y in 0..win.getmaxy() as usize {
+            let Some(line) = win.line(y) else { break };
+            let mut text = String::new();
+            // (first column, last column, attributes) of each attribute run
+            let mut runs: Vec<(usize, usize, AttrT)> = Vec::new();
+            for x in 0..line.width() {
+                #[cfg(feature = "wide")]
+                let (ch, attrs) = {
+                    let cell = line.get(x);
+                    // Right half of a wide character
+                    if cell.is_null() && x > 0 && line.get(x - 1).is_wide() {
+                        continue;
+                    }
+                    (cell.spacing_char(), cell.attrs())
+                };
+                #[cfg(not(feature = "wide"))]
+                let (ch, attrs) = {
+                    let cell = line.get(x);
+                    ((cell & A_CHARTEXT) as u8 as char, cell & !A_CHARTEXT)
+                };
+                text.push(if ch == '\0' || ch.is_control() {
+                    ' '
+                } else {
+                    ch
+                });
+
+                match runs.last_mut() {
+                    Some((_, end, a)) if *a == attrs && *end + 1 == x => *end = x,
+                    _ if attrs != A_NORMAL => runs.push((x, x, attrs)),
+                    _ => {}
+                }
+            }
+
+            let mark = if touched.contains(&y) { '*' } else { ' ' };
+            writeln!(w, "{}{:3}|{}|", mark, y, text)?;
+            for (start, end, attrs) in runs {
+                legend.push(format!(
+                    "{:4}: {}-{} {}",
+                    y,
+                    start,
+                    end,
+                    describe_attrs(attrs)
+                ));
+            }
+        }
+
+        if !legend.is_empty() {
+            writeln!(w, "attributes:")?;
+            for entry in legend {
+                writeln!(w, "{}", entry)?;
+            }
+        }
+        Ok(())
     }
 
-    /// Insert a string at the current position in stdscr.
+    // ========================================================================
+    // Input operations
+    // ========================================================================
+
+    /// Get the file descriptor the terminal reads input from.
     ///
-    /// Characters to the right are shifted right; characters shifted off
-    /// the right edge are lost. The cursor position does not change.
-    pub fn insstr(&mut self, s: &str) -> Result<()> {
-        self.stdscr.insstr(s)
+    /// Register this with `poll`, `mio` or a similar reactor, and call
+    /// [`process_available_input()`](Self::process_available_input) when it
+    /// becomes readable. Reading from the descriptor directly while also using
+    /// `getch()` is unsupported: bytes taken by one side are lost to the other.
+    pub fn input_fd(&self) -> RawFd {
+        self.terminal.input_fd()
     }
 
-    /// Insert at most n characters of a string at the current position in stdscr.
+    /// Log every raw input byte read from now on to the file at `path`.
     ///
-    /// If n is negative, the entire string is inserted.
-    pub fn insnstr(&mut self, s: &str, n: i32) -> Result<()> {
-        self.stdscr.insnstr(s, n)
+    /// The file can be fed back with [`Terminal::replay_from()`] to
+    /// reproduce the session, for example in an integration test. See
+    /// [`Terminal::record_input()`] for the format.
+    pub fn record_input(&mut self, path: impl AsRef<std::path::Path>) -> Result<()> {
+        let log = std::fs::File::create(path)?;
+        self.terminal.record_input(Box::new(log));
+        Ok(())
     }
 
-    /// Move cursor and insert a string in stdscr.
-    pub fn mvinsstr(&mut self, y: i32, x: i32, s: &str) -> Result<()> {
-        self.stdscr.mvinsstr(y, x, s)
+    /// Stop the log started by [`record_input()`](Self::record_input).
+    pub fn stop_recording_input(&mut self) -> Result<()> {
+        self.terminal.stop_recording()
     }
 
-    /// Move cursor and insert at most n characters in stdscr.
-    pub fn mvinsnstr(&mut self, y: i32, x: i32, s: &str, n: i32) -> Result<()> {
-        self.stdscr.mvinsnstr(y, x, s, n)
-    }
-
-    /// Insert a string at the current position in a window.
-    pub fn winsstr(&mut self, win: &mut Window, s: &str) -> Result<()> {
-        win.insstr(s)
-    }
-
-    /// Insert at most n characters of a string in a window.
-    pub fn winsnstr(&mut self, win: &mut Window, s: &str, n: i32) -> Result<()> {
-        win.insnstr(s, n)
+    /// Decode all input that is already available, without waiting for more.
+    ///
+    /// Returns the key codes in the order `getch()` would have returned them,
+    /// using the stdscr keypad setting. An escape sequence that has only
+    /// partially arrived may still wait up to ESCDELAY for the rest. Returns
+    /// `Err(Error::Eof)` only if input has ended and nothing was decoded.
+    pub fn process_available_input(&mut self) -> Result<Vec<i32>> {
+        let use_keypad = self.stdscr.is_keypad();
+        let mut keys = Vec::new();
+        loop {
+            match self.getch_internal(Delay::NoDelay, use_keypad) {
+                Ok(ch) => keys.push(ch),
+                Err(Error::NoInput) => break,
+                Err(Error::Eof) if !keys.is_empty() => break,
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(keys)
     }
 
-    /// Move cursor and insert a string in a window.
-    pub fn mvwinsstr(&mut self, win: &mut Window, y: i32, x: i32, s: &str) -> Result<()> {
-        win.mvinsstr(y, x, s)
-    }
+    /// Read a character from the terminal (using stdscr settings).
+    ///
+    /// With `echo()` on, printable characters are written to stdscr at the
+    /// cursor and shown at once. Control characters and function keys are
+    /// never echoed.
+    pub fn getch(&mut self) -> Result<i32> {
+        // If immedok is set, refresh first
+        if self.stdscr.is_immedok() {
+            self.refresh()?;
+        }
 
-    /// Move cursor and insert at most n characters in a window.
-    pub fn mvwinsnstr(&mut self, win: &mut Window, y: i32, x: i32, s: &str, n: i32) -> Result<()> {
-        win.mvinsnstr(y, x, s, n)
-    }
+        // Get delay setting from stdscr
+        let delay = Delay::from_raw(self.stdscr.getdelay());
+        let use_keypad = self.stdscr.is_keypad();
 
-    /// Delete the character at the current position in stdscr.
-    pub fn delch(&mut self) -> Result<()> {
-        self.stdscr.delch()
+        let ch = self.getch_internal(delay, use_keypad)?;
+        if self.echoes(ch) {
+            self.stdscr.addch(ch as ChType)?;
+            self.refresh()?;
+        }
+        Ok(ch)
     }
 
-    /// Insert a blank line above the current line in stdscr.
-    pub fn insertln(&mut self) -> Result<()> {
-        self.stdscr.insertln()
-    }
+    /// Read a character from a window.
+    ///
+    /// Echoes into `win` the same way [`getch()`](Self::getch) echoes into
+    /// stdscr.
+    pub fn wgetch(&mut self, win: &mut Window) -> Result<i32> {
+        // If immedok is set, refresh first
+        if win.is_immedok() {
+            self.wrefresh(win)?;
+        }
 
-    /// Delete the current line in stdscr.
-    pub fn deleteln(&mut self) -> Result<()> {
-        self.stdscr.deleteln()
-    }
+        // Get delay setting from window
+        let delay = Delay::from_raw(win.getdelay());
+        let use_keypad = win.is_keypad();
 
-    /// Set background character in stdscr.
-    pub fn bkgdset(&mut self, ch: ChType) {
-        self.stdscr.bkgdset(ch);
-    }
+        let ch = self.getch_internal(delay, use_keypad)?;
+        if self.echoes(ch) {
+            win.addch(ch as ChType)?;
+            self.wrefresh(win)?;
+        }
+        Ok(ch)
+    }
+
+    /// Read the next input byte as is.
+    ///
+    /// Unlike `getch()`, this ignores keypad mode, escape sequences and
+    /// newline translation, which suits binary data or replies to terminal
+    /// queries. Bytes pushed back with `ungetch()` are returned first, but a
+    /// pushed-back key code is left for `getch()` and ends raw reading.
+    /// Returns `None` if no byte arrives within `timeout`, and
+    /// [`Error::Eof`] at the end of input.
+    pub fn read_raw_byte(&mut self, timeout: Delay) -> Result<Option<u8>> {
+        if let Some(ch) = self.input_buffer.peek() {
+            return match u8::try_from(ch) {
+                Ok(byte) => {
+                    self.input_buffer.get();
+                    Ok(Some(byte))
+                }
+                Err(_) => Ok(None),
+            };
+        }
 
-    /// Set and apply background to stdscr.
-    pub fn bkgd(&mut self, ch: ChType) -> Result<()> {
-        self.stdscr.bkgd(ch)
+        let wait = match timeout {
+            Delay::NoDelay => Some(Duration::ZERO),
+            Delay::Blocking => None,
+            Delay::Timeout(ms) => Some(Duration::from_millis(ms.max(0) as u64)),
+        };
+        if !self.terminal.has_input() && !self.terminal.wait_for_input(wait) {
+            return Ok(None);
+        }
+        match self.terminal.read_byte()? {
+            Some(byte) => Ok(Some(byte)),
+            None => Err(Error::Eof),
+        }
     }
 
-    /// Print a string to stdscr (simplified version of C's printw).
-    ///
-    /// **Note:** Unlike C ncurses, this does not support printf-style format strings.
-    /// Use Rust's `format!` macro to format your string before calling this:
-    ///
-    /// ```rust,ignore
-    /// // C ncurses: printw("Value: %d", value);
-    /// // Rust:
-    /// screen.printw(&format!("Value: {}", value))?;
-    /// ```
-    pub fn printw(&mut self, s: &str) -> Result<()> {
-        self.stdscr.addstr(s)
+    /// Check whether `getch()` should echo `ch`.
+    fn echoes(&self, ch: i32) -> bool {
+        self.input_mode.echo && (0x20..0x7f).contains(&ch)
     }
 
-    /// Move cursor and print a string to stdscr.
-    ///
-    /// **Note:** Unlike C ncurses, this does not support printf-style format strings.
-    /// Use Rust's `format!` macro:
-    ///
-    /// ```rust,ignore
-    /// // C ncurses: mvprintw(y, x, "Value: %d", value);
-    /// // Rust:
-    /// screen.mvprintw(y, x, &format!("Value: {}", value))?;
-    /// ```
-    pub fn mvprintw(&mut self, y: i32, x: i32, s: &str) -> Result<()> {
+    /// Move cursor and read a character from stdscr.
+    pub fn mvgetch(&mut self, y: i32, x: i32) -> Result<i32> {
         self.stdscr.mv(y, x)?;
-        self.stdscr.addstr(s)
+        self.getch()
     }
 
-    /// Print a string to a window (simplified version of C's wprintw).
-    ///
-    /// **Note:** Unlike C ncurses, this does not support printf-style format strings.
-    /// Use Rust's `format!` macro:
-    ///
-    /// ```rust,ignore
-    /// // C ncurses: wprintw(win, "Value: %d", value);
-    /// // Rust:
-    /// screen.wprintw(win, &format!("Value: {}", value))?;
-    /// ```
-    pub fn wprintw(&mut self, win: &mut Window, s: &str) -> Result<()> {
-        win.addstr(s)
+    /// Move cursor and read a character from a window.
+    pub fn mvwgetch(&mut self, win: &mut Window, y: i32, x: i32) -> Result<i32> {
+        win.mv(y, x)?;
+        self.wgetch(win)
     }
 
-    /// Move cursor and print a string to a window.
-    ///
-    /// **Note:** Unlike C ncurses, this does not support printf-style format strings.
-    /// Use Rust's `format!` macro:
+    /// Read a wide character from the terminal (using stdscr settings).
     ///
-    /// ```rust,ignore
-    /// // C ncurses: mvwprintw(win, y, x, "Value: %d", value);
-    /// // Rust:
-    /// screen.mvwprintw(win, y, x, &format!("Value: {}", value))?;
-    /// ```
-    pub fn mvwprintw(&mut self, win: &mut Window, y: i32, x: i32, s: &str) -> Result<()> {
-        win.mv(y, x)?;
-        win.addstr(s)
-    }
+    /// Returns the wide character result, which can be a character or key code.
+    /// With `echo()` on, characters are echoed as [`getch()`](Self::getch)
+    /// echoes them. This is the Rust equivalent of `get_wch()`.
+    #[cfg(feature = "wide")]
+    pub fn get_wch(&mut self) -> Result<crate::wide::WideInput> {
+        // If immedok is set, refresh first
+        if self.stdscr.is_immedok() {
+            self.refresh()?;
+        }
 
-    // ========================================================================
-    // Additional ncurses compatibility functions
-    // ========================================================================
+        let delay = Delay::from_raw(self.stdscr.getdelay());
+        let use_keypad = self.stdscr.is_keypad();
 
-    /// Sleep for the specified number of milliseconds.
-    ///
-    /// This is the ncurses `napms()` function.
-    pub fn napms(&self, ms: i32) {
-        if ms > 0 {
-            std::thread::sleep(Duration::from_millis(ms as u64));
+        let input = self.get_wch_internal(delay, use_keypad)?;
+        if let Some(c) = self.wide_echo(&input) {
+            self.stdscr.addwstr(c.encode_utf8(&mut [0; 4]))?;
+            self.refresh()?;
         }
+        Ok(input)
     }
 
-    /// Output padding characters to delay for the specified number of milliseconds.
-    ///
-    /// This function inserts a delay in the output stream by outputting padding
-    /// characters. The actual delay may vary depending on terminal baud rate.
+    /// Read a wide character from a window.
     ///
-    /// In this implementation, we simply use a sleep since modern terminals
-    /// don't need padding characters.
-    pub fn delay_output(&mut self, ms: i32) -> Result<()> {
-        if ms > 0 {
-            self.terminal.flush()?;
-            std::thread::sleep(Duration::from_millis(ms as u64));
+    /// Returns the wide character result, which can be a character or key code.
+    /// This is the Rust equivalent of `wget_wch()`.
+    #[cfg(feature = "wide")]
+    pub fn wget_wch(&mut self, win: &mut Window) -> Result<crate::wide::WideInput> {
+        // If immedok is set, refresh first
+        if win.is_immedok() {
+            self.wrefresh(win)?;
         }
-        Ok(())
-    }
 
-    /// Save the current program terminal mode.
-    ///
-    /// This saves the current terminal settings so they can be restored
-    /// later with `reset_prog_mode()`.
-    pub fn def_prog_mode(&mut self) -> Result<()> {
-        self.terminal.save_prog_mode()
-    }
+        let delay = Delay::from_raw(win.getdelay());
+        let use_keypad = win.is_keypad();
 
-    /// Save the current shell terminal mode.
-    ///
-    /// This saves the current terminal settings (typically the shell's settings)
-    /// so they can be restored later with `reset_shell_mode()`.
-    pub fn def_shell_mode(&mut self) -> Result<()> {
-        self.terminal.save_shell_mode()
+        let input = self.get_wch_internal(delay, use_keypad)?;
+        if let Some(c) = self.wide_echo(&input) {
+            win.addwstr(c.encode_utf8(&mut [0; 4]))?;
+            self.wrefresh(win)?;
+        }
+        Ok(input)
     }
 
-    /// Restore the saved program terminal mode.
-    ///
-    /// This restores the terminal settings saved by `def_prog_mode()`.
-    pub fn reset_prog_mode(&mut self) -> Result<()> {
-        self.terminal.restore_prog_mode()
+    /// The character `get_wch()` should echo for `input`, if any.
+    #[cfg(feature = "wide")]
+    fn wide_echo(&self, input: &crate::wide::WideInput) -> Option<char> {
+        match *input {
+            crate::wide::WideInput::Char(c) if self.input_mode.echo && !c.is_control() => Some(c),
+            _ => None,
+        }
     }
 
-    /// Restore the saved shell terminal mode.
-    ///
-    /// This restores the terminal settings saved by `def_shell_mode()`.
-    pub fn reset_shell_mode(&mut self) -> Result<()> {
-        self.terminal.restore_shell_mode()
+    /// Move cursor and read a wide character from stdscr.
+    #[cfg(feature = "wide")]
+    pub fn mvget_wch(&mut self, y: i32, x: i32) -> Result<crate::wide::WideInput> {
+        self.stdscr.mv(y, x)?;
+        self.get_wch()
     }
 
-    /// Reset the terminal to program mode after shell escape.
-    ///
-    /// Equivalent to calling `reset_prog_mode()` followed by refreshing.
-    pub fn resetty(&mut self) -> Result<()> {
-        self.terminal.restore_prog_mode()?;
-        self.refresh()
+    /// Move cursor and read a wide character from a window.
+    #[cfg(feature = "wide")]
+    pub fn mvwget_wch(
+        &mut self,
+        win: &mut Window,
+        y: i32,
+        x: i32,
+    ) -> Result<crate::wide::WideInput> {
+        win.mv(y, x)?;
+        self.wget_wch(win)
     }
 
-    /// Save the terminal state for later restoration.
-    ///
-    /// Equivalent to calling `def_prog_mode()`.
-    pub fn savetty(&mut self) -> Result<()> {
-        self.terminal.save_prog_mode()
-    }
+    /// Internal wide character reading logic.
+    #[cfg(feature = "wide")]
+    fn get_wch_internal(
+        &mut self,
+        delay: Delay,
+        use_keypad: bool,
+    ) -> Result<crate::wide::WideInput> {
+        use crate::wide::WideInput;
 
-    /// Check if the terminal has insert/delete character capabilities.
-    ///
-    /// Returns true if the terminal supports inserting and deleting characters.
-    /// This is based on the terminal type detection.
-    pub fn has_ic(&self) -> bool {
-        self.terminal.has_ic()
-    }
+        // First get a character using the normal getch
+        let ch = match self.getch_internal(delay, use_keypad) {
+            Ok(c) => c,
+            Err(Error::Timeout) => return Ok(WideInput::None),
+            Err(Error::NoInput) => return Ok(WideInput::None),
+            Err(Error::Eof) => return Ok(WideInput::Eof),
+            Err(e) => return Err(e),
+        };
 
-    /// Check if the terminal has insert/delete line capabilities.
-    ///
-    /// Returns true if the terminal supports inserting and deleting lines.
-    /// This is based on the terminal type detection.
-    pub fn has_il(&self) -> bool {
-        self.terminal.has_il()
-    }
+        // Check if it's a key code (high values or negative)
+        if !(0..=255).contains(&ch) {
+            return Ok(WideInput::Key(ch));
+        }
 
-    /// Get the kill character (line kill).
-    pub fn killchar(&self) -> char {
-        crate::terminal::killchar()
-    }
+        // Handle as UTF-8
+        let first_byte = ch as u8;
 
-    /// Get the erase character (backspace).
-    pub fn erasechar(&self) -> char {
-        crate::terminal::erasechar()
-    }
+        // Single-byte ASCII
+        if first_byte < 0x80 {
+            return Ok(WideInput::Char(first_byte as char));
+        }
 
-    /// Get the erase character as a wide character.
-    ///
-    /// This is the ncurses `erasewchar()` function. It returns the terminal's
-    /// erase character as a wide character via the output parameter.
-    ///
-    /// # Arguments
-    /// * `ch` - Output parameter to receive the erase character
-    ///
-    /// # Returns
-    /// `Ok(())` on success
-    #[cfg(feature = "wide")]
-    pub fn erasewchar(&self, ch: &mut char) -> Result<()> {
-        *ch = crate::terminal::erasechar();
-        Ok(())
-    }
+        // Multi-byte UTF-8 sequence
+        let needed = if first_byte & 0xE0 == 0xC0 {
+            1 // 2-byte sequence
+        } else if first_byte & 0xF0 == 0xE0 {
+            2 // 3-byte sequence
+        } else if first_byte & 0xF8 == 0xF0 {
+            3 // 4-byte sequence
+        } else {
+            // Invalid UTF-8 start byte, return as-is
+            return Ok(WideInput::Char(first_byte as char));
+        };
 
-    /// Get a longer terminal description.
-    ///
-    /// In ncurses this returns the terminfo description, but we return
-    /// a simple description based on the terminal type.
-    pub fn longname(&self) -> String {
-        let term = self.terminal.term_type();
-        match term {
-            "xterm" => "xterm terminal emulator".to_string(),
-            "xterm-256color" => "xterm with 256 colors".to_string(),
-            "screen" => "GNU Screen".to_string(),
-            "screen-256color" => "GNU Screen with 256 colors".to_string(),
-            "tmux" => "tmux terminal multiplexer".to_string(),
-            "tmux-256color" => "tmux with 256 colors".to_string(),
-            "linux" => "Linux console".to_string(),
-            "dumb" => "dumb terminal".to_string(),
-            _ => format!("{} terminal", term),
+        let mut bytes = vec![first_byte];
+
+        // Read continuation bytes
+        for _ in 0..needed {
+            // Use a short timeout for continuation bytes
+            let timeout = Delay::Timeout(50);
+            match self.getch_internal(timeout, false) {
+                Ok(b @ 0x80..=0xbf) => {
+                    bytes.push(b as u8);
+                }
+                Ok(b) => {
+                    // Not a continuation byte (or a pushed-back key code),
+                    // push it back
+                    self.input_buffer.unget(b);
+                    break;
+                }
+                Err(_) => break,
+            }
         }
-    }
 
-    /// Check if filter mode is enabled.
-    ///
-    /// Filter mode restricts curses to a single line, turning off cursor
-    /// movement capabilities. This is typically used for simple line-editing
-    /// applications.
-    pub fn isfilter(&self) -> bool {
-        self.filtered
+        // Try to decode as UTF-8
+        match std::str::from_utf8(&bytes) {
+            Ok(s) => {
+                if let Some(c) = s.chars().next() {
+                    Ok(WideInput::Char(c))
+                } else {
+                    Ok(WideInput::Error)
+                }
+            }
+            Err(_) => {
+                // Invalid UTF-8, return first byte as char
+                Ok(WideInput::Char(first_byte as char))
+            }
+        }
     }
 
-    /// Enable filter mode.
-    ///
-    /// When filter mode is enabled, the terminal is treated as a single-line
-    /// terminal (LINES=1), and cursor movement functions are disabled.
-    /// This should be called before or immediately after screen initialization.
-    pub fn filter(&mut self) {
-        self.filtered = true;
-        // In filter mode, we only use one line
-        // Resize the screen to 1 line
-        let cols = self.terminal.columns();
-        let _ = self.resize_term(1, cols);
-    }
+    /// Internal character reading logic.
+    fn getch_internal(&mut self, delay: Delay, use_keypad: bool) -> Result<i32> {
+        // A resize is reported before any typeahead
+        if std::mem::take(&mut self.resize_pending) {
+            return Ok(KEY_RESIZE);
+        }
 
-    /// Disable filter mode.
-    ///
-    /// This restores normal terminal operation with full screen capabilities.
-    pub fn nofilter(&mut self) {
-        if self.filtered {
-            self.filtered = false;
-            // Restore full terminal size
-            let _ = self.terminal.update_size();
-            let lines = self.terminal.lines();
-            let cols = self.terminal.columns();
-            let _ = self.resize_term(lines, cols);
+        // Check input buffer first
+        if let Some(ch) = self.input_buffer.get() {
+            return Ok(ch);
         }
-    }
 
-    /// Check if the standard screen has clearok mode enabled.
-    ///
-    /// When clearok is enabled, the next refresh will clear the screen
-    /// and redraw from scratch.
-    pub fn is_cleared(&self) -> bool {
-        self.stdscr.is_cleared()
-    }
+        // Determine timeout
+        let timeout = match delay {
+            Delay::NoDelay => Some(Duration::ZERO),
+            Delay::Blocking => self.blocking_timeout(),
+            Delay::Timeout(ms) => Some(Duration::from_millis(ms as u64)),
+        };
 
-    /// Check if the standard screen is a pad window.
-    ///
-    /// The standard screen is never a pad, so this always returns false.
-    pub fn is_pad(&self) -> bool {
-        self.stdscr.is_pad()
-    }
+        let start = Instant::now();
 
-    /// Check if we're using the standard screen.
-    pub fn is_wintouched(&self) -> bool {
-        self.stdscr.is_wintouched()
-    }
+        loop {
+            // Check if input is available
+            if !self.terminal.has_input() {
+                // For NoDelay mode, return immediately if no input
+                if timeout == Some(Duration::ZERO) {
+                    return Err(Error::NoInput);
+                }
+                // Wait for input until the deadline (or forever when blocking)
+                let remaining = match timeout {
+                    Some(t) => match t.checked_sub(start.elapsed()) {
+                        Some(r) if !r.is_zero() => Some(r),
+                        _ => return Err(Error::Timeout),
+                    },
+                    None => None,
+                };
+                // A signal handler may have requested a resize
+                if !self.terminal.wait_for_input(remaining)
+                    && std::mem::take(&mut self.resize_pending)
+                {
+                    return Ok(KEY_RESIZE);
+                }
+                continue;
+            }
 
-    /// Control flushing of input queue on interrupt.
-    ///
-    /// If `enable` is true (the default), when an interrupt key is pressed,
-    /// all input in the queue will be flushed.
-    pub fn intrflush(&mut self, enable: bool) -> Result<()> {
-        self.terminal.intrflush(enable)
-    }
+            // Read a byte
+            let byte = match self.terminal.read_byte()? {
+                Some(b) => b,
+                None => return Err(Error::Eof),
+            };
 
-    /// Enable 8-bit input mode.
-    ///
-    /// If `enable` is true, the terminal passes 8-bit characters through
-    /// without stripping the high bit.
-    pub fn meta(&mut self, enable: bool) -> Result<()> {
-        self.terminal.meta(enable)
-    }
+            // Handle escape sequences if keypad mode is enabled
+            if use_keypad && byte == 0x1b {
+                // Start escape sequence parsing
+                self.escape_parser.reset();
+                match self.parse_escape_sequence()? {
+                    Some(key) => return Ok(key),
+                    None => continue,
+                }
+            }
 
-    /// Set the file descriptor for typeahead checking.
-    ///
-    /// If `fd` is -1, no typeahead checking is done. The default is stdin (0).
-    /// This controls whether curses checks for pending input before output.
-    pub fn typeahead(&mut self, fd: i32) {
-        self.terminal.set_typeahead_fd(fd);
-    }
+            // Handle newline translation
+            if self.input_mode.nl && byte == b'\r' {
+                return Ok(b'\n' as i32);
+            }
 
-    /// Enable flushing of output and input queues on interrupt.
-    ///
-    /// When enabled (the default), pressing an interrupt key will flush
-    /// both the input and output queues.
-    pub fn qiflush(&mut self) {
-        let _ = self.terminal.qiflush(true);
+            return Ok(byte as i32);
+        }
     }
 
-    /// Disable flushing of output and input queues on interrupt.
+    /// Parse an escape sequence after receiving ESC.
     ///
-    /// When disabled, pressing an interrupt key will not flush the queues.
-    pub fn noqiflush(&mut self) {
-        let _ = self.terminal.qiflush(false);
-    }
+    /// Returns `None` when the sequence was consumed without producing a key,
+    /// such as a mouse report that the mouse mask filters out.
+    fn parse_escape_sequence(&mut self) -> Result<Option<i32>> {
+        self.escape_parser.reset();
+        self.escape_parser.feed(0x1b);
 
-    // ========================================================================
-    // Window wrapper functions (w* prefix functions for ncurses compatibility)
-    // ========================================================================
+        let start = Instant::now();
+        let escape_timeout = Duration::from_millis(self.escdelay as u64);
 
-    /// Add a character to a window at current cursor position.
-    pub fn waddch(&mut self, win: &mut Window, ch: ChType) -> Result<()> {
-        win.addch(ch)
-    }
+        // Buffer to accumulate the sequence for mouse parsing
+        let mut sequence_buf: Vec<u8> = vec![0x1b];
 
-    /// Add a character to a window at specified position.
-    pub fn mvwaddch(&mut self, win: &mut Window, y: i32, x: i32, ch: ChType) -> Result<()> {
-        win.mvaddch(y, x, ch)
-    }
+        loop {
+            // Check timeout. With no delay at all the sequence ends as soon
+            // as nothing more is already waiting, without any sleep.
+            let timed_out = if escape_timeout.is_zero() {
+                !self.terminal.has_input()
+            } else {
+                start.elapsed() >= escape_timeout
+            };
+            if timed_out {
+                // Timeout - return the accumulated input
+                let input = self.escape_parser.current_input();
+                if input.len() == 1 {
+                    // Just ESC
+                    return Ok(Some(0x1b));
+                }
+                // Return current match if any, otherwise just ESC
+                if let Some(key) = self.escape_parser.current_match() {
+                    return Ok(Some(key));
+                }
+                if let Some(key) = self.alt_key(input) {
+                    return Ok(Some(key));
+                }
+                // Push remaining bytes back to buffer (except ESC which we return)
+                for &b in &input[1..] {
+                    self.input_buffer.push(b as i32);
+                }
+                return Ok(Some(0x1b));
+            }
 
-    /// Add a character and refresh immediately (stdscr).
-    ///
-    /// Equivalent to calling `addch()` followed by `refresh()`.
-    pub fn echochar(&mut self, ch: ChType) -> Result<()> {
-        self.stdscr.addch(ch)?;
-        self.refresh()
-    }
+            // Check for more input
+            if !self.terminal.has_input() {
+                let remaining = escape_timeout.saturating_sub(start.elapsed());
+                self.terminal.wait_for_input(Some(remaining));
+                continue;
+            }
 
-    /// Add a character to a window and refresh immediately.
+            let byte = match self.terminal.read_byte()? {
+                Some(b) => b,
+                None => {
+                    // EOF during escape - return what we have
+                    if let Some(key) = self.escape_parser.current_match() {
+                        return Ok(Some(key));
+                    }
+                    return Ok(Some(0x1b));
+                }
+            };
+
+            sequence_buf.push(byte);
+
+            // A typed ESC followed by a UTF-8 lead byte starts no sequence;
+            // leave the character for get_wch to assemble
+            if sequence_buf.len() == 2 && byte >= 0x80 {
+                self.escape_parser.reset();
+                self.input_buffer.push(byte as i32);
+                return Ok(Some(0x1b));
+            }
+
+            // Check for SGR mouse sequence: \x1b[<...M or \x1b[<...m
+            #[cfg(feature = "mouse")]
+            if self.mouse.is_enabled() && is_mouse_prefix(&sequence_buf) {
+                // Check if we have a complete mouse sequence
+                if sequence_buf.len() >= 3 && &sequence_buf[0..3] == b"\x1b[<" {
+                    // Look for terminator
+                    if byte == b'M' || byte == b'm' {
+                        // Complete mouse sequence
+                        if let Some(event) = parse_sgr_mouse(&sequence_buf) {
+                            if !self.mouse.process_event_at(event, Instant::now()) {
+                                return Ok(None);
+                            }
+                            return Ok(Some(KEY_MOUSE));
+                        }
+                    }
+                    // Continue accumulating if not complete
+                    if sequence_buf.len() < 20 {
+                        // Reasonable max length for mouse sequence
+                        continue;
+                    }
+                }
+            }
+
+            match self.escape_parser.feed(byte) {
+                EscapeMatch::Complete(KEY_PASTE) => {
+                    let text = self.read_paste()?;
+                    self.pastes.push_back(text);
+                    return Ok(Some(KEY_PASTE));
+                }
+                EscapeMatch::Complete(key) => {
+                    if self.take_late_reply(&sequence_buf) {
+                        return Ok(None);
+                    }
+                    return Ok(Some(key));
+                }
+                EscapeMatch::None => {
+                    if let Some(key) = self.alt_key(&sequence_buf) {
+                        return Ok(Some(key));
+                    }
+                    if sequence_buf.starts_with(b"\x1b[")
+                        && self.read_csi_tail(&mut sequence_buf)?
+                    {
+                        // A late reply to a terminal query is not a key
+                        if self.take_late_reply(&sequence_buf) {
+                            return Ok(None);
+                        }
+                        if self.unknown_csi_coalesce {
+                            self.unknown_csis.push_back(sequence_buf);
+                            return Ok(Some(KEY_UNKNOWN_CSI));
+                        }
+                    }
+                    // No match - return ESC and push rest to buffer. The
+                    // parser has already discarded its input at this point.
+                    for &b in &sequence_buf[1..] {
+                        self.input_buffer.push(b as i32);
+                    }
+                    return Ok(Some(0x1b));
+                }
+                EscapeMatch::Partial => {
+                    // Continue reading
+                }
+            }
+        }
+    }
+
+    /// Set how many entries the input buffer holds.
     ///
-    /// Equivalent to calling `waddch()` followed by `wrefresh()`.
-    pub fn wechochar(&mut self, win: &mut Window, ch: ChType) -> Result<()> {
-        win.addch(ch)?;
-        self.wrefresh(win)
+    /// The buffer keeps typeahead and everything pushed back with
+    /// `ungetch()`; it holds [`FIFO_SIZE`](crate::input::FIFO_SIZE) entries
+    /// by default. Raise the limit before replaying long keystroke macros.
+    /// Shrinking below the current contents discards the entries that would
+    /// be read last.
+    pub fn set_input_buffer_capacity(&mut self, capacity: usize) -> Result<()> {
+        if capacity == 0 {
+            return Err(Error::InvalidArgument(
+                "input buffer capacity must be positive".into(),
+            ));
+        }
+        self.input_buffer.set_capacity(capacity);
+        Ok(())
     }
 
-    /// Add a wide character and refresh immediately (stdscr).
+    /// Get the capacity of the input buffer.
+    pub fn input_buffer_capacity(&self) -> usize {
+        self.input_buffer.capacity()
+    }
+
+    /// Choose what happens when the input buffer is full.
     ///
-    /// Equivalent to calling `add_wch()` followed by `refresh()`.
-    #[cfg(feature = "wide")]
-    pub fn echo_wchar(&mut self, wch: &crate::wide::CCharT) -> Result<()> {
-        self.stdscr.add_wch(wch)?;
-        self.refresh()
+    /// With [`InputOverflow::Error`] (the default) `ungetch()` fails with
+    /// `Error::BufferFull`; with [`InputOverflow::DropOldest`] it succeeds and
+    /// the key pushed back earliest is lost.
+    pub fn set_input_overflow(&mut self, overflow: InputOverflow) {
+        self.input_buffer.set_overflow(overflow);
     }
 
-    /// Add a wide character to a window and refresh immediately.
+    /// Get the input buffer overflow policy.
+    pub fn input_overflow(&self) -> InputOverflow {
+        self.input_buffer.overflow()
+    }
+
+    /// Push a character back into the input buffer.
     ///
-    /// Equivalent to calling `wadd_wch()` followed by `wrefresh()`.
-    #[cfg(feature = "wide")]
-    pub fn wecho_wchar(&mut self, win: &mut Window, wch: &crate::wide::CCharT) -> Result<()> {
-        win.add_wch(wch)?;
-        self.wrefresh(win)
+    /// Fails with `Error::BufferFull` once the buffer holds
+    /// [`input_buffer_capacity()`](Self::input_buffer_capacity) entries,
+    /// unless [`set_input_overflow()`](Self::set_input_overflow) allows
+    /// dropping old ones.
+    ///
+    /// `KEY_RESIZE` is not buffered: it marks a resize as pending, which the
+    /// next `getch()` reports ahead of everything pushed back or typed, and
+    /// any number of pending resizes are read as a single `KEY_RESIZE`.
+    pub fn ungetch(&mut self, ch: i32) -> Result<()> {
+        if ch == KEY_RESIZE {
+            self.resize_pending = true;
+            return Ok(());
+        }
+        if self.input_buffer.unget(ch) {
+            Ok(())
+        } else {
+            Err(Error::BufferFull)
+        }
     }
 
-    /// Add a wide character to a pad and refresh immediately.
+    /// Push a wide character back into the input buffer.
     ///
-    /// Equivalent to calling `add_wch()` on the pad followed by `prefresh()`.
-    /// The pad must have had `prefresh` or `pnoutrefresh` called at least once
-    /// to establish display parameters.
+    /// This converts the wide character to its UTF-8 representation and
+    /// pushes the bytes back as a unit, ahead of anything already pushed
+    /// back: like `ungetch()`, the last character pushed is read first.
     #[cfg(feature = "wide")]
-    pub fn pecho_wchar(&mut self, pad: &mut Window, wch: &crate::wide::CCharT) -> Result<()> {
-        if !pad.is_pad() {
-            return Err(Error::InvalidArgument("window is not a pad".into()));
+    pub fn unget_wch(&mut self, wch: char) -> Result<()> {
+        let mut buf = [0u8; 4];
+        let bytes: Vec<i32> = wch.encode_utf8(&mut buf).bytes().map(i32::from).collect();
+
+        // All bytes or none, so a full buffer never splits a character
+        if self.input_buffer.unget_seq(&bytes) {
+            Ok(())
+        } else {
+            Err(Error::BufferFull)
         }
+    }
 
-        pad.add_wch(wch)?;
+    /// Check if there's typeahead input.
+    pub fn has_key(&self) -> bool {
+        self.resize_pending || self.input_buffer.has_input() || self.terminal.has_input()
+    }
 
-        // Get the stored pad parameters
-        let pad_data = pad
-            .pad_data()
-            .ok_or_else(|| Error::InvalidArgument("pad has no stored parameters".into()))?;
+    /// Flush the input buffer.
+    pub fn flushinp(&mut self) {
+        self.input_buffer.clear();
+        // Also try to drain the terminal input
+        while self.terminal.has_input() {
+            let _ = self.terminal.read_byte();
+        }
+    }
 
-        let pminrow = pad_data.pad_y as i32;
-        let pmincol = pad_data.pad_x as i32;
-        let sminrow = pad_data.pad_top as i32;
-        let smincol = pad_data.pad_left as i32;
-        let smaxrow = pad_data.pad_bottom as i32;
-        let smaxcol = pad_data.pad_right as i32;
+    /// Get a string from the user with simple line editing (using stdscr).
+    pub fn getstr(&mut self, maxlen: usize) -> Result<String> {
+        let mut result = String::new();
+        let echo_enabled = self.input_mode.echo;
 
-        self.prefresh(pad, pminrow, pmincol, sminrow, smincol, smaxrow, smaxcol)
-    }
+        // Get delay and keypad settings from stdscr
+        let delay = Delay::from_raw(self.stdscr.getdelay());
+        let use_keypad = self.stdscr.is_keypad();
 
-    /// Add a string to a window at current cursor position.
-    pub fn waddstr(&mut self, win: &mut Window, s: &str) -> Result<()> {
-        win.addstr(s)
-    }
+        loop {
+            let ch = self.getch_internal(delay, use_keypad)?;
 
-    /// Add at most n characters of a string to a window.
-    pub fn waddnstr(&mut self, win: &mut Window, s: &str, n: i32) -> Result<()> {
-        win.addnstr(s, n)
-    }
+            match ch {
+                // Enter/Return
+                0x0a | 0x0d => {
+                    if echo_enabled {
+                        self.stdscr.addch(b'\n' as ChType)?;
+                        self.refresh()?;
+                    }
+                    break;
+                }
+                // Backspace
+                0x08 | 0x7f if !result.is_empty() => {
+                    result.pop();
+                    if echo_enabled {
+                        let (y, x) = (self.stdscr.getcury(), self.stdscr.getcurx());
+                        if x > 0 {
+                            self.stdscr.mv(y, x - 1)?;
+                            self.stdscr.addch(b' ' as ChType)?;
+                            self.stdscr.mv(y, x - 1)?;
+                            self.refresh()?;
+                        }
+                    }
+                }
+                // Regular character
+                _ if (0x20..0x7f).contains(&ch) && result.len() < maxlen => {
+                    result.push(ch as u8 as char);
+                    if echo_enabled {
+                        self.stdscr.addch(ch as ChType)?;
+                        self.refresh()?;
+                    }
+                }
+                // Control-C, Control-D - cancel
+                0x03 | 0x04 => {
+                    return Err(Error::Interrupted);
+                }
+                _ => {}
+            }
+        }
 
-    /// Add a string to a window at specified position.
-    pub fn mvwaddstr(&mut self, win: &mut Window, y: i32, x: i32, s: &str) -> Result<()> {
-        win.mvaddstr(y, x, s)
+        Ok(result)
     }
 
-    /// Add at most n characters of a string to a window at specified position.
-    pub fn mvwaddnstr(&mut self, win: &mut Window, y: i32, x: i32, s: &str, n: i32) -> Result<()> {
-        win.mvaddnstr(y, x, s, n)
-    }
+    /// Get a string from a window with simple line editing.
+    pub fn wgetstr(&mut self, win: &mut Window, maxlen: usize) -> Result<String> {
+        let mut result = String::new();
+        let echo_enabled = self.input_mode.echo;
 
-    /// Add a character string to a window.
-    pub fn waddchstr(&mut self, win: &mut Window, chstr: &[ChType]) -> Result<()> {
-        win.addchstr(chstr)
-    }
+        // Get delay and keypad settings from window
+        let delay = Delay::from_raw(win.getdelay());
+        let use_keypad = win.is_keypad();
 
-    /// Add at most n characters from a character string to a window.
-    pub fn waddchnstr(&mut self, win: &mut Window, chstr: &[ChType], n: i32) -> Result<()> {
-        win.addchnstr(chstr, n)
-    }
+        loop {
+            let ch = self.getch_internal(delay, use_keypad)?;
 
-    // ========================================================================
-    // Wide character window wrapper functions
-    // ========================================================================
+            match ch {
+                // Enter/Return
+                0x0a | 0x0d => {
+                    if echo_enabled {
+                        win.addch(b'\n' as ChType)?;
+                        self.wrefresh(win)?;
+                    }
+                    break;
+                }
+                // Backspace
+                0x08 | 0x7f if !result.is_empty() => {
+                    result.pop();
+                    if echo_enabled {
+                        let (y, x) = (win.getcury(), win.getcurx());
+                        if x > 0 {
+                            win.mv(y, x - 1)?;
+                            win.addch(b' ' as ChType)?;
+                            win.mv(y, x - 1)?;
+                            self.wrefresh(win)?;
+                        }
+                    }
+                }
+                // Regular character
+                _ if (0x20..0x7f).contains(&ch) && result.len() < maxlen => {
+                    result.push(ch as u8 as char);
+                    if echo_enabled {
+                        win.addch(ch as ChType)?;
+                        self.wrefresh(win)?;
+                    }
+                }
+                // Control-C, Control-D - cancel
+                0x03 | 0x04 => {
+                    return Err(Error::Interrupted);
+                }
+                _ => {}
+            }
+        }
 
-    /// Add a wide character to stdscr at the current position.
-    ///
-    /// This is the ncurses `add_wch()` function.
-    #[cfg(feature = "wide")]
-    pub fn add_wch(&mut self, wch: &crate::wide::CCharT) -> Result<()> {
-        self.stdscr.add_wch(wch)
+        Ok(result)
     }
 
-    /// Add a wide character to a window at the current position.
+    /// Get a string from stdscr, echoing `mask` in place of each typed character.
     ///
-    /// This is the ncurses `wadd_wch()` function.
-    #[cfg(feature = "wide")]
-    pub fn wadd_wch(&mut self, win: &mut Window, wch: &crate::wide::CCharT) -> Result<()> {
-        win.add_wch(wch)
+    /// This is intended for password prompts: the returned string contains the
+    /// real input while the screen only ever shows the mask. The mask is echoed
+    /// regardless of the `echo()`/`noecho()` setting; pass `'\0'` to echo
+    /// nothing at all. A negative `maxlen` allows up to 1024 characters.
+    pub fn getstr_masked(&mut self, maxlen: i32, mask: char) -> Result<String> {
+        self.getstr_masked_internal(None, maxlen, mask)
     }
 
-    /// Move cursor and add a wide character to stdscr.
+    /// Get a masked string from a window.
     ///
-    /// This is the ncurses `mvadd_wch()` function.
-    #[cfg(feature = "wide")]
-    pub fn mvadd_wch(&mut self, y: i32, x: i32, wch: &crate::wide::CCharT) -> Result<()> {
-        self.stdscr.mvadd_wch(y, x, wch)
+    /// See [`getstr_masked()`](Self::getstr_masked) for details.
+    pub fn wgetstr_masked(&mut self, win: &mut Window, maxlen: i32, mask: char) -> Result<String> {
+        self.getstr_masked_internal(Some(win), maxlen, mask)
     }
 
-    /// Move cursor and add a wide character to a window.
-    ///
-    /// This is the ncurses `mvwadd_wch()` function.
-    #[cfg(feature = "wide")]
-    pub fn mvwadd_wch(
+    /// Move cursor and get a masked string from stdscr.
+    pub fn mvgetstr_masked(&mut self, y: i32, x: i32, maxlen: i32, mask: char) -> Result<String> {
+        self.stdscr.mv(y, x)?;
+        self.getstr_masked(maxlen, mask)
+    }
+
+    /// Move cursor and get a masked string from a window.
+    pub fn mvwgetstr_masked(
         &mut self,
         win: &mut Window,
         y: i32,
         x: i32,
-        wch: &crate::wide::CCharT,
-    ) -> Result<()> {
-        win.mvadd_wch(y, x, wch)
+        maxlen: i32,
+        mask: char,
+    ) -> Result<String> {
+        win.mv(y, x)?;
+        self.wgetstr_masked(win, maxlen, mask)
     }
 
-    /// Add a wide character string (array of cchar_t) to stdscr.
+    /// Read a line from stdscr using a [`LineEditor`].
     ///
-    /// This is the ncurses `add_wchstr()` function.
-    #[cfg(feature = "wide")]
-    pub fn add_wchstr(&mut self, wchstr: &[crate::wide::CCharT]) -> Result<()> {
-        self.stdscr.add_wchstr(wchstr)
-    }
+    /// `prompt` is shown at the cursor position and the line is edited after
+    /// it, with the cursor keys, Home/End and history recall handled by the
+    /// editor. The line is redrawn on every key regardless of `echo()`, and
+    /// escape sequences are decoded even without `keypad()`. Input longer
+    /// than the window scrolls horizontally. Returns the line when Enter is
+    /// pressed, after adding it to the editor's history.
+    pub fn edit_line(&mut self, editor: &mut LineEditor, prompt: &str) -> Result<String> {
+        let (y, x) = (self.stdscr.getcury(), self.stdscr.getcurx());
+        let delay = Delay::from_raw(self.stdscr.getdelay());
 
-    /// Add a wide character string (array of cchar_t) to a window.
-    ///
-    /// This is the ncurses `wadd_wchstr()` function.
-    #[cfg(feature = "wide")]
-    pub fn wadd_wchstr(&mut self, win: &mut Window, wchstr: &[crate::wide::CCharT]) -> Result<()> {
-        win.add_wchstr(wchstr)
-    }
+        loop {
+            self.render_edit_line(editor, prompt, y, x)?;
+            if self.edit_line_key(editor, delay)? == EditStatus::Done {
+                break;
+            }
+        }
 
-    /// Add at most n wide characters from a string to stdscr.
-    ///
-    /// This is the ncurses `add_wchnstr()` function.
-    #[cfg(feature = "wide")]
-    pub fn add_wchnstr(&mut self, wchstr: &[crate::wide::CCharT], n: i32) -> Result<()> {
-        self.stdscr.add_wchnstr(wchstr, n)
+        let line = editor.finish();
+        self.stdscr.addch(b'\n' as ChType)?;
+        self.refresh()?;
+        Ok(line)
     }
 
-    /// Add at most n wide characters from a string to a window.
-    ///
-    /// This is the ncurses `wadd_wchnstr()` function.
+    /// Read one key or character and apply it to `editor`.
     #[cfg(feature = "wide")]
-    pub fn wadd_wchnstr(
-        &mut self,
-        win: &mut Window,
-        wchstr: &[crate::wide::CCharT],
-        n: i32,
-    ) -> Result<()> {
-        win.add_wchnstr(wchstr, n)
-    }
+    fn edit_line_key(&mut self, editor: &mut LineEditor, delay: Delay) -> Result<EditStatus> {
+        use crate::wide::WideInput;
 
-    /// Move cursor and add a wide character string to stdscr.
-    ///
-    /// This is the ncurses `mvadd_wchstr()` function.
-    #[cfg(feature = "wide")]
-    pub fn mvadd_wchstr(&mut self, y: i32, x: i32, wchstr: &[crate::wide::CCharT]) -> Result<()> {
-        self.stdscr.mvadd_wchstr(y, x, wchstr)
+        match self.get_wch_internal(delay, true)? {
+            WideInput::Char(c) => editor.handle_char(c),
+            WideInput::Key(key) => editor.handle_key(key),
+            WideInput::None => Err(Error::Timeout),
+            WideInput::Eof => Err(Error::Eof),
+            WideInput::Error => Ok(EditStatus::Editing),
+        }
     }
 
-    /// Move cursor and add at most n wide characters to stdscr.
-    ///
-    /// This is the ncurses `mvadd_wchnstr()` function.
-    #[cfg(feature = "wide")]
-    pub fn mvadd_wchnstr(
-        &mut self,
-        y: i32,
-        x: i32,
-        wchstr: &[crate::wide::CCharT],
-        n: i32,
-    ) -> Result<()> {
-        self.stdscr.mvadd_wchnstr(y, x, wchstr, n)
+    /// Read one key and apply it to `editor`.
+    #[cfg(not(feature = "wide"))]
+    fn edit_line_key(&mut self, editor: &mut LineEditor, delay: Delay) -> Result<EditStatus> {
+        let ch = self.getch_internal(delay, true)?;
+        editor.handle_key(ch)
     }
 
-    /// Move cursor and add a wide character string to a window.
-    ///
-    /// This is the ncurses `mvwadd_wchstr()` function.
-    #[cfg(feature = "wide")]
-    pub fn mvwadd_wchstr(
+    /// Draw the prompt and the visible part of the edited line at `(y, x)`.
+    fn render_edit_line(
         &mut self,
-        win: &mut Window,
+        editor: &LineEditor,
+        prompt: &str,
         y: i32,
         x: i32,
-        wchstr: &[crate::wide::CCharT],
     ) -> Result<()> {
-        win.mvadd_wchstr(y, x, wchstr)
+        self.stdscr.mv(y, x)?;
+        self.stdscr.clrtoeol()?;
+        let used = self.stdscr.mvaddstr_clipped(y, x, prompt)? as i32;
+
+        // Keep the cursor inside the window, one cell from the right edge
+        let avail = (self.stdscr.getmaxx() - x - used).max(1) as usize;
+        let line: Vec<char> = editor.line().chars().collect();
+        let width = |c: &char| unicode_width::UnicodeWidthChar::width(*c).unwrap_or(0);
+        let mut first = editor.cursor();
+        let mut offset = 0;
+        while first > 0 && offset + width(&line[first - 1]) < avail {
+            first -= 1;
+            offset += width(&line[first]);
+        }
+        let visible: String = line[first..].iter().collect();
+        self.stdscr.mvaddstr_clipped(y, x + used, &visible)?;
+
+        self.stdscr.mv(y, x + used + offset as i32)?;
+        self.refresh()
     }
 
-    /// Move cursor and add at most n wide characters to a window.
+    /// Shared line editing loop for the masked getstr family.
     ///
-    /// This is the ncurses `mvwadd_wchnstr()` function.
-    #[cfg(feature = "wide")]
-    pub fn mvwadd_wchnstr(
+    /// `win` of `None` targets stdscr.
+    fn getstr_masked_internal(
         &mut self,
-        win: &mut Window,
-        y: i32,
-        x: i32,
-        wchstr: &[crate::wide::CCharT],
-        n: i32,
-    ) -> Result<()> {
-        win.mvadd_wchnstr(y, x, wchstr, n)
-    }
+        mut win: Option<&mut Window>,
+        maxlen: i32,
+        mask: char,
+    ) -> Result<String> {
+        let maxlen = getstr_limit(maxlen);
+        let mut result = String::new();
+        let echo_enabled = mask != '\0';
+
+        let mut mask_buf = [0u8; 4];
+        let mask_str: &str = mask.encode_utf8(&mut mask_buf);
+        let mask_width = unicode_width::UnicodeWidthChar::width(mask).unwrap_or(1) as i32;
+
+        let (delay, use_keypad) = match win.as_deref() {
+            Some(w) => (Delay::from_raw(w.getdelay()), w.is_keypad()),
+            None => (
+                Delay::from_raw(self.stdscr.getdelay()),
+                self.stdscr.is_keypad(),
+            ),
+        };
 
-    /// Move cursor in a window.
-    pub fn wmove(&mut self, win: &mut Window, y: i32, x: i32) -> Result<()> {
-        win.mv(y, x)
-    }
+        loop {
+            // Key codes are ignored
+            let Some(ch) = self.getstr_masked_char(delay, use_keypad)? else {
+                continue;
+            };
 
-    /// Clear the entire window.
-    pub fn wclear(&mut self, win: &mut Window) -> Result<()> {
-        win.clear()
-    }
+            match ch {
+                // Enter/Return
+                '\n' | '\r' => {
+                    if echo_enabled {
+                        self.echo_masked(win.as_deref_mut(), |w| w.addch(b'\n' as ChType))?;
+                    }
+                    break;
+                }
+                // Backspace
+                '\x08' | '\x7f' if !result.is_empty() => {
+                    result.pop();
+                    if echo_enabled {
+                        self.echo_masked(win.as_deref_mut(), |w| {
+                            let (y, x) = (w.getcury(), w.getcurx());
+                            if x >= mask_width {
+                                w.mv(y, x - mask_width)?;
+                                for _ in 0..mask_width {
+                                    w.addch(b' ' as ChType)?;
+                                }
+                                w.mv(y, x - mask_width)?;
+                            }
+                            Ok(())
+                        })?;
+                    }
+                }
+                // Regular character
+                _ if !ch.is_control() && result.chars().count() < maxlen => {
+                    result.push(ch);
+                    if echo_enabled {
+                        self.echo_masked(win.as_deref_mut(), |w| w.addstr(mask_str))?;
+                    }
+                }
+                // Control-C, Control-D - cancel
+                '\x03' | '\x04' => {
+                    return Err(Error::Interrupted);
+                }
+                _ => {}
+            }
+        }
 
-    /// Erase the window (fill with blanks).
-    pub fn werase(&mut self, win: &mut Window) -> Result<()> {
-        win.erase()
+        Ok(result)
     }
 
-    /// Clear from cursor to end of line.
-    pub fn wclrtoeol(&mut self, win: &mut Window) -> Result<()> {
-        win.clrtoeol()
+    /// Read one character for the masked getstr family, or `None` for a key.
+    ///
+    /// Multibyte UTF-8 input is decoded into a single character.
+    #[cfg(feature = "wide")]
+    fn getstr_masked_char(&mut self, delay: Delay, use_keypad: bool) -> Result<Option<char>> {
+        use crate::wide::WideInput;
+
+        match self.get_wch_internal(delay, use_keypad)? {
+            WideInput::Char(c) => Ok(Some(c)),
+            WideInput::Key(_) | WideInput::Error => Ok(None),
+            WideInput::None => Err(Error::Timeout),
+            WideInput::Eof => Err(Error::Eof),
+        }
     }
 
-    /// Clear from cursor to end of window.
-    pub fn wclrtobot(&mut self, win: &mut Window) -> Result<()> {
-        win.clrtobot()
+    /// Read one character for the masked getstr family, or `None` for a key.
+    ///
+    /// Only ASCII is accepted without the `wide` feature.
+    #[cfg(not(feature = "wide"))]
+    fn getstr_masked_char(&mut self, delay: Delay, use_keypad: bool) -> Result<Option<char>> {
+        let ch = self.getch_internal(delay, use_keypad)?;
+        Ok(u8::try_from(ch).ok().filter(u8::is_ascii).map(char::from))
     }
 
-    /// Draw a box around a window.
-    pub fn wbox(&mut self, win: &mut Window, verch: ChType, horch: ChType) -> Result<()> {
-        win.box_(verch, horch)
+    /// Apply an echo operation to `win` (or stdscr) and refresh it.
+    fn echo_masked<F>(&mut self, win: Option<&mut Window>, f: F) -> Result<()>
+    where
+        F: FnOnce(&mut Window) -> Result<()>,
+    {
+        match win {
+            Some(w) => {
+                f(w)?;
+                self.wrefresh(w)
+            }
+            None => {
+                f(&mut self.stdscr)?;
+                self.refresh()
+            }
+        }
     }
 
-    /// Draw a border around a window.
-    #[allow(clippy::too_many_arguments)]
-    pub fn wborder(
+    // ========================================================================
+    // Event loop
+    // ========================================================================
+
+    /// Read input and pass it to `on_event` until it returns
+    /// `ControlFlow::Break`.
+    ///
+    /// `KEY_RESIZE` is handled before the callback sees it: the terminal
+    /// size is re-read with [`update_term_size()`](Self::update_term_size)
+    /// and the callback gets [`Event::Resize`] to redraw. Mouse and paste
+    /// keys arrive with their data attached. With an input timeout the
+    /// callback gets [`Event::Timeout`] each time it expires; in `nodelay`
+    /// mode that is every call, so prefer `timeout()` for periodic work.
+    ///
+    /// ```rust,no_run
+    /// use ncurses::screen::Event;
+    /// use ncurses::{Key, Result, Screen};
+    /// use std::ops::ControlFlow;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let mut screen = Screen::init()?;
+    /// screen.run(|screen, event| {
+    ///     match event {
+    ///         Event::Key(Key::Char('q')) => return ControlFlow::Break(()),
+    ///         Event::Resize { lines, cols } => {
+    ///             let _ = screen.mvaddstr(0, 0, &format!("{lines}x{cols}"));
+    ///         }
+    ///         _ => {}
+    ///     }
+    ///     let _ = screen.refresh();
+    ///     ControlFlow::Continue(())
+    /// })?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn run(
         &mut self,
-        win: &mut Window,
-        ls: ChType,
-        rs: ChType,
-        ts: ChType,
-        bs: ChType,
-        tl: ChType,
-        tr: ChType,
-        bl: ChType,
-        br: ChType,
+        mut on_event: impl FnMut(&mut Screen, Event) -> std::ops::ControlFlow<()>,
     ) -> Result<()> {
-        win.border(ls, rs, ts, bs, tl, tr, bl, br)
+        loop {
+            let event = match self.getch() {
+                Ok(KEY_RESIZE) => {
+                    self.update_term_size()?;
+                    // Already being handled; don't report it a second time
+                    self.resize_pending = false;
+                    let (lines, cols) = self.getmaxyx();
+                    Event::Resize { lines, cols }
+                }
+                #[cfg(feature = "mouse")]
+                Ok(KEY_MOUSE) => match self.getmouse() {
+                    Some(mouse) => Event::Mouse(mouse),
+                    None => Event::Key(Key::Mouse),
+                },
+                Ok(KEY_PASTE) => match self.get_paste() {
+                    Some(text) => Event::Paste(text),
+                    None => Event::Key(Key::Paste),
+                },
+                Ok(ch) => Event::Key(Key::from_code(ch)),
+                Err(Error::Timeout | Error::NoInput) => Event::Timeout,
+                Err(e) => return Err(e),
+            };
+            if on_event(self, event).is_break() {
+                return Ok(());
+            }
+        }
     }
 
-    /// Draw a horizontal line.
-    pub fn whline(&mut self, win: &mut Window, ch: ChType, n: i32) -> Result<()> {
-        win.hline(ch, n)
-    }
+    // ========================================================================
+    // Convenience methods for stdscr operations
+    // ========================================================================
 
-    /// Draw a vertical line.
-    pub fn wvline(&mut self, win: &mut Window, ch: ChType, n: i32) -> Result<()> {
-        win.vline(ch, n)
+    /// Move cursor to position (y, x) in stdscr.
+    pub fn mv(&mut self, y: i32, x: i32) -> Result<()> {
+        self.stdscr.mv(y, x)
     }
 
-    /// Draw a horizontal line at position.
-    pub fn mvwhline(&mut self, win: &mut Window, y: i32, x: i32, ch: ChType, n: i32) -> Result<()> {
-        win.mv(y, x)?;
-        win.hline(ch, n)
+    /// Add a character at the current cursor position in stdscr.
+    pub fn addch(&mut self, ch: ChType) -> Result<()> {
+        self.stdscr.addch(ch)?;
+        self.immedok_refresh_stdscr()
     }
 
-    /// Draw a vertical line at position.
-    pub fn mvwvline(&mut self, win: &mut Window, y: i32, x: i32, ch: ChType, n: i32) -> Result<()> {
-        win.mv(y, x)?;
-        win.vline(ch, n)
+    /// Add a string at the current cursor position in stdscr.
+    pub fn addstr(&mut self, s: &str) -> Result<()> {
+        self.stdscr.addstr(s)?;
+        self.immedok_refresh_stdscr()
     }
 
-    /// Insert a character before the cursor.
-    pub fn winsch(&mut self, win: &mut Window, ch: ChType) -> Result<()> {
-        win.insch(ch)
+    /// Move to (y, x) and add a character in stdscr.
+    pub fn mvaddch(&mut self, y: i32, x: i32, ch: ChType) -> Result<()> {
+        self.stdscr.mv(y, x)?;
+        self.addch(ch)
     }
 
-    /// Insert a character at specified position.
-    pub fn mvwinsch(&mut self, win: &mut Window, y: i32, x: i32, ch: ChType) -> Result<()> {
-        win.mv(y, x)?;
-        win.insch(ch)
+    /// Move to (y, x) and add a string in stdscr.
+    pub fn mvaddstr(&mut self, y: i32, x: i32, s: &str) -> Result<()> {
+        self.stdscr.mv(y, x)?;
+        self.addstr(s)
     }
 
-    /// Delete the character under the cursor.
-    pub fn wdelch(&mut self, win: &mut Window) -> Result<()> {
-        win.delch()
+    /// Add a string to stdscr, clamping the position and truncating at the
+    /// right margin instead of failing or wrapping.
+    ///
+    /// Returns the number of `char`s written. See
+    /// [`Window::mvaddstr_clipped`].
+    pub fn mvaddstr_clipped(&mut self, y: i32, x: i32, s: &str) -> Result<usize> {
+        let written = self.stdscr.mvaddstr_clipped(y, x, s)?;
+        self.immedok_refresh_stdscr()?;
+        Ok(written)
     }
 
-    /// Delete a character at specified position.
-    pub fn mvwdelch(&mut self, win: &mut Window, y: i32, x: i32) -> Result<()> {
-        win.mv(y, x)?;
-        win.delch()
+    /// Add a wide string at the current cursor position in stdscr.
+    ///
+    /// This is the Rust equivalent of `addwstr()`.
+    #[cfg(feature = "wide")]
+    pub fn addwstr(&mut self, s: &str) -> Result<()> {
+        self.stdscr.addwstr(s)
     }
 
-    /// Insert a blank line above the cursor.
-    pub fn winsertln(&mut self, win: &mut Window) -> Result<()> {
-        win.insertln()
+    /// Add a wide string with a maximum length in stdscr.
+    ///
+    /// This is the Rust equivalent of `addnwstr()`.
+    #[cfg(feature = "wide")]
+    pub fn addnwstr(&mut self, s: &str, n: i32) -> Result<()> {
+        self.stdscr.addnwstr(s, n)
     }
 
-    /// Delete the line under the cursor.
-    pub fn wdeleteln(&mut self, win: &mut Window) -> Result<()> {
-        win.deleteln()
+    /// Move to (y, x) and add a wide string in stdscr.
+    #[cfg(feature = "wide")]
+    pub fn mvaddwstr(&mut self, y: i32, x: i32, s: &str) -> Result<()> {
+        self.stdscr.mvaddwstr(y, x, s)
     }
 
-    /// Insert/delete lines.
-    pub fn winsdelln(&mut self, win: &mut Window, n: i32) -> Result<()> {
-        win.insdelln(n)
+    /// Move to (y, x) and add a wide string with a maximum length in stdscr.
+    #[cfg(feature = "wide")]
+    pub fn mvaddnwstr(&mut self, y: i32, x: i32, s: &str, n: i32) -> Result<()> {
+        self.stdscr.mvaddnwstr(y, x, s, n)
     }
 
-    /// Get the character at the current cursor position.
-    pub fn winch(&self, win: &Window) -> ChType {
-        win.inch()
+    /// Add a wide string at the current cursor position in a window.
+    ///
+    /// This is the Rust equivalent of `waddwstr()`.
+    #[cfg(feature = "wide")]
+    pub fn waddwstr(&mut self, win: &mut Window, s: &str) -> Result<()> {
+        win.addwstr(s)
     }
 
-    /// Get the character at specified position.
-    pub fn mvwinch(&mut self, win: &mut Window, y: i32, x: i32) -> Result<ChType> {
-        win.mv(y, x)?;
-        Ok(win.inch())
+    /// Add a wide string with a maximum length in a window.
+    ///
+    /// This is the Rust equivalent of `waddnwstr()`.
+    #[cfg(feature = "wide")]
+    pub fn waddnwstr(&mut self, win: &mut Window, s: &str, n: i32) -> Result<()> {
+        win.addnwstr(s, n)
     }
 
-    /// Get a string from a window.
-    pub fn winnstr(&self, win: &Window, n: i32) -> String {
-        win.instr(n)
+    /// Move cursor and add a wide string in a window.
+    #[cfg(feature = "wide")]
+    pub fn mvwaddwstr(&mut self, win: &mut Window, y: i32, x: i32, s: &str) -> Result<()> {
+        win.mvaddwstr(y, x, s)
     }
 
-    /// Get a string from specified position.
-    pub fn mvwinnstr(&mut self, win: &mut Window, y: i32, x: i32, n: i32) -> Result<String> {
-        win.mv(y, x)?;
-        Ok(win.instr(n))
+    /// Move cursor and add a wide string with a maximum length in a window.
+    #[cfg(feature = "wide")]
+    pub fn mvwaddnwstr(&mut self, win: &mut Window, y: i32, x: i32, s: &str, n: i32) -> Result<()> {
+        win.mvaddnwstr(y, x, s, n)
     }
 
-    // ========================================================================
-    // stdscr inch/instr family
-    // ========================================================================
-
-    /// Get the character at the current cursor position on stdscr.
-    #[must_use]
-    pub fn inch(&self) -> ChType {
-        self.stdscr.inch()
+    /// Turn on attributes in stdscr.
+    pub fn attron(&mut self, attr: AttrT) -> Result<()> {
+        self.stdscr.attron(attr)
     }
 
-    /// Move cursor and get the character at that position on stdscr.
-    pub fn mvinch(&mut self, y: i32, x: i32) -> Result<ChType> {
-        self.stdscr.mvinch(y, x)
+    /// Turn off attributes in stdscr.
+    pub fn attroff(&mut self, attr: AttrT) -> Result<()> {
+        self.stdscr.attroff(attr)
     }
 
-    /// Get a string from stdscr at the current position.
-    #[must_use]
-    pub fn instr(&self, n: i32) -> String {
-        self.stdscr.instr(n)
+    /// Set attributes in stdscr.
+    pub fn attrset(&mut self, attr: AttrT) -> Result<()> {
+        self.stdscr.attrset(attr)
     }
 
-    /// Get a string from stdscr with a limit (alias for instr).
-    #[must_use]
-    pub fn innstr(&self, n: i32) -> String {
-        self.stdscr.innstr(n)
+    /// Clear the stdscr.
+    pub fn clear(&mut self) -> Result<()> {
+        self.stdscr.clear()
     }
 
-    /// Move cursor and get a string from stdscr.
-    pub fn mvinstr(&mut self, y: i32, x: i32, n: i32) -> Result<String> {
-        self.stdscr.mv(y, x)?;
-        Ok(self.stdscr.instr(n))
+    /// Erase the stdscr.
+    pub fn erase(&mut self) -> Result<()> {
+        self.stdscr.erase()
     }
 
-    /// Move cursor and get a string from stdscr with a limit.
-    pub fn mvinnstr(&mut self, y: i32, x: i32, n: i32) -> Result<String> {
-        self.stdscr.mvinnstr(y, x, n)
+    /// Clear to end of line in stdscr.
+    pub fn clrtoeol(&mut self) -> Result<()> {
+        self.stdscr.clrtoeol()
     }
 
-    // ========================================================================
-    // inchnstr family (character array with attributes)
-    // ========================================================================
-
-    /// Get a string of characters with attributes from stdscr.
-    pub fn inchnstr(&self, chstr: &mut [ChType], n: i32) -> i32 {
-        self.stdscr.inchnstr(chstr, n)
+    /// Clear to bottom of screen in stdscr.
+    pub fn clrtobot(&mut self) -> Result<()> {
+        self.stdscr.clrtobot()
     }
 
-    /// Move cursor and get a string of characters with attributes from stdscr.
-    pub fn mvinchnstr(&mut self, y: i32, x: i32, chstr: &mut [ChType], n: i32) -> Result<i32> {
-        self.stdscr.mvinchnstr(y, x, chstr, n)
+    /// Draw a border around stdscr.
+    #[allow(clippy::too_many_arguments)]
+    pub fn border(
+        &mut self,
+        ls: ChType,
+        rs: ChType,
+        ts: ChType,
+        bs: ChType,
+        tl: ChType,
+        tr: ChType,
+        bl: ChType,
+        br: ChType,
+    ) -> Result<()> {
+        self.stdscr.border(ls, rs, ts, bs, tl, tr, bl, br)
     }
 
-    /// Get a string of characters with attributes from a window.
-    pub fn winchnstr(&self, win: &Window, chstr: &mut [ChType], n: i32) -> i32 {
-        win.inchnstr(chstr, n)
+    /// Draw a box around stdscr using default characters.
+    pub fn r#box(&mut self, verch: ChType, horch: ChType) -> Result<()> {
+        self.stdscr.box_(verch, horch)
     }
 
-    /// Move cursor and get a string of characters with attributes from a window.
-    pub fn mvwinchnstr(
-        &mut self,
-        win: &mut Window,
-        y: i32,
-        x: i32,
-        chstr: &mut [ChType],
-        n: i32,
-    ) -> Result<i32> {
-        win.mvinchnstr(y, x, chstr, n)
+    /// Draw a horizontal line in stdscr.
+    pub fn hline(&mut self, ch: ChType, n: i32) -> Result<()> {
+        self.stdscr.hline(ch, n)
     }
 
-    /// Set window background character.
-    pub fn wbkgdset(&mut self, win: &mut Window, ch: ChType) {
-        win.bkgdset(ch);
+    /// Draw a vertical line in stdscr.
+    pub fn vline(&mut self, ch: ChType, n: i32) -> Result<()> {
+        self.stdscr.vline(ch, n)
     }
 
-    /// Get window background character.
-    pub fn wgetbkgd(&self, win: &Window) -> ChType {
-        win.getbkgd()
+    /// Get current cursor Y position in stdscr.
+    pub fn getcury(&self) -> i32 {
+        self.stdscr.getcury()
     }
 
-    /// Set window background and apply to all characters.
-    pub fn wbkgd(&mut self, win: &mut Window, ch: ChType) -> Result<()> {
-        win.bkgd(ch)
+    /// Get current cursor X position in stdscr.
+    pub fn getcurx(&self) -> i32 {
+        self.stdscr.getcurx()
     }
 
-    /// Turn on window attributes.
-    pub fn wattron(&mut self, win: &mut Window, attr: AttrT) -> Result<()> {
-        win.attron(attr)
+    /// Get current cursor position in stdscr (y, x).
+    pub fn getyx(&self) -> (i32, i32) {
+        self.stdscr.cursor()
     }
 
-    /// Turn off window attributes.
-    pub fn wattroff(&mut self, win: &mut Window, attr: AttrT) -> Result<()> {
-        win.attroff(attr)
+    /// Get the size of stdscr (lines, columns).
+    pub fn getmaxyx(&self) -> (i32, i32) {
+        (self.stdscr.getmaxy(), self.stdscr.getmaxx())
     }
 
-    /// Set window attributes.
-    pub fn wattrset(&mut self, win: &mut Window, attr: AttrT) -> Result<()> {
-        win.attrset(attr)
+    /// Get the origin of stdscr (y, x).
+    pub fn getbegyx(&self) -> (i32, i32) {
+        self.stdscr.origin()
     }
 
-    /// Get window attributes.
-    pub fn wgetattrs(&self, win: &Window) -> AttrT {
-        win.getattrs()
+    /// Get the position of stdscr relative to its parent (y, x).
+    pub fn getparyx(&self) -> (i32, i32) {
+        (self.stdscr.getpary(), self.stdscr.getparx())
     }
 
-    /// Change attributes of characters at current position in stdscr.
-    ///
-    /// Changes the attributes of `n` characters starting at the cursor.
-    /// If `n` is -1, changes attributes to end of line.
-    pub fn chgat(&mut self, n: i32, attr: AttrT, color: i16) -> Result<()> {
-        self.stdscr.chgat(n, attr, color)
+    /// Get maximum Y coordinate in stdscr.
+    pub fn getmaxy(&self) -> i32 {
+        self.stdscr.getmaxy()
     }
 
-    /// Move cursor and change attributes in stdscr.
-    pub fn mvchgat(&mut self, y: i32, x: i32, n: i32, attr: AttrT, color: i16) -> Result<()> {
-        self.stdscr.mvchgat(y, x, n, attr, color)
+    /// Get maximum X coordinate in stdscr.
+    pub fn getmaxx(&self) -> i32 {
+        self.stdscr.getmaxx()
     }
 
-    /// Change attributes of characters at current position in window.
-    pub fn wchgat(&mut self, win: &mut Window, n: i32, attr: AttrT, color: i16) -> Result<()> {
-        win.wchgat(n, attr, color)
+    /// Enable scrolling in stdscr.
+    pub fn scrollok(&mut self, bf: bool) {
+        self.stdscr.scrollok(bf);
     }
 
-    /// Move cursor and change attributes in window.
-    pub fn mvwchgat(
-        &mut self,
-        win: &mut Window,
-        y: i32,
-        x: i32,
-        n: i32,
-        attr: AttrT,
-        color: i16,
-    ) -> Result<()> {
-        win.mvwchgat(y, x, n, attr, color)
+    /// Set scrolling region in stdscr.
+    pub fn setscrreg(&mut self, top: i32, bot: i32) -> Result<()> {
+        self.stdscr.setscrreg(top, bot)
     }
 
-    /// Set window color pair.
-    pub fn wcolor_set(&mut self, win: &mut Window, pair: i16) -> Result<()> {
-        win.color_set(pair)
+    /// Scroll stdscr by n lines.
+    pub fn scroll(&mut self, n: i32) -> Result<()> {
+        self.stdscr.scrl(n)
     }
 
-    /// Scroll window up.
-    pub fn wscrl(&mut self, win: &mut Window, n: i32) -> Result<()> {
-        win.scrl(n)
+    /// Set keypad mode in stdscr.
+    pub fn keypad(&mut self, bf: bool) {
+        self.stdscr.keypad(bf);
     }
 
-    /// Set scrolling region.
-    pub fn wsetscrreg(&mut self, win: &mut Window, top: i32, bot: i32) -> Result<()> {
-        win.setscrreg(top, bot)
+    /// Set nodelay mode in stdscr.
+    pub fn nodelay(&mut self, bf: bool) {
+        self.stdscr.nodelay(bf);
     }
 
-    /// Get scrolling region.
-    pub fn wgetscrreg(&self, win: &Window) -> (i32, i32) {
-        win.getscrreg()
+    /// Set timeout for input in stdscr.
+    pub fn timeout(&mut self, delay: i32) {
+        self.stdscr.timeout(delay);
     }
 
-    /// Enable/disable scrolling for a window.
-    pub fn wscrollok(&mut self, win: &mut Window, bf: bool) {
-        win.scrollok(bf);
+    /// Run `f` with the stdscr input timeout set to `delay`.
+    ///
+    /// The previous delay is restored once `f` returns, whether it succeeds
+    /// or fails. Use [`Window::with_timeout()`] for other windows.
+    pub fn with_timeout<T, F>(&mut self, delay: i32, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut Self) -> Result<T>,
+    {
+        let saved = self.stdscr.getdelay();
+        self.stdscr.timeout(delay);
+        let result = f(self);
+        self.stdscr.timeout(saved);
+        result
     }
 
-    /// Check if scrolling is enabled for a window.
-    pub fn wis_scrollok(&self, win: &Window) -> bool {
-        win.is_scrollok()
+    /// Insert a character at the current position in stdscr.
+    pub fn insch(&mut self, ch: ChType) -> Result<()> {
+        self.stdscr.insch(ch)
     }
 
-    /// Enable/disable keypad for a window.
-    pub fn wkeypad(&mut self, win: &mut Window, bf: bool) {
-        win.keypad(bf);
+    /// Insert a string at the current position in stdscr.
+    ///
+    /// Characters to the right are shifted right; characters shifted off
+    /// the right edge are lost. The cursor position does not change.
+    pub fn insstr(&mut self, s: &str) -> Result<()> {
+        self.stdscr.insstr(s)
     }
 
-    /// Check if keypad is enabled for a window.
-    pub fn wis_keypad(&self, win: &Window) -> bool {
-        win.is_keypad()
+    /// Insert at most n characters of a string at the current position in stdscr.
+    ///
+    /// If n is negative, the entire string is inserted.
+    pub fn insnstr(&mut self, s: &str, n: i32) -> Result<()> {
+        self.stdscr.insnstr(s, n)
     }
 
-    /// Enable/disable nodelay mode for a window.
-    pub fn wnodelay(&mut self, win: &mut Window, bf: bool) {
-        win.nodelay(bf);
+    /// Move cursor and insert a string in stdscr.
+    pub fn mvinsstr(&mut self, y: i32, x: i32, s: &str) -> Result<()> {
+        self.stdscr.mvinsstr(y, x, s)
     }
 
-    /// Check if nodelay mode is enabled for a window.
-    pub fn wis_nodelay(&self, win: &Window) -> bool {
-        win.is_nodelay()
+    /// Move cursor and insert at most n characters in stdscr.
+    pub fn mvinsnstr(&mut self, y: i32, x: i32, s: &str, n: i32) -> Result<()> {
+        self.stdscr.mvinsnstr(y, x, s, n)
     }
 
-    /// Set input timeout for a window.
-    pub fn wtimeout(&mut self, win: &mut Window, delay: i32) {
-        win.timeout(delay);
+    /// Insert a string at the current position in a window.
+    pub fn winsstr(&mut self, win: &mut Window, s: &str) -> Result<()> {
+        win.insstr(s)
     }
 
-    /// Get input timeout for a window.
-    pub fn wgetdelay(&self, win: &Window) -> i32 {
-        win.getdelay()
+    /// Insert at most n characters of a string in a window.
+    pub fn winsnstr(&mut self, win: &mut Window, s: &str, n: i32) -> Result<()> {
+        win.insnstr(s, n)
     }
 
-    /// Enable/disable cursor leave mode for a window.
-    pub fn wleaveok(&mut self, win: &mut Window, bf: bool) {
-        win.leaveok(bf);
+    /// Move cursor and insert a string in a window.
+    pub fn mvwinsstr(&mut self, win: &mut Window, y: i32, x: i32, s: &str) -> Result<()> {
+        win.mvinsstr(y, x, s)
     }
 
-    /// Check if cursor leave mode is enabled.
-    pub fn wis_leaveok(&self, win: &Window) -> bool {
-        win.is_leaveok()
+    /// Move cursor and insert at most n characters in a window.
+    pub fn mvwinsnstr(&mut self, win: &mut Window, y: i32, x: i32, s: &str, n: i32) -> Result<()> {
+        win.mvinsnstr(y, x, s, n)
     }
 
-    /// Enable/disable clear-on-refresh for a window.
-    pub fn wclearok(&mut self, win: &mut Window, bf: bool) {
-        win.clearok(bf);
+    /// Delete the character at the current position in stdscr.
+    pub fn delch(&mut self) -> Result<()> {
+        self.stdscr.delch()
     }
 
-    /// Check if clear-on-refresh is enabled for a window.
-    pub fn wis_cleared(&self, win: &Window) -> bool {
-        win.is_cleared()
+    /// Insert a blank line above the current line in stdscr.
+    pub fn insertln(&mut self) -> Result<()> {
+        self.stdscr.insertln()
     }
 
-    /// Enable/disable hardware line insertion for a window.
-    pub fn widlok(&mut self, win: &mut Window, bf: bool) {
-        win.idlok(bf);
+    /// Delete the current line in stdscr.
+    pub fn deleteln(&mut self) -> Result<()> {
+        self.stdscr.deleteln()
     }
 
-    /// Check if hardware line insertion is enabled.
-    pub fn wis_idlok(&self, win: &Window) -> bool {
-        win.is_idlok()
+    /// Set background character in stdscr.
+    pub fn bkgdset(&mut self, ch: ChType) {
+        self.stdscr.bkgdset(ch);
     }
 
-    /// Enable/disable hardware character insertion for a window.
-    pub fn widcok(&mut self, win: &mut Window, bf: bool) {
-        win.idcok(bf);
+    /// Set and apply background to stdscr.
+    pub fn bkgd(&mut self, ch: ChType) -> Result<()> {
+        self.stdscr.bkgd(ch)
     }
 
-    /// Check if hardware character insertion is enabled.
-    pub fn wis_idcok(&self, win: &Window) -> bool {
-        win.is_idcok()
+    /// Print a string to stdscr (simplified version of C's printw).
+    ///
+    /// **Note:** Unlike C ncurses, this does not support printf-style format strings.
+    /// Use Rust's `format!` macro to format your string before calling this:
+    ///
+    /// ```rust,ignore
+    /// // C ncurses: printw("Value: %d", value);
+    /// // Rust:
+    /// screen.printw(&format!("Value: {}", value))?;
+    /// ```
+    pub fn printw(&mut self, s: &str) -> Result<()> {
+        self.stdscr.addstr(s)
     }
 
-    /// Enable/disable immediate refresh for a window.
-    pub fn wimmedok(&mut self, win: &mut Window, bf: bool) {
-        win.immedok(bf);
+    /// Move cursor and print a string to stdscr.
+    ///
+    /// **Note:** Unlike C ncurses, this does not support printf-style format strings.
+    /// Use Rust's `format!` macro:
+    ///
+    /// ```rust,ignore
+    /// // C ncurses: mvprintw(y, x, "Value: %d", value);
+    /// // Rust:
+    /// screen.mvprintw(y, x, &format!("Value: {}", value))?;
+    /// ```
+    pub fn mvprintw(&mut self, y: i32, x: i32, s: &str) -> Result<()> {
+        self.stdscr.mv(y, x)?;
+        self.stdscr.addstr(s)
     }
 
-    /// Check if immediate refresh is enabled.
-    pub fn wis_immedok(&self, win: &Window) -> bool {
-        win.is_immedok()
+    /// Print a string to a window (simplified version of C's wprintw).
+    ///
+    /// **Note:** Unlike C ncurses, this does not support printf-style format strings.
+    /// Use Rust's `format!` macro:
+    ///
+    /// ```rust,ignore
+    /// // C ncurses: wprintw(win, "Value: %d", value);
+    /// // Rust:
+    /// screen.wprintw(win, &format!("Value: {}", value))?;
+    /// ```
+    pub fn wprintw(&mut self, win: &mut Window, s: &str) -> Result<()> {
+        win.addstr(s)
     }
 
-    /// Enable/disable automatic sync for a window.
-    pub fn wsyncok(&mut self, win: &mut Window, bf: bool) {
-        win.syncok(bf);
+    /// Move cursor and print a string to a window.
+    ///
+    /// **Note:** Unlike C ncurses, this does not support printf-style format strings.
+    /// Use Rust's `format!` macro:
+    ///
+    /// ```rust,ignore
+    /// // C ncurses: mvwprintw(win, y, x, "Value: %d", value);
+    /// // Rust:
+    /// screen.mvwprintw(win, y, x, &format!("Value: {}", value))?;
+    /// ```
+    pub fn mvwprintw(&mut self, win: &mut Window, y: i32, x: i32, s: &str) -> Result<()> {
+        win.mv(y, x)?;
+        win.addstr(s)
     }
 
-    /// Check if automatic sync is enabled.
-    pub fn wis_syncok(&self, win: &Window) -> bool {
-        win.is_syncok()
-    }
+    // ========================================================================
+    // Additional ncurses compatibility functions
+    // ========================================================================
 
-    /// Enable/disable notimeout mode for a window.
-    pub fn wnotimeout(&mut self, win: &mut Window, bf: bool) {
-        win.notimeout(bf);
+    /// Sleep for the specified number of milliseconds.
+    ///
+    /// This is the ncurses `napms()` function.
+    pub fn napms(&self, ms: i32) {
+        if ms > 0 {
+            std::thread::sleep(Duration::from_millis(ms as u64));
+        }
     }
 
-    /// Check if notimeout mode is enabled.
-    pub fn wis_notimeout(&self, win: &Window) -> bool {
-        win.is_notimeout()
+    /// Output padding characters to delay for the specified number of milliseconds.
+    ///
+    /// This function inserts a delay in the output stream by outputting padding
+    /// characters. The actual delay may vary depending on terminal baud rate.
+    ///
+    /// In this implementation, we simply use a sleep since modern terminals
+    /// don't need padding characters.
+    pub fn delay_output(&mut self, ms: i32) -> Result<()> {
+        if ms > 0 {
+            self.terminal.flush()?;
+            std::thread::sleep(Duration::from_millis(ms as u64));
+        }
+        Ok(())
     }
 
-    /// Touch a window (mark all lines as changed).
-    pub fn touchwin(&mut self, win: &mut Window) {
-        win.touchwin();
+    /// Save the current program terminal mode.
+    ///
+    /// This saves the current terminal settings so they can be restored
+    /// later with `reset_prog_mode()`.
+    pub fn def_prog_mode(&mut self) -> Result<()> {
+        self.terminal.save_prog_mode()
     }
 
-    /// Untouch a window (mark all lines as unchanged).
-    pub fn untouchwin(&mut self, win: &mut Window) {
-        win.untouchwin();
+    /// Save the current shell terminal mode.
+    ///
+    /// This saves the current terminal settings (typically the shell's settings)
+    /// so they can be restored later with `reset_shell_mode()`.
+    pub fn def_shell_mode(&mut self) -> Result<()> {
+        self.terminal.save_shell_mode()
     }
 
-    /// Touch specific lines in a window.
-    pub fn wtouchln(&mut self, win: &mut Window, start: i32, count: i32, changed: bool) {
-        win.touchln(start, count, changed);
+    /// Restore the saved program terminal mode.
+    ///
+    /// This restores the terminal settings saved by `def_prog_mode()`.
+    pub fn reset_prog_mode(&mut self) -> Result<()> {
+        self.terminal.restore_prog_mode()
     }
 
-    /// Check if a specific line is touched.
-    pub fn is_linetouched(&self, win: &Window, line: i32) -> bool {
-        win.is_linetouched(line)
+    /// Restore the saved shell terminal mode.
+    ///
+    /// This restores the terminal settings saved by `def_shell_mode()`.
+    pub fn reset_shell_mode(&mut self) -> Result<()> {
+        self.terminal.restore_shell_mode()
     }
 
-    /// Check if any part of the window is touched.
-    pub fn wis_wintouched(&self, win: &Window) -> bool {
-        win.is_wintouched()
+    /// Reset the terminal to program mode after shell escape.
+    ///
+    /// Equivalent to calling `reset_prog_mode()` followed by refreshing.
+    pub fn resetty(&mut self) -> Result<()> {
+        self.terminal.restore_prog_mode()?;
+        self.refresh()
     }
 
-    /// Get window Y position (row count).
-    pub fn wgetmaxy(&self, win: &Window) -> i32 {
-        win.getmaxy()
+    /// Save the terminal state for later restoration.
+    ///
+    /// Equivalent to calling `def_prog_mode()`.
+    pub fn savetty(&mut self) -> Result<()> {
+        self.terminal.save_prog_mode()
     }
 
-    /// Get window X position (column count).
-    pub fn wgetmaxx(&self, win: &Window) -> i32 {
-        win.getmaxx()
+    /// Check if the terminal has insert/delete character capabilities.
+    ///
+    /// Returns true if the terminal supports inserting and deleting characters.
+    /// This is based on the terminal type detection.
+    pub fn has_ic(&self) -> bool {
+        self.terminal.has_ic()
     }
 
-    /// Get window begin Y position.
-    pub fn wgetbegy(&self, win: &Window) -> i32 {
-        win.getbegy()
+    /// Check if the terminal has insert/delete line capabilities.
+    ///
+    /// Returns true if the terminal supports inserting and deleting lines.
+    /// This is based on the terminal type detection.
+    pub fn has_il(&self) -> bool {
+        self.terminal.has_il()
     }
 
-    /// Get window begin X position.
-    pub fn wgetbegx(&self, win: &Window) -> i32 {
-        win.getbegx()
+    /// Get the kill character (line kill).
+    pub fn killchar(&self) -> char {
+        crate::terminal::killchar()
     }
 
-    /// Get current cursor Y position.
-    pub fn wgetcury(&self, win: &Window) -> i32 {
-        win.getcury()
+    /// Get the erase character (backspace).
+    pub fn erasechar(&self) -> char {
+        crate::terminal::erasechar()
     }
 
-    /// Get current cursor X position.
-    pub fn wgetcurx(&self, win: &Window) -> i32 {
-        win.getcurx()
+    /// Get the erase character as a wide character.
+    ///
+    /// This is the ncurses `erasewchar()` function. It returns the terminal's
+    /// erase character as a wide character via the output parameter.
+    ///
+    /// # Arguments
+    /// * `ch` - Output parameter to receive the erase character
+    ///
+    /// # Returns
+    /// `Ok(())` on success
+    #[cfg(feature = "wide")]
+    pub fn erasewchar(&self, ch: &mut char) -> Result<()> {
+        *ch = crate::terminal::erasechar();
+        Ok(())
     }
 
-    /// Get parent window relative Y position.
-    pub fn wgetpary(&self, win: &Window) -> i32 {
-        win.getpary()
+    /// Get a longer terminal description.
+    ///
+    /// In ncurses this returns the terminfo description, but we return
+    /// a simple description based on the terminal type.
+    pub fn longname(&self) -> String {
+        let term = self.terminal.term_type();
+        match term {
+            "xterm" => "xterm terminal emulator".to_string(),
+            "xterm-256color" => "xterm with 256 colors".to_string(),
+            "screen" => "GNU Screen".to_string(),
+            "screen-256color" => "GNU Screen with 256 colors".to_string(),
+            "tmux" => "tmux terminal multiplexer".to_string(),
+            "tmux-256color" => "tmux with 256 colors".to_string(),
+            "linux" => "Linux console".to_string(),
+            "dumb" => "dumb terminal".to_string(),
+            _ => format!("{} terminal", term),
+        }
     }
 
-    /// Get parent window relative X position.
-    pub fn wgetparx(&self, win: &Window) -> i32 {
-        win.getparx()
-    }
-
-    /// Get both max Y and X dimensions.
-    pub fn wgetmaxyx(&self, win: &Window) -> (i32, i32) {
-        (win.getmaxy(), win.getmaxx())
-    }
-
-    /// Get both begin Y and X positions.
-    pub fn wgetbegyx(&self, win: &Window) -> (i32, i32) {
-        (win.getbegy(), win.getbegx())
+    /// Check if filter mode is enabled.
+    ///
+    /// Filter mode restricts curses to a single line, turning off cursor
+    /// movement capabilities. This is typically used for simple line-editing
+    /// applications.
+    pub fn isfilter(&self) -> bool {
+        self.filtered
     }
 
-    /// Get both cursor Y and X positions.
-    pub fn wgetyx(&self, win: &Window) -> (i32, i32) {
-        (win.getcury(), win.getcurx())
+    /// Enable filter mode.
+    ///
+    /// When filter mode is enabled, the terminal is treated as a single-line
+    /// terminal (LINES=1), and cursor movement functions are disabled.
+    /// This should be called before or immediately after screen initialization.
+    pub fn filter(&mut self) {
+        self.filtered = true;
+        // In filter mode, we only use one line
+        // Resize the screen to 1 line
+        let cols = self.terminal.columns();
+        let _ = self.resize_term(1, cols);
     }
 
-    /// Get both parent Y and X positions.
-    pub fn wgetparyx(&self, win: &Window) -> (i32, i32) {
-        (win.getpary(), win.getparx())
+    /// Disable filter mode.
+    ///
+    /// This restores normal terminal operation with full screen capabilities.
+    pub fn nofilter(&mut self) {
+        if self.filtered {
+            self.filtered = false;
+            // Restore full terminal size
+            let _ = self.terminal.update_size();
+            let lines = self.terminal.lines();
+            let cols = self.terminal.columns();
+            let _ = self.resize_term(lines, cols);
+        }
     }
 
-    /// Check if window is a pad.
-    pub fn wis_pad(&self, win: &Window) -> bool {
-        win.is_pad()
+    /// Check if the standard screen has clearok mode enabled.
+    ///
+    /// When clearok is enabled, the next refresh will clear the screen
+    /// and redraw from scratch.
+    pub fn is_cleared(&self) -> bool {
+        self.stdscr.is_cleared()
     }
 
-    /// Check if window is a subwindow.
-    pub fn wis_subwin(&self, win: &Window) -> bool {
-        win.is_subwin()
+    /// Check if the standard screen is a pad window.
+    ///
+    /// The standard screen is never a pad, so this always returns false.
+    pub fn is_pad(&self) -> bool {
+        self.stdscr.is_pad()
     }
 
-    /// Create a subwindow.
-    pub fn wsubwin(
-        &mut self,
-        win: &Window,
-        nlines: i32,
-        ncols: i32,
-        begy: i32,
-        begx: i32,
-    ) -> Result<Window> {
-        win.subwin(nlines, ncols, begy, begx)
+    /// Check if we're using the standard screen.
+    pub fn is_wintouched(&self) -> bool {
+        self.stdscr.is_wintouched()
     }
 
-    /// Create a derived window (relative coordinates).
-    pub fn wderwin(
-        &mut self,
-        win: &Window,
-        nlines: i32,
-        ncols: i32,
-        begy: i32,
-        begx: i32,
-    ) -> Result<Window> {
-        win.derwin(nlines, ncols, begy, begx)
+    /// Control flushing of input queue on interrupt.
+    ///
+    /// If `enable` is true (the default), when an interrupt key is pressed,
+    /// all input in the queue will be flushed.
+    pub fn intrflush(&mut self, enable: bool) -> Result<()> {
+        self.terminal.intrflush(enable)
     }
 
-    /// Duplicate a window.
-    pub fn wdupwin(&mut self, win: &Window) -> Result<Window> {
-        win.dupwin()
+    /// Enable 8-bit input mode.
+    ///
+    /// If `enable` is true, the terminal passes 8-bit characters through
+    /// without stripping the high bit.
+    pub fn meta(&mut self, enable: bool) -> Result<()> {
+        self.terminal.meta(enable)
     }
 
-    /// Move a window to a new position.
-    pub fn wmvwin(&mut self, win: &mut Window, y: i32, x: i32) -> Result<()> {
-        win.mvwin(y, x)
+    /// Set the file descriptor for typeahead checking.
+    ///
+    /// If `fd` is -1, no typeahead checking is done. The default is stdin (0).
+    /// This controls whether curses checks for pending input before output.
+    pub fn typeahead(&mut self, fd: i32) {
+        self.terminal.set_typeahead_fd(fd);
     }
 
-    /// Resize a window.
+    /// Enable flushing of output and input queues on interrupt.
     ///
-    /// This changes the window's dimensions. Content is preserved where possible.
-    pub fn wresize(&mut self, win: &mut Window, lines: i32, cols: i32) -> Result<()> {
-        win.resize(lines, cols)
+    /// When enabled (the default), pressing an interrupt key will flush
+    /// both the input and output queues.
+    pub fn qiflush(&mut self) {
+        let _ = self.terminal.qiflush(true);
     }
 
-    /// Create a subpad.
-    pub fn wsubpad(
-        &mut self,
-        win: &Window,
-        nlines: i32,
-        ncols: i32,
-        begy: i32,
-        begx: i32,
-    ) -> Result<Window> {
-        win.subpad(nlines, ncols, begy, begx)
+    /// Disable flushing of output and input queues on interrupt.
+    ///
+    /// When disabled, pressing an interrupt key will not flush the queues.
+    pub fn noqiflush(&mut self) {
+        let _ = self.terminal.qiflush(false);
     }
 
-    /// Copy one window onto another.
-    #[allow(clippy::too_many_arguments)]
-    pub fn copywin(
-        &mut self,
-        src: &Window,
-        dst: &mut Window,
-        sminrow: i32,
-        smincol: i32,
-        dminrow: i32,
-        dmincol: i32,
-        dmaxrow: i32,
-        dmaxcol: i32,
-        overlay: bool,
-    ) -> Result<()> {
-        let src_maxy = src.getmaxy();
-        let src_maxx = src.getmaxx();
-        let dst_maxy = dst.getmaxy();
-        let dst_maxx = dst.getmaxx();
+    // ========================================================================
+    // Window wrapper functions (w* prefix functions for ncurses compatibility)
+    // ========================================================================
 
-        for dst_y in dminrow..=dmaxrow {
-            let src_y = sminrow + (dst_y - dminrow);
-            if src_y >= src_maxy || dst_y >= dst_maxy {
-                break;
-            }
+    /// Add a character to a window at current cursor position.
+    pub fn waddch(&mut self, win: &mut Window, ch: ChType) -> Result<()> {
+        win.addch(ch)?;
+        self.immedok_refresh(win)
+    }
 
-            for dst_x in dmincol..=dmaxcol {
-                let src_x = smincol + (dst_x - dmincol);
-                if src_x >= src_maxx || dst_x >= dst_maxx {
-                    break;
-                }
+    /// Add a character to a window at specified position.
+    pub fn mvwaddch(&mut self, win: &mut Window, y: i32, x: i32, ch: ChType) -> Result<()> {
+        win.mv(y, x)?;
+        self.waddch(win, ch)
+    }
 
-                if let Some(src_line) = src.line(src_y as usize) {
-                    let ch = src_line.get(src_x as usize);
-                    // In overlay mode, skip blanks
-                    if overlay {
-                        #[cfg(not(feature = "wide"))]
-                        let is_blank = (ch & 0xFF) == b' ' as ChType;
-                        #[cfg(feature = "wide")]
-                        let is_blank = ch.chars[0] == ' ';
-                        if is_blank {
-                            continue;
-                        }
-                    }
-                    if let Some(dst_line) = dst.line_mut(dst_y as usize) {
-                        dst_line.set(dst_x as usize, ch);
-                    }
-                }
-            }
+    /// Refresh `win` if it has immedok set.
+    ///
+    /// Output functions on `Screen` call this after changing a window so that
+    /// `immedok(true)` behaves as in ncurses. Calling the `Window` methods
+    /// directly bypasses the screen and never refreshes automatically.
+    fn immedok_refresh(&mut self, win: &mut Window) -> Result<()> {
+        if win.is_immedok() {
+            self.wrefresh(win)
+        } else {
+            Ok(())
         }
-
-        Ok(())
     }
 
-    /// Copy source window to destination, overlaying non-blank characters.
-    pub fn overlay(&mut self, src: &Window, dst: &mut Window) -> Result<()> {
-        let dmaxy = dst.getmaxy().min(src.getmaxy());
-        let dmaxx = dst.getmaxx().min(src.getmaxx());
-        self.copywin(src, dst, 0, 0, 0, 0, dmaxy - 1, dmaxx - 1, true)
+    /// Refresh stdscr if it has immedok set.
+    fn immedok_refresh_stdscr(&mut self) -> Result<()> {
+        if self.stdscr.is_immedok() {
+            self.refresh()
+        } else {
+            Ok(())
+        }
     }
 
-    /// Copy source window to destination, overwriting all characters.
-    pub fn overwrite(&mut self, src: &Window, dst: &mut Window) -> Result<()> {
-        let dmaxy = dst.getmaxy().min(src.getmaxy());
-        let dmaxx = dst.getmaxx().min(src.getmaxx());
-        self.copywin(src, dst, 0, 0, 0, 0, dmaxy - 1, dmaxx - 1, false)
+    /// Add a character and refresh immediately (stdscr).
+    ///
+    /// Equivalent to calling `addch()` followed by `refresh()`.
+    pub fn echochar(&mut self, ch: ChType) -> Result<()> {
+        self.stdscr.addch(ch)?;
+        self.refresh()
     }
 
-    /// Mark a window for complete redraw on next refresh.
-    pub fn redrawwin(&mut self, win: &mut Window) -> Result<()> {
-        win.touchwin();
-        Ok(())
+    /// Add a character to a window and refresh immediately.
+    ///
+    /// Equivalent to calling `waddch()` followed by `wrefresh()`.
+    pub fn wechochar(&mut self, win: &mut Window, ch: ChType) -> Result<()> {
+        win.addch(ch)?;
+        self.wrefresh(win)
     }
 
-    /// Request that lines be redrawn (to fix screen corruption).
-    pub fn wredrawln(&mut self, win: &mut Window, beg_line: i32, num_lines: i32) -> Result<()> {
-        win.touchln(beg_line, num_lines, true);
-        Ok(())
+    /// Add a wide character and refresh immediately (stdscr).
+    ///
+    /// Equivalent to calling `add_wch()` followed by `refresh()`.
+    #[cfg(feature = "wide")]
+    pub fn echo_wchar(&mut self, wch: &crate::wide::CCharT) -> Result<()> {
+        self.stdscr.add_wch(wch)?;
+        self.refresh()
     }
 
-    // ========================================================================
-    // Soft Label Key (SLK) functions
-    // ========================================================================
+    /// Add a wide character to a window and refresh immediately.
+    ///
+    /// Equivalent to calling `wadd_wch()` followed by `wrefresh()`.
+    #[cfg(feature = "wide")]
+    pub fn wecho_wchar(&mut self, win: &mut Window, wch: &crate::wide::CCharT) -> Result<()> {
+        win.add_wch(wch)?;
+        self.wrefresh(win)
+    }
 
-    /// Initialize soft labels with the specified format.
+    /// Add a wide character to a pad and refresh immediately.
     ///
-    /// This must be called before `Screen::init()` to reserve space for soft labels.
-    /// The format specifies how many labels and their arrangement:
-    /// - 0: 3-2-3 format (8 labels)
-    /// - 1: 4-4 format (8 labels)
-    /// - 2: 4-4-4 format (12 labels)
-    /// - 3: 4-4-4 PC style format (12 labels)
-    #[cfg(feature = "slk")]
-    pub fn slk_init(&mut self, fmt: i32) -> Result<()> {
-        let format = SlkFormat::from_int(fmt)
-            .ok_or_else(|| Error::InvalidArgument("invalid SLK format".to_string()))?;
-        let mut slk = SlkState::new(format);
-        slk.init_with_size(self.terminal.columns(), self.terminal.lines())?;
-        self.slk = Some(slk);
-        Ok(())
-    }
+    /// Equivalent to calling `add_wch()` on the pad followed by `prefresh()`.
+    /// The pad must have had `prefresh` or `pnoutrefresh` called at least once
+    /// to establish display parameters.
+    #[cfg(feature = "wide")]
+    pub fn pecho_wchar(&mut self, pad: &mut Window, wch: &crate::wide::CCharT) -> Result<()> {
+        if !pad.is_pad() {
+            return Err(Error::InvalidArgument("window is not a pad".into()));
+        }
 
-    /// Set a soft label.
-    ///
-    /// Sets the text for label `labnum` (0-based for 8 labels, 0-11 for 12 labels).
-    /// The `justify` parameter specifies text alignment:
-    /// - 0: Left justify
-    /// - 1: Center
-    /// - 2: Right justify
-    #[cfg(feature = "slk")]
-    pub fn slk_set(&mut self, labnum: i32, label: &str, justify: i32) -> Result<()> {
-        let slk = self.slk.as_mut().ok_or(Error::NotInitialized)?;
-        slk.set(labnum, label, justify)
+        pad.add_wch(wch)?;
+
+        // Get the stored pad parameters
+        let pad_data = pad
+            .pad_data()
+            .ok_or_else(|| Error::InvalidArgument("pad has no stored parameters".into()))?;
+
+        let pminrow = pad_data.pad_y as i32;
+        let pmincol = pad_data.pad_x as i32;
+        let sminrow = pad_data.pad_top as i32;
+        let smincol = pad_data.pad_left as i32;
+        let smaxrow = pad_data.pad_bottom as i32;
+        let smaxcol = pad_data.pad_right as i32;
+
+        self.prefresh(pad, pminrow, pmincol, sminrow, smincol, smaxrow, smaxcol)
     }
 
-    /// Get a soft label.
-    ///
-    /// Returns the text of label `labnum`, or None if not set.
-    #[cfg(feature = "slk")]
-    pub fn slk_label(&self, labnum: i32) -> Option<&str> {
-        self.slk.as_ref().and_then(|slk| slk.label(labnum))
+    /// Add a string to a window at current cursor position.
+    pub fn waddstr(&mut self, win: &mut Window, s: &str) -> Result<()> {
+        win.addstr(s)?;
+        self.immedok_refresh(win)
     }
 
-    /// Refresh the soft labels, writing them to the terminal.
-    ///
-    /// This outputs the soft labels to the screen immediately.
-    #[cfg(feature = "slk")]
-    pub fn slk_refresh(&mut self) -> Result<()> {
-        if let Some(slk) = &mut self.slk {
-            // Render the SLK line
-            let output = slk.render_ansi();
-            if !output.is_empty() {
-                self.terminal.write(output.as_bytes())?;
-                self.terminal.flush()?;
-            }
-            slk.refresh()?;
-        }
-        Ok(())
+    /// Add at most n characters of a string to a window.
+    pub fn waddnstr(&mut self, win: &mut Window, s: &str, n: i32) -> Result<()> {
+        win.addnstr(s, n)?;
+        self.immedok_refresh(win)
     }
 
-    /// Mark soft labels for refresh without writing to terminal.
-    ///
-    /// The labels will be refreshed on the next `doupdate()` call.
-    #[cfg(feature = "slk")]
-    pub fn slk_noutrefresh(&mut self) -> Result<()> {
-        if let Some(slk) = &mut self.slk {
-            slk.noutrefresh()?;
-        }
-        Ok(())
+    /// Add a string to a window at specified position.
+    pub fn mvwaddstr(&mut self, win: &mut Window, y: i32, x: i32, s: &str) -> Result<()> {
+        win.mv(y, x)?;
+        self.waddstr(win, s)
     }
 
-    /// Clear soft labels from the screen.
+    /// Add a string to a window, clamping the position and truncating at the
+    /// right margin instead of failing or wrapping.
     ///
-    /// The labels are hidden but not destroyed. Use `slk_restore()` to show them again.
-    #[cfg(feature = "slk")]
-    pub fn slk_clear(&mut self) -> Result<()> {
-        if let Some(slk) = &mut self.slk {
-            slk.clear_all()?;
-            // Clear the SLK area on screen
-            let row = slk.display_row();
-            let cols = self.terminal.columns();
-            let clear_line = format!("\x1b[{};1H{}\x1b[0m", row + 1, " ".repeat(cols as usize));
-            self.terminal.write(clear_line.as_bytes())?;
-            self.terminal.flush()?;
-        }
-        Ok(())
+    /// Returns the number of `char`s written. See
+    /// [`Window::mvaddstr_clipped`].
+    pub fn mvwaddstr_clipped(
+        &mut self,
+        win: &mut Window,
+        y: i32,
+        x: i32,
+        s: &str,
+    ) -> Result<usize> {
+        let written = win.mvaddstr_clipped(y, x, s)?;
+        self.immedok_refresh(win)?;
+        Ok(written)
     }
 
-    /// Restore soft labels after they were cleared.
-    #[cfg(feature = "slk")]
-    pub fn slk_restore(&mut self) -> Result<()> {
-        if let Some(slk) = &mut self.slk {
-            slk.restore()?;
-            self.slk_refresh()?;
-        }
-        Ok(())
+    /// Add at most n characters of a string to a window at specified position.
+    pub fn mvwaddnstr(&mut self, win: &mut Window, y: i32, x: i32, s: &str, n: i32) -> Result<()> {
+        win.mv(y, x)?;
+        self.waddnstr(win, s, n)
     }
 
-    /// Touch soft labels, marking them for refresh.
-    #[cfg(feature = "slk")]
-    pub fn slk_touch(&mut self) -> Result<()> {
-        if let Some(slk) = &mut self.slk {
-            slk.touch();
-        }
-        Ok(())
+    /// Add a character string to a window.
+    pub fn waddchstr(&mut self, win: &mut Window, chstr: &[ChType]) -> Result<()> {
+        win.addchstr(chstr)
     }
 
-    /// Set the attributes for soft labels.
-    #[cfg(feature = "slk")]
-    pub fn slk_attrset(&mut self, attrs: AttrT) -> Result<()> {
-        if let Some(slk) = &mut self.slk {
-            slk.attrset(attrs)?;
-        }
-        Ok(())
+    /// Add at most n characters from a character string to a window.
+    pub fn waddchnstr(&mut self, win: &mut Window, chstr: &[ChType], n: i32) -> Result<()> {
+        win.addchnstr(chstr, n)
     }
 
-    /// Turn on attributes for soft labels.
-    #[cfg(feature = "slk")]
-    pub fn slk_attron(&mut self, attrs: AttrT) -> Result<()> {
-        if let Some(slk) = &mut self.slk {
-            slk.attron(attrs)?;
-        }
-        Ok(())
-    }
+    // ========================================================================
+    // Wide character window wrapper functions
+    // ========================================================================
 
-    /// Turn off attributes for soft labels.
-    #[cfg(feature = "slk")]
-    pub fn slk_attroff(&mut self, attrs: AttrT) -> Result<()> {
-        if let Some(slk) = &mut self.slk {
-            slk.attroff(attrs)?;
-        }
-        Ok(())
+    /// Add a wide character to stdscr at the current position.
+    ///
+    /// This is the ncurses `add_wch()` function.
+    #[cfg(feature = "wide")]
+    pub fn add_wch(&mut self, wch: &crate::wide::CCharT) -> Result<()> {
+        self.stdscr.add_wch(wch)
     }
 
-    /// Get the current attributes for soft labels.
-    #[cfg(feature = "slk")]
-    pub fn slk_attr(&self) -> AttrT {
-        self.slk.as_ref().map_or(0, |slk| slk.attr())
+    /// Add a wide character to a window at the current position.
+    ///
+    /// This is the ncurses `wadd_wch()` function.
+    #[cfg(feature = "wide")]
+    pub fn wadd_wch(&mut self, win: &mut Window, wch: &crate::wide::CCharT) -> Result<()> {
+        win.add_wch(wch)
     }
 
-    /// Set the color pair for soft labels.
-    #[cfg(feature = "slk")]
-    pub fn slk_color(&mut self, pair: i16) -> Result<()> {
-        if let Some(slk) = &mut self.slk {
-            slk.color(pair)?;
-        }
-        Ok(())
+    /// Move cursor and add a wide character to stdscr.
+    ///
+    /// This is the ncurses `mvadd_wch()` function.
+    #[cfg(feature = "wide")]
+    pub fn mvadd_wch(&mut self, y: i32, x: i32, wch: &crate::wide::CCharT) -> Result<()> {
+        self.stdscr.mvadd_wch(y, x, wch)
     }
 
-    /// Turn off attributes for soft labels (X/Open style).
-    #[cfg(feature = "slk")]
-    pub fn slk_attr_off(&mut self, attrs: AttrT) -> Result<()> {
-        self.slk_attroff(attrs)
+    /// Move cursor and add a wide character to a window.
+    ///
+    /// This is the ncurses `mvwadd_wch()` function.
+    #[cfg(feature = "wide")]
+    pub fn mvwadd_wch(
+        &mut self,
+        win: &mut Window,
+        y: i32,
+        x: i32,
+        wch: &crate::wide::CCharT,
+    ) -> Result<()> {
+        win.mvadd_wch(y, x, wch)
     }
 
-    /// Turn on attributes for soft labels (X/Open style).
-    #[cfg(feature = "slk")]
-    pub fn slk_attr_on(&mut self, attrs: AttrT) -> Result<()> {
-        self.slk_attron(attrs)
+    /// Add a wide character string (array of cchar_t) to stdscr.
+    ///
+    /// This is the ncurses `add_wchstr()` function.
+    #[cfg(feature = "wide")]
+    pub fn add_wchstr(&mut self, wchstr: &[crate::wide::CCharT]) -> Result<()> {
+        self.stdscr.add_wchstr(wchstr)
     }
 
-    /// Set the attributes for soft labels (X/Open style).
-    #[cfg(feature = "slk")]
-    pub fn slk_attr_set(&mut self, attrs: AttrT, pair: i16) -> Result<()> {
-        let combined = attrs | attr::color_pair(pair);
-        self.slk_attrset(combined)
+    /// Add a wide character string (array of cchar_t) to a window.
+    ///
+    /// This is the ncurses `wadd_wchstr()` function.
+    #[cfg(feature = "wide")]
+    pub fn wadd_wchstr(&mut self, win: &mut Window, wchstr: &[crate::wide::CCharT]) -> Result<()> {
+        win.add_wchstr(wchstr)
     }
 
-    /// Set a soft label key using a wide string.
-    #[cfg(all(feature = "slk", feature = "wide"))]
-    pub fn slk_wset(&mut self, labnum: i32, label: &str, justify: i32) -> Result<()> {
-        self.slk_set(labnum, label, justify)
+    /// Add at most n wide characters from a string to stdscr.
+    ///
+    /// This is the ncurses `add_wchnstr()` function.
+    #[cfg(feature = "wide")]
+    pub fn add_wchnstr(&mut self, wchstr: &[crate::wide::CCharT], n: i32) -> Result<()> {
+        self.stdscr.add_wchnstr(wchstr, n)
     }
 
-    /// Check if soft labels are initialized.
-    #[cfg(feature = "slk")]
-    pub fn slk_is_initialized(&self) -> bool {
-        self.slk.as_ref().is_some_and(|slk| slk.is_initialized())
+    /// Add at most n wide characters from a string to a window.
+    ///
+    /// This is the ncurses `wadd_wchnstr()` function.
+    #[cfg(feature = "wide")]
+    pub fn wadd_wchnstr(
+        &mut self,
+        win: &mut Window,
+        wchstr: &[crate::wide::CCharT],
+        n: i32,
+    ) -> Result<()> {
+        win.add_wchnstr(wchstr, n)
     }
 
-    // ========================================================================
-    // Terminfo query functions
-    // ========================================================================
-
-    /// Get a boolean capability value from terminfo.
+    /// Move cursor and add a wide character string to stdscr.
     ///
-    /// Returns:
-    /// - 1 if the capability is present
-    /// - 0 if the capability is absent
-    /// - -1 if the capability name is not recognized
+    /// This is the ncurses `mvadd_wchstr()` function.
+    #[cfg(feature = "wide")]
+    pub fn mvadd_wchstr(&mut self, y: i32, x: i32, wchstr: &[crate::wide::CCharT]) -> Result<()> {
+        self.stdscr.mvadd_wchstr(y, x, wchstr)
+    }
+
+    /// Move cursor and add at most n wide characters to stdscr.
     ///
-    /// Common boolean capabilities:
-    /// - "am" - automatic margins
-    /// - "bce" - background color erase
-    /// - "km" - has a meta key
-    /// - "mc5i" - printer won't echo on screen
-    /// - "mir" - safe to move while in insert mode
-    /// - "msgr" - safe to move while in standout mode
-    /// - "xenl" - newline ignored after 80 cols
-    /// - "xon" - terminal uses XON/XOFF handshaking
-    pub fn tigetflag(&self, capname: &str) -> i32 {
-        let term_type = self.terminal.term_type();
-        let is_modern = matches!(
-            term_type,
+    /// This is the ncurses `mvadd_wchnstr()` function.
+    #[cfg(feature = "wide")]
+    pub fn mvadd_wchnstr(
+        &mut self,
+        y: i32,
+        x: i32,
+        wchstr: &[crate::wide::CCharT],
+        n: i32,
+    ) -> Result<()> {
+        self.stdscr.mvadd_wchnstr(y, x, wchstr, n)
+    }
+
+    /// Move cursor and add a wide character string to a window.
+    ///
+    /// This is the ncurses `mvwadd_wchstr()` function.
+    #[cfg(feature = "wide")]
+    pub fn mvwadd_wchstr(
+        &mut self,
+        win: &mut Window,
+        y: i32,
+        x: i32,
+        wchstr: &[crate::wide::CCharT],
+    ) -> Result<()> {
+        win.mvadd_wchstr(y, x, wchstr)
+    }
+
+    /// Move cursor and add at most n wide characters to a window.
+    ///
+    /// This is the ncurses `mvwadd_wchnstr()` function.
+    #[cfg(feature = "wide")]
+    pub fn mvwadd_wchnstr(
+        &mut self,
+        win: &mut Window,
+        y: i32,
+        x: i32,
+        wchstr: &[crate::wide::CCharT],
+        n: i32,
+    ) -> Result<()> {
+        win.mvadd_wchnstr(y, x, wchstr, n)
+    }
+
+    /// Move cursor in a window.
+    pub fn wmove(&mut self, win: &mut Window, y: i32, x: i32) -> Result<()> {
+        win.mv(y, x)
+    }
+
+    /// Clear the entire window.
+    pub fn wclear(&mut self, win: &mut Window) -> Result<()> {
+        win.clear()
+    }
+
+    /// Erase the window (fill with blanks).
+    pub fn werase(&mut self, win: &mut Window) -> Result<()> {
+        win.erase()
+    }
+
+    /// Clear from cursor to end of line.
+    pub fn wclrtoeol(&mut self, win: &mut Window) -> Result<()> {
+        win.clrtoeol()
+    }
+
+    /// Clear from cursor to end of window.
+    pub fn wclrtobot(&mut self, win: &mut Window) -> Result<()> {
+        win.clrtobot()
+    }
+
+    /// Draw a box around a window.
+    pub fn wbox(&mut self, win: &mut Window, verch: ChType, horch: ChType) -> Result<()> {
+        win.box_(verch, horch)
+    }
+
+    /// Draw a border around a window.
+    #[allow(clippy::too_many_arguments)]
+    pub fn wborder(
+        &mut self,
+        win: &mut Window,
+        ls: ChType,
+        rs: ChType,
+        ts: ChType,
+        bs: ChType,
+        tl: ChType,
+        tr: ChType,
+        bl: ChType,
+        br: ChType,
+    ) -> Result<()> {
+        win.border(ls, rs, ts, bs, tl, tr, bl, br)
+    }
+
+    /// Draw a horizontal line.
+    pub fn whline(&mut self, win: &mut Window, ch: ChType, n: i32) -> Result<()> {
+        win.hline(ch, n)
+    }
+
+    /// Draw a vertical line.
+    pub fn wvline(&mut self, win: &mut Window, ch: ChType, n: i32) -> Result<()> {
+        win.vline(ch, n)
+    }
+
+    /// Draw a horizontal line at position.
+    pub fn mvwhline(&mut self, win: &mut Window, y: i32, x: i32, ch: ChType, n: i32) -> Result<()> {
+        win.mv(y, x)?;
+        win.hline(ch, n)
+    }
+
+    /// Draw a vertical line at position.
+    pub fn mvwvline(&mut self, win: &mut Window, y: i32, x: i32, ch: ChType, n: i32) -> Result<()> {
+        win.mv(y, x)?;
+        win.vline(ch, n)
+    }
+
+    /// Insert a character before the cursor.
+    pub fn winsch(&mut self, win: &mut Window, ch: ChType) -> Result<()> {
+        win.insch(ch)
+    }
+
+    /// Insert a character at specified position.
+    pub fn mvwinsch(&mut self, win: &mut Window, y: i32, x: i32, ch: ChType) -> Result<()> {
+        win.mv(y, x)?;
+        win.insch(ch)
+    }
+
+    /// Delete the character under the cursor.
+    pub fn wdelch(&mut self, win: &mut Window) -> Result<()> {
+        win.delch()
+    }
+
+    /// Delete a character at specified position.
+    pub fn mvwdelch(&mut self, win: &mut Window, y: i32, x: i32) -> Result<()> {
+        win.mv(y, x)?;
+        win.delch()
+    }
+
+    /// Insert a blank line above the cursor.
+    pub fn winsertln(&mut self, win: &mut Window) -> Result<()> {
+        win.insertln()
+    }
+
+    /// Delete the line under the cursor.
+    pub fn wdeleteln(&mut self, win: &mut Window) -> Result<()> {
+        win.deleteln()
+    }
+
+    /// Insert/delete lines.
+    pub fn winsdelln(&mut self, win: &mut Window, n: i32) -> Result<()> {
+        win.insdelln(n)
+    }
+
+    /// Get the character at the current cursor position.
+    pub fn winch(&self, win: &Window) -> ChType {
+        win.inch()
+    }
+
+    /// Get the character at specified position.
+    pub fn mvwinch(&mut self, win: &mut Window, y: i32, x: i32) -> Result<ChType> {
+        win.mv(y, x)?;
+        Ok(win.inch())
+    }
+
+    /// Get a string from a window.
+    pub fn winnstr(&self, win: &Window, n: i32) -> String {
+        win.instr(n)
+    }
+
+    /// Get a string from specified position.
+    pub fn mvwinnstr(&mut self, win: &mut Window, y: i32, x: i32, n: i32) -> Result<String> {
+        win.mv(y, x)?;
+        Ok(win.instr(n))
+    }
+
+    // ========================================================================
+    // stdscr inch/instr family
+    // ========================================================================
+
+    /// Get the character at the current cursor position on stdscr.
+    #[must_use]
+    pub fn inch(&self) -> ChType {
+        self.stdscr.inch()
+    }
+
+    /// Move cursor and get the character at that position on stdscr.
+    pub fn mvinch(&mut self, y: i32, x: i32) -> Result<ChType> {
+        self.stdscr.mvinch(y, x)
+    }
+
+    /// Get a string from stdscr at the current position.
+    #[must_use]
+    pub fn instr(&self, n: i32) -> String {
+        self.stdscr.instr(n)
+    }
+
+    /// Get a string from stdscr with a limit (alias for instr).
+    #[must_use]
+    pub fn innstr(&self, n: i32) -> String {
+        self.stdscr.innstr(n)
+    }
+
+    /// Move cursor and get a string from stdscr.
+    pub fn mvinstr(&mut self, y: i32, x: i32, n: i32) -> Result<String> {
+        self.stdscr.mv(y, x)?;
+        Ok(self.stdscr.instr(n))
+    }
+
+    /// Move cursor and get a string from stdscr with a limit.
+    pub fn mvinnstr(&mut self, y: i32, x: i32, n: i32) -> Result<String> {
+        self.stdscr.mvinnstr(y, x, n)
+    }
+
+    // ========================================================================
+    // inchnstr family (character array with attributes)
+    // ========================================================================
+
+    /// Get a string of characters with attributes from stdscr.
+    pub fn inchnstr(&self, chstr: &mut [ChType], n: i32) -> i32 {
+        self.stdscr.inchnstr(chstr, n)
+    }
+
+    /// Move cursor and get a string of characters with attributes from stdscr.
+    pub fn mvinchnstr(&mut self, y: i32, x: i32, chstr: &mut [ChType], n: i32) -> Result<i32> {
+        self.stdscr.mvinchnstr(y, x, chstr, n)
+    }
+
+    /// Get a string of characters with attributes from a window.
+    pub fn winchnstr(&self, win: &Window, chstr: &mut [ChType], n: i32) -> i32 {
+        win.inchnstr(chstr, n)
+    }
+
+    /// Move cursor and get a string of characters with attributes from a window.
+    pub fn mvwinchnstr(
+        &mut self,
+        win: &mut Window,
+        y: i32,
+        x: i32,
+        chstr: &mut [ChType],
+        n: i32,
+    ) -> Result<i32> {
+        win.mvinchnstr(y, x, chstr, n)
+    }
+
+    /// Set window background character.
+    pub fn wbkgdset(&mut self, win: &mut Window, ch: ChType) {
+        win.bkgdset(ch);
+    }
+
+    /// Get window background character.
+    pub fn wgetbkgd(&self, win: &Window) -> ChType {
+        win.getbkgd()
+    }
+
+    /// Set window background and apply to all characters.
+    pub fn wbkgd(&mut self, win: &mut Window, ch: ChType) -> Result<()> {
+        win.bkgd(ch)
+    }
+
+    /// Turn on window attributes.
+    pub fn wattron(&mut self, win: &mut Window, attr: AttrT) -> Result<()> {
+        win.attron(attr)
+    }
+
+    /// Turn off window attributes.
+    pub fn wattroff(&mut self, win: &mut Window, attr: AttrT) -> Result<()> {
+        win.attroff(attr)
+    }
+
+    /// Set window attributes.
+    pub fn wattrset(&mut self, win: &mut Window, attr: AttrT) -> Result<()> {
+        win.attrset(attr)
+    }
+
+    /// Get window attributes.
+    pub fn wgetattrs(&self, win: &Window) -> AttrT {
+        win.getattrs()
+    }
+
+    /// Change attributes of characters at current position in stdscr.
+    ///
+    /// Changes the attributes of `n` characters starting at the cursor.
+    /// If `n` is -1, changes attributes to end of line.
+    pub fn chgat(&mut self, n: i32, attr: AttrT, color: i16) -> Result<()> {
+        self.stdscr.chgat(n, attr, color)
+    }
+
+    /// Move cursor and change attributes in stdscr.
+    pub fn mvchgat(&mut self, y: i32, x: i32, n: i32, attr: AttrT, color: i16) -> Result<()> {
+        self.stdscr.mvchgat(y, x, n, attr, color)
+    }
+
+    /// Change attributes of characters at current position in window.
+    pub fn wchgat(&mut self, win: &mut Window, n: i32, attr: AttrT, color: i16) -> Result<()> {
+        win.wchgat(n, attr, color)
+    }
+
+    /// Move cursor and change attributes in window.
+    pub fn mvwchgat(
+        &mut self,
+        win: &mut Window,
+        y: i32,
+        x: i32,
+        n: i32,
+        attr: AttrT,
+        color: i16,
+    ) -> Result<()> {
+        win.mvwchgat(y, x, n, attr, color)
+    }
+
+    /// Set window color pair.
+    pub fn wcolor_set(&mut self, win: &mut Window, pair: i16) -> Result<()> {
+        win.color_set(pair)
+    }
+
+    /// Scroll window up.
+    pub fn wscrl(&mut self, win: &mut Window, n: i32) -> Result<()> {
+        win.scrl(n)
+    }
+
+    /// Set scrolling region.
+    pub fn wsetscrreg(&mut self, win: &mut Window, top: i32, bot: i32) -> Result<()> {
+        win.setscrreg(top, bot)
+    }
+
+    /// Get scrolling region.
+    pub fn wgetscrreg(&self, win: &Window) -> (i32, i32) {
+        win.getscrreg()
+    }
+
+    /// Enable/disable scrolling for a window.
+    pub fn wscrollok(&mut self, win: &mut Window, bf: bool) {
+        win.scrollok(bf);
+    }
+
+    /// Check if scrolling is enabled for a window.
+    pub fn wis_scrollok(&self, win: &Window) -> bool {
+        win.is_scrollok()
+    }
+
+    /// Enable/disable keypad for a window.
+    pub fn wkeypad(&mut self, win: &mut Window, bf: bool) {
+        win.keypad(bf);
+    }
+
+    /// Check if keypad is enabled for a window.
+    pub fn wis_keypad(&self, win: &Window) -> bool {
+        win.is_keypad()
+    }
+
+    /// Enable/disable nodelay mode for a window.
+    pub fn wnodelay(&mut self, win: &mut Window, bf: bool) {
+        win.nodelay(bf);
+    }
+
+    /// Check if nodelay mode is enabled for a window.
+    pub fn wis_nodelay(&self, win: &Window) -> bool {
+        win.is_nodelay()
+    }
+
+    /// Set input timeout for a window.
+    pub fn wtimeout(&mut self, win: &mut Window, delay: i32) {
+        win.timeout(delay);
+    }
+
+    /// Get input timeout for a window.
+    pub fn wgetdelay(&self, win: &Window) -> i32 {
+        win.getdelay()
+    }
+
+    /// Enable/disable cursor leave mode for a window.
+    pub fn wleaveok(&mut self, win: &mut Window, bf: bool) {
+        win.leaveok(bf);
+    }
+
+    /// Check if cursor leave mode is enabled.
+    pub fn wis_leaveok(&self, win: &Window) -> bool {
+        win.is_leaveok()
+    }
+
+    /// Enable/disable clear-on-refresh for a window.
+    pub fn wclearok(&mut self, win: &mut Window, bf: bool) {
+        win.clearok(bf);
+    }
+
+    /// Check if clear-on-refresh is enabled for a window.
+    pub fn wis_cleared(&self, win: &Window) -> bool {
+        win.is_cleared()
+    }
+
+    /// Enable/disable hardware line insertion for a window.
+    pub fn widlok(&mut self, win: &mut Window, bf: bool) {
+        win.idlok(bf);
+    }
+
+    /// Check if hardware line insertion is enabled.
+    pub fn wis_idlok(&self, win: &Window) -> bool {
+        win.is_idlok()
+    }
+
+    /// Enable/disable hardware character insertion for a window.
+    ///
+    /// Enabling it also lets `doupdate()` redraw lines whose content was
+    /// shifted by inserting or deleting characters using the terminal's
+    /// insert/delete character capabilities, when [`has_ic()`](Self::has_ic)
+    /// is true. This is off until requested; the most recent call decides.
+    /// While it is on, a line is only shifted if every window that changed
+    /// it since the last update has idcok on, which is the default for
+    /// windows.
+    pub fn widcok(&mut self, win: &mut Window, bf: bool) {
+        win.idcok(bf);
+        self.idc_update = bf && self.has_ic();
+    }
+
+    /// Enable/disable hardware character insertion for stdscr.
+    ///
+    /// See [`widcok()`](Self::widcok).
+    pub fn idcok(&mut self, bf: bool) {
+        self.stdscr.idcok(bf);
+        self.idc_update = bf && self.has_ic();
+    }
+
+    /// Choose how line-drawing characters are sent to the terminal.
+    ///
+    /// The default, [`AcsMode::Unicode`], writes box-drawing characters as
+    /// UTF-8. [`AcsMode::Vt100`] switches to the terminal's alternate
+    /// character set around them instead, for terminals or locales that
+    /// cannot display UTF-8. Takes effect for cells drawn from now on; call
+    /// `clearok()` to redraw what is already on screen.
+    pub fn set_acs_mode(&mut self, mode: AcsMode) {
+        self.acs_mode = mode;
+    }
+
+    /// Get how line-drawing characters are sent to the terminal.
+    pub fn acs_mode(&self) -> AcsMode {
+        self.acs_mode
+    }
+
+    /// Check if hardware character insertion is enabled.
+    pub fn wis_idcok(&self, win: &Window) -> bool {
+        win.is_idcok()
+    }
+
+    /// Enable/disable immediate refresh for a window.
+    pub fn wimmedok(&mut self, win: &mut Window, bf: bool) {
+        win.immedok(bf);
+    }
+
+    /// Check if immediate refresh is enabled.
+    pub fn wis_immedok(&self, win: &Window) -> bool {
+        win.is_immedok()
+    }
+
+    /// Enable/disable automatic sync for a window.
+    pub fn wsyncok(&mut self, win: &mut Window, bf: bool) {
+        win.syncok(bf);
+    }
+
+    /// Check if automatic sync is enabled.
+    pub fn wis_syncok(&self, win: &Window) -> bool {
+        win.is_syncok()
+    }
+
+    /// Enable/disable notimeout mode for a window.
+    pub fn wnotimeout(&mut self, win: &mut Window, bf: bool) {
+        win.notimeout(bf);
+    }
+
+    /// Check if notimeout mode is enabled.
+    pub fn wis_notimeout(&self, win: &Window) -> bool {
+        win.is_notimeout()
+    }
+
+    /// Touch a window (mark all lines as changed).
+    pub fn touchwin(&mut self, win: &mut Window) {
+        win.touchwin();
+    }
+
+    /// Untouch a window (mark all lines as unchanged).
+    pub fn untouchwin(&mut self, win: &mut Window) {
+        win.untouchwin();
+    }
+
+    /// Touch specific lines in a window.
+    pub fn wtouchln(&mut self, win: &mut Window, start: i32, count: i32, changed: bool) {
+        win.touchln(start, count, changed);
+    }
+
+    /// Check if a specific line is touched.
+    pub fn is_linetouched(&self, win: &Window, line: i32) -> bool {
+        win.is_linetouched(line)
+    }
+
+    /// Check if any part of the window is touched.
+    pub fn wis_wintouched(&self, win: &Window) -> bool {
+        win.is_wintouched()
+    }
+
+    /// Get window Y position (row count).
+    pub fn wgetmaxy(&self, win: &Window) -> i32 {
+        win.getmaxy()
+    }
+
+    /// Get window X position (column count).
+    pub fn wgetmaxx(&self, win: &Window) -> i32 {
+        win.getmaxx()
+    }
+
+    /// Get window begin Y position.
+    pub fn wgetbegy(&self, win: &Window) -> i32 {
+        win.getbegy()
+    }
+
+    /// Get window begin X position.
+    pub fn wgetbegx(&self, win: &Window) -> i32 {
+        win.getbegx()
+    }
+
+    /// Get current cursor Y position.
+    pub fn wgetcury(&self, win: &Window) -> i32 {
+        win.getcury()
+    }
+
+    /// Get current cursor X position.
+    pub fn wgetcurx(&self, win: &Window) -> i32 {
+        win.getcurx()
+    }
+
+    /// Get parent window relative Y position.
+    pub fn wgetpary(&self, win: &Window) -> i32 {
+        win.getpary()
+    }
+
+    /// Get parent window relative X position.
+    pub fn wgetparx(&self, win: &Window) -> i32 {
+        win.getparx()
+    }
+
+    /// Get both max Y and X dimensions.
+    pub fn wgetmaxyx(&self, win: &Window) -> (i32, i32) {
+        (win.getmaxy(), win.getmaxx())
+    }
+
+    /// Get both begin Y and X positions.
+    pub fn wgetbegyx(&self, win: &Window) -> (i32, i32) {
+        win.origin()
+    }
+
+    /// Get both cursor Y and X positions.
+    pub fn wgetyx(&self, win: &Window) -> (i32, i32) {
+        win.cursor()
+    }
+
+    /// Get both cursor Y and X positions of a window.
+    ///
+    /// This is the same as [`wgetyx()`](Self::wgetyx), named to pair with
+    /// the stdscr form [`getyx()`](Self::getyx).
+    pub fn getyx_win(&self, win: &Window) -> (i32, i32) {
+        win.cursor()
+    }
+
+    /// Get both parent Y and X positions.
+    pub fn wgetparyx(&self, win: &Window) -> (i32, i32) {
+        (win.getpary(), win.getparx())
+    }
+
+    /// Check if window is a pad.
+    pub fn wis_pad(&self, win: &Window) -> bool {
+        win.is_pad()
+    }
+
+    /// Check if window is a subwindow.
+    pub fn wis_subwin(&self, win: &Window) -> bool {
+        win.is_subwin()
+    }
+
+    /// Create a subwindow.
+    pub fn wsubwin(
+        &mut self,
+        win: &Window,
+        nlines: i32,
+        ncols: i32,
+        begy: i32,
+        begx: i32,
+    ) -> Result<Window> {
+        win.subwin(nlines, ncols, begy, begx)
+    }
+
+    /// Create a derived window (relative coordinates).
+    pub fn wderwin(
+        &mut self,
+        win: &Window,
+        nlines: i32,
+        ncols: i32,
+        begy: i32,
+        begx: i32,
+    ) -> Result<Window> {
+        win.derwin(nlines, ncols, begy, begx)
+    }
+
+    /// Duplicate a window.
+    pub fn wdupwin(&mut self, win: &Window) -> Result<Window> {
+        win.dupwin()
+    }
+
+    /// Move a window to a new position.
+    pub fn wmvwin(&mut self, win: &mut Window, y: i32, x: i32) -> Result<()> {
+        win.mvwin(y, x)
+    }
+
+    /// Resize a window.
+    ///
+    /// This changes the window's dimensions. Content is preserved where possible.
+    pub fn wresize(&mut self, win: &mut Window, lines: i32, cols: i32) -> Result<()> {
+        win.resize(lines, cols)
+    }
+
+    /// Create a subpad.
+    pub fn wsubpad(
+        &mut self,
+        win: &Window,
+        nlines: i32,
+        ncols: i32,
+        begy: i32,
+        begx: i32,
+    ) -> Result<Window> {
+        win.subpad(nlines, ncols, begy, begx)
+    }
+
+    /// Copy one window onto another.
+    ///
+    /// Double-width characters are never split: one cut off by the edge of
+    /// the copied area, or by the cells written over in `dst`, is replaced
+    /// by a blank.
+    #[allow(clippy::too_many_arguments)]
+    pub fn copywin(
+        &mut self,
+        src: &Window,
+        dst: &mut Window,
+        sminrow: i32,
+        smincol: i32,
+        dminrow: i32,
+        dmincol: i32,
+        dmaxrow: i32,
+        dmaxcol: i32,
+        overlay: bool,
+    ) -> Result<()> {
+        let src_maxy = src.getmaxy();
+        let src_maxx = src.getmaxx();
+        let dst_maxy = dst.getmaxy();
+        let dst_maxx = dst.getmaxx();
+
+        for dst_y in dminrow..=dmaxrow {
+            let src_y = sminrow + (dst_y - dminrow);
+            if src_y >= src_maxy || dst_y >= dst_maxy {
+                break;
+            }
+
+            for dst_x in dmincol..=dmaxcol {
+                let src_x = smincol + (dst_x - dmincol);
+                if src_x >= src_maxx || dst_x >= dst_maxx {
+                    break;
+                }
+
+                if let Some(src_line) = src.line(src_y as usize) {
+                    let ch = src_line.get(src_x as usize);
+                    // In overlay mode, skip blanks
+                    if overlay {
+                        #[cfg(not(feature = "wide"))]
+                        let is_blank = (ch & 0xFF) == b' ' as ChType;
+                        #[cfg(feature = "wide")]
+                        let is_blank = ch.chars[0] == ' ';
+                        if is_blank {
+                            continue;
+                        }
+                    }
+                    if let Some(dst_line) = dst.line_mut(dst_y as usize) {
+                        dst_line.set(dst_x as usize, ch);
+                    }
+                }
+            }
+
+            #[cfg(feature = "wide")]
+            if let Some(dst_line) = dst.line_mut(dst_y as usize) {
+                let end = (dmaxcol + 1)
+                    .min(dmincol + src_maxx - smincol)
+                    .min(dst_maxx);
+                for x in dmincol.max(0)..=end {
+                    dst_line.fix_wide_boundary(x as usize);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Copy source window to destination, overlaying non-blank characters.
+    pub fn overlay(&mut self, src: &Window, dst: &mut Window) -> Result<()> {
+        let dmaxy = dst.getmaxy().min(src.getmaxy());
+        let dmaxx = dst.getmaxx().min(src.getmaxx());
+        self.copywin(src, dst, 0, 0, 0, 0, dmaxy - 1, dmaxx - 1, true)
+    }
+
+    /// Copy source window to destination, overwriting all characters.
+    pub fn overwrite(&mut self, src: &Window, dst: &mut Window) -> Result<()> {
+        let dmaxy = dst.getmaxy().min(src.getmaxy());
+        let dmaxx = dst.getmaxx().min(src.getmaxx());
+        self.copywin(src, dst, 0, 0, 0, 0, dmaxy - 1, dmaxx - 1, false)
+    }
+
+    /// Mark a window for complete redraw on next refresh.
+    pub fn redrawwin(&mut self, win: &mut Window) -> Result<()> {
+        win.touchwin();
+        Ok(())
+    }
+
+    /// Request that lines be redrawn (to fix screen corruption).
+    pub fn wredrawln(&mut self, win: &mut Window, beg_line: i32, num_lines: i32) -> Result<()> {
+        win.touchln(beg_line, num_lines, true);
+        Ok(())
+    }
+
+    // ========================================================================
+    // Soft Label Key (SLK) functions
+    // ========================================================================
+
+    /// Initialize soft labels with the specified format.
+    ///
+    /// This must be called before `Screen::init()` to reserve space for soft labels.
+    /// The format specifies how many labels and their arrangement:
+    /// - 0: 3-2-3 format (8 labels)
+    /// - 1: 4-4 format (8 labels)
+    /// - 2: 4-4-4 format (12 labels)
+    /// - 3: 4-4-4 PC style format (12 labels)
+    #[cfg(feature = "slk")]
+    pub fn slk_init(&mut self, fmt: i32) -> Result<()> {
+        let format = SlkFormat::from_int(fmt)
+            .ok_or_else(|| Error::InvalidArgument("invalid SLK format".to_string()))?;
+        let mut slk = SlkState::new(format);
+        slk.init_with_size(self.terminal.columns(), self.terminal.lines())?;
+        self.slk = Some(slk);
+        Ok(())
+    }
+
+    /// Set a soft label.
+    ///
+    /// Sets the text for label `labnum` (0-based for 8 labels, 0-11 for 12 labels).
+    /// The `justify` parameter specifies text alignment:
+    /// - 0: Left justify
+    /// - 1: Center
+    /// - 2: Right justify
+    #[cfg(feature = "slk")]
+    pub fn slk_set(&mut self, labnum: i32, label: &str, justify: i32) -> Result<()> {
+        let slk = self.slk.as_mut().ok_or(Error::NotInitialized)?;
+        slk.set(labnum, label, justify)
+    }
+
+    /// Get a soft label.
+    ///
+    /// Returns the text of label `labnum`, or None if not set.
+    #[cfg(feature = "slk")]
+    pub fn slk_label(&self, labnum: i32) -> Option<&str> {
+        self.slk.as_ref().and_then(|slk| slk.label(labnum))
+    }
+
+    /// Refresh the soft labels, writing them to the terminal.
+    ///
+    /// This outputs the soft labels to the screen immediately.
+    #[cfg(feature = "slk")]
+    pub fn slk_refresh(&mut self) -> Result<()> {
+        if let Some(slk) = &mut self.slk {
+            // Render the SLK line
+            let output = slk.render_ansi();
+            if !output.is_empty() {
+                self.terminal.write(output.as_bytes())?;
+                self.terminal.flush()?;
+            }
+            slk.refresh()?;
+        }
+        Ok(())
+    }
+
+    /// Mark soft labels for refresh without writing to terminal.
+    ///
+    /// The labels will be refreshed on the next `doupdate()` call.
+    #[cfg(feature = "slk")]
+    pub fn slk_noutrefresh(&mut self) -> Result<()> {
+        if let Some(slk) = &mut self.slk {
+            slk.noutrefresh()?;
+        }
+        Ok(())
+    }
+
+    /// Clear soft labels from the screen.
+    ///
+    /// The labels are hidden but not destroyed. Use `slk_restore()` to show them again.
+    #[cfg(feature = "slk")]
+    pub fn slk_clear(&mut self) -> Result<()> {
+        if let Some(slk) = &mut self.slk {
+            slk.clear_all()?;
+            // Clear the SLK area on screen
+            let row = slk.display_row();
+            let cols = self.terminal.columns();
+            let clear_line = format!("\x1b[{};1H{}\x1b[0m", row + 1, " ".repeat(cols as usize));
+            self.terminal.write(clear_line.as_bytes())?;
+            self.terminal.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Restore soft labels after they were cleared.
+    #[cfg(feature = "slk")]
+    pub fn slk_restore(&mut self) -> Result<()> {
+        if let Some(slk) = &mut self.slk {
+            slk.restore()?;
+            self.slk_refresh()?;
+        }
+        Ok(())
+    }
+
+    /// Touch soft labels, marking them for refresh.
+    #[cfg(feature = "slk")]
+    pub fn slk_touch(&mut self) -> Result<()> {
+        if let Some(slk) = &mut self.slk {
+            slk.touch();
+        }
+        Ok(())
+    }
+
+    /// Set the attributes for soft labels.
+    #[cfg(feature = "slk")]
+    pub fn slk_attrset(&mut self, attrs: AttrT) -> Result<()> {
+        if let Some(slk) = &mut self.slk {
+            slk.attrset(attrs)?;
+        }
+        Ok(())
+    }
+
+    /// Turn on attributes for soft labels.
+    #[cfg(feature = "slk")]
+    pub fn slk_attron(&mut self, attrs: AttrT) -> Result<()> {
+        if let Some(slk) = &mut self.slk {
+            slk.attron(attrs)?;
+        }
+        Ok(())
+    }
+
+    /// Turn off attributes for soft labels.
+    #[cfg(feature = "slk")]
+    pub fn slk_attroff(&mut self, attrs: AttrT) -> Result<()> {
+        if let Some(slk) = &mut self.slk {
+            slk.attroff(attrs)?;
+        }
+        Ok(())
+    }
+
+    /// Get the current attributes for soft labels.
+    #[cfg(feature = "slk")]
+    pub fn slk_attr(&self) -> AttrT {
+        self.slk.as_ref().map_or(0, |slk| slk.attr())
+    }
+
+    /// Set the color pair for soft labels.
+    #[cfg(feature = "slk")]
+    pub fn slk_color(&mut self, pair: i16) -> Result<()> {
+        if let Some(slk) = &mut self.slk {
+            slk.color(pair)?;
+        }
+        Ok(())
+    }
+
+    /// Turn off attributes for soft labels (X/Open style).
+    #[cfg(feature = "slk")]
+    pub fn slk_attr_off(&mut self, attrs: AttrT) -> Result<()> {
+        self.slk_attroff(attrs)
+    }
+
+    /// Turn on attributes for soft labels (X/Open style).
+    #[cfg(feature = "slk")]
+    pub fn slk_attr_on(&mut self, attrs: AttrT) -> Result<()> {
+        self.slk_attron(attrs)
+    }
+
+    /// Set the attributes for soft labels (X/Open style).
+    #[cfg(feature = "slk")]
+    pub fn slk_attr_set(&mut self, attrs: AttrT, pair: i16) -> Result<()> {
+        let combined = attrs | attr::color_pair(pair);
+        self.slk_attrset(combined)
+    }
+
+    /// Set a soft label key using a wide string.
+    #[cfg(all(feature = "slk", feature = "wide"))]
+    pub fn slk_wset(&mut self, labnum: i32, label: &str, justify: i32) -> Result<()> {
+        self.slk_set(labnum, label, justify)
+    }
+
+    /// Check if soft labels are initialized.
+    #[cfg(feature = "slk")]
+    pub fn slk_is_initialized(&self) -> bool {
+        self.slk.as_ref().is_some_and(|slk| slk.is_initialized())
+    }
+
+    // ========================================================================
+    // Terminfo query functions
+    // ========================================================================
+
+    /// Check whether `TERM` names a modern terminal emulator, which is
+    /// assumed to support the usual xterm extensions.
+    fn is_modern_terminal(&self) -> bool {
+        if let Some(caps) = &self.capabilities {
+            return caps.is_modern();
+        }
+        matches!(
+            self.terminal.term_type(),
             "xterm"
                 | "xterm-256color"
                 | "screen"
@@ -3163,2199 +5509,4545 @@ impl Screen {
                 | "wezterm"
                 | "iterm2"
                 | "vte"
+        )
+    }
+
+    /// Get a boolean capability value from terminfo.
+    ///
+    /// Returns:
+    /// - 1 if the capability is present
+    /// - 0 if the capability is absent
+    /// - -1 if the capability name is not recognized
+    ///
+    /// Common boolean capabilities:
+    /// - "am" - automatic margins
+    /// - "bce" - background color erase
+    /// - "km" - has a meta key
+    /// - "mc5i" - printer won't echo on screen
+    /// - "mir" - safe to move while in insert mode
+    /// - "msgr" - safe to move while in standout mode
+    /// - "xenl" - newline ignored after 80 cols
+    /// - "xon" - terminal uses XON/XOFF handshaking
+    pub fn tigetflag(&self, capname: &str) -> i32 {
+        let is_modern = self.is_modern_terminal();
+
+        match capname {
+            // Automatic margins (most terminals have this)
+            "am" => 1,
+            // Background color erase (modern terminals)
+            "bce" => {
+                if is_modern {
+                    1
+                } else {
+                    0
+                }
+            }
+            // Has a meta key
+            "km" => 1,
+            // Terminal uses XON/XOFF
+            "xon" => 0,
+            // Move in insert mode safe
+            "mir" => {
+                if is_modern {
+                    1
+                } else {
+                    0
+                }
+            }
+            // Move in standout mode safe
+            "msgr" => {
+                if is_modern {
+                    1
+                } else {
+                    0
+                }
+            }
+            // Newline ignored after 80 cols (xenl bug)
+            "xenl" => {
+                if is_modern {
+                    1
+                } else {
+                    0
+                }
+            }
+            // Has hardware tabs
+            "ht" | "it" => 1,
+            // Can change color
+            "ccc" => {
+                if self.terminal.can_change_color() {
+                    1
+                } else {
+                    0
+                }
+            }
+            // Has insert character
+            "ich" => {
+                if self.terminal.has_ic() {
+                    1
+                } else {
+                    0
+                }
+            }
+            // Has insert line
+            "il" => {
+                if self.terminal.has_il() {
+                    1
+                } else {
+                    0
+                }
+            }
+            // Unknown capability
+            _ => -1,
+        }
+    }
+
+    /// Get a numeric capability value from terminfo.
+    ///
+    /// Returns:
+    /// - The capability value if present
+    /// - -2 if the capability is absent
+    /// - -1 if the capability name is not recognized
+    ///
+    /// Common numeric capabilities:
+    /// - "cols" - number of columns
+    /// - "lines" - number of lines
+    /// - "colors" - max number of colors
+    /// - "pairs" - max number of color pairs
+    pub fn tigetnum(&self, capname: &str) -> i32 {
+        match capname {
+            "cols" | "co" => self.terminal.columns(),
+            "lines" | "li" => self.terminal.lines(),
+            "colors" => self.terminal.colors(),
+            "pairs" => self.terminal.color_pairs(),
+            "it" => self.tabsize, // initial tab size
+            // Unknown capability
+            _ => -1,
+        }
+    }
+
+    /// Get a string capability value from terminfo.
+    ///
+    /// Returns:
+    /// - Some(string) if the capability is present
+    /// - None if the capability is absent or not recognized
+    ///
+    /// Common string capabilities:
+    /// - "clear" - clear screen
+    /// - "cup" - cursor position
+    /// - "cuf1" - cursor forward one
+    /// - "cub1" - cursor back one
+    /// - "cuu1" - cursor up one
+    /// - "cud1" - cursor down one
+    /// - "smcup" - enter cursor-addressing mode
+    /// - "rmcup" - exit cursor-addressing mode
+    /// - "smso" - enter standout mode
+    /// - "rmso" - exit standout mode
+    /// - "setaf" - set ANSI foreground color
+    /// - "setab" - set ANSI background color
+    pub fn tigetstr(&self, capname: &str) -> Option<String> {
+        match capname {
+            // Clear screen
+            "clear" | "cl" => Some("\x1b[H\x1b[J".to_string()),
+            // Cursor position (template: \x1b[%d;%dH)
+            "cup" | "cm" => Some("\x1b[%i%p1%d;%p2%dH".to_string()),
+            // Cursor movements
+            "cuf1" => Some("\x1b[C".to_string()),
+            "cub1" => Some("\x08".to_string()), // backspace
+            "cuu1" => Some("\x1b[A".to_string()),
+            "cud1" => Some("\x1b[B".to_string()),
+            "home" => Some("\x1b[H".to_string()),
+            // Cursor n positions
+            "cuf" => Some("\x1b[%p1%dC".to_string()),
+            "cub" => Some("\x1b[%p1%dD".to_string()),
+            "cuu" => Some("\x1b[%p1%dA".to_string()),
+            "cud" => Some("\x1b[%p1%dB".to_string()),
+            // Enter/exit cursor addressing mode (alternate screen)
+            "smcup" => Some("\x1b[?1049h".to_string()),
+            "rmcup" => Some("\x1b[?1049l".to_string()),
+            // Enter/exit standout mode
+            "smso" => Some("\x1b[7m".to_string()),
+            "rmso" => Some("\x1b[27m".to_string()),
+            // Enter/exit underline mode
+            "smul" => Some("\x1b[4m".to_string()),
+            "rmul" => Some("\x1b[24m".to_string()),
+            // Bold mode
+            "bold" => Some("\x1b[1m".to_string()),
+            // Dim mode
+            "dim" => Some("\x1b[2m".to_string()),
+            // Blink mode
+            "blink" => Some("\x1b[5m".to_string()),
+            // Reverse mode
+            "rev" => Some("\x1b[7m".to_string()),
+            // Reset all attributes
+            "sgr0" => Some("\x1b[0m".to_string()),
+            // Set foreground color (ANSI)
+            "setaf" => Some("\x1b[3%p1%dm".to_string()),
+            // Set background color (ANSI)
+            "setab" => Some("\x1b[4%p1%dm".to_string()),
+            // Original colors
+            "op" => Some("\x1b[39;49m".to_string()),
+            // Invisible cursor
+            "civis" => Some("\x1b[?25l".to_string()),
+            // Normal cursor
+            "cnorm" => Some("\x1b[?25h".to_string()),
+            // Very visible cursor
+            "cvvis" => Some("\x1b[?25h\x1b[?12h".to_string()),
+            // Insert/delete character
+            "ich1" => Some("\x1b[@".to_string()),
+            "dch1" => Some("\x1b[P".to_string()),
+            // Insert/delete line
+            "il1" => Some("\x1b[L".to_string()),
+            "dl1" => Some("\x1b[M".to_string()),
+            // Scroll forward/reverse
+            "ind" => Some("\x1b[S".to_string()),
+            "ri" => Some("\x1b[T".to_string()),
+            // Clear to end of line/screen
+            "el" => Some("\x1b[K".to_string()),
+            "ed" => Some("\x1b[J".to_string()),
+            // Visible bell (reverse the screen and back)
+            "flash" if self.is_modern_terminal() => Some("\x1b[?5h$<100/>\x1b[?5l".to_string()),
+            // Key sequences
+            "kcuu1" => Some("\x1b[A".to_string()),
+            "kcud1" => Some("\x1b[B".to_string()),
+            "kcuf1" => Some("\x1b[C".to_string()),
+            "kcub1" => Some("\x1b[D".to_string()),
+            "khome" => Some("\x1b[H".to_string()),
+            "kend" => Some("\x1b[F".to_string()),
+            "kpp" => Some("\x1b[5~".to_string()),   // page up
+            "knp" => Some("\x1b[6~".to_string()),   // page down
+            "kich1" => Some("\x1b[2~".to_string()), // insert
+            "kdch1" => Some("\x1b[3~".to_string()), // delete
+            "kbs" => Some("\x7f".to_string()),      // backspace
+            // Function keys
+            "kf1" => Some("\x1bOP".to_string()),
+            "kf2" => Some("\x1bOQ".to_string()),
+            "kf3" => Some("\x1bOR".to_string()),
+            "kf4" => Some("\x1bOS".to_string()),
+            "kf5" => Some("\x1b[15~".to_string()),
+            "kf6" => Some("\x1b[17~".to_string()),
+            "kf7" => Some("\x1b[18~".to_string()),
+            "kf8" => Some("\x1b[19~".to_string()),
+            "kf9" => Some("\x1b[20~".to_string()),
+            "kf10" => Some("\x1b[21~".to_string()),
+            "kf11" => Some("\x1b[23~".to_string()),
+            "kf12" => Some("\x1b[24~".to_string()),
+            // Unknown capability
+            _ => None,
+        }
+    }
+
+    /// Output a terminfo string to the terminal.
+    ///
+    /// This is equivalent to tputs() in ncurses. The string is sent
+    /// immediately, together with any other buffered output.
+    pub fn putp(&mut self, s: &str) -> Result<()> {
+        self.terminal.write(s.as_bytes())?;
+        self.terminal.flush()
+    }
+
+    /// Parameterized terminal string.
+    ///
+    /// This is a simplified version of tparm() that handles basic parameter
+    /// substitution in terminfo strings.
+    ///
+    /// Supports:
+    /// - %p1 through %p9: parameter values
+    /// - %d: output parameter as decimal
+    /// - %i: increment first two parameters by 1
+    /// - %%: literal %
+    pub fn tparm(&self, s: &str, params: &[i32]) -> String {
+        let mut result = String::new();
+        let mut chars = s.chars().peekable();
+        let mut params = params.to_vec();
+        // Extend params to at least 9 elements
+        params.resize(9, 0);
+
+        while let Some(ch) = chars.next() {
+            if ch == '%' {
+                match chars.next() {
+                    Some('%') => result.push('%'),
+                    Some('i') => {
+                        // Increment first two parameters
+                        if !params.is_empty() {
+                            params[0] += 1;
+                        }
+                        if params.len() > 1 {
+                            params[1] += 1;
+                        }
+                    }
+                    Some('p') => {
+                        // Parameter reference %p1 through %p9
+                        if let Some(digit) = chars.next() {
+                            if let Some(n) = digit.to_digit(10) {
+                                if (1..=9).contains(&n) {
+                                    let idx = (n - 1) as usize;
+                                    // Look for the format specifier
+                                    if chars.peek() == Some(&'%') {
+                                        chars.next();
+                                        if chars.peek() == Some(&'d') {
+                                            chars.next();
+                                            result.push_str(&params[idx].to_string());
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    Some('d') => {
+                        // Direct decimal output (legacy format)
+                        if !params.is_empty() {
+                            result.push_str(&params[0].to_string());
+                        }
+                    }
+                    Some(c) => {
+                        // Unknown format, preserve it
+                        result.push('%');
+                        result.push(c);
+                    }
+                    None => result.push('%'),
+                }
+            } else {
+                result.push(ch);
+            }
+        }
+
+        result
+    }
+
+    // ========================================================================
+    // Screen dump/restore functions
+    // ========================================================================
+
+    /// Dump the screen contents to a file.
+    ///
+    /// This saves the virtual screen (newscr) contents to a file that can be
+    /// restored later with `scr_restore()`.
+    pub fn scr_dump(&self, filename: &str) -> Result<()> {
+        std::fs::write(filename, self.scr_dump_to_vec())?;
+        Ok(())
+    }
+
+    /// Dump the screen contents to memory.
+    ///
+    /// The result holds the same data `scr_dump()` writes to its file and can
+    /// be restored with `scr_restore_from_slice()`.
+    pub fn scr_dump_to_vec(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+
+        // Write header: magic number, version, dimensions
+        let lines = self.newscr.getmaxy();
+        let cols = self.newscr.getmaxx();
+
+        // Simple binary format: NCDUMP + version(1) + lines(4) + cols(4) + data
+        buf.extend_from_slice(b"NCDUMP");
+        buf.push(1); // version
+        buf.extend_from_slice(&(lines as u32).to_le_bytes());
+        buf.extend_from_slice(&(cols as u32).to_le_bytes());
+
+        // Write screen data
+        for y in 0..lines {
+            if let Some(line) = self.newscr.line(y as usize) {
+                for x in 0..cols {
+                    let ch = line.get(x as usize);
+                    #[cfg(not(feature = "wide"))]
+                    {
+                        buf.extend_from_slice(&(ch as u32).to_le_bytes());
+                    }
+                    #[cfg(feature = "wide")]
+                    {
+                        // For wide characters, serialize the primary char and attrs
+                        let c = ch.chars[0] as u32;
+                        let a = ch.attr;
+                        buf.extend_from_slice(&c.to_le_bytes());
+                        buf.extend_from_slice(&a.to_le_bytes());
+                    }
+                }
+            }
+        }
+
+        buf
+    }
+
+    /// Restore screen contents from a file.
+    ///
+    /// This restores screen contents saved by `scr_dump()`. The screen should
+    /// be refreshed after calling this to display the restored contents.
+    pub fn scr_restore(&mut self, filename: &str) -> Result<()> {
+        self.scr_restore_from_slice(&std::fs::read(filename)?)
+    }
+
+    /// Restore screen contents from memory.
+    ///
+    /// This accepts the data produced by `scr_dump_to_vec()` or written by
+    /// `scr_dump()`. The screen should be refreshed afterwards.
+    pub fn scr_restore_from_slice(&mut self, data: &[u8]) -> Result<()> {
+        use std::io::Read;
+
+        let mut input = data;
+
+        // Read and verify header
+        let mut magic = [0u8; 6];
+        input.read_exact(&mut magic)?;
+        if &magic != b"NCDUMP" {
+            return Err(Error::InvalidArgument(
+                "Invalid screen dump data".to_string(),
+            ));
+        }
+
+        let mut version = [0u8; 1];
+        input.read_exact(&mut version)?;
+        if version[0] != 1 {
+            return Err(Error::InvalidArgument(
+                "Unsupported dump file version".to_string(),
+            ));
+        }
+
+        let mut lines_bytes = [0u8; 4];
+        let mut cols_bytes = [0u8; 4];
+        input.read_exact(&mut lines_bytes)?;
+        input.read_exact(&mut cols_bytes)?;
+
+        let file_lines = u32::from_le_bytes(lines_bytes) as i32;
+        let file_cols = u32::from_le_bytes(cols_bytes) as i32;
+
+        // Read screen data (limited to current screen size)
+        let screen_lines = self.newscr.getmaxy().min(file_lines);
+        let screen_cols = self.newscr.getmaxx().min(file_cols);
+
+        for y in 0..file_lines {
+            for x in 0..file_cols {
+                #[cfg(not(feature = "wide"))]
+                {
+                    let mut ch_bytes = [0u8; 4];
+                    input.read_exact(&mut ch_bytes)?;
+                    let ch = u32::from_le_bytes(ch_bytes) as ChType;
+
+                    if y < screen_lines && x < screen_cols {
+                        if let Some(line) = self.newscr.line_mut(y as usize) {
+                            line.set(x as usize, ch);
+                        }
+                    }
+                }
+                #[cfg(feature = "wide")]
+                {
+                    let mut c_bytes = [0u8; 4];
+                    let mut a_bytes = [0u8; 4];
+                    input.read_exact(&mut c_bytes)?;
+                    input.read_exact(&mut a_bytes)?;
+                    let c = u32::from_le_bytes(c_bytes);
+                    let a = u32::from_le_bytes(a_bytes);
+
+                    if y < screen_lines && x < screen_cols {
+                        if let Some(line) = self.newscr.line_mut(y as usize) {
+                            let mut cchar = crate::wide::CCharT::default();
+                            cchar.chars[0] = char::from_u32(c).unwrap_or(' ');
+                            cchar.attr = a;
+                            line.set(x as usize, cchar);
+                        }
+                    }
+                }
+            }
+        }
+
+        // Mark screen as needing refresh
+        self.newscr.touchwin();
+        Ok(())
+    }
+
+    /// Initialize screen from a dump file.
+    ///
+    /// This is similar to `scr_restore()` but is intended to be called before
+    /// the first refresh to pre-populate the screen.
+    pub fn scr_init(&mut self, filename: &str) -> Result<()> {
+        self.scr_restore(filename)
+    }
+
+    /// Set the screen contents from a dump file.
+    ///
+    /// This combines the functionality of `scr_init()` and `scr_restore()`.
+    pub fn scr_set(&mut self, filename: &str) -> Result<()> {
+        self.scr_restore(filename)?;
+        // Also update curscr to match
+        let lines = self.newscr.getmaxy();
+        let cols = self.newscr.getmaxx();
+        for y in 0..lines {
+            if let (Some(src_line), Some(dst_line)) = (
+                self.newscr.line(y as usize),
+                self.curscr.line_mut(y as usize),
+            ) {
+                for x in 0..cols {
+                    dst_line.set(x as usize, src_line.get(x as usize));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Save a window to a file.
+    ///
+    /// This saves the window contents in a format that can be restored
+    /// with `getwin()`, along with the cursor, attributes, background,
+    /// scrolling region, input delay, tab size and option flags
+    /// (`scrollok`, `keypad`, `leaveok`, `clearok`, `idlok`, `idcok`,
+    /// `immedok`, `syncok`, `notimeout` and wrapping).
+    pub fn putwin(&self, win: &Window, filename: &str) -> Result<()> {
+        use std::fs::File;
+        use std::io::Write;
+
+        let mut file = File::create(filename)?;
+
+        // Write header
+        let lines = win.getmaxy();
+        let cols = win.getmaxx();
+        let begy = win.getbegy();
+        let begx = win.getbegx();
+
+        // Format: NCWIN + version(1) + lines(4) + cols(4) + begy(4) + begx(4)
+        // + state + data
+        file.write_all(b"NCWIN")?;
+        file.write_all(&[WINDOW_DUMP_VERSION])?;
+        file.write_all(&(lines as u32).to_le_bytes())?;
+        file.write_all(&(cols as u32).to_le_bytes())?;
+        file.write_all(&begy.to_le_bytes())?;
+        file.write_all(&begx.to_le_bytes())?;
+
+        // Window state (version 2): cury, curx, options, attrs, delay,
+        // tabsize, regtop, regbottom, background (character, attributes and
+        // extended color pair in the wide format)
+        let (cury, curx) = win.cursor();
+        let (top, bottom) = win.getscrreg();
+        for value in [cury, curx] {
+            file.write_all(&value.to_le_bytes())?;
+        }
+        file.write_all(&window_options(win).to_le_bytes())?;
+        file.write_all(&win.getattrs().to_le_bytes())?;
+        for value in [win.getdelay(), win.get_tabsize(), top, bottom] {
+            file.write_all(&value.to_le_bytes())?;
+        }
+        #[cfg(not(feature = "wide"))]
+        file.write_all(&(win.getbkgd() as u32).to_le_bytes())?;
+        #[cfg(feature = "wide")]
+        {
+            let bkgrnd = win.getbkgrnd();
+            file.write_all(&(bkgrnd.spacing_char() as u32).to_le_bytes())?;
+            file.write_all(&bkgrnd.attr.to_le_bytes())?;
+            // The extended color pair, written either way so the layout
+            // does not depend on ext-colors
+            #[cfg(feature = "ext-colors")]
+            let ext_color = bkgrnd.ext_color;
+            #[cfg(not(feature = "ext-colors"))]
+            let ext_color = 0i32;
+            file.write_all(&ext_color.to_le_bytes())?;
+        }
+
+        // Write window data
+        for y in 0..lines {
+            if let Some(line) = win.line(y as usize) {
+                for x in 0..cols {
+                    let ch = line.get(x as usize);
+                    #[cfg(not(feature = "wide"))]
+                    {
+                        file.write_all(&(ch as u32).to_le_bytes())?;
+                    }
+                    #[cfg(feature = "wide")]
+                    {
+                        let c = ch.chars[0] as u32;
+                        let a = ch.attr;
+                        file.write_all(&c.to_le_bytes())?;
+                        file.write_all(&a.to_le_bytes())?;
+                    }
+                }
+            }
+        }
+
+        file.flush()?;
+        Ok(())
+    }
+
+    /// Restore a window from a file.
+    ///
+    /// This creates a new window with the contents and state saved by
+    /// `putwin()`. Files written by older versions, which hold only the
+    /// contents, are still accepted; their windows get default settings.
+    pub fn getwin(&self, filename: &str) -> Result<Window> {
+        use std::fs::File;
+        use std::io::Read;
+
+        let mut file = File::open(filename)?;
+
+        // Read and verify header
+        let mut magic = [0u8; 5];
+        file.read_exact(&mut magic)?;
+        if &magic != b"NCWIN" {
+            return Err(Error::InvalidArgument(
+                "Invalid window dump file".to_string(),
+            ));
+        }
+
+        let mut version = [0u8; 1];
+        file.read_exact(&mut version)?;
+        if version[0] == 0 || version[0] > WINDOW_DUMP_VERSION {
+            return Err(Error::InvalidArgument(
+                "Unsupported dump file version".to_string(),
+            ));
+        }
+
+        let mut read_u32 = || -> Result<u32> {
+            let mut bytes = [0u8; 4];
+            file.read_exact(&mut bytes)?;
+            Ok(u32::from_le_bytes(bytes))
+        };
+
+        let lines = read_u32()? as i32;
+        let cols = read_u32()? as i32;
+        let begy = read_u32()? as i32;
+        let begx = read_u32()? as i32;
+
+        // Create the window
+        let mut win = Window::new(lines, cols, begy, begx)?;
+
+        if version[0] >= 2 {
+            let cury = read_u32()? as i32;
+            let curx = read_u32()? as i32;
+            let options = read_u32()?;
+            let attrs: AttrT = read_u32()?;
+            let delay = read_u32()? as i32;
+            let tabsize = read_u32()? as i32;
+            let top = read_u32()? as i32;
+            let bottom = read_u32()? as i32;
+            #[cfg(not(feature = "wide"))]
+            win.bkgdset(read_u32()? as ChType);
+            #[cfg(feature = "wide")]
+            {
+                let c = char::from_u32(read_u32()?).unwrap_or(' ');
+                let a: AttrT = read_u32()?;
+                #[cfg_attr(not(feature = "ext-colors"), allow(unused_variables))]
+                let ext_color = read_u32()? as i32;
+                win.bkgrndset(&crate::wide::CCharT {
+                    #[cfg(feature = "ext-colors")]
+                    ext_color,
+                    ..crate::wide::CCharT::from_char_attr(c, a)
+                });
+            }
+
+            win.mv(cury, curx)?;
+            set_window_options(&mut win, options);
+            win.attrset(attrs)?;
+            win.timeout(delay);
+            win.set_tabsize(tabsize);
+            win.setscrreg(top, bottom)?;
+        }
+
+        // Read window data
+        for y in 0..lines {
+            for x in 0..cols {
+                #[cfg(not(feature = "wide"))]
+                {
+                    let mut ch_bytes = [0u8; 4];
+                    file.read_exact(&mut ch_bytes)?;
+                    let ch = u32::from_le_bytes(ch_bytes) as ChType;
+
+                    if let Some(line) = win.line_mut(y as usize) {
+                        line.set(x as usize, ch);
+                    }
+                }
+                #[cfg(feature = "wide")]
+                {
+                    let mut c_bytes = [0u8; 4];
+                    let mut a_bytes = [0u8; 4];
+                    file.read_exact(&mut c_bytes)?;
+                    file.read_exact(&mut a_bytes)?;
+                    let c = u32::from_le_bytes(c_bytes);
+                    let a = u32::from_le_bytes(a_bytes);
+
+                    if let Some(line) = win.line_mut(y as usize) {
+                        let mut cchar = crate::wide::CCharT::default();
+                        cchar.chars[0] = char::from_u32(c).unwrap_or(' ');
+                        cchar.attr = a;
+                        line.set(x as usize, cchar);
+                    }
+                }
+            }
+        }
+
+        Ok(win)
+    }
+
+    // ========================================================================
+    // Deprecated/Unavailable C-style functions
+    // ========================================================================
+    // These functions exist in C ncurses but don't translate well to Rust.
+    // The printw family is implemented above with working alternatives.
+    // The scanw family below provides compile-time errors with guidance.
+
+    /// **Not available in Rust** - Use `getstr()` and parse manually instead.
+    ///
+    /// In C ncurses, `scanw` reads formatted input:
+    /// ```c
+    /// int value;
+    /// scanw("%d", &value);
+    /// ```
+    ///
+    /// In Rust, use `getstr` and parse:
+    /// ```rust,ignore
+    /// let input = screen.getstr(100)?;
+    /// let value: i32 = input.trim().parse().unwrap_or(0);
+    /// ```
+    ///
+    /// # Panics
+    /// Always panics - this function is not implemented.
+    #[deprecated(since = "0.1.0", note = "Use getstr() and parse the result instead")]
+    pub fn scanw(&mut self, _fmt: &str) -> Result<()> {
+        panic!(
+            "scanw is not available in Rust. Use getstr() and parse the result instead.\n\
+             Example:\n\
+             let input = screen.getstr(100)?;\n\
+             let value: i32 = input.trim().parse().unwrap_or(0);"
+        );
+    }
+
+    /// **Not available in Rust** - Use `wgetstr()` and parse manually instead.
+    ///
+    /// In C ncurses, `wscanw` reads formatted input from a window:
+    /// ```c
+    /// int value;
+    /// wscanw(win, "%d", &value);
+    /// ```
+    ///
+    /// In Rust, use `wgetstr` and parse:
+    /// ```rust,ignore
+    /// let input = screen.wgetstr(win, 100)?;
+    /// let value: i32 = input.trim().parse().unwrap_or(0);
+    /// ```
+    ///
+    /// # Panics
+    /// Always panics - this function is not implemented.
+    #[deprecated(since = "0.1.0", note = "Use wgetstr() and parse the result instead")]
+    pub fn wscanw(&mut self, _win: &mut Window, _fmt: &str) -> Result<()> {
+        panic!(
+            "wscanw is not available in Rust. Use wgetstr() and parse the result instead.\n\
+             Example:\n\
+             let input = screen.wgetstr(win, 100)?;\n\
+             let value: i32 = input.trim().parse().unwrap_or(0);"
+        );
+    }
+
+    /// **Not available in Rust** - Use `mvgetstr()` and parse manually instead.
+    ///
+    /// # Panics
+    /// Always panics - this function is not implemented.
+    #[deprecated(
+        since = "0.1.0",
+        note = "Use mv() + getstr() and parse the result instead"
+    )]
+    pub fn mvscanw(&mut self, _y: i32, _x: i32, _fmt: &str) -> Result<()> {
+        panic!(
+            "mvscanw is not available in Rust. Use mv() + getstr() and parse instead.\n\
+             Example:\n\
+             screen.mv(y, x)?;\n\
+             let input = screen.getstr(100)?;\n\
+             let value: i32 = input.trim().parse().unwrap_or(0);"
+        );
+    }
+
+    /// **Not available in Rust** - Use `wmove()` + `wgetstr()` and parse manually instead.
+    ///
+    /// # Panics
+    /// Always panics - this function is not implemented.
+    #[deprecated(
+        since = "0.1.0",
+        note = "Use wmove() + wgetstr() and parse the result instead"
+    )]
+    pub fn mvwscanw(&mut self, _win: &mut Window, _y: i32, _x: i32, _fmt: &str) -> Result<()> {
+        panic!(
+            "mvwscanw is not available in Rust. Use wmove() + wgetstr() and parse instead.\n\
+             Example:\n\
+             screen.wmove(win, y, x)?;\n\
+             let input = screen.wgetstr(win, 100)?;\n\
+             let value: i32 = input.trim().parse().unwrap_or(0);"
         );
+    }
+
+    // ========================================================================
+    // Alias functions for ncurses API compatibility
+    // ========================================================================
+
+    /// Move the cursor on stdscr.
+    ///
+    /// This is equivalent to `wmove(stdscr, y, x)`.
+    pub fn r#move(&mut self, y: i32, x: i32) -> Result<()> {
+        self.stdscr.mv(y, x)
+    }
+
+    /// Turn off standout mode on stdscr.
+    pub fn standend(&mut self) -> Result<()> {
+        self.stdscr.standend()
+    }
+
+    /// Turn on standout mode on stdscr.
+    pub fn standout(&mut self) -> Result<()> {
+        self.stdscr.standout()
+    }
+
+    /// Turn off standout mode on a window.
+    pub fn wstandend(&mut self, win: &mut Window) -> Result<()> {
+        win.standend()
+    }
+
+    /// Turn on standout mode on a window.
+    pub fn wstandout(&mut self, win: &mut Window) -> Result<()> {
+        win.standout()
+    }
+
+    // ========================================================================
+    // Attribute get/set functions (attr_* family)
+    // ========================================================================
+
+    /// Get the current attributes and color pair from stdscr.
+    pub fn attr_get(&self, attrs: &mut AttrT, pair: &mut i16) -> Result<()> {
+        *attrs = self.stdscr.getattrs();
+        *pair = attr::pair_number(*attrs);
+        Ok(())
+    }
+
+    /// Turn off attributes on stdscr.
+    ///
+    /// Unlike [`attroff()`](Self::attroff), color bits in `attrs` are
+    /// ignored; the color pair only changes through `attr_set()` or
+    /// `color_set()`.
+    pub fn attr_off(&mut self, attrs: AttrT) -> Result<()> {
+        self.stdscr.attroff(attrs & !attr::A_COLOR)
+    }
+
+    /// Turn on attributes on stdscr.
+    ///
+    /// Unlike [`attron()`](Self::attron), color bits in `attrs` are ignored.
+    pub fn attr_on(&mut self, attrs: AttrT) -> Result<()> {
+        self.stdscr.attron(attrs & !attr::A_COLOR)
+    }
+
+    /// Set attributes on stdscr.
+    pub fn attr_set(&mut self, attrs: AttrT, pair: i16) -> Result<()> {
+        self.stdscr.attrset(attrs & !attr::A_COLOR)?;
+        self.stdscr.color_set(pair)
+    }
+
+    /// Get the current attributes and color pair from a window.
+    pub fn wattr_get(&self, win: &Window, attrs: &mut AttrT, pair: &mut i16) -> Result<()> {
+        *attrs = win.getattrs();
+        *pair = attr::pair_number(*attrs);
+        Ok(())
+    }
+
+    /// Turn off attributes on a window, ignoring color bits in `attrs`.
+    pub fn wattr_off(&mut self, win: &mut Window, attrs: AttrT) -> Result<()> {
+        win.attroff(attrs & !attr::A_COLOR)
+    }
 
-        match capname {
-            // Automatic margins (most terminals have this)
-            "am" => 1,
-            // Background color erase (modern terminals)
-            "bce" => {
-                if is_modern {
-                    1
-                } else {
-                    0
-                }
-            }
-            // Has a meta key
-            "km" => 1,
-            // Terminal uses XON/XOFF
-            "xon" => 0,
-            // Move in insert mode safe
-            "mir" => {
-                if is_modern {
-                    1
-                } else {
-                    0
-                }
-            }
-            // Move in standout mode safe
-            "msgr" => {
-                if is_modern {
-                    1
-                } else {
-                    0
-                }
-            }
-            // Newline ignored after 80 cols (xenl bug)
-            "xenl" => {
-                if is_modern {
-                    1
-                } else {
-                    0
-                }
-            }
-            // Has hardware tabs
-            "ht" | "it" => 1,
-            // Can change color
-            "ccc" => {
-                if self.terminal.can_change_color() {
-                    1
-                } else {
-                    0
-                }
-            }
-            // Has insert character
-            "ich" => {
-                if self.terminal.has_ic() {
-                    1
-                } else {
-                    0
-                }
-            }
-            // Has insert line
-            "il" => {
-                if self.terminal.has_il() {
-                    1
-                } else {
-                    0
-                }
-            }
-            // Unknown capability
-            _ => -1,
+    /// Turn on attributes on a window, ignoring color bits in `attrs`.
+    pub fn wattr_on(&mut self, win: &mut Window, attrs: AttrT) -> Result<()> {
+        win.attron(attrs & !attr::A_COLOR)
+    }
+
+    /// Set attributes on a window.
+    pub fn wattr_set(&mut self, win: &mut Window, attrs: AttrT, pair: i16) -> Result<()> {
+        win.attrset(attrs & !attr::A_COLOR)?;
+        win.color_set(pair)
+    }
+
+    /// Get the current attributes and extended color pair from stdscr.
+    ///
+    /// Unlike [`attr_get()`](Self::attr_get) this reports pairs beyond the
+    /// range that fits in the attribute bits.
+    #[cfg(feature = "ext-colors")]
+    pub fn attr_get_ext(&self, attrs: &mut AttrT, pair: &mut i32) -> Result<()> {
+        self.wattr_get_ext(&self.stdscr, attrs, pair)
+    }
+
+    /// Set attributes and an extended color pair on stdscr.
+    #[cfg(feature = "ext-colors")]
+    pub fn attr_set_ext(&mut self, attrs: AttrT, pair: i32) -> Result<()> {
+        self.stdscr.attrset(attrs & !attr::A_COLOR)?;
+        self.stdscr.extended_color_set(pair)
+    }
+
+    /// Get the current attributes and extended color pair from a window.
+    #[cfg(feature = "ext-colors")]
+    pub fn wattr_get_ext(&self, win: &Window, attrs: &mut AttrT, pair: &mut i32) -> Result<()> {
+        *attrs = win.getattrs();
+        *pair = win.extended_color();
+        Ok(())
+    }
+
+    /// Set attributes and an extended color pair on a window.
+    #[cfg(feature = "ext-colors")]
+    pub fn wattr_set_ext(&mut self, win: &mut Window, attrs: AttrT, pair: i32) -> Result<()> {
+        win.attrset(attrs & !attr::A_COLOR)?;
+        win.extended_color_set(pair)
+    }
+
+    // ========================================================================
+    // Move + operation wrappers (mv* family)
+    // ========================================================================
+
+    /// Move cursor and delete character on stdscr.
+    pub fn mvdelch(&mut self, y: i32, x: i32) -> Result<()> {
+        self.stdscr.mv(y, x)?;
+        self.stdscr.delch()
+    }
+
+    /// Move cursor and draw horizontal line on stdscr.
+    pub fn mvhline(&mut self, y: i32, x: i32, ch: ChType, n: i32) -> Result<()> {
+        self.stdscr.mv(y, x)?;
+        self.stdscr.hline(ch, n)
+    }
+
+    /// Move cursor and draw vertical line on stdscr.
+    pub fn mvvline(&mut self, y: i32, x: i32, ch: ChType, n: i32) -> Result<()> {
+        self.stdscr.mv(y, x)?;
+        self.stdscr.vline(ch, n)
+    }
+
+    /// Move cursor and insert character on stdscr.
+    pub fn mvinsch(&mut self, y: i32, x: i32, ch: ChType) -> Result<()> {
+        self.stdscr.mv(y, x)?;
+        self.stdscr.insch(ch)
+    }
+
+    /// Move cursor and add a string of chtype on stdscr.
+    pub fn mvaddchstr(&mut self, y: i32, x: i32, chstr: &[ChType]) -> Result<()> {
+        self.stdscr.mv(y, x)?;
+        self.stdscr.addchnstr(chstr, -1)
+    }
+
+    /// Move cursor and add at most n chtype on stdscr.
+    pub fn mvaddchnstr(&mut self, y: i32, x: i32, chstr: &[ChType], n: i32) -> Result<()> {
+        self.stdscr.mv(y, x)?;
+        self.stdscr.addchnstr(chstr, n)
+    }
+
+    /// Move cursor and add a string of chtype on a window.
+    pub fn mvwaddchstr(
+        &mut self,
+        win: &mut Window,
+        y: i32,
+        x: i32,
+        chstr: &[ChType],
+    ) -> Result<()> {
+        win.mv(y, x)?;
+        win.addchnstr(chstr, -1)
+    }
+
+    /// Move cursor and add at most n chtype on a window.
+    pub fn mvwaddchnstr(
+        &mut self,
+        win: &mut Window,
+        y: i32,
+        x: i32,
+        chstr: &[ChType],
+        n: i32,
+    ) -> Result<()> {
+        win.mv(y, x)?;
+        win.addchnstr(chstr, n)
+    }
+
+    /// Move a derived window relative to its parent.
+    pub fn mvderwin(&mut self, win: &mut Window, y: i32, x: i32) -> Result<()> {
+        win.mvderwin(y, x)
+    }
+
+    /// Turn a subwindow into an independent window.
+    ///
+    /// Use this to keep a subwindow around after its parent is deleted. See
+    /// [`Window::detach()`] for details.
+    pub fn derwin_detach(&mut self, win: &mut Window) -> Result<()> {
+        win.detach()
+    }
+
+    // ========================================================================
+    // Unbounded *chstr variants (read to end of line)
+    // ========================================================================
+
+    /// Get a string of characters with attributes from stdscr (unbounded).
+    pub fn inchstr(&self, chstr: &mut [ChType]) -> i32 {
+        self.stdscr.inchnstr(chstr, -1)
+    }
+
+    /// Move and get a string of characters with attributes from stdscr (unbounded).
+    pub fn mvinchstr(&mut self, y: i32, x: i32, chstr: &mut [ChType]) -> Result<i32> {
+        self.stdscr.mv(y, x)?;
+        Ok(self.stdscr.inchnstr(chstr, -1))
+    }
+
+    /// Get a string of characters with attributes from a window (unbounded).
+    pub fn winchstr(&self, win: &Window, chstr: &mut [ChType]) -> i32 {
+        win.inchnstr(chstr, -1)
+    }
+
+    /// Move and get a string of characters with attributes from a window (unbounded).
+    pub fn mvwinchstr(
+        &mut self,
+        win: &mut Window,
+        y: i32,
+        x: i32,
+        chstr: &mut [ChType],
+    ) -> Result<i32> {
+        win.mv(y, x)?;
+        Ok(win.inchnstr(chstr, -1))
+    }
+
+    // ========================================================================
+    // Additional string input functions (getstr family variants)
+    // ========================================================================
+
+    /// Get a string of at most `n` characters from stdscr.
+    ///
+    /// As in ncurses, a negative `n` means no limit; input is then capped at
+    /// 1024 characters so a runaway stream cannot grow the result forever.
+    pub fn getnstr(&mut self, n: i32) -> Result<String> {
+        self.getstr(getstr_limit(n))
+    }
+
+    /// Move cursor and get a string from stdscr.
+    pub fn mvgetstr(&mut self, y: i32, x: i32, maxlen: usize) -> Result<String> {
+        self.stdscr.mv(y, x)?;
+        self.getstr(maxlen)
+    }
+
+    /// Move cursor and get a string of at most `n` characters from stdscr.
+    ///
+    /// See [`getnstr()`](Self::getnstr) for the meaning of `n`.
+    pub fn mvgetnstr(&mut self, y: i32, x: i32, n: i32) -> Result<String> {
+        self.stdscr.mv(y, x)?;
+        self.getnstr(n)
+    }
+
+    /// Get a string of at most `n` characters from a window.
+    ///
+    /// See [`getnstr()`](Self::getnstr) for the meaning of `n`.
+    pub fn wgetnstr(&mut self, win: &mut Window, n: i32) -> Result<String> {
+        self.wgetstr(win, getstr_limit(n))
+    }
+
+    /// Move cursor and get a string from a window.
+    pub fn mvwgetstr(&mut self, win: &mut Window, y: i32, x: i32, maxlen: usize) -> Result<String> {
+        win.mv(y, x)?;
+        self.wgetstr(win, maxlen)
+    }
+
+    /// Move cursor and get a string of at most `n` characters from a window.
+    ///
+    /// See [`getnstr()`](Self::getnstr) for the meaning of `n`.
+    pub fn mvwgetnstr(&mut self, win: &mut Window, y: i32, x: i32, n: i32) -> Result<String> {
+        win.mv(y, x)?;
+        self.wgetnstr(win, n)
+    }
+
+    // ========================================================================
+    // Unbounded instr variants
+    // ========================================================================
+
+    /// Get a string from a window at the current position (unbounded).
+    pub fn winstr(&self, win: &Window) -> String {
+        win.instr(-1)
+    }
+
+    /// Move and get a string from a window (unbounded).
+    pub fn mvwinstr(&mut self, win: &mut Window, y: i32, x: i32) -> Result<String> {
+        win.mv(y, x)?;
+        Ok(win.instr(-1))
+    }
+
+    // ========================================================================
+    // Sync functions
+    // ========================================================================
+
+    /// Synchronize cursor position with ancestors.
+    ///
+    /// See [`Window::cursyncup()`]; this does nothing for a window that is
+    /// not a subwindow.
+    pub fn wcursyncup(&mut self, win: &mut Window) {
+        if win.is_subwin() {
+            let _ = win.cursyncup();
+        }
+    }
+
+    /// Synchronize window with descendants.
+    ///
+    /// See [`Window::syncdown()`]; this does nothing for a window that is
+    /// not a subwindow.
+    pub fn wsyncdown(&mut self, win: &mut Window) {
+        if win.is_subwin() {
+            let _ = win.syncdown();
+        }
+    }
+
+    /// Synchronize window with ancestors.
+    ///
+    /// See [`Window::syncup()`]; this does nothing for a window that is not
+    /// a subwindow.
+    pub fn wsyncup(&mut self, win: &mut Window) {
+        if win.is_subwin() {
+            let _ = win.syncup();
         }
     }
 
-    /// Get a numeric capability value from terminfo.
+    /// Touch a line range in a window.
+    pub fn touchline(&mut self, win: &mut Window, start: i32, count: i32) -> Result<()> {
+        win.touchln(start, count, true);
+        Ok(())
+    }
+
+    // ========================================================================
+    // Mouse transformation
+    // ========================================================================
+
+    /// Transform mouse coordinates from screen to window coordinates.
     ///
-    /// Returns:
-    /// - The capability value if present
-    /// - -2 if the capability is absent
-    /// - -1 if the capability name is not recognized
+    /// If `to_screen` is true, transforms from window to screen coordinates.
+    /// Returns true if the coordinates are within the window.
+    #[cfg(feature = "mouse")]
+    pub fn mouse_trafo(&self, y: &mut i32, x: &mut i32, to_screen: bool) -> bool {
+        self.wmouse_trafo(&self.stdscr, y, x, to_screen)
+    }
+
+    /// Transform mouse coordinates for a specific window.
     ///
-    /// Common numeric capabilities:
-    /// - "cols" - number of columns
-    /// - "lines" - number of lines
-    /// - "colors" - max number of colors
-    /// - "pairs" - max number of color pairs
-    pub fn tigetnum(&self, capname: &str) -> i32 {
-        match capname {
-            "cols" | "co" => self.terminal.columns(),
-            "lines" | "li" => self.terminal.lines(),
-            "colors" => self.terminal.colors(),
-            "pairs" => self.terminal.color_pairs(),
-            "it" => self.tabsize, // initial tab size
-            // Unknown capability
-            _ => -1,
+    /// If `to_screen` is true, transforms from window to screen coordinates.
+    /// Returns true if the coordinates are within the window.
+    #[cfg(feature = "mouse")]
+    pub fn wmouse_trafo(&self, win: &Window, y: &mut i32, x: &mut i32, to_screen: bool) -> bool {
+        if to_screen {
+            *y += win.getbegy();
+            *x += win.getbegx();
+            return true;
+        }
+        match win.screen_to_local(*y, *x) {
+            Some(local) => {
+                (*y, *x) = local;
+                true
+            }
+            None => false,
         }
     }
 
-    /// Get a string capability value from terminfo.
-    ///
-    /// Returns:
-    /// - Some(string) if the capability is present
-    /// - None if the capability is absent or not recognized
+    // ========================================================================
+    // Wide character functions
+    // ========================================================================
+
+    /// Set the background character (wide) on stdscr.
+    #[cfg(feature = "wide")]
+    pub fn bkgrnd(&mut self, wch: &crate::wide::CCharT) -> Result<()> {
+        self.stdscr.wbkgrnd(wch)
+    }
+
+    /// Set the background character (wide) on a window.
+    #[cfg(feature = "wide")]
+    pub fn wbkgrnd(&mut self, win: &mut Window, wch: &crate::wide::CCharT) -> Result<()> {
+        win.wbkgrnd(wch)
+    }
+
+    /// Set the background character (wide) on stdscr without changing
+    /// existing content.
+    #[cfg(feature = "wide")]
+    pub fn bkgrndset(&mut self, wch: &crate::wide::CCharT) {
+        self.stdscr.wbkgrndset(wch);
+    }
+
+    /// Set the background character (wide) on a window without changing
+    /// existing content.
+    #[cfg(feature = "wide")]
+    pub fn wbkgrndset(&mut self, win: &mut Window, wch: &crate::wide::CCharT) {
+        win.wbkgrndset(wch);
+    }
+
+    /// Get the background character (wide) of stdscr.
     ///
-    /// Common string capabilities:
-    /// - "clear" - clear screen
-    /// - "cup" - cursor position
-    /// - "cuf1" - cursor forward one
-    /// - "cub1" - cursor back one
-    /// - "cuu1" - cursor up one
-    /// - "cud1" - cursor down one
-    /// - "smcup" - enter cursor-addressing mode
-    /// - "rmcup" - exit cursor-addressing mode
-    /// - "smso" - enter standout mode
-    /// - "rmso" - exit standout mode
-    /// - "setaf" - set ANSI foreground color
-    /// - "setab" - set ANSI background color
-    pub fn tigetstr(&self, capname: &str) -> Option<String> {
-        match capname {
-            // Clear screen
-            "clear" | "cl" => Some("\x1b[H\x1b[J".to_string()),
-            // Cursor position (template: \x1b[%d;%dH)
-            "cup" | "cm" => Some("\x1b[%i%p1%d;%p2%dH".to_string()),
-            // Cursor movements
-            "cuf1" => Some("\x1b[C".to_string()),
-            "cub1" => Some("\x08".to_string()), // backspace
-            "cuu1" => Some("\x1b[A".to_string()),
-            "cud1" => Some("\x1b[B".to_string()),
-            "home" => Some("\x1b[H".to_string()),
-            // Cursor n positions
-            "cuf" => Some("\x1b[%p1%dC".to_string()),
-            "cub" => Some("\x1b[%p1%dD".to_string()),
-            "cuu" => Some("\x1b[%p1%dA".to_string()),
-            "cud" => Some("\x1b[%p1%dB".to_string()),
-            // Enter/exit cursor addressing mode (alternate screen)
-            "smcup" => Some("\x1b[?1049h".to_string()),
-            "rmcup" => Some("\x1b[?1049l".to_string()),
-            // Enter/exit standout mode
-            "smso" => Some("\x1b[7m".to_string()),
-            "rmso" => Some("\x1b[27m".to_string()),
-            // Enter/exit underline mode
-            "smul" => Some("\x1b[4m".to_string()),
-            "rmul" => Some("\x1b[24m".to_string()),
-            // Bold mode
-            "bold" => Some("\x1b[1m".to_string()),
-            // Dim mode
-            "dim" => Some("\x1b[2m".to_string()),
-            // Blink mode
-            "blink" => Some("\x1b[5m".to_string()),
-            // Reverse mode
-            "rev" => Some("\x1b[7m".to_string()),
-            // Reset all attributes
-            "sgr0" => Some("\x1b[0m".to_string()),
-            // Set foreground color (ANSI)
-            "setaf" => Some("\x1b[3%p1%dm".to_string()),
-            // Set background color (ANSI)
-            "setab" => Some("\x1b[4%p1%dm".to_string()),
-            // Original colors
-            "op" => Some("\x1b[39;49m".to_string()),
-            // Invisible cursor
-            "civis" => Some("\x1b[?25l".to_string()),
-            // Normal cursor
-            "cnorm" => Some("\x1b[?25h".to_string()),
-            // Very visible cursor
-            "cvvis" => Some("\x1b[?25h\x1b[?12h".to_string()),
-            // Insert/delete character
-            "ich1" => Some("\x1b[@".to_string()),
-            "dch1" => Some("\x1b[P".to_string()),
-            // Insert/delete line
-            "il1" => Some("\x1b[L".to_string()),
-            "dl1" => Some("\x1b[M".to_string()),
-            // Scroll forward/reverse
-            "ind" => Some("\x1b[S".to_string()),
-            "ri" => Some("\x1b[T".to_string()),
-            // Clear to end of line/screen
-            "el" => Some("\x1b[K".to_string()),
-            "ed" => Some("\x1b[J".to_string()),
-            // Key sequences
-            "kcuu1" => Some("\x1b[A".to_string()),
-            "kcud1" => Some("\x1b[B".to_string()),
-            "kcuf1" => Some("\x1b[C".to_string()),
-            "kcub1" => Some("\x1b[D".to_string()),
-            "khome" => Some("\x1b[H".to_string()),
-            "kend" => Some("\x1b[F".to_string()),
-            "kpp" => Some("\x1b[5~".to_string()),   // page up
-            "knp" => Some("\x1b[6~".to_string()),   // page down
-            "kich1" => Some("\x1b[2~".to_string()), // insert
-            "kdch1" => Some("\x1b[3~".to_string()), // delete
-            "kbs" => Some("\x7f".to_string()),      // backspace
-            // Function keys
-            "kf1" => Some("\x1bOP".to_string()),
-            "kf2" => Some("\x1bOQ".to_string()),
-            "kf3" => Some("\x1bOR".to_string()),
-            "kf4" => Some("\x1bOS".to_string()),
-            "kf5" => Some("\x1b[15~".to_string()),
-            "kf6" => Some("\x1b[17~".to_string()),
-            "kf7" => Some("\x1b[18~".to_string()),
-            "kf8" => Some("\x1b[19~".to_string()),
-            "kf9" => Some("\x1b[20~".to_string()),
-            "kf10" => Some("\x1b[21~".to_string()),
-            "kf11" => Some("\x1b[23~".to_string()),
-            "kf12" => Some("\x1b[24~".to_string()),
-            // Unknown capability
-            _ => None,
+    /// The complete `CCharT` is returned, including combining characters
+    /// and attributes.
+    #[cfg(feature = "wide")]
+    pub fn getbkgrnd(&self, wch: &mut crate::wide::CCharT) -> Result<()> {
+        *wch = self.stdscr.wgetbkgrnd();
+        Ok(())
+    }
+
+    /// Get the background character (wide) of a window.
+    #[cfg(feature = "wide")]
+    pub fn wgetbkgrnd(&self, win: &Window, wch: &mut crate::wide::CCharT) -> Result<()> {
+        *wch = win.wgetbkgrnd();
+        Ok(())
+    }
+
+    /// Get a wide character and attributes at the current position.
+    #[cfg(feature = "wide")]
+    pub fn getcchar(
+        wch: &crate::wide::CCharT,
+        wc: &mut [char],
+        attrs: &mut AttrT,
+        color_pair: &mut i16,
+    ) -> Result<()> {
+        let chars = std::iter::once(wch.spacing_char()).chain(wch.combining());
+        for (slot, c) in wc.iter_mut().zip(chars.chain(std::iter::once('\0'))) {
+            *slot = c;
         }
+        *attrs = wch.attrs();
+        *color_pair = attr::pair_number(*attrs);
+        Ok(())
     }
 
-    /// Output a terminfo string to the terminal.
+    /// Create a complex character from components.
     ///
-    /// This is equivalent to tputs() in ncurses.
-    pub fn putp(&mut self, s: &str) -> Result<()> {
-        self.terminal.write(s.as_bytes())?;
-        self.terminal.flush()
+    /// `wc` holds the spacing character followed by its combining
+    /// characters, optionally terminated by `'\0'`. Returns an error if it
+    /// has more than [`MAX_COMBINING`](crate::types::MAX_COMBINING)
+    /// combining characters; `wch` is left unchanged in that case.
+    #[cfg(feature = "wide")]
+    pub fn setcchar(
+        wch: &mut crate::wide::CCharT,
+        wc: &[char],
+        attrs: AttrT,
+        color_pair: i16,
+    ) -> Result<()> {
+        let c = wc.first().copied().unwrap_or(' ');
+        let mut cell = crate::wide::CCharT::from_char_attr(c, attrs | attr::color_pair(color_pair));
+        for &mark in wc.iter().skip(1).take_while(|&&m| m != '\0') {
+            if !cell.push_combining(mark) {
+                return Err(Error::InvalidArgument(format!(
+                    "at most {} combining characters per cell",
+                    crate::types::MAX_COMBINING
+                )));
+            }
+        }
+        *wch = cell;
+        Ok(())
     }
 
-    /// Parameterized terminal string.
-    ///
-    /// This is a simplified version of tparm() that handles basic parameter
-    /// substitution in terminfo strings.
-    ///
-    /// Supports:
-    /// - %p1 through %p9: parameter values
-    /// - %d: output parameter as decimal
-    /// - %i: increment first two parameters by 1
-    /// - %%: literal %
-    pub fn tparm(&self, s: &str, params: &[i32]) -> String {
+    /// Move and get a wide character from stdscr.
+    #[cfg(feature = "wide")]
+    pub fn mvin_wch(&mut self, y: i32, x: i32, wcval: &mut crate::wide::CCharT) -> Result<()> {
+        self.stdscr.mv(y, x)?;
+        *wcval = self.stdscr.in_wch();
+        Ok(())
+    }
+
+    /// Move and get a wide character from a window.
+    #[cfg(feature = "wide")]
+    pub fn mvwin_wch(
+        &mut self,
+        win: &mut Window,
+        y: i32,
+        x: i32,
+        wcval: &mut crate::wide::CCharT,
+    ) -> Result<()> {
+        win.mv(y, x)?;
+        *wcval = win.in_wch();
+        Ok(())
+    }
+
+    // ========================================================================
+    // Wide string input/output functions
+    // ========================================================================
+
+    /// Get a wide string from stdscr.
+    #[cfg(feature = "wide")]
+    pub fn get_wstr(&mut self, maxlen: i32) -> Result<String> {
+        use crate::wide::WideInput;
+
         let mut result = String::new();
-        let mut chars = s.chars().peekable();
-        let mut params = params.to_vec();
-        // Extend params to at least 9 elements
-        params.resize(9, 0);
+        let max = getstr_limit(maxlen);
+
+        // Get delay and keypad settings from stdscr
+        let delay = Delay::from_raw(self.stdscr.getdelay());
+        let use_keypad = self.stdscr.is_keypad();
+
+        loop {
+            if result.len() >= max {
+                break;
+            }
 
-        while let Some(ch) = chars.next() {
-            if ch == '%' {
-                match chars.next() {
-                    Some('%') => result.push('%'),
-                    Some('i') => {
-                        // Increment first two parameters
-                        if !params.is_empty() {
-                            params[0] += 1;
-                        }
-                        if params.len() > 1 {
-                            params[1] += 1;
-                        }
-                    }
-                    Some('p') => {
-                        // Parameter reference %p1 through %p9
-                        if let Some(digit) = chars.next() {
-                            if let Some(n) = digit.to_digit(10) {
-                                if (1..=9).contains(&n) {
-                                    let idx = (n - 1) as usize;
-                                    // Look for the format specifier
-                                    if chars.peek() == Some(&'%') {
-                                        chars.next();
-                                        if chars.peek() == Some(&'d') {
-                                            chars.next();
-                                            result.push_str(&params[idx].to_string());
-                                        }
-                                    }
-                                }
-                            }
-                        }
+            match self.get_wch_internal(delay, use_keypad)? {
+                WideInput::Char(c) => {
+                    if c == '\n' {
+                        break;
                     }
-                    Some('d') => {
-                        // Direct decimal output (legacy format)
-                        if !params.is_empty() {
-                            result.push_str(&params[0].to_string());
-                        }
+                    if c == '\x7f' || c == '\x08' {
+                        result.pop();
+                        continue;
                     }
-                    Some(c) => {
-                        // Unknown format, preserve it
-                        result.push('%');
-                        result.push(c);
+                    result.push(c);
+                }
+                WideInput::Key(k) => {
+                    if k == crate::key::KEY_ENTER {
+                        break;
                     }
-                    None => result.push('%'),
                 }
-            } else {
-                result.push(ch);
+                WideInput::None | WideInput::Eof | WideInput::Error => break,
             }
         }
 
-        result
+        Ok(result)
     }
 
-    // ========================================================================
-    // Screen dump/restore functions
-    // ========================================================================
+    /// Get a wide string with length limit from stdscr.
+    #[cfg(feature = "wide")]
+    pub fn getn_wstr(&mut self, maxlen: i32) -> Result<String> {
+        self.get_wstr(maxlen)
+    }
 
-    /// Dump the screen contents to a file.
-    ///
-    /// This saves the virtual screen (newscr) contents to a file that can be
-    /// restored later with `scr_restore()`.
-    pub fn scr_dump(&self, filename: &str) -> Result<()> {
-        use std::fs::File;
-        use std::io::Write;
+    /// Move and get a wide string from stdscr.
+    #[cfg(feature = "wide")]
+    pub fn mvget_wstr(&mut self, y: i32, x: i32, maxlen: i32) -> Result<String> {
+        self.stdscr.mv(y, x)?;
+        self.get_wstr(maxlen)
+    }
 
-        let mut file = File::create(filename)?;
+    /// Move and get a wide string with length limit from stdscr.
+    #[cfg(feature = "wide")]
+    pub fn mvgetn_wstr(&mut self, y: i32, x: i32, maxlen: i32) -> Result<String> {
+        self.mvget_wstr(y, x, maxlen)
+    }
 
-        // Write header: magic number, version, dimensions
-        let lines = self.newscr.getmaxy();
-        let cols = self.newscr.getmaxx();
+    /// Get a wide string from a window.
+    #[cfg(feature = "wide")]
+    pub fn wget_wstr(&mut self, win: &mut Window, maxlen: i32) -> Result<String> {
+        use crate::wide::WideInput;
 
-        // Simple binary format: NCDUMP + version(1) + lines(4) + cols(4) + data
-        file.write_all(b"NCDUMP")?;
-        file.write_all(&[1u8]) // version
-            ?;
-        file.write_all(&(lines as u32).to_le_bytes())?;
-        file.write_all(&(cols as u32).to_le_bytes())?;
+        let mut result = String::new();
+        let max = getstr_limit(maxlen);
 
-        // Write screen data
-        for y in 0..lines {
-            if let Some(line) = self.newscr.line(y as usize) {
-                for x in 0..cols {
-                    let ch = line.get(x as usize);
-                    #[cfg(not(feature = "wide"))]
-                    {
-                        file.write_all(&(ch as u32).to_le_bytes())?;
+        loop {
+            if result.len() >= max {
+                break;
+            }
+
+            match self.wget_wch(win)? {
+                WideInput::Char(c) => {
+                    if c == '\n' {
+                        break;
                     }
-                    #[cfg(feature = "wide")]
-                    {
-                        // For wide characters, serialize the primary char and attrs
-                        let c = ch.chars[0] as u32;
-                        let a = ch.attr;
-                        file.write_all(&c.to_le_bytes())?;
-                        file.write_all(&a.to_le_bytes())?;
+                    if c == '\x7f' || c == '\x08' {
+                        result.pop();
+                        continue;
+                    }
+                    result.push(c);
+                }
+                WideInput::Key(k) => {
+                    if k == crate::key::KEY_ENTER {
+                        break;
                     }
                 }
+                WideInput::None | WideInput::Eof | WideInput::Error => break,
             }
         }
 
-        file.flush()?;
-        Ok(())
+        Ok(result)
     }
 
-    /// Restore screen contents from a file.
-    ///
-    /// This restores screen contents saved by `scr_dump()`. The screen should
-    /// be refreshed after calling this to display the restored contents.
-    pub fn scr_restore(&mut self, filename: &str) -> Result<()> {
-        use std::fs::File;
-        use std::io::Read;
+    /// Get a wide string with length limit from a window.
+    #[cfg(feature = "wide")]
+    pub fn wgetn_wstr(&mut self, win: &mut Window, maxlen: i32) -> Result<String> {
+        self.wget_wstr(win, maxlen)
+    }
 
-        let mut file = File::open(filename)?;
+    /// Move and get a wide string from a window.
+    #[cfg(feature = "wide")]
+    pub fn mvwget_wstr(&mut self, win: &mut Window, y: i32, x: i32, maxlen: i32) -> Result<String> {
+        win.mv(y, x)?;
+        self.wget_wstr(win, maxlen)
+    }
 
-        // Read and verify header
-        let mut magic = [0u8; 6];
-        file.read_exact(&mut magic)?;
-        if &magic != b"NCDUMP" {
-            return Err(Error::InvalidArgument(
-                "Invalid screen dump file".to_string(),
-            ));
-        }
+    /// Move and get a wide string with length limit from a window.
+    #[cfg(feature = "wide")]
+    pub fn mvwgetn_wstr(
+        &mut self,
+        win: &mut Window,
+        y: i32,
+        x: i32,
+        maxlen: i32,
+    ) -> Result<String> {
+        self.mvwget_wstr(win, y, x, maxlen)
+    }
 
-        let mut version = [0u8; 1];
-        file.read_exact(&mut version)?;
-        if version[0] != 1 {
-            return Err(Error::InvalidArgument(
-                "Unsupported dump file version".to_string(),
-            ));
-        }
+    /// Get a wide character string from stdscr (unbounded).
+    #[cfg(feature = "wide")]
+    pub fn inwstr(&self) -> String {
+        self.winnwstr(&self.stdscr, -1)
+    }
 
-        let mut lines_bytes = [0u8; 4];
-        let mut cols_bytes = [0u8; 4];
-        file.read_exact(&mut lines_bytes)?;
-        file.read_exact(&mut cols_bytes)?;
+    /// Get a wide character string from stdscr with limit.
+    #[cfg(feature = "wide")]
+    pub fn innwstr(&self, n: i32) -> String {
+        self.winnwstr(&self.stdscr, n)
+    }
 
-        let file_lines = u32::from_le_bytes(lines_bytes) as i32;
-        let file_cols = u32::from_le_bytes(cols_bytes) as i32;
+    /// Move and get a wide character string from stdscr.
+    #[cfg(feature = "wide")]
+    pub fn mvinwstr(&mut self, y: i32, x: i32) -> Result<String> {
+        self.stdscr.mv(y, x)?;
+        Ok(self.stdscr.instr(-1))
+    }
 
-        // Read screen data (limited to current screen size)
-        let screen_lines = self.newscr.getmaxy().min(file_lines);
-        let screen_cols = self.newscr.getmaxx().min(file_cols);
+    /// Move and get a wide character string from stdscr with limit.
+    #[cfg(feature = "wide")]
+    pub fn mvinnwstr(&mut self, y: i32, x: i32, n: i32) -> Result<String> {
+        self.stdscr.mv(y, x)?;
+        Ok(self.stdscr.instr(n))
+    }
 
-        for y in 0..file_lines {
-            for x in 0..file_cols {
-                #[cfg(not(feature = "wide"))]
-                {
-                    let mut ch_bytes = [0u8; 4];
-                    file.read_exact(&mut ch_bytes)?;
-                    let ch = u32::from_le_bytes(ch_bytes) as ChType;
+    /// Get a wide character string from a window (unbounded).
+    #[cfg(feature = "wide")]
+    pub fn winwstr(&self, win: &Window) -> String {
+        self.winnwstr(win, -1)
+    }
 
-                    if y < screen_lines && x < screen_cols {
-                        if let Some(line) = self.newscr.line_mut(y as usize) {
-                            line.set(x as usize, ch);
-                        }
-                    }
-                }
-                #[cfg(feature = "wide")]
-                {
-                    let mut c_bytes = [0u8; 4];
-                    let mut a_bytes = [0u8; 4];
-                    file.read_exact(&mut c_bytes)?;
-                    file.read_exact(&mut a_bytes)?;
-                    let c = u32::from_le_bytes(c_bytes);
-                    let a = u32::from_le_bytes(a_bytes);
+    /// Get a wide character string from a window with limit.
+    #[cfg(feature = "wide")]
+    pub fn winnwstr(&self, win: &Window, n: i32) -> String {
+        win.instr(n)
+    }
+
+    /// Move and get a wide character string from a window.
+    #[cfg(feature = "wide")]
+    pub fn mvwinwstr(&mut self, win: &mut Window, y: i32, x: i32) -> Result<String> {
+        self.mvwinnwstr(win, y, x, -1)
+    }
+
+    /// Move and get a wide character string from a window with limit.
+    #[cfg(feature = "wide")]
+    pub fn mvwinnwstr(&mut self, win: &mut Window, y: i32, x: i32, n: i32) -> Result<String> {
+        win.mv(y, x)?;
+        Ok(win.instr(n))
+    }
+
+    /// Insert a wide string on stdscr.
+    #[cfg(feature = "wide")]
+    pub fn ins_wstr(&mut self, wstr: &str) -> Result<()> {
+        self.ins_nwstr(wstr, -1)
+    }
 
-                    if y < screen_lines && x < screen_cols {
-                        if let Some(line) = self.newscr.line_mut(y as usize) {
-                            let mut cchar = crate::wide::CCharT::default();
-                            cchar.chars[0] = char::from_u32(c).unwrap_or(' ');
-                            cchar.attr = a;
-                            line.set(x as usize, cchar);
-                        }
-                    }
-                }
-            }
+    /// Insert at most n characters of a wide string on stdscr.
+    #[cfg(feature = "wide")]
+    pub fn ins_nwstr(&mut self, wstr: &str, n: i32) -> Result<()> {
+        let chars: Vec<char> = wstr.chars().collect();
+        let limit = if n < 0 {
+            chars.len()
+        } else {
+            (n as usize).min(chars.len())
+        };
+        for c in chars.into_iter().take(limit).rev() {
+            let wch = crate::wide::CCharT::from_char(c);
+            self.stdscr.ins_wch(&wch)?;
         }
-
-        // Mark screen as needing refresh
-        self.newscr.touchwin();
         Ok(())
     }
 
-    /// Initialize screen from a dump file.
-    ///
-    /// This is similar to `scr_restore()` but is intended to be called before
-    /// the first refresh to pre-populate the screen.
-    pub fn scr_init(&mut self, filename: &str) -> Result<()> {
-        self.scr_restore(filename)
+    /// Move and insert a wide string on stdscr.
+    #[cfg(feature = "wide")]
+    pub fn mvins_wstr(&mut self, y: i32, x: i32, wstr: &str) -> Result<()> {
+        self.stdscr.mv(y, x)?;
+        self.ins_wstr(wstr)
     }
 
-    /// Set the screen contents from a dump file.
-    ///
-    /// This combines the functionality of `scr_init()` and `scr_restore()`.
-    pub fn scr_set(&mut self, filename: &str) -> Result<()> {
-        self.scr_restore(filename)?;
-        // Also update curscr to match
-        let lines = self.newscr.getmaxy();
-        let cols = self.newscr.getmaxx();
-        for y in 0..lines {
-            if let (Some(src_line), Some(dst_line)) = (
-                self.newscr.line(y as usize),
-                self.curscr.line_mut(y as usize),
-            ) {
-                for x in 0..cols {
-                    dst_line.set(x as usize, src_line.get(x as usize));
-                }
-            }
+    /// Move and insert at most n characters of a wide string on stdscr.
+    #[cfg(feature = "wide")]
+    pub fn mvins_nwstr(&mut self, y: i32, x: i32, wstr: &str, n: i32) -> Result<()> {
+        self.stdscr.mv(y, x)?;
+        self.ins_nwstr(wstr, n)
+    }
+
+    /// Insert a wide string on a window.
+    #[cfg(feature = "wide")]
+    pub fn wins_wstr(&mut self, win: &mut Window, wstr: &str) -> Result<()> {
+        self.wins_nwstr(win, wstr, -1)
+    }
+
+    /// Insert at most n characters of a wide string on a window.
+    #[cfg(feature = "wide")]
+    pub fn wins_nwstr(&mut self, win: &mut Window, wstr: &str, n: i32) -> Result<()> {
+        let chars: Vec<char> = wstr.chars().collect();
+        let limit = if n < 0 {
+            chars.len()
+        } else {
+            (n as usize).min(chars.len())
+        };
+        for c in chars.into_iter().take(limit).rev() {
+            let wch = crate::wide::CCharT::from_char(c);
+            win.ins_wch(&wch)?;
         }
         Ok(())
     }
 
-    /// Save a window to a file.
-    ///
-    /// This saves the window contents in a format that can be restored
-    /// with `getwin()`.
-    pub fn putwin(&self, win: &Window, filename: &str) -> Result<()> {
-        use std::fs::File;
-        use std::io::Write;
+    /// Move and insert a wide string on a window.
+    #[cfg(feature = "wide")]
+    pub fn mvwins_wstr(&mut self, win: &mut Window, y: i32, x: i32, wstr: &str) -> Result<()> {
+        win.mv(y, x)?;
+        self.wins_wstr(win, wstr)
+    }
 
-        let mut file = File::create(filename)?;
+    /// Move and insert at most n characters of a wide string on a window.
+    #[cfg(feature = "wide")]
+    pub fn mvwins_nwstr(
+        &mut self,
+        win: &mut Window,
+        y: i32,
+        x: i32,
+        wstr: &str,
+        n: i32,
+    ) -> Result<()> {
+        win.mv(y, x)?;
+        self.wins_nwstr(win, wstr, n)
+    }
 
-        // Write header
-        let lines = win.getmaxy();
-        let cols = win.getmaxx();
-        let begy = win.getbegy();
-        let begx = win.getbegx();
+    // ========================================================================
+    // Wide character array functions (in_wch* family)
+    // ========================================================================
 
-        // Format: NCWIN + version(1) + lines(4) + cols(4) + begy(4) + begx(4) + data
-        file.write_all(b"NCWIN")?;
-        file.write_all(&[1u8])?;
-        file.write_all(&(lines as u32).to_le_bytes())?;
-        file.write_all(&(cols as u32).to_le_bytes())?;
-        file.write_all(&begy.to_le_bytes())?;
-        file.write_all(&begx.to_le_bytes())?;
+    /// Get a wide character array from stdscr (unbounded).
+    #[cfg(feature = "wide")]
+    pub fn in_wchstr(&self, wchstr: &mut [crate::wide::CCharT]) -> i32 {
+        self.win_wchnstr(&self.stdscr, wchstr, -1)
+    }
 
-        // Write window data
-        for y in 0..lines {
-            if let Some(line) = win.line(y as usize) {
-                for x in 0..cols {
-                    let ch = line.get(x as usize);
-                    #[cfg(not(feature = "wide"))]
-                    {
-                        file.write_all(&(ch as u32).to_le_bytes())?;
-                    }
-                    #[cfg(feature = "wide")]
-                    {
-                        let c = ch.chars[0] as u32;
-                        let a = ch.attr;
-                        file.write_all(&c.to_le_bytes())?;
-                        file.write_all(&a.to_le_bytes())?;
-                    }
-                }
-            }
-        }
+    /// Get a wide character array from stdscr with limit.
+    #[cfg(feature = "wide")]
+    pub fn in_wchnstr(&self, wchstr: &mut [crate::wide::CCharT], n: i32) -> i32 {
+        self.win_wchnstr(&self.stdscr, wchstr, n)
+    }
 
-        file.flush()?;
-        Ok(())
+    /// Move and get a wide character array from stdscr (unbounded).
+    #[cfg(feature = "wide")]
+    pub fn mvin_wchstr(
+        &mut self,
+        y: i32,
+        x: i32,
+        wchstr: &mut [crate::wide::CCharT],
+    ) -> Result<i32> {
+        self.stdscr.mv(y, x)?;
+        Ok(self.stdscr.in_wchnstr(wchstr, -1))
     }
 
-    /// Restore a window from a file.
+    /// Move and get a wide character array from stdscr with limit.
+    #[cfg(feature = "wide")]
+    pub fn mvin_wchnstr(
+        &mut self,
+        y: i32,
+        x: i32,
+        wchstr: &mut [crate::wide::CCharT],
+        n: i32,
+    ) -> Result<i32> {
+        self.stdscr.mv(y, x)?;
+        Ok(self.stdscr.in_wchnstr(wchstr, n))
+    }
+
+    /// Get a wide character array from a window (unbounded).
+    #[cfg(feature = "wide")]
+    pub fn win_wchstr(&self, win: &Window, wchstr: &mut [crate::wide::CCharT]) -> i32 {
+        self.win_wchnstr(win, wchstr, -1)
+    }
+
+    /// Get a wide character array from a window with limit.
+    #[cfg(feature = "wide")]
+    pub fn win_wchnstr(&self, win: &Window, wchstr: &mut [crate::wide::CCharT], n: i32) -> i32 {
+        win.in_wchnstr(wchstr, n)
+    }
+
+    /// Move and get a wide character array from a window (unbounded).
+    #[cfg(feature = "wide")]
+    pub fn mvwin_wchstr(
+        &mut self,
+        win: &mut Window,
+        y: i32,
+        x: i32,
+        wchstr: &mut [crate::wide::CCharT],
+    ) -> Result<i32> {
+        self.mvwin_wchnstr(win, y, x, wchstr, -1)
+    }
+
+    /// Move and get a wide character array from a window with limit.
+    #[cfg(feature = "wide")]
+    pub fn mvwin_wchnstr(
+        &mut self,
+        win: &mut Window,
+        y: i32,
+        x: i32,
+        wchstr: &mut [crate::wide::CCharT],
+        n: i32,
+    ) -> Result<i32> {
+        win.mv(y, x)?;
+        Ok(win.in_wchnstr(wchstr, n))
+    }
+
+    // ========================================================================
+    // Wide border/line functions with complex characters
+    // ========================================================================
+
+    /// Draw a border around stdscr with complex characters.
     ///
-    /// This creates a new window with the contents saved by `putwin()`.
-    pub fn getwin(&self, filename: &str) -> Result<Window> {
-        use std::fs::File;
-        use std::io::Read;
+    /// Arguments are the left, right, top and bottom sides, then the
+    /// top-left, top-right, bottom-left and bottom-right corners. `None`
+    /// selects the default line-drawing character.
+    #[cfg(feature = "wide")]
+    #[allow(clippy::too_many_arguments)]
+    pub fn border_set(
+        &mut self,
+        ls: Option<&crate::wide::CCharT>,
+        rs: Option<&crate::wide::CCharT>,
+        ts: Option<&crate::wide::CCharT>,
+        bs: Option<&crate::wide::CCharT>,
+        tl: Option<&crate::wide::CCharT>,
+        tr: Option<&crate::wide::CCharT>,
+        bl: Option<&crate::wide::CCharT>,
+        br: Option<&crate::wide::CCharT>,
+    ) -> Result<()> {
+        self.stdscr.wborder_set(ls, rs, ts, bs, tl, tr, bl, br)
+    }
 
-        let mut file = File::open(filename)?;
+    /// Draw a border around a window with complex characters.
+    #[cfg(feature = "wide")]
+    #[allow(clippy::too_many_arguments)]
+    pub fn wborder_set(
+        &mut self,
+        win: &mut Window,
+        ls: Option<&crate::wide::CCharT>,
+        rs: Option<&crate::wide::CCharT>,
+        ts: Option<&crate::wide::CCharT>,
+        bs: Option<&crate::wide::CCharT>,
+        tl: Option<&crate::wide::CCharT>,
+        tr: Option<&crate::wide::CCharT>,
+        bl: Option<&crate::wide::CCharT>,
+        br: Option<&crate::wide::CCharT>,
+    ) -> Result<()> {
+        win.wborder_set(ls, rs, ts, bs, tl, tr, bl, br)
+    }
 
-        // Read and verify header
-        let mut magic = [0u8; 5];
-        file.read_exact(&mut magic)?;
-        if &magic != b"NCWIN" {
-            return Err(Error::InvalidArgument(
-                "Invalid window dump file".to_string(),
-            ));
-        }
+    /// Draw a box around stdscr with complex characters.
+    #[cfg(feature = "wide")]
+    pub fn box_set(
+        &mut self,
+        verch: Option<&crate::wide::CCharT>,
+        horch: Option<&crate::wide::CCharT>,
+    ) -> Result<()> {
+        self.stdscr.box_set(verch, horch)
+    }
 
-        let mut version = [0u8; 1];
-        file.read_exact(&mut version)?;
-        if version[0] != 1 {
-            return Err(Error::InvalidArgument(
-                "Unsupported dump file version".to_string(),
-            ));
-        }
+    /// Draw a box around a window with complex characters.
+    #[cfg(feature = "wide")]
+    pub fn wbox_set(
+        &mut self,
+        win: &mut Window,
+        verch: Option<&crate::wide::CCharT>,
+        horch: Option<&crate::wide::CCharT>,
+    ) -> Result<()> {
+        win.box_set(verch, horch)
+    }
 
-        let mut lines_bytes = [0u8; 4];
-        let mut cols_bytes = [0u8; 4];
-        let mut begy_bytes = [0u8; 4];
-        let mut begx_bytes = [0u8; 4];
-        file.read_exact(&mut lines_bytes)?;
-        file.read_exact(&mut cols_bytes)?;
-        file.read_exact(&mut begy_bytes)?;
-        file.read_exact(&mut begx_bytes)?;
-
-        let lines = u32::from_le_bytes(lines_bytes) as i32;
-        let cols = u32::from_le_bytes(cols_bytes) as i32;
-        let begy = i32::from_le_bytes(begy_bytes);
-        let begx = i32::from_le_bytes(begx_bytes);
+    /// Draw a horizontal line with a complex character on stdscr.
+    #[cfg(feature = "wide")]
+    pub fn hline_set(&mut self, wch: &crate::wide::CCharT, n: i32) -> Result<()> {
+        self.stdscr.hline_set(wch, n)
+    }
 
-        // Create the window
-        let mut win = Window::new(lines, cols, begy, begx)?;
+    /// Move and draw a horizontal line with a complex character on stdscr.
+    #[cfg(feature = "wide")]
+    pub fn mvhline_set(&mut self, y: i32, x: i32, wch: &crate::wide::CCharT, n: i32) -> Result<()> {
+        self.stdscr.mv(y, x)?;
+        self.hline_set(wch, n)
+    }
 
-        // Read window data
-        for y in 0..lines {
-            for x in 0..cols {
-                #[cfg(not(feature = "wide"))]
-                {
-                    let mut ch_bytes = [0u8; 4];
-                    file.read_exact(&mut ch_bytes)?;
-                    let ch = u32::from_le_bytes(ch_bytes) as ChType;
+    /// Draw a horizontal line with a complex character on a window.
+    #[cfg(feature = "wide")]
+    pub fn whline_set(
+        &mut self,
+        win: &mut Window,
+        wch: &crate::wide::CCharT,
+        n: i32,
+    ) -> Result<()> {
+        win.hline_set(wch, n)
+    }
 
-                    if let Some(line) = win.line_mut(y as usize) {
-                        line.set(x as usize, ch);
-                    }
-                }
-                #[cfg(feature = "wide")]
-                {
-                    let mut c_bytes = [0u8; 4];
-                    let mut a_bytes = [0u8; 4];
-                    file.read_exact(&mut c_bytes)?;
-                    file.read_exact(&mut a_bytes)?;
-                    let c = u32::from_le_bytes(c_bytes);
-                    let a = u32::from_le_bytes(a_bytes);
+    /// Move and draw a horizontal line with a complex character on a window.
+    #[cfg(feature = "wide")]
+    pub fn mvwhline_set(
+        &mut self,
+        win: &mut Window,
+        y: i32,
+        x: i32,
+        wch: &crate::wide::CCharT,
+        n: i32,
+    ) -> Result<()> {
+        win.mv(y, x)?;
+        win.hline_set(wch, n)
+    }
 
-                    if let Some(line) = win.line_mut(y as usize) {
-                        let mut cchar = crate::wide::CCharT::default();
-                        cchar.chars[0] = char::from_u32(c).unwrap_or(' ');
-                        cchar.attr = a;
-                        line.set(x as usize, cchar);
-                    }
-                }
-            }
-        }
+    /// Draw a vertical line with a complex character on stdscr.
+    #[cfg(feature = "wide")]
+    pub fn vline_set(&mut self, wch: &crate::wide::CCharT, n: i32) -> Result<()> {
+        self.stdscr.vline_set(wch, n)
+    }
 
-        Ok(win)
+    /// Move and draw a vertical line with a complex character on stdscr.
+    #[cfg(feature = "wide")]
+    pub fn mvvline_set(&mut self, y: i32, x: i32, wch: &crate::wide::CCharT, n: i32) -> Result<()> {
+        self.stdscr.mv(y, x)?;
+        self.vline_set(wch, n)
     }
 
-    // ========================================================================
-    // Deprecated/Unavailable C-style functions
-    // ========================================================================
-    // These functions exist in C ncurses but don't translate well to Rust.
-    // The printw family is implemented above with working alternatives.
-    // The scanw family below provides compile-time errors with guidance.
+    /// Draw a vertical line with a complex character on a window.
+    #[cfg(feature = "wide")]
+    pub fn wvline_set(
+        &mut self,
+        win: &mut Window,
+        wch: &crate::wide::CCharT,
+        n: i32,
+    ) -> Result<()> {
+        win.vline_set(wch, n)
+    }
 
-    /// **Not available in Rust** - Use `getstr()` and parse manually instead.
-    ///
-    /// In C ncurses, `scanw` reads formatted input:
-    /// ```c
-    /// int value;
-    /// scanw("%d", &value);
-    /// ```
-    ///
-    /// In Rust, use `getstr` and parse:
-    /// ```rust,ignore
-    /// let input = screen.getstr(100)?;
-    /// let value: i32 = input.trim().parse().unwrap_or(0);
-    /// ```
-    ///
-    /// # Panics
-    /// Always panics - this function is not implemented.
-    #[deprecated(since = "0.1.0", note = "Use getstr() and parse the result instead")]
-    pub fn scanw(&mut self, _fmt: &str) -> Result<()> {
-        panic!(
-            "scanw is not available in Rust. Use getstr() and parse the result instead.\n\
-             Example:\n\
-             let input = screen.getstr(100)?;\n\
-             let value: i32 = input.trim().parse().unwrap_or(0);"
-        );
+    /// Move and draw a vertical line with a complex character on a window.
+    #[cfg(feature = "wide")]
+    pub fn mvwvline_set(
+        &mut self,
+        win: &mut Window,
+        y: i32,
+        x: i32,
+        wch: &crate::wide::CCharT,
+        n: i32,
+    ) -> Result<()> {
+        win.mv(y, x)?;
+        win.vline_set(wch, n)
     }
+}
 
-    /// **Not available in Rust** - Use `wgetstr()` and parse manually instead.
-    ///
-    /// In C ncurses, `wscanw` reads formatted input from a window:
-    /// ```c
-    /// int value;
-    /// wscanw(win, "%d", &value);
-    /// ```
-    ///
-    /// In Rust, use `wgetstr` and parse:
-    /// ```rust,ignore
-    /// let input = screen.wgetstr(win, 100)?;
-    /// let value: i32 = input.trim().parse().unwrap_or(0);
-    /// ```
-    ///
-    /// # Panics
-    /// Always panics - this function is not implemented.
-    #[deprecated(since = "0.1.0", note = "Use wgetstr() and parse the result instead")]
-    pub fn wscanw(&mut self, _win: &mut Window, _fmt: &str) -> Result<()> {
-        panic!(
-            "wscanw is not available in Rust. Use wgetstr() and parse the result instead.\n\
-             Example:\n\
-             let input = screen.wgetstr(win, 100)?;\n\
-             let value: i32 = input.trim().parse().unwrap_or(0);"
-        );
+impl Drop for Screen {
+    fn drop(&mut self) {
+        // Try to restore terminal state
+        let _ = self.endwin();
     }
+}
 
-    /// **Not available in Rust** - Use `mvgetstr()` and parse manually instead.
-    ///
-    /// # Panics
-    /// Always panics - this function is not implemented.
-    #[deprecated(
-        since = "0.1.0",
-        note = "Use mv() + getstr() and parse the result instead"
-    )]
-    pub fn mvscanw(&mut self, _y: i32, _x: i32, _fmt: &str) -> Result<()> {
-        panic!(
-            "mvscanw is not available in Rust. Use mv() + getstr() and parse instead.\n\
-             Example:\n\
-             screen.mv(y, x)?;\n\
-             let input = screen.getstr(100)?;\n\
-             let value: i32 = input.trim().parse().unwrap_or(0);"
-        );
+// ============================================================================
+// Global screen dimensions (thread-local storage)
+// ============================================================================
+
+use std::cell::Cell;
+use std::cell::RefCell;
+
+thread_local! {
+    /// Thread-local storage for screen lines (rows).
+    static SCREEN_LINES: Cell<i32> = const { Cell::new(24) };
+    /// Thread-local storage for screen columns.
+    static SCREEN_COLS: Cell<i32> = const { Cell::new(80) };
+    /// Whether to use environment variables (LINES, COLUMNS) for screen size.
+    static USE_ENV: Cell<bool> = const { Cell::new(true) };
+    /// Whether to use ioctl for screen size detection.
+    static USE_TIOCTL: Cell<bool> = const { Cell::new(true) };
+    /// Whether `init()` asks the terminal for its capabilities.
+    static QUERY_CAPABILITIES_AT_INIT: Cell<bool> = const { Cell::new(false) };
+    /// Ripoff line specifications (called before initscr).
+    static RIPOFF_LINES: RefCell<Vec<RipoffSpec>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Current version of the `putwin()` file format.
+///
+/// Version 1 holds the size, origin and cells; version 2 adds the window
+/// state in front of the cells.
+const WINDOW_DUMP_VERSION: u8 = 2;
+
+/// Getter and setter of a boolean window option.
+type WindowOption = (fn(&Window) -> bool, fn(&mut Window, bool));
+
+/// Boolean window options in the order of their bits in a window dump.
+const WINDOW_OPTIONS: [WindowOption; 10] = [
+    (Window::is_scrollok, Window::scrollok),
+    (Window::is_keypad, Window::keypad),
+    (Window::is_leaveok, Window::leaveok),
+    (Window::is_cleared, Window::clearok),
+    (Window::is_idlok, Window::idlok),
+    (Window::is_idcok, Window::idcok),
+    (Window::is_immedok, Window::immedok),
+    (Window::is_syncok, Window::syncok),
+    (Window::is_notimeout, Window::notimeout),
+    (Window::is_wrap, Window::set_wrap),
+];
+
+/// Pack the boolean options of `win` for a window dump.
+fn window_options(win: &Window) -> u32 {
+    WINDOW_OPTIONS
+        .iter()
+        .enumerate()
+        .filter(|(_, (get, _))| get(win))
+        .fold(0, |bits, (i, _)| bits | 1 << i)
+}
+
+/// Apply options packed by [`window_options`].
+fn set_window_options(win: &mut Window, bits: u32) {
+    for (i, (_, set)) in WINDOW_OPTIONS.iter().enumerate() {
+        set(win, bits & 1 << i != 0);
     }
+}
 
-    /// **Not available in Rust** - Use `wmove()` + `wgetstr()` and parse manually instead.
-    ///
-    /// # Panics
-    /// Always panics - this function is not implemented.
-    #[deprecated(
-        since = "0.1.0",
-        note = "Use wmove() + wgetstr() and parse the result instead"
-    )]
-    pub fn mvwscanw(&mut self, _win: &mut Window, _y: i32, _x: i32, _fmt: &str) -> Result<()> {
-        panic!(
-            "mvwscanw is not available in Rust. Use wmove() + wgetstr() and parse instead.\n\
-             Example:\n\
-             screen.wmove(win, y, x)?;\n\
-             let input = screen.wgetstr(win, 100)?;\n\
-             let value: i32 = input.trim().parse().unwrap_or(0);"
-        );
+/// Character and attributes to display for cell `x` of `line`.
+///
+/// Returns `None` for the right half of a double-width character, which is
+/// drawn with its left half. Line-drawing characters are translated to
+/// Unicode and unprintable cells shown as spaces.
+fn display_cell(line: &LineData, x: usize) -> Option<(char, AttrT)> {
+    let cell = line.get(x);
+    #[cfg(feature = "wide")]
+    {
+        if cell.is_null() && x > 0 && line.get(x - 1).is_wide() {
+            return None;
+        }
+        let c = cell.spacing_char();
+        Some((if c == '\0' { ' ' } else { c }, cell.attrs()))
+    }
+    #[cfg(not(feature = "wide"))]
+    {
+        let c = (cell & A_CHARTEXT) as u8;
+        let ch = if cell & attr::A_ALTCHARSET != 0 {
+            acs::vt100_unicode(c).unwrap_or(c as char)
+        } else if (0x20..0x7f).contains(&c) {
+            c as char
+        } else {
+            ' '
+        };
+        Some((ch, cell & !A_CHARTEXT))
     }
+}
 
-    // ========================================================================
-    // Alias functions for ncurses API compatibility
-    // ========================================================================
+/// Count the top and bottom lines in `ripoffs`.
+fn ripoff_counts(ripoffs: &[RipoffSpec]) -> (i32, i32) {
+    let top = ripoffs.iter().filter(|r| r.line > 0).count() as i32;
+    (top, ripoffs.len() as i32 - top)
+}
+
+/// Specification for a ripped-off line.
+#[derive(Clone)]
+struct RipoffSpec {
+    /// Positive for top, negative for bottom.
+    line: i32,
+    /// Initialization callback, run with the window and its width.
+    callback: Option<fn(&mut Window, i32) -> i32>,
+}
+
+/// Global-style functions for ncurses compatibility.
+///
+/// These provide access to screen dimensions without needing a Screen reference.
+/// Note that in Rust, using the Screen instance methods (`screen.lines()` and
+/// `screen.cols()`) is preferred over these globals.
+///
+/// # Thread Safety
+///
+/// These values are stored in thread-local storage, so each thread has its own
+/// copy. If you're using multiple screens in different threads, each thread
+/// will have the dimensions of the last Screen initialized in that thread.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use ncurses::screen::*;
+///
+/// let screen = Screen::init()?;
+/// // After init, LINES() and COLS() return the screen dimensions
+/// let lines = LINES();
+/// let cols = COLS();
+/// ```
+pub mod globals {
+    use super::*;
 
-    /// Move the cursor on stdscr.
+    /// Get the number of screen lines (rows).
     ///
-    /// This is equivalent to `wmove(stdscr, y, x)`.
-    pub fn r#move(&mut self, y: i32, x: i32) -> Result<()> {
-        self.stdscr.mv(y, x)
-    }
-
-    /// Turn off standout mode on stdscr.
-    pub fn standend(&mut self) -> Result<()> {
-        self.stdscr.standend()
-    }
-
-    /// Turn on standout mode on stdscr.
-    pub fn standout(&mut self) -> Result<()> {
-        self.stdscr.standout()
-    }
-
-    /// Turn off standout mode on a window.
-    pub fn wstandend(&mut self, win: &mut Window) -> Result<()> {
-        win.standend()
+    /// This returns the value set by the last Screen initialization in this thread.
+    /// Prefer using `screen.lines()` when you have a Screen reference.
+    #[allow(non_snake_case)]
+    pub fn LINES() -> i32 {
+        SCREEN_LINES.with(|l| l.get())
     }
 
-    /// Turn on standout mode on a window.
-    pub fn wstandout(&mut self, win: &mut Window) -> Result<()> {
-        win.standout()
+    /// Get the number of screen columns.
+    ///
+    /// This returns the value set by the last Screen initialization in this thread.
+    /// Prefer using `screen.cols()` when you have a Screen reference.
+    #[allow(non_snake_case)]
+    pub fn COLS() -> i32 {
+        SCREEN_COLS.with(|c| c.get())
     }
 
-    // ========================================================================
-    // Attribute get/set functions (attr_* family)
-    // ========================================================================
-
-    /// Get the current attributes and color pair from stdscr.
-    pub fn attr_get(&self, attrs: &mut AttrT, pair: &mut i16) -> Result<()> {
-        *attrs = self.stdscr.getattrs();
-        *pair = attr::pair_number(*attrs);
-        Ok(())
+    /// Set the screen dimensions (called internally by Screen).
+    #[doc(hidden)]
+    pub fn set_dimensions(lines: i32, cols: i32) {
+        SCREEN_LINES.with(|l| l.set(lines));
+        SCREEN_COLS.with(|c| c.set(cols));
     }
 
-    /// Turn off attributes on stdscr.
-    pub fn attr_off(&mut self, attrs: AttrT) -> Result<()> {
-        self.stdscr.attroff(attrs)
+    /// Control whether environment variables are used for screen size.
+    ///
+    /// This function must be called **before** `Screen::init()` to have an effect.
+    ///
+    /// When `f` is `true` (the default), the LINES and COLUMNS environment
+    /// variables are used to determine the screen size. When `false`, only
+    /// the terminfo/termcap values and ioctl are used.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use ncurses::screen::globals::use_env;
+    ///
+    /// // Don't use LINES/COLUMNS environment variables
+    /// use_env(false);
+    ///
+    /// let screen = Screen::init()?;
+    /// ```
+    pub fn use_env(f: bool) {
+        USE_ENV.with(|v| v.set(f));
     }
 
-    /// Turn on attributes on stdscr.
-    pub fn attr_on(&mut self, attrs: AttrT) -> Result<()> {
-        self.stdscr.attron(attrs)
+    /// Get the current use_env setting.
+    pub fn get_use_env() -> bool {
+        USE_ENV.with(|v| v.get())
     }
 
-    /// Set attributes on stdscr.
-    pub fn attr_set(&mut self, attrs: AttrT, pair: i16) -> Result<()> {
-        let combined = attrs | attr::color_pair(pair);
-        self.stdscr.attrset(combined)
+    /// Control whether ioctl is used for screen size detection.
+    ///
+    /// This function must be called **before** `Screen::init()` to have an effect.
+    ///
+    /// When `f` is `true` (the default), the terminal ioctl (TIOCGWINSZ) is
+    /// used to get the actual terminal size. When `false`, only the terminfo
+    /// values and environment variables (if enabled) are used.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use ncurses::screen::globals::use_tioctl;
+    ///
+    /// // Don't use ioctl for size detection
+    /// use_tioctl(false);
+    ///
+    /// let screen = Screen::init()?;
+    /// ```
+    pub fn use_tioctl(f: bool) {
+        USE_TIOCTL.with(|v| v.set(f));
     }
 
-    /// Get the current attributes and color pair from a window.
-    pub fn wattr_get(&self, win: &Window, attrs: &mut AttrT, pair: &mut i16) -> Result<()> {
-        *attrs = win.getattrs();
-        *pair = attr::pair_number(*attrs);
-        Ok(())
+    /// Get the current use_tioctl setting.
+    pub fn get_use_tioctl() -> bool {
+        USE_TIOCTL.with(|v| v.get())
     }
 
-    /// Turn off attributes on a window.
-    pub fn wattr_off(&mut self, win: &mut Window, attrs: AttrT) -> Result<()> {
-        win.attroff(attrs)
+    /// Control whether `Screen::init()` asks the terminal for its
+    /// capabilities.
+    ///
+    /// This function must be called **before** `Screen::init()` to have an effect.
+    ///
+    /// When `f` is `true`, `init()` sends the primary device attributes
+    /// request and waits up to 200 ms for the reply, which is recorded as by
+    /// [`Screen::detect_capabilities()`]. This is off by default so that
+    /// starting up never waits on a terminal that stays silent.
+    pub fn query_capabilities_at_init(f: bool) {
+        QUERY_CAPABILITIES_AT_INIT.with(|v| v.set(f));
     }
 
-    /// Turn on attributes on a window.
-    pub fn wattr_on(&mut self, win: &mut Window, attrs: AttrT) -> Result<()> {
-        win.attron(attrs)
+    /// Get the current query_capabilities_at_init setting.
+    pub fn get_query_capabilities_at_init() -> bool {
+        QUERY_CAPABILITIES_AT_INIT.with(|v| v.get())
     }
 
-    /// Set attributes on a window.
-    pub fn wattr_set(&mut self, win: &mut Window, attrs: AttrT, pair: i16) -> Result<()> {
-        let combined = attrs | attr::color_pair(pair);
-        win.attrset(combined)
-    }
+    /// Rip off a line from the top or bottom of the screen.
+    ///
+    /// This function must be called **before** `Screen::init()` to have an effect.
+    /// It reduces the size of stdscr by one line and creates a separate window
+    /// for that line.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - If positive, rip off a line from the top of the screen.
+    ///   If negative, rip off a line from the bottom.
+    /// * `init` - An optional initialization callback that will be called with
+    ///   the window and its width, at init and again after every
+    ///   `resize_term()`. The windows are also available afterwards from
+    ///   [`Screen::ripoff_window`].
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(())` if the ripoff was registered, `Err` if the maximum
+    /// number of ripoffs (5) has been reached.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use ncurses::screen::globals::ripoffline;
+    ///
+    /// // Reserve a line at the top for a header
+    /// ripoffline(1, None)?;
+    ///
+    /// // Reserve a line at the bottom for a status bar
+    /// ripoffline(-1, None)?;
+    ///
+    /// let screen = Screen::init()?;
+    /// ```
+    pub fn ripoffline(line: i32, init: Option<fn(&mut Window, i32) -> i32>) -> Result<()> {
+        const MAX_RIPS: usize = 5;
 
-    // ========================================================================
-    // Move + operation wrappers (mv* family)
-    // ========================================================================
+        if line == 0 {
+            return Ok(());
+        }
 
-    /// Move cursor and delete character on stdscr.
-    pub fn mvdelch(&mut self, y: i32, x: i32) -> Result<()> {
-        self.stdscr.mv(y, x)?;
-        self.stdscr.delch()
+        RIPOFF_LINES.with(|rips| {
+            let mut rips = rips.borrow_mut();
+            if rips.len() >= MAX_RIPS {
+                return Err(Error::InvalidArgument(
+                    "maximum ripoff lines reached".to_string(),
+                ));
+            }
+            rips.push(RipoffSpec {
+                line: if line > 0 { 1 } else { -1 },
+                callback: init,
+            });
+            Ok(())
+        })
     }
 
-    /// Move cursor and draw horizontal line on stdscr.
-    pub fn mvhline(&mut self, y: i32, x: i32, ch: ChType, n: i32) -> Result<()> {
-        self.stdscr.mv(y, x)?;
-        self.stdscr.hline(ch, n)
+    /// Get the number of ripped-off lines.
+    #[doc(hidden)]
+    pub fn get_ripoff_count() -> (i32, i32) {
+        RIPOFF_LINES.with(|rips| ripoff_counts(&rips.borrow()))
     }
 
-    /// Move cursor and draw vertical line on stdscr.
-    pub fn mvvline(&mut self, y: i32, x: i32, ch: ChType, n: i32) -> Result<()> {
-        self.stdscr.mv(y, x)?;
-        self.stdscr.vline(ch, n)
+    /// Clear ripoff specifications (called after Screen::init).
+    #[doc(hidden)]
+    pub fn clear_ripoffs() {
+        RIPOFF_LINES.with(|rips| {
+            rips.borrow_mut().clear();
+        });
     }
+}
 
-    /// Move cursor and insert character on stdscr.
-    pub fn mvinsch(&mut self, y: i32, x: i32, ch: ChType) -> Result<()> {
-        self.stdscr.mv(y, x)?;
-        self.stdscr.insch(ch)
-    }
+// ============================================================================
+// Terminfo low-level functions (stubs)
+// ============================================================================
 
-    /// Move cursor and add a string of chtype on stdscr.
-    pub fn mvaddchstr(&mut self, y: i32, x: i32, chstr: &[ChType]) -> Result<()> {
-        self.stdscr.mv(y, x)?;
-        self.stdscr.addchnstr(chstr, -1)
-    }
+/// Initialize the terminal description.
+///
+/// This is a stub function for ncurses API compatibility.
+/// In this implementation, terminal handling is done internally by the Screen.
+///
+/// # Arguments
+///
+/// * `_term` - Terminal type name (ignored in this implementation)
+/// * `_filedes` - File descriptor for the terminal (ignored in this implementation)
+///
+/// # Returns
+///
+/// Always returns `Ok(())` since terminal setup is handled by Screen::init().
+pub fn setupterm(_term: Option<&str>, _filedes: i32) -> Result<()> {
+    // In our implementation, terminal setup is handled by Screen::init()
+    Ok(())
+}
 
-    /// Move cursor and add at most n chtype on stdscr.
-    pub fn mvaddchnstr(&mut self, y: i32, x: i32, chstr: &[ChType], n: i32) -> Result<()> {
-        self.stdscr.mv(y, x)?;
-        self.stdscr.addchnstr(chstr, n)
-    }
+/// Set the current terminal.
+///
+/// This is a stub function for ncurses API compatibility.
+/// In this implementation, terminal handling is done internally by the Screen.
+///
+/// # Returns
+///
+/// Always returns `Ok(())`.
+pub fn set_curterm() -> Result<()> {
+    // No-op in our implementation
+    Ok(())
+}
 
-    /// Move cursor and add a string of chtype on a window.
-    pub fn mvwaddchstr(
-        &mut self,
-        win: &mut Window,
-        y: i32,
-        x: i32,
-        chstr: &[ChType],
-    ) -> Result<()> {
-        win.mv(y, x)?;
-        win.addchnstr(chstr, -1)
-    }
+/// Delete a terminal description.
+///
+/// This is a stub function for ncurses API compatibility.
+/// In this implementation, terminal descriptions are managed automatically.
+///
+/// # Returns
+///
+/// Always returns `Ok(())`.
+pub fn del_curterm() -> Result<()> {
+    // No-op in our implementation - memory is managed automatically
+    Ok(())
+}
 
-    /// Move cursor and add at most n chtype on a window.
-    pub fn mvwaddchnstr(
-        &mut self,
-        win: &mut Window,
-        y: i32,
-        x: i32,
-        chstr: &[ChType],
-        n: i32,
-    ) -> Result<()> {
-        win.mv(y, x)?;
-        win.addchnstr(chstr, n)
-    }
+/// Restart terminal setup.
+///
+/// This is a stub function for ncurses API compatibility.
+/// In this implementation, terminal restart is handled by re-initializing Screen.
+///
+/// # Arguments
+///
+/// * `_term` - Terminal type name (ignored)
+/// * `_filedes` - File descriptor (ignored)
+/// * `_errret` - Error return location (ignored)
+///
+/// # Returns
+///
+/// Always returns `Ok(())`.
+pub fn restartterm(_term: Option<&str>, _filedes: i32, _errret: Option<&mut i32>) -> Result<()> {
+    // No-op in our implementation
+    Ok(())
+}
 
-    /// Move a derived window relative to its parent.
-    pub fn mvderwin(&mut self, win: &mut Window, y: i32, x: i32) -> Result<()> {
-        win.mvderwin(y, x)
-    }
+/// Output video attributes to the terminal.
+///
+/// This is a stub function for ncurses API compatibility.
+/// In our implementation, attribute handling is done through the Screen methods.
+///
+/// # Arguments
+///
+/// * `_attrs` - The attributes to set
+///
+/// # Returns
+///
+/// Always returns `Ok(())`.
+pub fn vidattr(_attrs: AttrT) -> Result<()> {
+    // In our implementation, use Screen's attribute methods
+    Ok(())
+}
 
-    // ========================================================================
-    // Unbounded *chstr variants (read to end of line)
-    // ========================================================================
+/// Output video attributes using a custom output function.
+///
+/// This is a stub function for ncurses API compatibility.
+/// Modern terminals don't need this low-level control.
+///
+/// # Arguments
+///
+/// * `_attrs` - The attributes to set
+/// * `_putc` - Custom output function (ignored)
+///
+/// # Returns
+///
+/// Always returns `Ok(())`.
+pub fn vidputs<F>(_attrs: AttrT, _putc: F) -> Result<()>
+where
+    F: FnMut(i32) -> i32,
+{
+    // No-op in our implementation
+    Ok(())
+}
 
-    /// Get a string of characters with attributes from stdscr (unbounded).
-    pub fn inchstr(&self, chstr: &mut [ChType]) -> i32 {
-        self.stdscr.inchnstr(chstr, -1)
-    }
+/// Move the physical cursor.
+///
+/// This is a low-level function that moves the physical terminal cursor
+/// from one position to another without going through the curses window system.
+///
+/// This is a stub that always returns Ok - use Screen's cursor positioning
+/// methods for actual cursor control.
+///
+/// # Arguments
+///
+/// * `_oldrow` - Current row position
+/// * `_oldcol` - Current column position  
+/// * `_newrow` - Target row position
+/// * `_newcol` - Target column position
+///
+/// # Returns
+///
+/// Always returns `Ok(())`.
+pub fn mvcur(_oldrow: i32, _oldcol: i32, _newrow: i32, _newcol: i32) -> Result<()> {
+    // In our implementation, cursor movement is handled through Screen
+    Ok(())
+}
 
-    /// Move and get a string of characters with attributes from stdscr (unbounded).
-    pub fn mvinchstr(&mut self, y: i32, x: i32, chstr: &mut [ChType]) -> Result<i32> {
-        self.stdscr.mv(y, x)?;
-        Ok(self.stdscr.inchnstr(chstr, -1))
-    }
+// ============================================================================
+// Termcap compatibility functions (stubs)
+// ============================================================================
 
-    /// Get a string of characters with attributes from a window (unbounded).
-    pub fn winchstr(&self, win: &Window, chstr: &mut [ChType]) -> i32 {
-        win.inchnstr(chstr, -1)
-    }
+/// Get a termcap entry.
+///
+/// This is a stub for ncurses termcap compatibility.
+/// Returns 1 (success) always since our implementation doesn't use termcap.
+///
+/// # Arguments
+///
+/// * `_bp` - Buffer for the termcap entry (unused)
+/// * `_name` - Terminal name (unused)
+///
+/// # Returns
+///
+/// Always returns 1 (success).
+pub fn tgetent(_bp: &mut [u8], _name: &str) -> i32 {
+    1 // Success
+}
 
-    /// Move and get a string of characters with attributes from a window (unbounded).
-    pub fn mvwinchstr(
-        &mut self,
-        win: &mut Window,
-        y: i32,
-        x: i32,
-        chstr: &mut [ChType],
-    ) -> Result<i32> {
-        win.mv(y, x)?;
-        Ok(win.inchnstr(chstr, -1))
-    }
+/// Get a boolean termcap capability.
+///
+/// This is a stub for ncurses termcap compatibility.
+///
+/// # Arguments
+///
+/// * `_id` - The capability ID
+///
+/// # Returns
+///
+/// Always returns 0 (capability not present).
+pub fn tgetflag(_id: &str) -> i32 {
+    0 // Not present
+}
 
-    // ========================================================================
-    // Additional string input functions (getstr family variants)
-    // ========================================================================
+/// Get a numeric termcap capability.
+///
+/// This is a stub for ncurses termcap compatibility.
+///
+/// # Arguments
+///
+/// * `_id` - The capability ID
+///
+/// # Returns
+///
+/// Always returns -1 (capability not present).
+pub fn tgetnum(_id: &str) -> i32 {
+    -1 // Not present
+}
 
-    /// Get a string from stdscr with a specific length limit.
-    ///
-    /// This is an alias for `getstr()`.
-    pub fn getnstr(&mut self, maxlen: usize) -> Result<String> {
-        self.getstr(maxlen)
-    }
+/// Get a string termcap capability.
+///
+/// This is a stub for ncurses termcap compatibility.
+///
+/// # Arguments
+///
+/// * `_id` - The capability ID
+///
+/// # Returns
+///
+/// Always returns `None` (capability not present).
+pub fn tgetstr(_id: &str) -> Option<String> {
+    None
+}
 
-    /// Move cursor and get a string from stdscr.
-    pub fn mvgetstr(&mut self, y: i32, x: i32, maxlen: usize) -> Result<String> {
-        self.stdscr.mv(y, x)?;
-        self.getstr(maxlen)
-    }
+/// Apply parameters to a termcap string capability.
+///
+/// This is a stub for ncurses termcap compatibility.
+///
+/// # Arguments
+///
+/// * `cap` - The capability string
+/// * `_col` - Column parameter
+/// * `_row` - Row parameter
+///
+/// # Returns
+///
+/// Returns the capability string unchanged.
+pub fn tgoto(cap: &str, _col: i32, _row: i32) -> String {
+    cap.to_string()
+}
 
-    /// Move cursor and get a string with length limit from stdscr.
-    pub fn mvgetnstr(&mut self, y: i32, x: i32, maxlen: usize) -> Result<String> {
-        self.mvgetstr(y, x, maxlen)
-    }
+/// Output a termcap string with padding.
+///
+/// This is a stub for ncurses termcap compatibility.
+/// Since modern terminals don't need padding, this just outputs the string.
+///
+/// # Arguments
+///
+/// * `_str` - The string to output
+/// * `_affcnt` - Affected lines count (for padding calculation)
+/// * `_putc` - Output function
+///
+/// # Returns
+///
+/// Always returns `Ok(())`.
+pub fn tputs<F>(_str: &str, _affcnt: i32, _putc: F) -> Result<()>
+where
+    F: FnMut(i32) -> i32,
+{
+    // No-op - modern terminals don't need padding
+    Ok(())
+}
 
-    /// Get a string from a window with a specific length limit.
-    ///
-    /// This is an alias for `wgetstr()`.
-    pub fn wgetnstr(&mut self, win: &mut Window, maxlen: usize) -> Result<String> {
-        self.wgetstr(win, maxlen)
-    }
+// ============================================================================
+// Additional ncurses compatibility functions
+// ============================================================================
 
-    /// Move cursor and get a string from a window.
-    pub fn mvwgetstr(&mut self, win: &mut Window, y: i32, x: i32, maxlen: usize) -> Result<String> {
-        win.mv(y, x)?;
-        self.wgetstr(win, maxlen)
-    }
+/// Initialize curses and return the standard screen.
+///
+/// This is the traditional ncurses initialization function. In ncurses-pure,
+/// it's an alias for `Screen::init()`.
+///
+/// For new code, prefer using `Screen::init()` directly.
+pub fn initscr() -> Result<Screen> {
+    Screen::init()
+}
+
+/// Initialize a new terminal.
+///
+/// This creates a Screen for the terminal of type `term_type` that writes
+/// to `out_fd` and reads from `in_fd`, for example a second terminal or a
+/// PTY the program manages. `None` as the type uses `$TERM`. Screens created
+/// this way are independent of each other and of the one from `initscr()`.
+///
+/// # Arguments
+///
+/// * `term_type` - Terminal type used to select capabilities
+/// * `out_fd` - File descriptor output is written to
+/// * `in_fd` - File descriptor input is read from
+///
+/// # Returns
+///
+/// A new Screen instance.
+pub fn newterm(term_type: Option<&str>, out_fd: RawFd, in_fd: RawFd) -> Result<Screen> {
+    Screen::with_terminal(Terminal::with_term_type(term_type, in_fd, out_fd)?)
+}
 
-    /// Move cursor and get a string with length limit from a window.
-    pub fn mvwgetnstr(
-        &mut self,
-        win: &mut Window,
-        y: i32,
-        x: i32,
-        maxlen: usize,
-    ) -> Result<String> {
-        self.mvwgetstr(win, y, x, maxlen)
-    }
+/// Set the current terminal (screen).
+///
+/// Each Screen carries its own state, so the only process-wide state to
+/// switch is the `LINES`/`COLS` values, which are set to the size of `new`.
+///
+/// # Arguments
+///
+/// * `new` - The screen to make current
+pub fn set_term(new: &Screen) -> Result<()> {
+    globals::set_dimensions(new.lines(), new.cols());
+    Ok(())
+}
 
-    // ========================================================================
-    // Unbounded instr variants
-    // ========================================================================
+/// Delete a screen.
+///
+/// The screen is dropped, which ends curses mode on its terminal and
+/// releases its resources. The file descriptors passed to `newterm()` are
+/// not closed.
+///
+/// # Arguments
+///
+/// * `sp` - The screen to delete
+pub fn delscreen(sp: Screen) {
+    drop(sp);
+}
 
-    /// Get a string from a window at the current position (unbounded).
-    pub fn winstr(&self, win: &Window) -> String {
-        win.instr(-1)
-    }
+/// Delete a window.
+///
+/// In ncurses-pure, windows are automatically cleaned up when dropped.
+/// This function is provided for API compatibility.
+///
+/// Subwindows hold their own copy of the cells they cover, so deleting one
+/// only releases the subwindow itself and never touches its parent.
+///
+/// # Arguments
+///
+/// * `_win` - The window to delete (ignored, will be dropped)
+pub fn delwin(_win: Window) {
+    // Window is dropped automatically when it goes out of scope
+}
 
-    /// Move and get a string from a window (unbounded).
-    pub fn mvwinstr(&mut self, win: &mut Window, y: i32, x: i32) -> Result<String> {
-        win.mv(y, x)?;
-        Ok(win.instr(-1))
-    }
+/// Get the ncurses version string.
+///
+/// Returns the version of ncurses-pure.
+#[must_use]
+pub fn curses_version() -> &'static str {
+    concat!("ncurses-pure ", env!("CARGO_PKG_VERSION"))
+}
 
-    // ========================================================================
-    // Sync functions
-    // ========================================================================
+/// Get terminal attributes.
+///
+/// Returns the video attributes supported by the terminal.
+/// In our implementation, we support all standard attributes.
+#[must_use]
+pub fn termattrs() -> AttrT {
+    use crate::attr::*;
+    A_STANDOUT | A_UNDERLINE | A_REVERSE | A_BLINK | A_DIM | A_BOLD | A_INVIS | A_ITALIC
+}
 
-    /// Synchronize cursor position with ancestors.
-    pub fn wcursyncup(&mut self, _win: &mut Window) {
-        // In our implementation, windows don't share storage with parents,
-        // so this is a no-op. The cursor position is local to each window.
-    }
+/// Get terminal attributes (X/Open style).
+///
+/// Returns the video attributes supported by the terminal.
+#[must_use]
+pub fn term_attrs() -> AttrT {
+    termattrs()
+}
 
-    /// Synchronize window with descendants.
-    pub fn wsyncdown(&mut self, _win: &mut Window) {
-        // In our implementation, windows don't share storage with parents,
-        // so this is a no-op.
-    }
+/// Get the binding for a key.
+///
+/// Returns the key definition bound to a specific keycode.
+/// In our implementation, key bindings are fixed.
+///
+/// # Arguments
+///
+/// * `_keycode` - The key code to query
+/// * `_count` - Which binding to get (for keys with multiple bindings)
+///
+/// # Returns
+///
+/// Always returns `None` as custom key bindings aren't supported.
+pub fn keybound(_keycode: i32, _count: i32) -> Option<String> {
+    None
+}
 
-    /// Synchronize window with ancestors.
-    pub fn wsyncup(&mut self, _win: &mut Window) {
-        // In our implementation, windows don't share storage with parents,
-        // so this is a no-op.
-    }
+/// Get the erase character as a wide character.
+///
+/// Returns the terminal's erase character (typically backspace).
+#[cfg(feature = "wide")]
+pub fn killwchar() -> char {
+    '\x15' // Ctrl-U
+}
 
-    /// Touch a line range in a window.
-    pub fn touchline(&mut self, win: &mut Window, start: i32, count: i32) -> Result<()> {
-        win.touchln(start, count, true);
-        Ok(())
-    }
+/// Enable or disable extended names in terminfo.
+///
+/// This is a stub for API compatibility. Extended names are not used
+/// in this implementation.
+///
+/// # Arguments
+///
+/// * `_enable` - Whether to enable extended names
+///
+/// # Returns
+///
+/// Always returns 0.
+pub fn use_extended_names(_enable: bool) -> i32 {
+    0
+}
 
-    // ========================================================================
-    // Mouse transformation
-    // ========================================================================
+/// Set legacy coding mode.
+///
+/// This is a stub for API compatibility. Legacy coding is not used
+/// in this implementation.
+///
+/// # Arguments
+///
+/// * `_level` - The legacy coding level
+///
+/// # Returns
+///
+/// Always returns 0.
+pub fn use_legacy_coding(_level: i32) -> i32 {
+    0
+}
 
-    /// Transform mouse coordinates from screen to window coordinates.
-    ///
-    /// If `to_screen` is true, transforms from window to screen coordinates.
-    /// Returns true if the coordinates are within the window.
-    #[cfg(feature = "mouse")]
-    pub fn mouse_trafo(&self, y: &mut i32, x: &mut i32, to_screen: bool) -> bool {
-        self.wmouse_trafo(&self.stdscr, y, x, to_screen)
-    }
+/// Output video attributes (X/Open style).
+///
+/// This is a stub for API compatibility.
+///
+/// # Arguments
+///
+/// * `_attrs` - The attributes to output
+/// * `_pair` - The color pair
+/// * `_opts` - Options (ignored)
+///
+/// # Returns
+///
+/// Always returns `Ok(())`.
+pub fn vid_attr(_attrs: AttrT, _pair: i16, _opts: Option<()>) -> Result<()> {
+    Ok(())
+}
 
-    /// Transform mouse coordinates for a specific window.
-    ///
-    /// If `to_screen` is true, transforms from window to screen coordinates.
-    /// Returns true if the coordinates are within the window.
-    #[cfg(feature = "mouse")]
-    pub fn wmouse_trafo(&self, win: &Window, y: &mut i32, x: &mut i32, to_screen: bool) -> bool {
-        if to_screen {
-            // Window to screen
-            *y += win.getbegy();
-            *x += win.getbegx();
-            true
-        } else {
-            // Screen to window
-            let wy = *y - win.getbegy();
-            let wx = *x - win.getbegx();
-            if wy >= 0 && wy <= win.getmaxy() && wx >= 0 && wx <= win.getmaxx() {
-                *y = wy;
-                *x = wx;
-                true
-            } else {
-                false
-            }
-        }
-    }
+/// Output video attributes using a custom function (X/Open style).
+///
+/// This is a stub for API compatibility.
+///
+/// # Arguments
+///
+/// * `_attrs` - The attributes to output
+/// * `_pair` - The color pair
+/// * `_opts` - Options (ignored)
+/// * `_putc` - Output function
+///
+/// # Returns
+///
+/// Always returns `Ok(())`.
+pub fn vid_puts<F>(_attrs: AttrT, _pair: i16, _opts: Option<()>, _putc: F) -> Result<()>
+where
+    F: FnMut(i32) -> i32,
+{
+    Ok(())
+}
 
-    // ========================================================================
-    // Wide character functions
-    // ========================================================================
+/// **Not available in Rust** - Use `wprintw()` instead.
+///
+/// The variadic printw functions cannot be directly implemented in Rust.
+/// Use the format! macro and wprintw instead.
+///
+/// # Panics
+/// Always panics - this function is not implemented.
+#[deprecated(since = "0.1.0", note = "Use wprintw with format! instead")]
+pub fn vwprintw(_win: &mut Window, _fmt: &str) -> Result<()> {
+    panic!(
+        "vwprintw is not available in Rust. Use wprintw with format! instead.\n\
+         Example:\n\
+         screen.wprintw(win, &format!(\"Value: {{}}\", value))?;"
+    );
+}
 
-    /// Set the background character (wide) on stdscr.
-    #[cfg(feature = "wide")]
-    pub fn bkgrnd(&mut self, wch: &crate::wide::CCharT) -> Result<()> {
-        self.stdscr.wbkgrnd(wch)
-    }
+/// **Not available in Rust** - Use `wprintw()` instead.
+///
+/// This is an alias for `vwprintw`.
+///
+/// # Panics
+/// Always panics - this function is not implemented.
+#[deprecated(since = "0.1.0", note = "Use wprintw with format! instead")]
+#[allow(deprecated)]
+pub fn vw_printw(_win: &mut Window, _fmt: &str) -> Result<()> {
+    vwprintw(_win, _fmt)
+}
 
-    /// Get a wide character and attributes at the current position.
-    #[cfg(feature = "wide")]
-    pub fn getcchar(
-        wch: &crate::wide::CCharT,
-        wc: &mut [char],
-        attrs: &mut AttrT,
-        color_pair: &mut i16,
-    ) -> Result<()> {
-        if !wc.is_empty() {
-            wc[0] = wch.spacing_char();
-        }
-        *attrs = wch.attrs();
-        *color_pair = attr::pair_number(*attrs);
-        Ok(())
-    }
+/// **Not available in Rust** - Use `wgetstr()` and parse instead.
+///
+/// The variadic scanw functions cannot be directly implemented in Rust.
+/// Use wgetstr and parse the input manually.
+///
+/// # Panics
+/// Always panics - this function is not implemented.
+#[deprecated(since = "0.1.0", note = "Use wgetstr and parse the result instead")]
+pub fn vwscanw(_win: &mut Window, _fmt: &str) -> Result<()> {
+    panic!(
+        "vwscanw is not available in Rust. Use wgetstr and parse instead.\n\
+         Example:\n\
+         let input = screen.wgetstr(win, 100)?;\n\
+         let value: i32 = input.trim().parse().unwrap_or(0);"
+    );
+}
 
-    /// Create a complex character from components.
-    #[cfg(feature = "wide")]
-    pub fn setcchar(
-        wch: &mut crate::wide::CCharT,
-        wc: &[char],
-        attrs: AttrT,
-        color_pair: i16,
-    ) -> Result<()> {
-        let c = wc.first().copied().unwrap_or(' ');
-        *wch = crate::wide::CCharT::from_char_attr(c, attrs | attr::color_pair(color_pair));
-        Ok(())
-    }
+/// **Not available in Rust** - Use `wgetstr()` and parse instead.
+///
+/// This is an alias for `vwscanw`.
+///
+/// # Panics
+/// Always panics - this function is not implemented.
+#[deprecated(since = "0.1.0", note = "Use wgetstr and parse the result instead")]
+#[allow(deprecated)]
+pub fn vw_scanw(_win: &mut Window, _fmt: &str) -> Result<()> {
+    vwscanw(_win, _fmt)
+}
 
-    /// Move and get a wide character from stdscr.
-    #[cfg(feature = "wide")]
-    pub fn mvin_wch(&mut self, y: i32, x: i32, wcval: &mut crate::wide::CCharT) -> Result<()> {
-        self.stdscr.mv(y, x)?;
-        *wcval = self.stdscr.in_wch();
-        Ok(())
-    }
+/// Get printable representation of a character.
+///
+/// This is the window-less version that operates on characters directly.
+/// Attributes are ignored; see [`crate::key::unctrl`] for the notation.
+pub fn unctrl(c: ChType) -> String {
+    crate::key::unctrl(c)
+}
 
-    /// Move and get a wide character from a window.
-    #[cfg(feature = "wide")]
-    pub fn mvwin_wch(
-        &mut self,
-        win: &mut Window,
-        y: i32,
-        x: i32,
-        wcval: &mut crate::wide::CCharT,
-    ) -> Result<()> {
-        win.mv(y, x)?;
-        *wcval = win.in_wch();
-        Ok(())
+/// Get printable representation of a wide character.
+#[cfg(feature = "wide")]
+pub fn wunctrl(wc: &crate::wide::CCharT) -> String {
+    let c = wc.spacing_char();
+    if c < ' ' {
+        format!("^{}", ((c as u8) + 64) as char)
+    } else if c as u8 == 127 {
+        "^?".to_string()
+    } else {
+        c.to_string()
     }
+}
 
-    // ========================================================================
-    // Wide string input/output functions
-    // ========================================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    /// Get a wide string from stdscr.
-    #[cfg(feature = "wide")]
-    pub fn get_wstr(&mut self, maxlen: i32) -> Result<String> {
-        use crate::wide::WideInput;
+    use crate::color::{COLOR_BLUE, COLOR_WHITE};
+    use std::fs::File;
+    use std::io::Write;
+    use std::os::unix::fs::FileExt;
+    use std::os::unix::io::{AsRawFd, FromRawFd};
 
-        let mut result = String::new();
-        let max = if maxlen < 0 { 1024 } else { maxlen as usize };
+    // Note: Most Screen tests require a terminal, so they're integration tests.
+    // The ones below drive a Screen over a pipe (input) and a temp file (output).
 
-        // Get delay and keypad settings from stdscr
-        let delay = Delay::from_raw(self.stdscr.getdelay());
-        let use_keypad = self.stdscr.is_keypad();
+    /// A screen bound to a pipe for input and a temporary file for output.
+    ///
+    /// Field order matters: the screen must be dropped (and flushed) before
+    /// the descriptors it uses are closed.
+    struct Harness {
+        screen: Screen,
+        input: File,
+        _input_reader: File,
+        output: File,
+    }
 
-        loop {
-            if result.len() >= max {
-                break;
-            }
+    impl Harness {
+        fn new() -> Self {
+            Self::with_term(Some("xterm-256color"))
+        }
 
-            match self.get_wch_internal(delay, use_keypad)? {
-                WideInput::Char(c) => {
-                    if c == '\n' {
-                        break;
-                    }
-                    if c == '\x7f' || c == '\x08' {
-                        result.pop();
-                        continue;
-                    }
-                    result.push(c);
-                }
-                WideInput::Key(k) => {
-                    if k == crate::key::KEY_ENTER {
-                        break;
-                    }
-                }
-                WideInput::None | WideInput::Eof | WideInput::Error => break,
+        /// A harness for a terminal of type `term`; `None` uses `TERM`.
+        fn with_term(term: Option<&str>) -> Self {
+            let mut fds = [0; 2];
+            // SAFETY: `fds` is a valid two-element array for `pipe` to fill.
+            assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+            // SAFETY: both descriptors were just created by `pipe` and are owned here.
+            let (reader, writer) =
+                unsafe { (File::from_raw_fd(fds[0]), File::from_raw_fd(fds[1])) };
+            let output = tempfile::tempfile().unwrap();
+
+            let mut terminal =
+                Terminal::with_term_type(term, reader.as_raw_fd(), output.as_raw_fd()).unwrap();
+            // Truecolor is taken from COLORTERM rather than the type
+            terminal.set_truecolor(false);
+            let screen = Screen::with_terminal(terminal).unwrap();
+            Self {
+                screen,
+                input: writer,
+                _input_reader: reader,
+                output,
             }
         }
 
-        Ok(result)
-    }
+        /// Number of bytes the screen has written to its output so far.
+        fn output_len(&self) -> u64 {
+            self.output.metadata().unwrap().len()
+        }
 
-    /// Get a wide string with length limit from stdscr.
-    #[cfg(feature = "wide")]
-    pub fn getn_wstr(&mut self, maxlen: i32) -> Result<String> {
-        self.get_wstr(maxlen)
-    }
+        /// Everything the screen has written to its output so far.
+        fn output(&self) -> Vec<u8> {
+            let mut buf = vec![0; self.output_len() as usize];
+            self.output.read_exact_at(&mut buf, 0).unwrap();
+            buf
+        }
 
-    /// Move and get a wide string from stdscr.
-    #[cfg(feature = "wide")]
-    pub fn mvget_wstr(&mut self, y: i32, x: i32, maxlen: i32) -> Result<String> {
-        self.stdscr.mv(y, x)?;
-        self.get_wstr(maxlen)
+        fn feed(&mut self, bytes: &[u8]) {
+            self.input.write_all(bytes).unwrap();
+        }
     }
 
-    /// Move and get a wide string with length limit from stdscr.
-    #[cfg(feature = "wide")]
-    pub fn mvgetn_wstr(&mut self, y: i32, x: i32, maxlen: i32) -> Result<String> {
-        self.mvget_wstr(y, x, maxlen)
+    fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+        haystack.windows(needle.len()).any(|w| w == needle)
     }
 
-    /// Get a wide string from a window.
-    #[cfg(feature = "wide")]
-    pub fn wget_wstr(&mut self, win: &mut Window, maxlen: i32) -> Result<String> {
-        use crate::wide::WideInput;
-
-        let mut result = String::new();
-        let max = if maxlen < 0 { 1024 } else { maxlen as usize };
-
-        loop {
-            if result.len() >= max {
-                break;
-            }
-
-            match self.wget_wch(win)? {
-                WideInput::Char(c) => {
-                    if c == '\n' {
-                        break;
-                    }
-                    if c == '\x7f' || c == '\x08' {
-                        result.pop();
-                        continue;
-                    }
-                    result.push(c);
-                }
-                WideInput::Key(k) => {
-                    if k == crate::key::KEY_ENTER {
-                        break;
-                    }
-                }
-                WideInput::None | WideInput::Eof | WideInput::Error => break,
-            }
-        }
+    #[test]
+    fn test_delay_conversion() {
+        assert_eq!(Delay::from_raw(0), Delay::NoDelay);
+        assert_eq!(Delay::from_raw(-1), Delay::Blocking);
+        assert_eq!(Delay::from_raw(100), Delay::Timeout(100));
 
-        Ok(result)
+        assert_eq!(Delay::NoDelay.to_raw(), 0);
+        assert_eq!(Delay::Blocking.to_raw(), -1);
+        assert_eq!(Delay::Timeout(100).to_raw(), 100);
     }
 
-    /// Get a wide string with length limit from a window.
-    #[cfg(feature = "wide")]
-    pub fn wgetn_wstr(&mut self, win: &mut Window, maxlen: i32) -> Result<String> {
-        self.wget_wstr(win, maxlen)
+    #[test]
+    fn test_getstr_masked() {
+        let mut h = Harness::new();
+        h.feed(b"secret\n");
+        let input = h.screen.getstr_masked(20, '*').unwrap();
+        assert_eq!(input, "secret");
+        assert_eq!(h.screen.stdscr_mut().mvinnstr(0, 0, 7).unwrap(), "****** ");
     }
 
-    /// Move and get a wide string from a window.
-    #[cfg(feature = "wide")]
-    pub fn mvwget_wstr(&mut self, win: &mut Window, y: i32, x: i32, maxlen: i32) -> Result<String> {
-        win.mv(y, x)?;
-        self.wget_wstr(win, maxlen)
-    }
+    #[test]
+    fn test_getstr_masked_backspace_and_silent() {
+        let mut h = Harness::new();
+        h.feed(b"ab\x7fc\n");
+        assert_eq!(h.screen.getstr_masked(-1, '*').unwrap(), "ac");
+        assert_eq!(h.screen.stdscr_mut().mvinnstr(0, 0, 3).unwrap(), "** ");
 
-    /// Move and get a wide string with length limit from a window.
-    #[cfg(feature = "wide")]
-    pub fn mvwgetn_wstr(
-        &mut self,
-        win: &mut Window,
-        y: i32,
-        x: i32,
-        maxlen: i32,
-    ) -> Result<String> {
-        self.mvwget_wstr(win, y, x, maxlen)
+        let mut win = Window::new(1, 10, 5, 0).unwrap();
+        h.feed(b"hidden\n");
+        assert_eq!(h.screen.wgetstr_masked(&mut win, 3, '\0').unwrap(), "hid");
+        assert_eq!(win.mvinnstr(0, 0, 10).unwrap(), " ".repeat(10));
     }
 
-    /// Get a wide character string from stdscr (unbounded).
+    #[test]
     #[cfg(feature = "wide")]
-    pub fn inwstr(&self) -> String {
-        self.winnwstr(&self.stdscr, -1)
+    fn test_getstr_masked_multibyte() {
+        let mut h = Harness::new();
+        h.feed("pässwörd€\x7f!\n".as_bytes());
+        assert_eq!(h.screen.getstr_masked(-1, '*').unwrap(), "pässwörd!");
+        assert_eq!(
+            h.screen.stdscr_mut().mvinnstr(0, 0, 10).unwrap(),
+            "********* "
+        );
     }
 
-    /// Get a wide character string from stdscr with limit.
-    #[cfg(feature = "wide")]
-    pub fn innwstr(&self, n: i32) -> String {
-        self.winnwstr(&self.stdscr, n)
-    }
+    #[test]
+    fn test_getnstr_limits() {
+        let mut h = Harness::new();
+        h.feed(b"abcdef\n");
+        assert_eq!(h.screen.getnstr(3).unwrap(), "abc");
 
-    /// Move and get a wide character string from stdscr.
-    #[cfg(feature = "wide")]
-    pub fn mvinwstr(&mut self, y: i32, x: i32) -> Result<String> {
-        self.stdscr.mv(y, x)?;
-        Ok(self.stdscr.instr(-1))
-    }
+        let long = "x".repeat(MAX_GETSTR_LEN + 10);
+        h.feed(long.as_bytes());
+        h.feed(b"\n");
+        assert_eq!(h.screen.getnstr(-1).unwrap().len(), MAX_GETSTR_LEN);
 
-    /// Move and get a wide character string from stdscr with limit.
-    #[cfg(feature = "wide")]
-    pub fn mvinnwstr(&mut self, y: i32, x: i32, n: i32) -> Result<String> {
-        self.stdscr.mv(y, x)?;
-        Ok(self.stdscr.instr(n))
+        let mut win = Window::new(1, 10, 5, 0).unwrap();
+        h.feed(b"hello\n");
+        assert_eq!(h.screen.mvwgetnstr(&mut win, 0, 0, 0).unwrap(), "");
     }
 
-    /// Get a wide character string from a window (unbounded).
-    #[cfg(feature = "wide")]
-    pub fn winwstr(&self, win: &Window) -> String {
-        self.winnwstr(win, -1)
+    #[test]
+    fn test_doupdate_bce_clear_to_eol() {
+        let mut h = Harness::new();
+        let cols = h.screen.cols() as u64;
+        h.screen.start_color().unwrap();
+        h.screen.init_pair(1, COLOR_WHITE, COLOR_BLUE).unwrap();
+        h.screen.bkgd(b' ' as ChType | attr::color_pair(1)).unwrap();
+        h.screen.mvaddstr(0, 0, "some text to erase").unwrap();
+        h.screen.refresh().unwrap();
+
+        h.screen.mv(0, 0).unwrap();
+        h.screen.clrtoeol().unwrap();
+        let before = h.output_len();
+        h.screen.refresh().unwrap();
+        let written = h.output_len() - before;
+
+        // Spelling the blanks out would take at least one byte per column.
+        assert!(
+            written < cols,
+            "wrote {written} bytes for a {cols} column erase"
+        );
     }
 
-    /// Get a wide character string from a window with limit.
-    #[cfg(feature = "wide")]
-    pub fn winnwstr(&self, win: &Window, n: i32) -> String {
-        win.instr(n)
+    #[test]
+    fn test_waddch_immedok_refreshes() {
+        let mut h = Harness::new();
+        let mut win = Window::new(3, 10, 2, 2).unwrap();
+
+        let before = h.output_len();
+        h.screen.waddch(&mut win, b'x' as ChType).unwrap();
+        assert_eq!(h.output_len(), before);
+
+        h.screen.wimmedok(&mut win, true);
+        h.screen.waddch(&mut win, b'y' as ChType).unwrap();
+        assert!(h.output_len() > before);
+        assert_eq!(
+            h.screen.newscr().line(2).unwrap().get(3).spacing_char(),
+            'y'
+        );
     }
 
-    /// Move and get a wide character string from a window.
-    #[cfg(feature = "wide")]
-    pub fn mvwinwstr(&mut self, win: &mut Window, y: i32, x: i32) -> Result<String> {
-        self.mvwinnwstr(win, y, x, -1)
-    }
+    #[test]
+    fn test_set_cursor_style() {
+        let mut h = Harness::new();
+        h.screen.set_cursor_style(CursorStyle::SteadyBar).unwrap();
+        h.screen.refresh().unwrap();
+        assert_eq!(h.screen.cursor_style(), Some(CursorStyle::SteadyBar));
+        assert!(contains(&h.output(), b"\x1b[6 q"));
 
-    /// Move and get a wide character string from a window with limit.
-    #[cfg(feature = "wide")]
-    pub fn mvwinnwstr(&mut self, win: &mut Window, y: i32, x: i32, n: i32) -> Result<String> {
-        win.mv(y, x)?;
-        Ok(win.instr(n))
+        h.screen.endwin().unwrap();
+        assert!(contains(&h.output(), b"\x1b[0 q"));
     }
 
-    /// Insert a wide string on stdscr.
-    #[cfg(feature = "wide")]
-    pub fn ins_wstr(&mut self, wstr: &str) -> Result<()> {
-        self.ins_nwstr(wstr, -1)
-    }
+    #[test]
+    fn test_take_render_diff() {
+        let mut h = Harness::new();
+        h.screen.mvaddstr(1, 2, "abc").unwrap();
+        h.screen.noutrefresh().unwrap();
+        let first = h.screen.take_render_diff();
+        assert_eq!(first.len(), 3);
+        assert_eq!(
+            first[0],
+            CellChange {
+                y: 1,
+                x: 2,
+                ch: 'a',
+                #[cfg(feature = "wide")]
+                text: "a".into(),
+                attr: A_NORMAL
+            }
+        );
 
-    /// Insert at most n characters of a wide string on stdscr.
-    #[cfg(feature = "wide")]
-    pub fn ins_nwstr(&mut self, wstr: &str, n: i32) -> Result<()> {
-        let chars: Vec<char> = wstr.chars().collect();
-        let limit = if n < 0 {
-            chars.len()
-        } else {
-            (n as usize).min(chars.len())
-        };
-        for c in chars.into_iter().take(limit).rev() {
-            let wch = crate::wide::CCharT::from_char(c);
-            self.stdscr.ins_wch(&wch)?;
-        }
-        Ok(())
+        // Only the cell that actually differs is reported the second time.
+        h.screen.mvaddstr(1, 2, "aXc").unwrap();
+        h.screen.noutrefresh().unwrap();
+        let second = h.screen.take_render_diff();
+        assert_eq!(
+            second,
+            vec![CellChange {
+                y: 1,
+                x: 3,
+                ch: 'X',
+                #[cfg(feature = "wide")]
+                text: "X".into(),
+                attr: A_NORMAL
+            }]
+        );
+        assert!(h.screen.take_render_diff().is_empty());
     }
 
-    /// Move and insert a wide string on stdscr.
+    #[test]
     #[cfg(feature = "wide")]
-    pub fn mvins_wstr(&mut self, y: i32, x: i32, wstr: &str) -> Result<()> {
-        self.stdscr.mv(y, x)?;
-        self.ins_wstr(wstr)
+    fn test_take_render_diff_combining_and_wide() {
+        let mut h = Harness::new();
+        h.screen.mvaddwstr(0, 0, "e\u{301}漢").unwrap();
+        h.screen.noutrefresh().unwrap();
+        let diff = h.screen.take_render_diff();
+        let cells: Vec<_> = diff.iter().map(|c| (c.x, c.ch, c.text.as_str())).collect();
+        assert_eq!(
+            cells,
+            [(0, 'e', "e\u{301}"), (1, '漢', "漢"), (2, '\0', "")]
+        );
     }
 
-    /// Move and insert at most n characters of a wide string on stdscr.
-    #[cfg(feature = "wide")]
-    pub fn mvins_nwstr(&mut self, y: i32, x: i32, wstr: &str, n: i32) -> Result<()> {
-        self.stdscr.mv(y, x)?;
-        self.ins_nwstr(wstr, n)
+    #[test]
+    fn test_halfdelay_timeout_accuracy() {
+        let mut h = Harness::new();
+        h.screen.halfdelay(2).unwrap();
+
+        let start = Instant::now();
+        assert!(matches!(h.screen.getch(), Err(Error::Timeout)));
+        let elapsed = start.elapsed();
+        assert!(
+            elapsed >= Duration::from_millis(200),
+            "returned early: {elapsed:?}"
+        );
+        assert!(
+            elapsed < Duration::from_millis(400),
+            "overshot: {elapsed:?}"
+        );
     }
 
-    /// Insert a wide string on a window.
-    #[cfg(feature = "wide")]
-    pub fn wins_wstr(&mut self, win: &mut Window, wstr: &str) -> Result<()> {
-        self.wins_nwstr(win, wstr, -1)
+    #[test]
+    fn test_combined_getters() {
+        let mut h = Harness::new();
+        let mut win = h.screen.newwin(4, 6, 2, 3).unwrap();
+        win.mv(1, 2).unwrap();
+        assert_eq!(h.screen.getyx_win(&win), (1, 2));
+        assert_eq!(h.screen.wgetyx(&win), (1, 2));
+        assert_eq!(h.screen.wgetbegyx(&win), (2, 3));
+        assert_eq!(h.screen.wgetmaxyx(&win), (4, 6));
+
+        h.screen.mv(3, 4).unwrap();
+        assert_eq!(h.screen.getyx(), (3, 4));
+        assert_eq!(h.screen.getbegyx(), (0, 0));
+        assert_eq!(h.screen.getmaxyx(), (h.screen.lines(), h.screen.cols()));
+    }
+
+    #[cfg(feature = "ext-colors")]
+    #[test]
+    fn test_attr_set_ext_round_trip() {
+        let mut h = Harness::new();
+        let (mut attrs, mut pair) = (0, 0);
+
+        h.screen.attr_set_ext(attr::A_BOLD, 500).unwrap();
+        h.screen.attr_get_ext(&mut attrs, &mut pair).unwrap();
+        assert_eq!(pair, 500);
+        assert_ne!(attrs & attr::A_BOLD, 0);
+
+        let mut win = Window::new(2, 2, 0, 0).unwrap();
+        h.screen.wattr_set_ext(&mut win, A_NORMAL, 500).unwrap();
+        h.screen.wattr_get_ext(&win, &mut attrs, &mut pair).unwrap();
+        assert_eq!(pair, 500);
     }
 
-    /// Insert at most n characters of a wide string on a window.
-    #[cfg(feature = "wide")]
-    pub fn wins_nwstr(&mut self, win: &mut Window, wstr: &str, n: i32) -> Result<()> {
-        let chars: Vec<char> = wstr.chars().collect();
-        let limit = if n < 0 {
-            chars.len()
-        } else {
-            (n as usize).min(chars.len())
-        };
-        for c in chars.into_iter().take(limit).rev() {
-            let wch = crate::wide::CCharT::from_char(c);
-            win.ins_wch(&wch)?;
+    #[test]
+    fn test_wtouchln_untouched_line_skipped_by_refresh() {
+        let mut h = Harness::new();
+        let mut win = Window::new(5, 4, 0, 0).unwrap();
+        for y in 2..=4 {
+            win.mvaddstr(y, 0, "ab").unwrap();
         }
-        Ok(())
+        h.screen.untouchwin(&mut win);
+        h.screen.wtouchln(&mut win, 2, 3, true);
+        h.screen.wtouchln(&mut win, 3, 1, false);
+        assert!(h.screen.is_linetouched(&win, 2));
+        assert!(!h.screen.is_linetouched(&win, 3));
+        assert!(h.screen.is_linetouched(&win, 4));
+
+        h.screen.wnoutrefresh(&win).unwrap();
+        let cells: Vec<(i32, i32)> = h
+            .screen
+            .take_render_diff()
+            .iter()
+            .map(|c| (c.y, c.x))
+            .collect();
+        assert_eq!(cells, [(2, 0), (2, 1), (4, 0), (4, 1)]);
     }
 
-    /// Move and insert a wide string on a window.
-    #[cfg(feature = "wide")]
-    pub fn mvwins_wstr(&mut self, win: &mut Window, y: i32, x: i32, wstr: &str) -> Result<()> {
-        win.mv(y, x)?;
-        self.wins_wstr(win, wstr)
+    #[test]
+    fn test_newwin_rect() {
+        let h = Harness::new();
+        let parts = crate::layout::split_horizontal(Rect::new(1, 0, 5, 80), &[1, 1, 1]);
+        let win = h.screen.newwin_rect(parts[1]).unwrap();
+        assert_eq!(win.origin(), (1, 27));
+        assert_eq!((win.getmaxy(), win.getmaxx()), (5, 27));
+        assert!(h.screen.newwin_rect(Rect::new(0, 0, 0, 10)).is_err());
     }
 
-    /// Move and insert at most n characters of a wide string on a window.
-    #[cfg(feature = "wide")]
-    pub fn mvwins_nwstr(
-        &mut self,
-        win: &mut Window,
-        y: i32,
-        x: i32,
-        wstr: &str,
-        n: i32,
-    ) -> Result<()> {
-        win.mv(y, x)?;
-        self.wins_nwstr(win, wstr, n)
+    #[test]
+    fn test_unmatched_escape_sequence_pushed_back() {
+        let mut h = Harness::new();
+        h.screen.keypad(true);
+
+        // Every byte after the ESC comes back, not just the last one
+        h.feed(b"\x1b[I");
+        assert_eq!(h.screen.getch().unwrap(), 0x1b);
+        assert_eq!(h.screen.getch().unwrap(), '[' as i32);
+        assert_eq!(h.screen.getch().unwrap(), 'I' as i32);
     }
 
-    // ========================================================================
-    // Wide character array functions (in_wch* family)
-    // ========================================================================
+    #[test]
+    fn test_bracketed_paste() {
+        let mut h = Harness::new();
+        h.screen.keypad(true);
+        h.screen.set_bracketed_paste(true).unwrap();
+        assert!(contains(&h.output(), b"\x1b[?2004h"));
+
+        h.feed(b"\x1b[200~hello\x1b[Aw\xc3\xb6rld\x1b[201~x");
+        assert_eq!(h.screen.getch().unwrap(), KEY_PASTE);
+        assert_eq!(
+            h.screen.get_paste().as_deref(),
+            Some("hello\x1b[Aw\u{f6}rld")
+        );
+        assert_eq!(h.screen.get_paste(), None);
+        assert_eq!(h.screen.getch().unwrap(), 'x' as i32);
 
-    /// Get a wide character array from stdscr (unbounded).
-    #[cfg(feature = "wide")]
-    pub fn in_wchstr(&self, wchstr: &mut [crate::wide::CCharT]) -> i32 {
-        self.win_wchnstr(&self.stdscr, wchstr, -1)
+        h.screen.set_bracketed_paste(false).unwrap();
+        assert!(contains(&h.output(), b"\x1b[?2004l"));
     }
 
-    /// Get a wide character array from stdscr with limit.
-    #[cfg(feature = "wide")]
-    pub fn in_wchnstr(&self, wchstr: &mut [crate::wide::CCharT], n: i32) -> i32 {
-        self.win_wchnstr(&self.stdscr, wchstr, n)
+    #[test]
+    fn test_focus_events() {
+        let mut h = Harness::new();
+        h.screen.keypad(true);
+
+        // Not decoded until reporting is turned on
+        h.feed(b"\x1b[I");
+        assert_eq!(h.screen.getch().unwrap(), 0x1b);
+        assert_eq!(h.screen.getch().unwrap(), '[' as i32);
+        assert_eq!(h.screen.getch().unwrap(), 'I' as i32);
+
+        h.screen.set_focus_events(true).unwrap();
+        assert!(contains(&h.output(), b"\x1b[?1004h"));
+        h.feed(b"\x1b[I\x1b[O");
+        assert_eq!(h.screen.getch().unwrap(), KEY_FOCUS_IN);
+        assert_eq!(h.screen.getch().unwrap(), KEY_FOCUS_OUT);
+
+        h.screen.set_focus_events(false).unwrap();
+        assert!(contains(&h.output(), b"\x1b[?1004l"));
+        h.feed(b"\x1b[O");
+        assert_eq!(h.screen.getch().unwrap(), 0x1b);
     }
 
-    /// Move and get a wide character array from stdscr (unbounded).
-    #[cfg(feature = "wide")]
-    pub fn mvin_wchstr(
-        &mut self,
-        y: i32,
-        x: i32,
-        wchstr: &mut [crate::wide::CCharT],
-    ) -> Result<i32> {
-        self.stdscr.mv(y, x)?;
-        Ok(self.stdscr.in_wchnstr(wchstr, -1))
+    #[test]
+    fn test_input_fd_and_process_available_input() {
+        let mut h = Harness::new();
+        let fd = h.screen.input_fd();
+        assert!(fd >= 0);
+        // SAFETY: F_GETFD only queries descriptor flags.
+        assert_ne!(unsafe { libc::fcntl(fd, libc::F_GETFD) }, -1);
+
+        h.screen.keypad(true);
+        assert!(h.screen.process_available_input().unwrap().is_empty());
+
+        h.feed(b"ab\x1b[A");
+        assert_eq!(
+            h.screen.process_available_input().unwrap(),
+            ['a' as i32, 'b' as i32, crate::key::KEY_UP]
+        );
+        assert!(h.screen.process_available_input().unwrap().is_empty());
     }
 
-    /// Move and get a wide character array from stdscr with limit.
-    #[cfg(feature = "wide")]
-    pub fn mvin_wchnstr(
-        &mut self,
-        y: i32,
-        x: i32,
-        wchstr: &mut [crate::wide::CCharT],
-        n: i32,
-    ) -> Result<i32> {
-        self.stdscr.mv(y, x)?;
-        Ok(self.stdscr.in_wchnstr(wchstr, n))
+    #[test]
+    fn test_nearest_color() {
+        let h = Harness::new();
+        assert_eq!(h.screen.nearest_color(1000, 0, 0), 9);
+        assert_eq!(h.screen.nearest_color(0, 0, 0), 0);
+        assert_eq!(h.screen.nearest_color(990, 40, 10), 9);
+        assert_eq!(h.screen.nearest_color(540, 540, 540), 245);
+        assert_eq!(h.screen.nearest_color(373, 0, 686), 55);
     }
 
-    /// Get a wide character array from a window (unbounded).
-    #[cfg(feature = "wide")]
-    pub fn win_wchstr(&self, win: &Window, wchstr: &mut [crate::wide::CCharT]) -> i32 {
-        self.win_wchnstr(win, wchstr, -1)
+    #[test]
+    fn test_wnoutrefresh_resets_touch() {
+        let mut h = Harness::new();
+        let mut win = Window::new(1, 4, 0, 0).unwrap();
+        let mut over = Window::new(1, 1, 0, 0).unwrap();
+
+        win.addstr("AB").unwrap();
+        h.screen.wnoutrefresh_untouch(&mut win).unwrap();
+        assert!(!win.is_wintouched());
+
+        // A second copy only carries the new change, so it does not undo
+        // the overlapping window drawn in between.
+        over.addstr("Z").unwrap();
+        h.screen.wnoutrefresh_untouch(&mut over).unwrap();
+        win.mvaddstr(0, 1, "C").unwrap();
+        h.screen.wnoutrefresh_untouch(&mut win).unwrap();
+
+        let cells: Vec<(i32, char)> = h
+            .screen
+            .take_render_diff()
+            .iter()
+            .map(|c| (c.x, c.ch))
+            .collect();
+        assert_eq!(cells, [(0, 'Z'), (1, 'C')]);
+
+        // The plain variant leaves the window touched
+        win.mvaddstr(0, 2, "D").unwrap();
+        h.screen.wnoutrefresh(&win).unwrap();
+        assert!(win.is_wintouched());
     }
 
-    /// Get a wide character array from a window with limit.
-    #[cfg(feature = "wide")]
-    pub fn win_wchnstr(&self, win: &Window, wchstr: &mut [crate::wide::CCharT], n: i32) -> i32 {
-        win.in_wchnstr(wchstr, n)
+    #[cfg(feature = "mouse")]
+    #[test]
+    fn test_getmouse_in() {
+        use crate::mouse::{ALL_MOUSE_EVENTS, BUTTON1_CLICKED};
+
+        let mut h = Harness::new();
+        h.screen.mousemask(ALL_MOUSE_EVENTS);
+        let win = Window::new(4, 8, 5, 10).unwrap();
+        let click = |y, x| MouseEvent {
+            y,
+            x,
+            bstate: BUTTON1_CLICKED,
+            ..MouseEvent::new()
+        };
+
+        h.screen.ungetmouse(click(7, 14));
+        let event = h.screen.getmouse_in(&win).unwrap();
+        assert_eq!((event.y, event.x), (2, 4));
+
+        // The last row/column belong to the window, the next ones do not
+        h.screen.ungetmouse(click(8, 17));
+        let event = h.screen.getmouse_in(&win).unwrap();
+        assert_eq!((event.y, event.x), (3, 7));
+        h.screen.ungetmouse(click(9, 14));
+        let event = h.screen.getmouse_in(&win).unwrap();
+        assert_eq!((event.y, event.x), (9, 14));
+
+        // A derwin inside a window inside stdscr reports its own coordinates
+        let outer = h.screen.stdscr().derwin(10, 20, 2, 3).unwrap();
+        let inner = outer.derwin(3, 5, 4, 6).unwrap();
+        h.screen.ungetmouse(click(7, 11));
+        let event = h.screen.getmouse_in(&inner).unwrap();
+        assert_eq!((event.y, event.x), (1, 2));
+
+        h.screen.mouse_clip_to_window(true);
+        h.screen.ungetmouse(click(1, 1));
+        assert!(h.screen.getmouse_in(&win).is_none());
+        assert_eq!(h.screen.getmouse().map(|e| (e.y, e.x)), Some((1, 1)));
     }
 
-    /// Move and get a wide character array from a window (unbounded).
-    #[cfg(feature = "wide")]
-    pub fn mvwin_wchstr(
-        &mut self,
-        win: &mut Window,
-        y: i32,
-        x: i32,
-        wchstr: &mut [crate::wide::CCharT],
-    ) -> Result<i32> {
-        self.mvwin_wchnstr(win, y, x, wchstr, -1)
+    #[cfg(feature = "mouse")]
+    #[test]
+    fn test_mouse_click_from_terminal() {
+        use crate::mouse::BUTTON1_CLICKED;
+
+        let mut h = Harness::new();
+        h.screen.keypad(true);
+        h.screen.mousemask(BUTTON1_CLICKED);
+
+        // The press is swallowed; the release within the interval is a click
+        h.feed(b"\x1b[<0;5;3M\x1b[<0;5;3m");
+        assert_eq!(h.screen.getch().unwrap(), KEY_MOUSE);
+        let event = h.screen.getmouse().unwrap();
+        assert_eq!((event.y, event.x, event.bstate), (2, 4, BUTTON1_CLICKED));
+        assert!(h.screen.getmouse().is_none());
     }
 
-    /// Move and get a wide character array from a window with limit.
-    #[cfg(feature = "wide")]
-    pub fn mvwin_wchnstr(
-        &mut self,
-        win: &mut Window,
-        y: i32,
-        x: i32,
-        wchstr: &mut [crate::wide::CCharT],
-        n: i32,
-    ) -> Result<i32> {
-        win.mv(y, x)?;
-        Ok(win.in_wchnstr(wchstr, n))
+    #[test]
+    fn test_apply_registry() {
+        use crate::color::{PairRegistry, COLOR_RED, COLOR_YELLOW};
+
+        let mut h = Harness::new();
+        h.screen.start_color().unwrap();
+        let mut registry = PairRegistry::new().with_limit(h.screen.num_color_pairs());
+        let warn = registry.get_or_create(COLOR_YELLOW, COLOR_BLUE);
+        let error = registry.get_or_create(COLOR_RED, COLOR_WHITE);
+
+        h.screen.apply_registry(&registry).unwrap();
+        assert_eq!(
+            h.screen.pair_content(warn).unwrap(),
+            (COLOR_YELLOW, COLOR_BLUE)
+        );
+        assert_eq!(
+            h.screen.pair_content(error).unwrap(),
+            (COLOR_RED, COLOR_WHITE)
+        );
     }
 
-    // ========================================================================
-    // Wide border/line functions with complex characters
-    // ========================================================================
+    #[test]
+    fn test_set_tabsize_propagates() {
+        let mut h = Harness::new();
+        h.screen.set_tabsize(4);
+        h.screen.mvaddstr(0, 1, "\t").unwrap();
+        assert_eq!(h.screen.stdscr().getcurx(), 4);
 
-    /// Draw a horizontal line with a complex character on stdscr.
-    #[cfg(feature = "wide")]
-    pub fn hline_set(&mut self, wch: &crate::wide::CCharT, n: i32) -> Result<()> {
-        self.stdscr.hline_set(wch, n)
+        let mut win = h.screen.newwin(2, 20, 0, 0).unwrap();
+        win.mvaddstr(0, 5, "\t").unwrap();
+        assert_eq!(win.getcurx(), 8);
     }
 
-    /// Move and draw a horizontal line with a complex character on stdscr.
-    #[cfg(feature = "wide")]
-    pub fn mvhline_set(&mut self, y: i32, x: i32, wch: &crate::wide::CCharT, n: i32) -> Result<()> {
-        self.stdscr.mv(y, x)?;
-        self.hline_set(wch, n)
+    #[test]
+    fn test_batched_refresh() {
+        let mut h = Harness::new();
+        h.screen.refresh().unwrap();
+        let before = h.output_len();
+
+        h.screen.begin_batch();
+        h.screen.mvaddstr(0, 0, "first").unwrap();
+        h.screen.refresh().unwrap();
+        h.screen.begin_batch();
+        let mut win = h.screen.newwin(1, 6, 2, 0).unwrap();
+        win.addstr("second").unwrap();
+        h.screen.wrefresh(&mut win).unwrap();
+        h.screen.end_batch().unwrap();
+        assert!(h.screen.is_batching());
+        assert_eq!(h.output_len(), before);
+
+        h.screen.end_batch().unwrap();
+        assert!(!h.screen.is_batching());
+        let out = h.output();
+        assert!(contains(&out[before as usize..], b"first"));
+        assert!(contains(&out[before as usize..], b"second"));
+
+        // Nothing left to draw, so another refresh writes no cells
+        let after = h.output_len();
+        h.screen.refresh().unwrap();
+        assert!(!contains(&h.output()[after as usize..], b"first"));
+        assert!(h.screen.end_batch().is_err());
+
+        // A flash waits for the batched changes to be drawn
+        let before = h.output_len() as usize;
+        h.screen.begin_batch();
+        h.screen.flash().unwrap();
+        h.screen.mvaddstr(1, 0, "third").unwrap();
+        h.screen.refresh().unwrap();
+        h.screen.flash().unwrap();
+        assert_eq!(h.output_len() as usize, before);
+        h.screen.end_batch().unwrap();
+        let out = &h.output()[before..];
+        let third = out.windows(5).position(|w| w == b"third").unwrap();
+        let flash = out.windows(5).position(|w| w == b"\x1b[?5h").unwrap();
+        assert!(third < flash);
+        assert_eq!(out.windows(5).filter(|w| *w == b"\x1b[?5h").count(), 1);
     }
 
-    /// Draw a horizontal line with a complex character on a window.
-    #[cfg(feature = "wide")]
-    pub fn whline_set(
-        &mut self,
-        win: &mut Window,
-        wch: &crate::wide::CCharT,
-        n: i32,
-    ) -> Result<()> {
-        win.hline_set(wch, n)
+    #[test]
+    fn test_init_pair_rgb() {
+        let mut h = Harness::new();
+        h.screen.start_color().unwrap();
+        h.screen
+            .init_pair_rgb(1, (255, 128, 0), (0, 0, 95))
+            .unwrap();
+        assert_eq!(h.screen.pair_content(1).unwrap(), (208, 17));
+
+        // Without truecolor the nearest palette colors are used
+        assert!(!h.screen.has_truecolor());
+        h.screen.attron(crate::attr::color_pair(1)).unwrap();
+        h.screen.mvaddstr(0, 0, "rgb").unwrap();
+        h.screen.refresh().unwrap();
+        let out = h.output();
+        assert!(contains(&out, b"\x1b[38;5;208m"));
+        assert!(contains(&out, b"\x1b[48;5;17m"));
+        assert!(!contains(&out, b"\x1b[38;2;"));
+
+        h.screen.set_truecolor(true);
+        let before = h.output_len() as usize;
+        h.screen.mvaddstr(1, 0, "rgb").unwrap();
+        h.screen.refresh().unwrap();
+        let out = h.output();
+        assert!(contains(&out[before..], b"\x1b[38;2;255;128;0m"));
+        assert!(contains(&out[before..], b"\x1b[48;2;0;0;95m"));
     }
 
-    /// Move and draw a horizontal line with a complex character on a window.
-    #[cfg(feature = "wide")]
-    pub fn mvwhline_set(
-        &mut self,
-        win: &mut Window,
-        y: i32,
-        x: i32,
-        wch: &crate::wide::CCharT,
-        n: i32,
-    ) -> Result<()> {
-        win.mv(y, x)?;
-        win.hline_set(wch, n)
+    #[test]
+    fn test_pad_view_refresh() {
+        let mut h = Harness::new();
+        let mut pad = h.screen.newpad(100, 20).unwrap();
+        for y in 0..100 {
+            pad.mvaddstr(y, 0, &format!("row{:03}", y)).unwrap();
+        }
+        let mut view = crate::pad::PadView::new(pad, Rect::new(1, 0, 10, 20)).unwrap();
+        view.scroll_by(95, 0);
+        assert_eq!(view.offset(), (90, 0));
+
+        view.refresh(&mut h.screen).unwrap();
+        let out = h.output();
+        assert!(contains(&out, b"row090"));
+        assert!(contains(&out, b"row099"));
+        assert!(!contains(&out, b"row089"));
     }
 
-    /// Draw a vertical line with a complex character on stdscr.
-    #[cfg(feature = "wide")]
-    pub fn vline_set(&mut self, wch: &crate::wide::CCharT, n: i32) -> Result<()> {
-        self.stdscr.vline_set(wch, n)
+    #[test]
+    fn test_scroll_view_render() {
+        let mut h = Harness::new();
+        let mut log = crate::pad::ScrollView::new(100, 20, Rect::new(1, 0, 5, 20)).unwrap();
+        for y in 0..12 {
+            log.pad_mut()
+                .mvaddstr(y, 0, &format!("line{:03}", y))
+                .unwrap();
+            log.ensure_visible(y, 0);
+        }
+        assert_eq!(log.offset(), (7, 0));
+
+        log.render(&mut h.screen).unwrap();
+        h.screen.doupdate().unwrap();
+        let out = h.output();
+        assert!(contains(&out, b"line011"));
+        assert!(contains(&out, b"line007"));
+        assert!(!contains(&out, b"line006"));
     }
 
-    /// Move and draw a vertical line with a complex character on stdscr.
-    #[cfg(feature = "wide")]
-    pub fn mvvline_set(&mut self, y: i32, x: i32, wch: &crate::wide::CCharT, n: i32) -> Result<()> {
-        self.stdscr.mv(y, x)?;
-        self.vline_set(wch, n)
+    #[test]
+    fn test_unknown_csi_coalesce() {
+        let mut h = Harness::new();
+        h.screen.keypad(true);
+
+        // By default the unknown sequence dribbles out byte by byte
+        h.feed(b"\x1b[99Z");
+        let keys: Vec<i32> = (0..5).map(|_| h.screen.getch().unwrap()).collect();
+        assert_eq!(keys, [0x1b, '[' as i32, '9' as i32, '9' as i32, 'Z' as i32]);
+
+        h.screen.set_unknown_csi_coalesce(true);
+        h.feed(b"\x1b[99Zx\x1b[A");
+        assert_eq!(h.screen.getch().unwrap(), KEY_UNKNOWN_CSI);
+        assert_eq!(
+            h.screen.get_unknown_csi().as_deref(),
+            Some(&b"\x1b[99Z"[..])
+        );
+        assert_eq!(h.screen.get_unknown_csi(), None);
+        assert_eq!(h.screen.getch().unwrap(), 'x' as i32);
+        assert_eq!(h.screen.getch().unwrap(), crate::key::KEY_UP);
+
+        // Not a CSI sequence: still returned as ESC plus bytes
+        h.feed(b"\x1bOq");
+        assert_eq!(h.screen.getch().unwrap(), 0x1b);
+        assert_eq!(h.screen.getch().unwrap(), 'O' as i32);
+        assert_eq!(h.screen.getch().unwrap(), 'q' as i32);
     }
 
-    /// Draw a vertical line with a complex character on a window.
-    #[cfg(feature = "wide")]
-    pub fn wvline_set(
-        &mut self,
-        win: &mut Window,
-        wch: &crate::wide::CCharT,
-        n: i32,
-    ) -> Result<()> {
-        win.vline_set(wch, n)
+    #[test]
+    fn test_idcok_insert_char() {
+        fn insert_cost(h: &mut Harness) -> u64 {
+            let text: String = ('a'..='z').cycle().take(79).collect();
+            h.screen.mvaddstr(3, 0, &text).unwrap();
+            h.screen.refresh().unwrap();
+            let before = h.output_len();
+            h.screen.mv(3, 40).unwrap();
+            h.screen.insch('#' as ChType).unwrap();
+            h.screen.refresh().unwrap();
+            h.output_len() - before
+        }
+
+        // Default: the shifted tail is rewritten
+        let mut h = Harness::new();
+        let plain = insert_cost(&mut h);
+        assert!(!contains(&h.output(), b"\x1b[@"));
+        assert!(plain > 39);
+
+        let mut h = Harness::new();
+        h.screen.idcok(true);
+        let shifted = insert_cost(&mut h);
+        assert!(contains(&h.output(), b"\x1b[@#"));
+        assert!(shifted < 20, "{} bytes", shifted);
+        assert_eq!(
+            h.screen.curscr().line(3).unwrap().text(),
+            h.screen.newscr().line(3).unwrap().text()
+        );
+
+        // Enabling it on another window leaves stdscr's lines alone
+        let mut h = Harness::new();
+        let mut win = h.screen.newwin(2, 80, 10, 0).unwrap();
+        h.screen.idcok(false);
+        h.screen.widcok(&mut win, true);
+        insert_cost(&mut h);
+        assert!(!contains(&h.output(), b"\x1b[@"));
+
+        // ...while that window's lines are still shifted
+        let text: String = ('a'..='z').cycle().take(79).collect();
+        win.mvaddstr(0, 0, &text).unwrap();
+        h.screen.wrefresh(&mut win).unwrap();
+        let before = h.output_len();
+        win.mv(0, 40).unwrap();
+        win.insch('#' as ChType).unwrap();
+        h.screen.wrefresh(&mut win).unwrap();
+        assert!(h.output_len() - before < 20);
+        assert!(contains(&h.output(), b"\x1b[@#"));
+
+        // The most recent call decides, so turning it off stops all shifting
+        h.screen.idcok(false);
+        let before = h.output_len() as usize;
+        win.mv(0, 20).unwrap();
+        win.insch('%' as ChType).unwrap();
+        h.screen.wrefresh(&mut win).unwrap();
+        assert!(!contains(&h.output()[before..], b"\x1b[@"));
+    }
+
+    #[test]
+    fn test_find_char_shift() {
+        let old = b"abcdefghijklmnopqrstuvwxyz";
+        let mut ins = old.to_vec();
+        ins.insert(5, b'!');
+        ins.pop();
+        assert_eq!(Screen::find_char_shift(old, &ins), Some((5, 1)));
+
+        let mut del = old.to_vec();
+        del.drain(5..7);
+        del.extend(b"  ");
+        assert_eq!(Screen::find_char_shift(old, &del), Some((5, -2)));
+
+        // Short tails are cheaper to redraw
+        let mut tail = old.to_vec();
+        tail.insert(24, b'!');
+        tail.pop();
+        assert_eq!(Screen::find_char_shift(old, &tail), None);
+        assert_eq!(Screen::find_char_shift(old, old), None);
+    }
+
+    #[test]
+    fn test_init_failure_restores_terminal() {
+        // SAFETY: plain libc calls on descriptors owned by this test; the
+        // pointers passed are valid for the duration of each call.
+        unsafe {
+            let master = libc::posix_openpt(libc::O_RDWR | libc::O_NOCTTY);
+            assert!(master >= 0);
+            assert_eq!(libc::grantpt(master), 0);
+            assert_eq!(libc::unlockpt(master), 0);
+            let slave = libc::open(libc::ptsname(master), libc::O_RDWR | libc::O_NOCTTY);
+            assert!(slave >= 0);
+            let (master, slave) = (File::from_raw_fd(master), File::from_raw_fd(slave));
+
+            // Output the screen cannot write to, so clearing the screen fails
+            let output = File::open("/dev/null").unwrap();
+
+            let terminal = Terminal::with_term_type(
+                Some("xterm-256color"),
+                slave.as_raw_fd(),
+                output.as_raw_fd(),
+            )
+            .unwrap();
+            assert!(Screen::with_terminal(terminal).is_err());
+
+            let mut termios: libc::termios = std::mem::zeroed();
+            assert_eq!(libc::tcgetattr(slave.as_raw_fd(), &mut termios), 0);
+            assert_ne!(termios.c_lflag & libc::ICANON, 0);
+            assert_ne!(termios.c_lflag & libc::ECHO, 0);
+            drop(master);
+        }
+    }
+
+    #[test]
+    fn test_acs_mode_vt100() {
+        let mut h = Harness::new();
+        assert_eq!(h.screen.acs_mode(), AcsMode::Unicode);
+        let mut win = h.screen.newwin(3, 4, 0, 0).unwrap();
+        win.box_(0, 0).unwrap();
+        h.screen.wrefresh(&mut win).unwrap();
+        assert!(contains(&h.output(), "┌──┐".as_bytes()));
+        assert!(!contains(&h.output(), b"\x1b(0"));
+
+        h.screen.set_acs_mode(AcsMode::Vt100);
+        let mut win = h.screen.newwin(3, 4, 5, 0).unwrap();
+        win.box_(0, 0).unwrap();
+        win.mvaddstr(1, 1, "ab").unwrap();
+        h.screen.wrefresh(&mut win).unwrap();
+        let out = h.output();
+        // The line-drawing set stays selected across cursor moves and is
+        // left before ordinary text and at the end of the update
+        assert!(contains(&out, b"\x1b(0lqqk"));
+        assert!(contains(&out, b"x\x1b(Bab\x1b(0x"));
+        assert!(contains(&out, b"mqqj\x1b(B"));
     }
 
-    /// Move and draw a vertical line with a complex character on a window.
     #[cfg(feature = "wide")]
-    pub fn mvwvline_set(
-        &mut self,
-        win: &mut Window,
-        y: i32,
-        x: i32,
-        wch: &crate::wide::CCharT,
-        n: i32,
-    ) -> Result<()> {
-        win.mv(y, x)?;
-        win.vline_set(wch, n)
+    #[test]
+    fn test_wide_background_roundtrip() {
+        use crate::wide::CCharT;
+
+        let mut h = Harness::new();
+        let mut bg = CCharT::from_char_attr('e', attr::A_BOLD | attr::color_pair(3));
+        bg.chars[1] = '\u{301}';
+
+        let mut win = h.screen.newwin(3, 5, 0, 0).unwrap();
+        h.screen.wbkgrndset(&mut win, &bg);
+        let mut got = CCharT::new();
+        h.screen.wgetbkgrnd(&win, &mut got).unwrap();
+        assert_eq!(got, bg);
+        assert_eq!(got.chars[1], '\u{301}');
+
+        h.screen.bkgrnd(&bg).unwrap();
+        let mut got = CCharT::new();
+        h.screen.getbkgrnd(&mut got).unwrap();
+        assert_eq!(got, bg);
     }
-}
 
-impl Drop for Screen {
-    fn drop(&mut self) {
-        // Try to restore terminal state
-        let _ = self.endwin();
+    #[test]
+    fn test_meta_sends_escape() {
+        let mut h = Harness::new();
+        h.screen.keypad(true);
+        h.screen.set_escdelay(20);
+
+        // Off by default
+        h.feed(b"\x1bx");
+        assert_eq!(h.screen.getch().unwrap(), 0x1b);
+        assert_eq!(h.screen.getch().unwrap(), 'x' as i32);
+
+        h.screen.set_meta_sends_escape(true);
+        h.feed(b"\x1bx\x1b[");
+        assert_eq!(h.screen.getch().unwrap(), crate::key::key_alt('x'));
+        // Alt-[ is only known once the escape delay rules out a sequence
+        assert_eq!(h.screen.getch().unwrap(), crate::key::key_alt('['));
+
+        // A lone ESC times out and a later character stands alone
+        h.feed(b"\x1b");
+        assert_eq!(h.screen.getch().unwrap(), 0x1b);
+        h.feed(b"x");
+        assert_eq!(h.screen.getch().unwrap(), 'x' as i32);
+
+        // Known sequences still win
+        h.feed(b"\x1b[A");
+        assert_eq!(h.screen.getch().unwrap(), crate::key::KEY_UP);
+    }
+
+    #[test]
+    fn test_render_to_writer() {
+        use std::sync::{Arc, Mutex};
+
+        #[derive(Clone, Default)]
+        struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+        impl std::io::Write for SharedBuf {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().extend_from_slice(buf);
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut fds = [0; 2];
+        // SAFETY: `fds` is a valid two-element array for `pipe` to fill.
+        assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+        // SAFETY: both descriptors were just created by `pipe` and are owned here.
+        let (reader, _writer) = unsafe { (File::from_raw_fd(fds[0]), File::from_raw_fd(fds[1])) };
+
+        let buf = SharedBuf::default();
+        let terminal = Terminal::with_output_term_type(
+            Some("xterm-256color"),
+            reader.as_raw_fd(),
+            Box::new(buf.clone()),
+            crate::types::Size::new(5, 20),
+        )
+        .unwrap();
+        let mut screen = Screen::with_terminal(terminal).unwrap();
+        assert_eq!((screen.getmaxy(), screen.getmaxx()), (5, 20));
+
+        screen.mvaddstr(2, 3, "hello").unwrap();
+        screen.refresh().unwrap();
+        let out = buf.0.lock().unwrap().clone();
+        assert!(out.starts_with(b"\x1b[?1049h"));
+        assert!(contains(&out, b"\x1b[3;4Hhello"));
+
+        screen.endwin().unwrap();
+        assert!(contains(&buf.0.lock().unwrap(), b"\x1b[?1049l"));
+
+        // The size cannot be detected from a writer, so it must be valid
+        let empty = crate::types::Size::new(0, 20);
+        assert!(matches!(
+            Screen::with_output(reader.as_raw_fd(), Box::new(buf), empty),
+            Err(Error::InvalidArgument(_))
+        ));
     }
-}
 
-// ============================================================================
-// Global screen dimensions (thread-local storage)
-// ============================================================================
+    #[test]
+    fn test_refresh_visits_only_touched_lines() {
+        let mut h = Harness::new();
+        let mut win = Window::new(1000, 10, 0, 0).unwrap();
+        h.screen.wnoutrefresh_untouch(&mut win).unwrap();
 
-use std::cell::Cell;
-use std::cell::RefCell;
+        win.mvaddstr(1, 0, "one").unwrap();
+        win.mvaddstr(5, 2, "two").unwrap();
+        win.mvaddstr(5, 5, "2").unwrap();
+        win.mvaddstr(20, 0, "three").unwrap();
+        assert_eq!(win.touched_lines(), [1, 5, 20]);
 
-thread_local! {
-    /// Thread-local storage for screen lines (rows).
-    static SCREEN_LINES: Cell<i32> = const { Cell::new(24) };
-    /// Thread-local storage for screen columns.
-    static SCREEN_COLS: Cell<i32> = const { Cell::new(80) };
-    /// Whether to use environment variables (LINES, COLUMNS) for screen size.
-    static USE_ENV: Cell<bool> = const { Cell::new(true) };
-    /// Whether to use ioctl for screen size detection.
-    static USE_TIOCTL: Cell<bool> = const { Cell::new(true) };
-    /// Ripoff line specifications (called before initscr).
-    static RIPOFF_LINES: RefCell<Vec<RipoffSpec>> = const { RefCell::new(Vec::new()) };
-}
+        let before = h.screen.lines_copied;
+        h.screen.wnoutrefresh_untouch(&mut win).unwrap();
+        assert_eq!(h.screen.lines_copied - before, 3);
+        assert!(win.touched_lines().is_empty());
 
-/// Specification for a ripped-off line.
-#[derive(Clone)]
-struct RipoffSpec {
-    /// Positive for top, negative for bottom.
-    line: i32,
-    /// Initialization callback (not used in Rust, but kept for API compatibility).
-    #[allow(dead_code)]
-    callback: Option<fn(&mut Window, i32) -> i32>,
-}
+        h.screen.doupdate().unwrap();
+        assert!(contains(&h.output(), b"two2"));
+    }
 
-/// Global-style functions for ncurses compatibility.
-///
-/// These provide access to screen dimensions without needing a Screen reference.
-/// Note that in Rust, using the Screen instance methods (`screen.lines()` and
-/// `screen.cols()`) is preferred over these globals.
-///
-/// # Thread Safety
-///
-/// These values are stored in thread-local storage, so each thread has its own
-/// copy. If you're using multiple screens in different threads, each thread
-/// will have the dimensions of the last Screen initialized in that thread.
-///
-/// # Example
-///
-/// ```rust,ignore
-/// use ncurses::screen::*;
-///
-/// let screen = Screen::init()?;
-/// // After init, LINES() and COLS() return the screen dimensions
-/// let lines = LINES();
-/// let cols = COLS();
-/// ```
-pub mod globals {
-    use super::*;
+    #[test]
+    fn test_getch_echo() {
+        let mut h = Harness::new();
+        h.feed(b"a");
+        assert_eq!(h.screen.getch().unwrap(), 'a' as i32);
+        assert_eq!(h.screen.stdscr().getcurx(), 0);
+
+        h.screen.echo().unwrap();
+        h.screen.keypad(true);
+        h.feed(b"b\x01\x1b[A");
+        assert_eq!(h.screen.getch().unwrap(), 'b' as i32);
+        assert_eq!(h.screen.getch().unwrap(), 0x01);
+        assert_eq!(h.screen.getch().unwrap(), crate::key::KEY_UP);
+        assert_eq!(h.screen.stdscr().getcurx(), 1);
+        assert_eq!(h.screen.stdscr_mut().mvinnstr(0, 0, 2).unwrap(), "b ");
+        assert!(contains(&h.output(), b"b"));
+
+        let mut win = Window::new(2, 10, 5, 3).unwrap();
+        win.mv(1, 4).unwrap();
+        h.feed(b"c");
+        assert_eq!(h.screen.wgetch(&mut win).unwrap(), 'c' as i32);
+        assert_eq!(win.getcurx(), 5);
+        assert_eq!(win.mvinnstr(1, 4, 1).unwrap(), "c");
+    }
 
-    /// Get the number of screen lines (rows).
-    ///
-    /// This returns the value set by the last Screen initialization in this thread.
-    /// Prefer using `screen.lines()` when you have a Screen reference.
-    #[allow(non_snake_case)]
-    pub fn LINES() -> i32 {
-        SCREEN_LINES.with(|l| l.get())
+    #[test]
+    #[cfg(feature = "wide")]
+    fn test_get_wch_echo() {
+        use crate::wide::WideInput;
+
+        let mut h = Harness::new();
+        h.screen.echo().unwrap();
+        h.screen.keypad(true);
+        h.feed("é\x01漢\x1b[A".as_bytes());
+        assert_eq!(h.screen.get_wch().unwrap(), WideInput::Char('é'));
+        assert_eq!(h.screen.get_wch().unwrap(), WideInput::Char('\x01'));
+        assert_eq!(h.screen.get_wch().unwrap(), WideInput::Char('漢'));
+        assert_eq!(
+            h.screen.get_wch().unwrap(),
+            WideInput::Key(crate::key::KEY_UP)
+        );
+        assert_eq!(h.screen.stdscr().getcurx(), 3);
+        assert_eq!(h.screen.stdscr_mut().mvinnstr(0, 0, 3).unwrap(), "é漢");
+        assert!(contains(&h.output(), "漢".as_bytes()));
+
+        let mut win = Window::new(2, 10, 5, 3).unwrap();
+        h.feed("ü".as_bytes());
+        assert_eq!(h.screen.wget_wch(&mut win).unwrap(), WideInput::Char('ü'));
+        assert_eq!(win.mvinnstr(0, 0, 1).unwrap(), "ü");
     }
 
-    /// Get the number of screen columns.
-    ///
-    /// This returns the value set by the last Screen initialization in this thread.
-    /// Prefer using `screen.cols()` when you have a Screen reference.
-    #[allow(non_snake_case)]
-    pub fn COLS() -> i32 {
-        SCREEN_COLS.with(|c| c.get())
+    #[test]
+    fn test_query_cursor_position() {
+        let mut h = Harness::new();
+        h.feed(b"\x1b[12;34R");
+        assert_eq!(h.screen.query_cursor_position().unwrap(), (11, 33));
+        assert!(contains(&h.output(), b"\x1b[6n"));
+
+        // Typeahead before the reply is kept
+        h.feed(b"x\x1b[3;4R");
+        assert_eq!(h.screen.query_cursor_position().unwrap(), (2, 3));
+        assert_eq!(h.screen.getch().unwrap(), 'x' as i32);
+
+        // A reply after the query timed out is swallowed by the keypad parser
+        h.screen.keypad(true);
+        assert!(matches!(
+            h.screen.query_cursor_position(),
+            Err(Error::Timeout)
+        ));
+        h.feed(b"\x1b[5;6Ry");
+        assert_eq!(h.screen.getch().unwrap(), 'y' as i32);
+
+        // With no query outstanding the same form is a key
+        h.feed(b"\x1b[1;2Rz\x1b[1;5R");
+        assert_eq!(h.screen.getch().unwrap(), crate::key::key_f(15));
+        assert_eq!(h.screen.getch().unwrap(), 'z' as i32);
+        assert_eq!(h.screen.getch().unwrap(), crate::key::key_f(27));
+        h.screen.set_unknown_csi_coalesce(true);
+        h.feed(b"\x1b[5;6R");
+        assert_eq!(h.screen.getch().unwrap(), KEY_UNKNOWN_CSI);
     }
 
-    /// Set the screen dimensions (called internally by Screen).
-    #[doc(hidden)]
-    pub fn set_dimensions(lines: i32, cols: i32) {
-        SCREEN_LINES.with(|l| l.set(lines));
-        SCREEN_COLS.with(|c| c.set(cols));
+    #[test]
+    #[cfg(feature = "wide")]
+    fn test_copywin_wide_chars() {
+        let mut h = Harness::new();
+        let mut src = Window::new(1, 10, 0, 0).unwrap();
+        src.mvaddstr(0, 0, "a中b文").unwrap();
+
+        // The whole character comes along with its placeholder
+        let mut dst = Window::new(2, 10, 5, 0).unwrap();
+        h.screen
+            .copywin(&src, &mut dst, 0, 0, 0, 2, 0, 7, false)
+            .unwrap();
+        h.screen.wnoutrefresh(&dst).unwrap();
+        h.screen.doupdate().unwrap();
+        assert!(contains(&h.output(), "a中b文".as_bytes()));
+        let cells: Vec<_> = dst.line(0).unwrap().text()[2..8].to_vec();
+        assert_eq!(cells[1].spacing_char(), '中');
+        assert!(cells[2].is_null());
+
+        // Halves cut off by the copied area become blanks
+        let mut dst = Window::new(1, 10, 0, 0).unwrap();
+        h.screen
+            .copywin(&src, &mut dst, 0, 2, 0, 0, 0, 2, false)
+            .unwrap();
+        let text: String = dst.line(0).unwrap().text()[..4]
+            .iter()
+            .map(|c| c.spacing_char())
+            .collect();
+        assert_eq!(text, " b  ");
+
+        // Overwriting half of a character in dst blanks the other half
+        let mut dst = Window::new(1, 10, 0, 0).unwrap();
+        dst.mvaddstr(0, 0, "中中").unwrap();
+        let mut small = Window::new(1, 1, 0, 0).unwrap();
+        small.mvaddstr(0, 0, "x").unwrap();
+        h.screen
+            .copywin(&small, &mut dst, 0, 0, 0, 1, 0, 1, false)
+            .unwrap();
+        let text = dst.line(0).unwrap().text();
+        assert_eq!(text[0].spacing_char(), ' ');
+        assert_eq!(text[1].spacing_char(), 'x');
+        assert_eq!(text[2].spacing_char(), '中');
+        assert!(text[3].is_null());
     }
 
-    /// Control whether environment variables are used for screen size.
-    ///
-    /// This function must be called **before** `Screen::init()` to have an effect.
-    ///
-    /// When `f` is `true` (the default), the LINES and COLUMNS environment
-    /// variables are used to determine the screen size. When `false`, only
-    /// the terminfo/termcap values and ioctl are used.
-    ///
-    /// # Example
-    ///
-    /// ```rust,ignore
-    /// use ncurses::screen::globals::use_env;
-    ///
-    /// // Don't use LINES/COLUMNS environment variables
-    /// use_env(false);
-    ///
-    /// let screen = Screen::init()?;
-    /// ```
-    pub fn use_env(f: bool) {
-        USE_ENV.with(|v| v.set(f));
+    #[test]
+    fn test_flash_fallback() {
+        let mut h = Harness::new();
+        h.screen.mvaddstr(0, 0, "hello").unwrap();
+        h.screen.refresh().unwrap();
+
+        let before = h.output_len() as usize;
+        h.screen.flash().unwrap();
+        assert!(contains(&h.output()[before..], b"\x1b[?5h"));
+
+        // Without the capability the screen is repainted reversed, then back
+        h.screen.terminal.set_term_type("vt100");
+        assert!(h.screen.tigetstr("flash").is_none());
+        h.screen.mvaddstr(1, 0, "pending").unwrap();
+        h.screen.noutrefresh().unwrap();
+
+        let before = h.output_len() as usize;
+        h.screen.flash().unwrap();
+        let out = h.output()[before..].to_vec();
+        assert!(!contains(&out, b"\x1b[?5h"));
+        let reverse = out.windows(6).position(|w| w == b"\x1b[0;7m").unwrap();
+        let restore = out.windows(4).position(|w| w == b"\x1b[0m").unwrap();
+        assert!(reverse < restore);
+        assert_eq!(out.windows(5).filter(|w| w == b"hello").count(), 2);
+        assert!(!contains(&out, b"pending"));
+
+        // The pending update is still shown by the next doupdate
+        h.screen.doupdate().unwrap();
+        assert!(contains(&h.output(), b"pending"));
     }
 
-    /// Get the current use_env setting.
-    pub fn get_use_env() -> bool {
-        USE_ENV.with(|v| v.get())
+    #[test]
+    #[cfg(feature = "wide")]
+    fn test_setcchar_combining_capacity() {
+        use crate::types::MAX_COMBINING;
+        use crate::wide::CCharT;
+
+        let mut wc = vec!['e'];
+        wc.extend(std::iter::repeat_n('\u{0301}', MAX_COMBINING));
+        wc.push('\0');
+        let mut cell = CCharT::new();
+        Screen::setcchar(&mut cell, &wc, attr::A_BOLD, 0).unwrap();
+        assert_eq!(cell.char_count(), 1 + MAX_COMBINING);
+
+        let mut out = ['x'; 8];
+        let (mut attrs, mut pair) = (0, 0);
+        Screen::getcchar(&cell, &mut out, &mut attrs, &mut pair).unwrap();
+        assert_eq!(&out[..wc.len()], &wc[..]);
+        assert_ne!(attrs & attr::A_BOLD, 0);
+
+        // Too many marks is an error and leaves the target alone
+        wc.insert(1, '\u{0308}');
+        let before = cell;
+        assert!(matches!(
+            Screen::setcchar(&mut cell, &wc, attr::A_NORMAL, 0),
+            Err(Error::InvalidArgument(_))
+        ));
+        assert_eq!(cell, before);
     }
 
-    /// Control whether ioctl is used for screen size detection.
-    ///
-    /// This function must be called **before** `Screen::init()` to have an effect.
-    ///
-    /// When `f` is `true` (the default), the terminal ioctl (TIOCGWINSZ) is
-    /// used to get the actual terminal size. When `false`, only the terminfo
-    /// values and environment variables (if enabled) are used.
-    ///
-    /// # Example
-    ///
-    /// ```rust,ignore
-    /// use ncurses::screen::globals::use_tioctl;
-    ///
-    /// // Don't use ioctl for size detection
-    /// use_tioctl(false);
-    ///
-    /// let screen = Screen::init()?;
-    /// ```
-    pub fn use_tioctl(f: bool) {
-        USE_TIOCTL.with(|v| v.set(f));
+    #[test]
+    fn test_resize_term_preserves_contents() {
+        let mut h = Harness::new();
+        let (lines, cols) = (h.screen.lines(), h.screen.cols());
+        h.screen.mvaddstr(1, 2, "kept").unwrap();
+        h.screen.mvaddstr(lines - 1, 0, "bottom").unwrap();
+
+        assert!(!h.screen.is_term_resized(lines, cols));
+        assert!(h.screen.is_term_resized(lines + 10, cols + 20));
+        h.screen.resize_term(lines + 10, cols + 20).unwrap();
+        assert!(!h.screen.is_term_resized(lines + 10, cols + 20));
+
+        let stdscr = h.screen.stdscr_mut();
+        assert_eq!(stdscr.mvinnstr(1, 2, 4).unwrap(), "kept");
+        assert_eq!(stdscr.mvinnstr(lines - 1, 0, 6).unwrap(), "bottom");
+        assert_eq!(stdscr.getmaxy(), lines + 10);
+        assert_eq!(stdscr.getmaxx(), cols + 20);
+
+        // Scrolling still reaches the new bottom line
+        assert_eq!(stdscr.getscrreg(), (0, lines + 9));
+
+        // The next update clears the terminal and repaints the kept contents
+        let before = h.output_len() as usize;
+        h.screen.refresh().unwrap();
+        let out = &h.output()[before..];
+        assert!(contains(out, b"\x1b[2J"));
+        assert!(contains(out, b"kept"));
+        assert!(contains(out, b"bottom"));
     }
 
-    /// Get the current use_tioctl setting.
-    pub fn get_use_tioctl() -> bool {
-        USE_TIOCTL.with(|v| v.get())
+    #[test]
+    #[cfg(feature = "wide")]
+    fn test_get_wch_utf8_with_and_without_keypad() {
+        use crate::wide::WideInput;
+
+        let mut h = Harness::new();
+        h.screen.stdscr_mut().nodelay(true);
+        for keypad in [false, true] {
+            h.screen.stdscr_mut().keypad(keypad);
+            h.feed("€".as_bytes());
+            assert_eq!(h.screen.get_wch().unwrap(), WideInput::Char('€'));
+
+            // A typed ESC directly followed by a multibyte character
+            h.feed("\x1b€x".as_bytes());
+            assert_eq!(h.screen.get_wch().unwrap(), WideInput::Char('\x1b'));
+            assert_eq!(h.screen.get_wch().unwrap(), WideInput::Char('€'));
+            assert_eq!(h.screen.get_wch().unwrap(), WideInput::Char('x'));
+            assert_eq!(h.screen.get_wch().unwrap(), WideInput::None);
+        }
+
+        // A pushed-back key code is never taken for a continuation byte
+        h.screen.ungetch(KEY_MOUSE).unwrap();
+        h.screen.ungetch(0xe2).unwrap();
+        assert_eq!(h.screen.get_wch().unwrap(), WideInput::Char('\u{e2}'));
+        assert_eq!(h.screen.get_wch().unwrap(), WideInput::Key(KEY_MOUSE));
     }
 
-    /// Rip off a line from the top or bottom of the screen.
-    ///
-    /// This function must be called **before** `Screen::init()` to have an effect.
-    /// It reduces the size of stdscr by one line and creates a separate window
-    /// for that line.
-    ///
-    /// # Arguments
-    ///
-    /// * `line` - If positive, rip off a line from the top of the screen.
-    ///   If negative, rip off a line from the bottom.
-    /// * `init` - An optional initialization callback that will be called with
-    ///   the window and its width. In Rust, you typically don't need
-    ///   this callback; you can access ripped-off windows after init.
-    ///
-    /// # Returns
-    ///
-    /// Returns `Ok(())` if the ripoff was registered, `Err` if the maximum
-    /// number of ripoffs (5) has been reached.
-    ///
-    /// # Example
-    ///
-    /// ```rust,ignore
-    /// use ncurses::screen::globals::ripoffline;
-    ///
-    /// // Reserve a line at the top for a header
-    /// ripoffline(1, None)?;
-    ///
-    /// // Reserve a line at the bottom for a status bar
-    /// ripoffline(-1, None)?;
-    ///
-    /// let screen = Screen::init()?;
-    /// ```
-    ///
-    /// # Note
-    ///
-    /// In ncurses-pure, the callback argument is optional and mostly for API
-    /// compatibility. The recommended pattern is to create your own windows
-    /// after initialization for header/footer lines.
-    pub fn ripoffline(line: i32, init: Option<fn(&mut Window, i32) -> i32>) -> Result<()> {
-        const MAX_RIPS: usize = 5;
+    #[test]
+    fn test_dump_state() {
+        let mut h = Harness::new();
+        h.screen.resize_term(3, 12).unwrap();
+        h.screen.mvaddstr(0, 0, "hello").unwrap();
+        h.screen.attron(attr::A_BOLD).unwrap();
+        h.screen.mvaddstr(1, 3, "bold").unwrap();
+        h.screen.attroff(attr::A_BOLD).unwrap();
+        h.screen.refresh().unwrap();
+        h.screen.mvaddstr(2, 0, "pending").unwrap();
+
+        let mut dump = Vec::new();
+        h.screen.dump_state(&mut dump).unwrap();
+        let dump = String::from_utf8(dump).unwrap();
+        let sections: Vec<&str> = dump.split("newscr").collect();
+        let (stdscr, rest) = (sections[0], sections[1]);
+        let (newscr, curscr) = rest.split_once("curscr").unwrap();
+
+        assert!(stdscr.starts_with("stdscr 3x12 cursor (2,7) touched [2]"));
+        assert!(stdscr.contains("\n   0|hello       |\n"));
+        assert!(stdscr.contains("\n*  2|pending     |\n"));
+        assert!(stdscr.contains("attributes:\n   1: 3-6 BOLD\n"));
+        // The last change has not reached the virtual or physical screen
+        assert!(newscr.contains("\n   1|   bold     |\n"));
+        assert!(newscr.contains("\n   2|            |\n"));
+        assert!(curscr.contains("\n   0|hello       |\n"));
+    }
 
-        if line == 0 {
-            return Ok(());
+    #[test]
+    fn test_edit_line_history() {
+        let mut h = Harness::new();
+        let mut editor = LineEditor::new();
+        h.feed(b"first\rsecond\r");
+        assert_eq!(h.screen.edit_line(&mut editor, "> ").unwrap(), "first");
+        assert_eq!(h.screen.edit_line(&mut editor, "> ").unwrap(), "second");
+        assert_eq!(h.screen.stdscr_mut().mvinnstr(0, 0, 7).unwrap(), "> first");
+
+        // Up twice recalls the older entry, which is then edited
+        h.feed(b"\x1b[A\x1b[A\x1b[H!\r");
+        assert_eq!(h.screen.edit_line(&mut editor, "> ").unwrap(), "!first");
+        assert_eq!(editor.history(), ["first", "second", "!first"]);
+        assert!(contains(&h.output(), b"!first"));
+
+        #[cfg(feature = "wide")]
+        {
+            h.feed("héllo €\x1b[D日\r".as_bytes());
+            assert_eq!(h.screen.edit_line(&mut editor, "> ").unwrap(), "héllo 日€");
         }
+    }
 
-        RIPOFF_LINES.with(|rips| {
-            let mut rips = rips.borrow_mut();
-            if rips.len() >= MAX_RIPS {
-                return Err(Error::InvalidArgument(
-                    "maximum ripoff lines reached".to_string(),
-                ));
-            }
-            rips.push(RipoffSpec {
-                line: if line > 0 { 1 } else { -1 },
-                callback: init,
-            });
-            Ok(())
-        })
+    #[test]
+    fn test_flush_sends_buffered_output() {
+        let mut h = Harness::new();
+        let before = h.output_len();
+        h.screen.curs_set(0).unwrap();
+        assert_eq!(h.output_len(), before);
+
+        h.screen.flush().unwrap();
+        assert!(contains(&h.output()[before as usize..], b"\x1b[?25l"));
+        // Nothing left to send
+        let after = h.output_len();
+        h.screen.flush().unwrap();
+        assert_eq!(h.output_len(), after);
     }
 
-    /// Get the number of ripped-off lines.
-    #[doc(hidden)]
-    pub fn get_ripoff_count() -> (i32, i32) {
-        RIPOFF_LINES.with(|rips| {
-            let rips = rips.borrow();
-            let top = rips.iter().filter(|r| r.line > 0).count() as i32;
-            let bottom = rips.iter().filter(|r| r.line < 0).count() as i32;
-            (top, bottom)
-        })
+    #[test]
+    fn test_newterm_binds_type_and_fds() {
+        let mut fds = [0; 2];
+        // SAFETY: `fds` is a valid two-element array for `pipe` to fill.
+        assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+        // SAFETY: both descriptors were just created by `pipe` and are owned here.
+        let (reader, mut writer) =
+            unsafe { (File::from_raw_fd(fds[0]), File::from_raw_fd(fds[1])) };
+        let output = tempfile::tempfile().unwrap();
+
+        let mut screen = newterm(Some("vt100"), output.as_raw_fd(), reader.as_raw_fd()).unwrap();
+        assert_eq!(screen.termname(), "vt100");
+        assert!(!screen.has_colors());
+
+        // Input and output go through the given descriptors
+        writer.write_all(b"q").unwrap();
+        assert_eq!(screen.getch().unwrap(), b'q' as i32);
+        screen.mvaddstr(0, 0, "vt100-out").unwrap();
+        screen.refresh().unwrap();
+        let mut buf = vec![0; output.metadata().unwrap().len() as usize];
+        output.read_exact_at(&mut buf, 0).unwrap();
+        assert!(contains(&buf, b"vt100-out"));
+
+        set_term(&screen).unwrap();
+        assert_eq!(globals::LINES(), screen.lines());
+        delscreen(screen);
     }
 
-    /// Clear ripoff specifications (called after Screen::init).
-    #[doc(hidden)]
-    pub fn clear_ripoffs() {
-        RIPOFF_LINES.with(|rips| {
-            rips.borrow_mut().clear();
-        });
+    #[test]
+    fn test_escdelay_zero() {
+        let mut h = Harness::new();
+        h.screen.stdscr_mut().keypad(true);
+        h.screen.set_escdelay(0);
+
+        h.feed(b"\x1b");
+        let start = Instant::now();
+        assert_eq!(h.screen.getch().unwrap(), 0x1b);
+        assert!(start.elapsed() < Duration::from_millis(50));
+
+        // A sequence that is already buffered is still decoded
+        h.feed(b"\x1b[A");
+        assert_eq!(h.screen.getch().unwrap(), crate::key::KEY_UP);
     }
-}
 
-// ============================================================================
-// Terminfo low-level functions (stubs)
-// ============================================================================
+    #[test]
+    fn test_scr_dump_in_memory_round_trip() {
+        let mut h = Harness::new();
+        h.screen.attron(attr::A_BOLD).unwrap();
+        h.screen.mvaddstr(1, 2, "saved").unwrap();
+        h.screen.attroff(attr::A_BOLD).unwrap();
+        h.screen.refresh().unwrap();
+        let dump = h.screen.scr_dump_to_vec();
+
+        // The file version writes the same bytes
+        let path = tempfile::NamedTempFile::new().unwrap().into_temp_path();
+        h.screen.scr_dump(path.to_str().unwrap()).unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), dump);
+
+        h.screen.erase().unwrap();
+        h.screen.refresh().unwrap();
+        h.screen.scr_restore_from_slice(&dump).unwrap();
+        let cell = h.screen.newscr().line(1).unwrap().get(2);
+        #[cfg(feature = "wide")]
+        assert_eq!((cell.spacing_char(), cell.attrs()), ('s', attr::A_BOLD));
+        #[cfg(not(feature = "wide"))]
+        assert_eq!(cell, b's' as ChType | attr::A_BOLD);
+        assert_eq!(h.screen.scr_dump_to_vec(), dump);
 
-/// Initialize the terminal description.
-///
-/// This is a stub function for ncurses API compatibility.
-/// In this implementation, terminal handling is done internally by the Screen.
-///
-/// # Arguments
-///
-/// * `_term` - Terminal type name (ignored in this implementation)
-/// * `_filedes` - File descriptor for the terminal (ignored in this implementation)
-///
-/// # Returns
-///
-/// Always returns `Ok(())` since terminal setup is handled by Screen::init().
-pub fn setupterm(_term: Option<&str>, _filedes: i32) -> Result<()> {
-    // In our implementation, terminal setup is handled by Screen::init()
-    Ok(())
-}
+        assert!(h
+            .screen
+            .scr_restore_from_slice(&dump[..dump.len() / 2])
+            .is_err());
+        assert!(h.screen.scr_restore_from_slice(b"NOTADUMP").is_err());
+    }
 
-/// Set the current terminal.
-///
-/// This is a stub function for ncurses API compatibility.
-/// In this implementation, terminal handling is done internally by the Screen.
-///
-/// # Returns
-///
-/// Always returns `Ok(())`.
-pub fn set_curterm() -> Result<()> {
-    // No-op in our implementation
-    Ok(())
-}
+    #[test]
+    #[cfg(feature = "wide")]
+    fn test_unget_wch_order() {
+        use crate::wide::WideInput;
+
+        let mut h = Harness::new();
+        h.screen.stdscr_mut().nodelay(true);
+        h.screen.unget_wch('a').unwrap();
+        h.screen.unget_wch('€').unwrap();
+        h.screen.ungetch(b'x' as i32).unwrap();
+        h.screen.unget_wch('漢').unwrap();
+        for expected in ['漢', 'x', '€', 'a'] {
+            assert_eq!(h.screen.get_wch().unwrap(), WideInput::Char(expected));
+        }
+        assert_eq!(h.screen.get_wch().unwrap(), WideInput::None);
+
+        // A character that does not fit is rejected whole
+        while h.screen.ungetch(b'y' as i32).is_ok() {}
+        h.screen.getch().unwrap();
+        assert!(matches!(h.screen.unget_wch('€'), Err(Error::BufferFull)));
+        assert_eq!(h.screen.get_wch().unwrap(), WideInput::Char('y'));
+    }
 
-/// Delete a terminal description.
-///
-/// This is a stub function for ncurses API compatibility.
-/// In this implementation, terminal descriptions are managed automatically.
-///
-/// # Returns
-///
-/// Always returns `Ok(())`.
-pub fn del_curterm() -> Result<()> {
-    // No-op in our implementation - memory is managed automatically
-    Ok(())
-}
+    #[test]
+    fn test_ripoff_lines_follow_resize() {
+        thread_local! {
+            static WIDTH: Cell<i32> = const { Cell::new(0) };
+        }
+        fn header(win: &mut Window, cols: i32) -> i32 {
+            WIDTH.with(|w| w.set(cols));
+            win.mvaddstr(0, 0, "header").map_or(-1, |_| 0)
+        }
 
-/// Restart terminal setup.
-///
-/// This is a stub function for ncurses API compatibility.
-/// In this implementation, terminal restart is handled by re-initializing Screen.
-///
-/// # Arguments
-///
-/// * `_term` - Terminal type name (ignored)
-/// * `_filedes` - File descriptor (ignored)
-/// * `_errret` - Error return location (ignored)
-///
-/// # Returns
-///
-/// Always returns `Ok(())`.
-pub fn restartterm(_term: Option<&str>, _filedes: i32, _errret: Option<&mut i32>) -> Result<()> {
-    // No-op in our implementation
-    Ok(())
-}
+        globals::ripoffline(1, Some(header)).unwrap();
+        globals::ripoffline(-1, None).unwrap();
+        let mut h = Harness::new();
+        assert_eq!(globals::get_ripoff_count(), (0, 0));
+        let (lines, cols) = (h.screen.lines(), h.screen.cols());
+
+        assert_eq!(h.screen.stdscr().getmaxy(), lines - 2);
+        assert_eq!(h.screen.stdscr().getbegy(), 1);
+        assert_eq!(h.screen.ripoff_window(1).unwrap().getbegy(), lines - 1);
+        assert!(h.screen.ripoff_window(2).is_none());
+        assert_eq!(WIDTH.with(Cell::get), cols);
+
+        h.screen.mvaddstr(0, 0, "body").unwrap();
+        h.screen.refresh().unwrap();
+        assert!(contains(&h.output(), b"header"));
+        assert_eq!(h.screen.newscr.mvinnstr(1, 0, 4).unwrap(), "body");
+
+        // The hardware cursor is placed in screen coordinates
+        h.screen.mv(5, 4).unwrap();
+        h.screen.refresh().unwrap();
+        assert!(h.output().ends_with(b"\x1b[7;5H"));
+
+        h.screen.resize_term(lines + 5, cols + 10).unwrap();
+        assert_eq!(h.screen.stdscr().getmaxy(), lines + 3);
+        assert_eq!(h.screen.stdscr().getbegy(), 1);
+        for i in 0..2 {
+            assert_eq!(h.screen.ripoff_window(i).unwrap().getmaxx(), cols + 10);
+        }
+        assert_eq!(h.screen.ripoff_window(0).unwrap().getbegy(), 0);
+        assert_eq!(h.screen.ripoff_window(1).unwrap().getbegy(), lines + 4);
+        assert_eq!(WIDTH.with(Cell::get), cols + 10);
+    }
 
-/// Output video attributes to the terminal.
-///
-/// This is a stub function for ncurses API compatibility.
-/// In our implementation, attribute handling is done through the Screen methods.
-///
-/// # Arguments
-///
-/// * `_attrs` - The attributes to set
-///
-/// # Returns
-///
-/// Always returns `Ok(())`.
-pub fn vidattr(_attrs: AttrT) -> Result<()> {
-    // In our implementation, use Screen's attribute methods
-    Ok(())
-}
+    #[test]
+    fn test_with_timeout_restores_delay() {
+        let mut h = Harness::new();
+        h.screen.timeout(250);
 
-/// Output video attributes using a custom output function.
-///
-/// This is a stub function for ncurses API compatibility.
-/// Modern terminals don't need this low-level control.
-///
-/// # Arguments
-///
-/// * `_attrs` - The attributes to set
-/// * `_putc` - Custom output function (ignored)
-///
-/// # Returns
-///
-/// Always returns `Ok(())`.
-pub fn vidputs<F>(_attrs: AttrT, _putc: F) -> Result<()>
-where
-    F: FnMut(i32) -> i32,
-{
-    // No-op in our implementation
-    Ok(())
-}
+        let key = h.screen.with_timeout(0, |screen| {
+            assert_eq!(screen.stdscr().getdelay(), 0);
+            screen.getch()
+        });
+        assert!(matches!(key, Err(Error::NoInput)));
+        assert_eq!(h.screen.stdscr().getdelay(), 250);
 
-/// Move the physical cursor.
-///
-/// This is a low-level function that moves the physical terminal cursor
-/// from one position to another without going through the curses window system.
-///
-/// This is a stub that always returns Ok - use Screen's cursor positioning
-/// methods for actual cursor control.
-///
-/// # Arguments
-///
-/// * `_oldrow` - Current row position
-/// * `_oldcol` - Current column position  
-/// * `_newrow` - Target row position
-/// * `_newcol` - Target column position
-///
-/// # Returns
-///
-/// Always returns `Ok(())`.
-pub fn mvcur(_oldrow: i32, _oldcol: i32, _newrow: i32, _newcol: i32) -> Result<()> {
-    // In our implementation, cursor movement is handled through Screen
-    Ok(())
-}
+        h.feed(b"a");
+        let key = h.screen.with_timeout(-1, |screen| screen.getch());
+        assert_eq!(key.unwrap(), b'a' as i32);
+        assert_eq!(h.screen.stdscr().getdelay(), 250);
+    }
 
-// ============================================================================
-// Termcap compatibility functions (stubs)
-// ============================================================================
+    #[test]
+    fn test_key_resize_coalesced() {
+        let mut h = Harness::new();
+        h.screen.nodelay(true);
+        h.feed(b"x");
+        h.screen.ungetch(b'y' as i32).unwrap();
 
-/// Get a termcap entry.
-///
-/// This is a stub for ncurses termcap compatibility.
-/// Returns 1 (success) always since our implementation doesn't use termcap.
-///
-/// # Arguments
-///
-/// * `_bp` - Buffer for the termcap entry (unused)
-/// * `_name` - Terminal name (unused)
-///
-/// # Returns
-///
-/// Always returns 1 (success).
-pub fn tgetent(_bp: &mut [u8], _name: &str) -> i32 {
-    1 // Success
-}
+        h.screen.update_term_size().unwrap();
+        h.screen.update_term_size().unwrap();
+        h.screen.ungetch(KEY_RESIZE).unwrap();
+        assert!(h.screen.has_key());
 
-/// Get a boolean termcap capability.
-///
-/// This is a stub for ncurses termcap compatibility.
-///
-/// # Arguments
-///
-/// * `_id` - The capability ID
-///
-/// # Returns
-///
-/// Always returns 0 (capability not present).
-pub fn tgetflag(_id: &str) -> i32 {
-    0 // Not present
-}
+        assert_eq!(h.screen.getch().unwrap(), KEY_RESIZE);
+        assert_eq!(h.screen.getch().unwrap(), b'y' as i32);
+        assert_eq!(h.screen.getch().unwrap(), b'x' as i32);
+        assert!(matches!(h.screen.getch(), Err(Error::NoInput)));
+    }
 
-/// Get a numeric termcap capability.
-///
-/// This is a stub for ncurses termcap compatibility.
-///
-/// # Arguments
-///
-/// * `_id` - The capability ID
-///
-/// # Returns
-///
-/// Always returns -1 (capability not present).
-pub fn tgetnum(_id: &str) -> i32 {
-    -1 // Not present
-}
+    #[test]
+    fn test_doupdate_short_cursor_moves() {
+        let mut h = Harness::new();
+        h.screen.mvaddstr(3, 0, "..........").unwrap();
+        h.screen.refresh().unwrap();
+        let start = h.output_len();
+
+        for x in [2, 4, 6, 9] {
+            h.screen.mvaddch(3, x, b'#' as ChType).unwrap();
+        }
+        h.screen.mv(5, 0).unwrap();
+        h.screen.refresh().unwrap();
+        let out = h.output()[start as usize..].to_vec();
 
-/// Get a string termcap capability.
-///
-/// This is a stub for ncurses termcap compatibility.
-///
-/// # Arguments
-///
-/// * `_id` - The capability ID
-///
-/// # Returns
-///
-/// Always returns `None` (capability not present).
-pub fn tgetstr(_id: &str) -> Option<String> {
-    None
-}
+        // One absolute move to the first run; the gaps are rewritten and
+        // the final cursor position is reached relatively
+        let moves = out.windows(2).filter(|w| w == b"\x1b[").count();
+        assert!(moves < 4, "{:?}", String::from_utf8_lossy(&out));
+        assert!(contains(&out, b"\x1b[4;3H#.#.#..#"));
+        assert!(contains(&out, b"\x1b[2B\r"));
+    }
 
-/// Apply parameters to a termcap string capability.
-///
-/// This is a stub for ncurses termcap compatibility.
-///
-/// # Arguments
-///
-/// * `cap` - The capability string
-/// * `_col` - Column parameter
-/// * `_row` - Row parameter
-///
-/// # Returns
-///
-/// Returns the capability string unchanged.
-pub fn tgoto(cap: &str, _col: i32, _row: i32) -> String {
-    cap.to_string()
-}
+    #[test]
+    fn test_read_raw_byte() {
+        let mut h = Harness::new();
+        h.screen.keypad(true);
+        h.screen.nl().unwrap();
+        h.feed(b"\x1b[A\r");
+
+        let mut bytes = Vec::new();
+        while let Some(b) = h.screen.read_raw_byte(Delay::NoDelay).unwrap() {
+            bytes.push(b);
+        }
+        assert_eq!(bytes, b"\x1b[A\r");
+        assert_eq!(h.screen.read_raw_byte(Delay::Timeout(10)).unwrap(), None);
 
-/// Output a termcap string with padding.
-///
-/// This is a stub for ncurses termcap compatibility.
-/// Since modern terminals don't need padding, this just outputs the string.
-///
-/// # Arguments
-///
-/// * `_str` - The string to output
-/// * `_affcnt` - Affected lines count (for padding calculation)
-/// * `_putc` - Output function
-///
-/// # Returns
-///
-/// Always returns `Ok(())`.
-pub fn tputs<F>(_str: &str, _affcnt: i32, _putc: F) -> Result<()>
-where
-    F: FnMut(i32) -> i32,
-{
-    // No-op - modern terminals don't need padding
-    Ok(())
-}
+        // Pushed-back bytes come first; key codes are left for getch()
+        h.screen.ungetch(crate::key::KEY_UP).unwrap();
+        h.screen.ungetch(b'z' as i32).unwrap();
+        assert_eq!(h.screen.read_raw_byte(Delay::NoDelay).unwrap(), Some(b'z'));
+        assert_eq!(h.screen.read_raw_byte(Delay::NoDelay).unwrap(), None);
+        assert_eq!(h.screen.getch().unwrap(), crate::key::KEY_UP);
+    }
 
-// ============================================================================
-// Additional ncurses compatibility functions
-// ============================================================================
+    #[cfg(feature = "mouse")]
+    #[test]
+    fn test_endwin_disables_all_mouse_modes() {
+        use crate::mouse::ALL_MOUSE_EVENTS;
 
-/// Initialize curses and return the standard screen.
-///
-/// This is the traditional ncurses initialization function. In ncurses-pure,
-/// it's an alias for `Screen::init()`.
-///
-/// For new code, prefer using `Screen::init()` directly.
-pub fn initscr() -> Result<Screen> {
-    Screen::init()
-}
+        let mut h = Harness::new();
+        h.screen.mousemask(ALL_MOUSE_EVENTS);
+        h.screen
+            .set_mouse_protocol(MouseProtocol::AnyEvent)
+            .unwrap();
+        assert!(contains(&h.output(), b"\x1b[?1003h"));
+        assert!(h.screen.set_mouse_protocol(MouseProtocol::None).is_err());
 
-/// Initialize a new terminal.
-///
-/// This function creates a new Screen for a specific terminal type and output.
-/// In this implementation, it's an alias for `Screen::init()` as we only
-/// support the default terminal.
-///
-/// # Arguments
-///
-/// * `_type` - Terminal type (ignored, uses $TERM)
-/// * `_outf` - Output file (ignored, uses stdout)
-/// * `_inf` - Input file (ignored, uses stdin)
-///
-/// # Returns
-///
-/// A new Screen instance.
-pub fn newterm(_type: Option<&str>, _outf: Option<()>, _inf: Option<()>) -> Result<Screen> {
-    Screen::init()
-}
+        let start = h.output_len() as usize;
+        h.screen.endwin().unwrap();
+        let out = h.output()[start..].to_vec();
+        assert!(contains(&out, b"\x1b[?1003l"));
+        assert!(contains(&out, b"\x1b[?1002l"));
+        assert!(contains(&out, b"\x1b[?1006l"));
+    }
 
-/// Set the current terminal (screen).
-///
-/// In ncurses-pure, there is no global current screen, so this is a no-op stub.
-/// Each Screen instance manages its own state.
-///
-/// # Arguments
-///
-/// * `_new` - The new screen to set as current (ignored)
-///
-/// # Returns
-///
-/// Always returns `Ok(())`.
-pub fn set_term(_new: &Screen) -> Result<()> {
-    // In our implementation, each Screen is independent
-    Ok(())
-}
+    #[test]
+    fn test_render_to_ansi() {
+        use crate::color::{COLOR_BLUE, COLOR_GREEN, COLOR_RED, COLOR_YELLOW};
+
+        let mut h = Harness::new();
+        h.screen.start_color().unwrap();
+        h.screen.init_pair(1, COLOR_RED, COLOR_BLUE).unwrap();
+        h.screen.init_pair(2, COLOR_YELLOW, COLOR_GREEN).unwrap();
+        h.screen
+            .stdscr_mut()
+            .attrset(attr::A_BOLD | attr::color_pair(1))
+            .unwrap();
+        h.screen.stdscr_mut().mvaddstr(0, 0, "hot").unwrap();
+        h.screen.stdscr_mut().attrset(attr::color_pair(2)).unwrap();
+        h.screen.stdscr_mut().mvaddstr(1, 2, "cold").unwrap();
+        h.screen.refresh().unwrap();
+
+        let ansi = h.screen.render_to_ansi();
+        assert!(ansi.contains("\x1b[0;1m\x1b[31m\x1b[44mhot"));
+        assert!(ansi.contains("\x1b[0m\x1b[33m\x1b[42mcold"));
+        assert_eq!(ansi.lines().count(), h.screen.lines() as usize);
+
+        let text = h.screen.render_to_text();
+        let rows: Vec<&str> = text.lines().collect();
+        assert_eq!(rows.len(), h.screen.lines() as usize);
+        assert!(rows[0].starts_with("hot "));
+        assert!(rows[1].starts_with("  cold "));
+        assert_eq!(rows[0].chars().count(), h.screen.cols() as usize);
+    }
+
+    #[test]
+    fn test_putwin_getwin_round_trip() {
+        let h = Harness::new();
+        let path = tempfile::NamedTempFile::new().unwrap().into_temp_path();
+        let path = path.to_str().unwrap();
+
+        let mut win = Window::new(6, 12, 2, 3).unwrap();
+        win.scrollok(true);
+        win.keypad(true);
+        win.set_wrap(false);
+        win.timeout(250);
+        win.setscrreg(1, 4).unwrap();
+        win.bkgdset(b'.' as ChType | attr::color_pair(3));
+        win.attrset(attr::A_BOLD | attr::color_pair(2)).unwrap();
+        win.mvaddstr(2, 1, "saved").unwrap();
+        h.screen.putwin(&win, path).unwrap();
+
+        let restored = h.screen.getwin(path).unwrap();
+        assert_eq!(restored.cursor(), win.cursor());
+        assert_eq!(restored.origin(), (2, 3));
+        assert!(restored.is_scrollok());
+        assert!(restored.is_keypad());
+        assert!(!restored.is_wrap());
+        assert_eq!(restored.getdelay(), 250);
+        assert_eq!(restored.getscrreg(), (1, 4));
+        assert_eq!(restored.getbkgd(), win.getbkgd());
+        assert_eq!(restored.getattrs(), win.getattrs());
+        assert_eq!(restored.snapshot(), win.snapshot());
+
+        // Extended background pairs beyond the attribute bits survive too
+        #[cfg(all(feature = "wide", feature = "ext-colors"))]
+        {
+            let mut bkgrnd = win.getbkgrnd();
+            bkgrnd.ext_color = 300;
+            win.bkgrndset(&bkgrnd);
+            h.screen.putwin(&win, path).unwrap();
+            let restored = h.screen.getwin(path).unwrap();
+            assert_eq!(restored.getbkgrnd().ext_color, 300);
+            assert_eq!(restored.getbkgrnd(), win.getbkgrnd());
+        }
+    }
+
+    #[test]
+    fn test_getwin_reads_version_1() {
+        let h = Harness::new();
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        let mut data = b"NCWIN\x01".to_vec();
+        for value in [1u32, 2, 0, 0] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        for c in ['o', 'k'] {
+            data.extend_from_slice(&(c as u32).to_le_bytes());
+            #[cfg(feature = "wide")]
+            data.extend_from_slice(&0u32.to_le_bytes());
+        }
+        file.write_all(&data).unwrap();
+
+        let mut win = h.screen.getwin(file.path().to_str().unwrap()).unwrap();
+        assert_eq!((win.getmaxy(), win.getmaxx()), (1, 2));
+        assert_eq!(win.cursor(), (0, 0));
+        assert!(!win.is_scrollok());
+        assert!(win.is_wrap());
+        assert_eq!(win.mvinch(0, 1).unwrap() & attr::A_CHARTEXT, b'k' as ChType);
+    }
+
+    #[test]
+    fn test_monochrome_fallback() {
+        use crate::color::{COLOR_BLACK, COLOR_BLUE, COLOR_RED, COLOR_WHITE};
+
+        let mut h = Harness::with_term(Some("vt100"));
+        assert!(!h.screen.has_colors());
+        assert_eq!(h.screen.num_colors(), 0);
+        h.screen.start_color().unwrap();
+        h.screen.init_pair(1, COLOR_RED + 8, COLOR_BLACK).unwrap();
+        h.screen.init_pair(2, COLOR_WHITE, COLOR_BLUE).unwrap();
+
+        h.screen.attrset(attr::color_pair(1)).unwrap();
+        h.screen.mvaddstr(0, 0, "alert").unwrap();
+        h.screen.attrset(attr::color_pair(2)).unwrap();
+        h.screen.mvaddstr(1, 0, "title").unwrap();
+        h.screen.refresh().unwrap();
+
+        let out = h.output();
+        assert!(contains(&out, b"\x1b[0;1malert"));
+        assert!(contains(&out, b"\x1b[0;7mtitle"));
+        for color in [&b"[39m"[..], b"[49m", b"[91m", b"[30m", b"[37m", b"[44m"] {
+            assert!(!contains(&out, color));
+        }
+    }
 
-/// Delete a screen.
-///
-/// In ncurses-pure, screens are automatically cleaned up when dropped.
-/// This function is a no-op stub for API compatibility.
-///
-/// # Arguments
-///
-/// * `_sp` - The screen to delete (ignored)
-pub fn delscreen(_sp: Screen) {
-    // Screen is dropped automatically when it goes out of scope
-}
+    #[test]
+    fn test_input_buffer_capacity() {
+        let mut h = Harness::new();
+        assert_eq!(h.screen.input_buffer_capacity(), crate::input::FIFO_SIZE);
+        assert!(h.screen.set_input_buffer_capacity(0).is_err());
+
+        h.screen.set_input_buffer_capacity(4).unwrap();
+        for ch in "dcba".chars() {
+            h.screen.ungetch(ch as i32).unwrap();
+        }
+        assert!(matches!(
+            h.screen.ungetch('x' as i32),
+            Err(Error::BufferFull)
+        ));
 
-/// Delete a window.
-///
-/// In ncurses-pure, windows are automatically cleaned up when dropped.
-/// This function is provided for API compatibility.
-///
-/// # Arguments
-///
-/// * `_win` - The window to delete (ignored, will be dropped)
-pub fn delwin(_win: Window) {
-    // Window is dropped automatically when it goes out of scope
-}
+        h.screen.set_input_overflow(InputOverflow::DropOldest);
+        h.screen.ungetch('x' as i32).unwrap();
+        let keys: Vec<i32> = (0..4).map(|_| h.screen.getch().unwrap()).collect();
+        assert_eq!(keys, "xabc".chars().map(|c| c as i32).collect::<Vec<_>>());
+        assert!(!h.screen.has_key());
+    }
 
-/// Get the ncurses version string.
-///
-/// Returns the version of ncurses-pure.
-#[must_use]
-pub fn curses_version() -> &'static str {
-    concat!("ncurses-pure ", env!("CARGO_PKG_VERSION"))
-}
+    #[test]
+    fn test_halfdelay_cbreak_toggle() {
+        let mut h = Harness::new();
+        assert_eq!(h.screen.halfdelay_tenths(), None);
+        assert_eq!(h.screen.blocking_timeout(), None);
+
+        h.screen.halfdelay(1).unwrap();
+        assert_eq!(h.screen.halfdelay_tenths(), Some(1));
+        assert_eq!(
+            h.screen.blocking_timeout(),
+            Some(Duration::from_millis(100))
+        );
+        assert!(matches!(h.screen.getch(), Err(Error::Timeout)));
 
-/// Get terminal attributes.
-///
-/// Returns the video attributes supported by the terminal.
-/// In our implementation, we support all standard attributes.
-#[must_use]
-pub fn termattrs() -> AttrT {
-    use crate::attr::*;
-    A_STANDOUT | A_UNDERLINE | A_REVERSE | A_BLINK | A_DIM | A_BOLD | A_INVIS | A_ITALIC
-}
+        h.screen.cbreak().unwrap();
+        assert_eq!(h.screen.halfdelay_tenths(), None);
+        assert_eq!(h.screen.blocking_timeout(), None);
 
-/// Get terminal attributes (X/Open style).
-///
-/// Returns the video attributes supported by the terminal.
-#[must_use]
-pub fn term_attrs() -> AttrT {
-    termattrs()
-}
+        h.screen.halfdelay(3).unwrap();
+        assert_eq!(
+            h.screen.blocking_timeout(),
+            Some(Duration::from_millis(300))
+        );
+        h.feed(b"k");
+        assert_eq!(h.screen.getch().unwrap(), 'k' as i32);
 
-/// Get the binding for a key.
-///
-/// Returns the key definition bound to a specific keycode.
-/// In our implementation, key bindings are fixed.
-///
-/// # Arguments
-///
-/// * `_keycode` - The key code to query
-/// * `_count` - Which binding to get (for keys with multiple bindings)
-///
-/// # Returns
-///
-/// Always returns `None` as custom key bindings aren't supported.
-pub fn keybound(_keycode: i32, _count: i32) -> Option<String> {
-    None
-}
+        h.screen.nocbreak().unwrap();
+        assert_eq!(h.screen.halfdelay_tenths(), None);
+        assert!(h.screen.halfdelay(0).is_err());
+        assert_eq!(h.screen.halfdelay_tenths(), None);
+    }
 
-/// Get the erase character as a wide character.
-///
-/// Returns the terminal's erase character (typically backspace).
-#[cfg(feature = "wide")]
-pub fn killwchar() -> char {
-    '\x15' // Ctrl-U
-}
+    #[test]
+    fn test_record_and_replay_input() {
+        use crate::terminal::ReplayTiming;
+        use crate::types::Size;
+
+        let mut h = Harness::new();
+        let log = tempfile::NamedTempFile::new().unwrap().into_temp_path();
+        h.screen.record_input(&log).unwrap();
+        h.screen.keypad(true);
+        h.feed(b"ab\x1b[Aq");
+        let keys: Vec<i32> = (0..4).map(|_| h.screen.getch().unwrap()).collect();
+        assert_eq!(
+            keys,
+            ['a' as i32, 'b' as i32, crate::key::KEY_UP, 'q' as i32]
+        );
+        h.screen.stop_recording_input().unwrap();
+
+        for timing in [ReplayTiming::Immediate, ReplayTiming::Recorded] {
+            let terminal =
+                Terminal::replay_from(&log, timing, Box::new(std::io::sink()), Size::new(24, 80))
+                    .unwrap();
+            let mut screen = Screen::with_terminal(terminal).unwrap();
+            screen.keypad(true);
+            let replayed: Vec<i32> = (0..4).map(|_| screen.getch().unwrap()).collect();
+            assert_eq!(replayed, keys);
+            assert!(matches!(screen.getch(), Err(Error::Eof)));
+        }
+    }
 
-/// Enable or disable extended names in terminfo.
-///
-/// This is a stub for API compatibility. Extended names are not used
-/// in this implementation.
-///
-/// # Arguments
-///
-/// * `_enable` - Whether to enable extended names
-///
-/// # Returns
-///
-/// Always returns 0.
-pub fn use_extended_names(_enable: bool) -> i32 {
-    0
-}
+    #[test]
+    fn test_wnoutrefresh_rejects_pad() {
+        let mut h = Harness::new();
+        let mut pad = Window::new_pad(10, 40).unwrap();
+        pad.mvaddstr(0, 0, "pad").unwrap();
 
-/// Set legacy coding mode.
-///
-/// This is a stub for API compatibility. Legacy coding is not used
-/// in this implementation.
-///
-/// # Arguments
-///
-/// * `_level` - The legacy coding level
-///
-/// # Returns
-///
-/// Always returns 0.
-pub fn use_legacy_coding(_level: i32) -> i32 {
-    0
-}
+        let err = h.screen.wnoutrefresh(&pad).unwrap_err();
+        assert!(matches!(err, Error::InvalidArgument(ref msg) if msg.contains("pnoutrefresh")));
+        assert!(h.screen.wrefresh(&mut pad).is_err());
+        assert!(pad.is_wintouched());
 
-/// Output video attributes (X/Open style).
-///
-/// This is a stub for API compatibility.
-///
-/// # Arguments
-///
-/// * `_attrs` - The attributes to output
-/// * `_pair` - The color pair
-/// * `_opts` - Options (ignored)
-///
-/// # Returns
-///
-/// Always returns `Ok(())`.
-pub fn vid_attr(_attrs: AttrT, _pair: i16, _opts: Option<()>) -> Result<()> {
-    Ok(())
-}
+        h.screen.pnoutrefresh(&mut pad, 0, 0, 2, 5, 3, 20).unwrap();
+        h.screen.doupdate().unwrap();
+        assert!(contains(&h.output(), b"pad"));
+    }
 
-/// Output video attributes using a custom function (X/Open style).
-///
-/// This is a stub for API compatibility.
-///
-/// # Arguments
-///
-/// * `_attrs` - The attributes to output
-/// * `_pair` - The color pair
-/// * `_opts` - Options (ignored)
-/// * `_putc` - Output function
-///
-/// # Returns
-///
-/// Always returns `Ok(())`.
-pub fn vid_puts<F>(_attrs: AttrT, _pair: i16, _opts: Option<()>, _putc: F) -> Result<()>
-where
-    F: FnMut(i32) -> i32,
-{
-    Ok(())
-}
+    #[test]
+    fn test_window_title() {
+        let mut h = Harness::new();
+        h.screen.push_title().unwrap();
+        h.screen.set_title("build: ok\x07").unwrap();
+        h.screen.flush().unwrap();
+        let out = h.output();
+        assert!(contains(&out, b"\x1b[22;0t\x1b]0;build: ok\x07"));
+
+        assert!(h.screen.pop_title().is_ok());
+        assert!(h.screen.pop_title().is_err());
+        h.screen.push_title().unwrap();
+        let start = h.output_len() as usize;
+        h.screen.endwin().unwrap();
+        assert!(contains(&h.output()[start..], b"\x1b[23;0t"));
+
+        let mut vt = Harness::with_term(Some("vt100"));
+        assert!(matches!(
+            vt.screen.set_title("x"),
+            Err(Error::NotSupported(_))
+        ));
+        assert!(matches!(
+            vt.screen.push_title(),
+            Err(Error::NotSupported(_))
+        ));
+        vt.screen.flush().unwrap();
+        assert!(!contains(&vt.output(), b"\x1b]0;"));
+    }
 
-/// **Not available in Rust** - Use `wprintw()` instead.
-///
-/// The variadic printw functions cannot be directly implemented in Rust.
-/// Use the format! macro and wprintw instead.
-///
-/// # Panics
-/// Always panics - this function is not implemented.
-#[deprecated(since = "0.1.0", note = "Use wprintw with format! instead")]
-pub fn vwprintw(_win: &mut Window, _fmt: &str) -> Result<()> {
-    panic!(
-        "vwprintw is not available in Rust. Use wprintw with format! instead.\n\
-         Example:\n\
-         screen.wprintw(win, &format!(\"Value: {{}}\", value))?;"
-    );
-}
+    #[test]
+    fn test_attr_on_keeps_color_pair() {
+        let mut h = Harness::new();
+        h.screen.attr_set(attr::A_NORMAL, 2).unwrap();
+
+        h.screen
+            .attr_on(attr::A_BOLD | attr::color_pair(3))
+            .unwrap();
+        let (mut attrs, mut pair) = (0, 0);
+        h.screen.attr_get(&mut attrs, &mut pair).unwrap();
+        assert_ne!(attrs & attr::A_BOLD, 0);
+        assert_eq!(pair, 2);
+
+        h.screen
+            .attr_off(attr::A_BOLD | attr::color_pair(2))
+            .unwrap();
+        h.screen.attr_get(&mut attrs, &mut pair).unwrap();
+        assert_eq!(attrs & attr::A_BOLD, 0);
+        assert_eq!(pair, 2);
+
+        // attron still takes the color bits as given
+        h.screen.attron(attr::color_pair(3)).unwrap();
+        h.screen.attr_get(&mut attrs, &mut pair).unwrap();
+        assert_eq!(pair, 3);
+    }
 
-/// **Not available in Rust** - Use `wprintw()` instead.
-///
-/// This is an alias for `vwprintw`.
-///
-/// # Panics
-/// Always panics - this function is not implemented.
-#[deprecated(since = "0.1.0", note = "Use wprintw with format! instead")]
-#[allow(deprecated)]
-pub fn vw_printw(_win: &mut Window, _fmt: &str) -> Result<()> {
-    vwprintw(_win, _fmt)
-}
+    #[test]
+    fn test_detect_capabilities() {
+        let mut h = Harness::with_term(Some("vt100"));
+        assert_eq!(h.screen.tigetflag("bce"), 0);
+
+        h.feed(b"x\x1bP>|WezTerm 20240203\x1b\\\x1b[?65;1;9c");
+        let caps = h.screen.detect_capabilities().unwrap().clone();
+        assert!(contains(&h.output(), b"\x1b[>0q\x1b[c"));
+        assert_eq!(caps.device_class, 65);
+        assert_eq!(caps.features, [1, 9]);
+        assert_eq!(caps.version.as_deref(), Some("WezTerm 20240203"));
+        assert_eq!(h.screen.capabilities(), Some(&caps));
+        assert_eq!(h.screen.tigetflag("bce"), 1);
+        assert!(h.screen.has_truecolor());
+        assert_eq!(h.screen.getch().unwrap(), 'x' as i32);
+
+        // No XTVERSION reply: the DA1 answer alone settles it
+        let mut h = Harness::new();
+        h.feed(b"\x1b[?1;2c");
+        let caps = h.screen.detect_capabilities().unwrap();
+        assert_eq!(caps.version, None);
+        assert!(!caps.is_modern());
+        assert_eq!(h.screen.tigetflag("bce"), 0);
+        assert!(!h.screen.has_truecolor());
+
+        // The short query init() makes: a late reply is recorded, not read
+        let mut h = Harness::with_term(Some("vt100"));
+        h.screen.keypad(true);
+        assert!(matches!(
+            h.screen
+                .query_capabilities(b"\x1b[c", Duration::from_millis(10)),
+            Err(Error::Timeout)
+        ));
+        assert_eq!(h.screen.capabilities(), None);
+        h.feed(b"\x1b[?65;1;9ca");
+        assert_eq!(h.screen.getch().unwrap(), 'a' as i32);
+        assert_eq!(h.screen.capabilities().map(|c| c.device_class), Some(65));
+        assert_eq!(h.screen.tigetflag("bce"), 1);
+    }
 
-/// **Not available in Rust** - Use `wgetstr()` and parse instead.
-///
-/// The variadic scanw functions cannot be directly implemented in Rust.
-/// Use wgetstr and parse the input manually.
-///
-/// # Panics
-/// Always panics - this function is not implemented.
-#[deprecated(since = "0.1.0", note = "Use wgetstr and parse the result instead")]
-pub fn vwscanw(_win: &mut Window, _fmt: &str) -> Result<()> {
-    panic!(
-        "vwscanw is not available in Rust. Use wgetstr and parse instead.\n\
-         Example:\n\
-         let input = screen.wgetstr(win, 100)?;\n\
-         let value: i32 = input.trim().parse().unwrap_or(0);"
-    );
-}
+    #[test]
+    #[cfg(feature = "wide")]
+    fn test_border_set_keeps_cell_attributes() {
+        use crate::wide::{wacs, CCharT};
+
+        let mut h = Harness::new();
+        let corner = |ch, pair| CCharT::from_char_attr(ch, attr::A_BOLD | attr::color_pair(pair));
+        let (tl, tr, bl, br) = (
+            corner('╔', 1),
+            corner('╗', 2),
+            corner('╚', 3),
+            corner('╝', 4),
+        );
+        let mut win = Window::new(4, 6, 2, 2).unwrap();
+        h.screen
+            .wborder_set(
+                &mut win,
+                None,
+                None,
+                None,
+                None,
+                Some(&tl),
+                Some(&tr),
+                Some(&bl),
+                Some(&br),
+            )
+            .unwrap();
+        assert_eq!(win.mvin_wch(0, 0).unwrap(), tl);
+        assert_eq!(win.mvin_wch(0, 5).unwrap(), tr);
+        assert_eq!(win.mvin_wch(3, 0).unwrap(), bl);
+        assert_eq!(win.mvin_wch(3, 5).unwrap(), br);
+        assert_eq!(win.mvin_wch(1, 0).unwrap(), wacs::VLINE);
+
+        let side = CCharT::from_char_attr('═', attr::color_pair(5));
+        h.screen.box_set(None, Some(&side)).unwrap();
+        let (lines, cols) = h.screen.getmaxyx();
+        assert_eq!(h.screen.stdscr_mut().mvin_wch(0, cols / 2).unwrap(), side);
+        assert_eq!(
+            h.screen.stdscr_mut().mvin_wch(lines - 1, 0).unwrap(),
+            wacs::LLCORNER
+        );
+    }
 
-/// **Not available in Rust** - Use `wgetstr()` and parse instead.
-///
-/// This is an alias for `vwscanw`.
-///
-/// # Panics
-/// Always panics - this function is not implemented.
-#[deprecated(since = "0.1.0", note = "Use wgetstr and parse the result instead")]
-#[allow(deprecated)]
-pub fn vw_scanw(_win: &mut Window, _fmt: &str) -> Result<()> {
-    vwscanw(_win, _fmt)
-}
+    #[test]
+    fn test_blocking_getch_survives_signal() {
+        extern "C" fn ignore(_: libc::c_int) {}
+
+        // SAFETY: installs a handler that does nothing, without SA_RESTART
+        // so the signal interrupts the wait in getch().
+        unsafe {
+            let mut action: libc::sigaction = std::mem::zeroed();
+            action.sa_sigaction = ignore as extern "C" fn(libc::c_int) as libc::sighandler_t;
+            libc::sigemptyset(&mut action.sa_mask);
+            assert_eq!(
+                libc::sigaction(libc::SIGUSR1, &action, std::ptr::null_mut()),
+                0
+            );
+        }
 
-/// Get printable representation of a character.
-///
-/// This is the window-less version that operates on characters directly.
-pub fn unctrl(c: ChType) -> String {
-    let ch = (c & crate::attr::A_CHARTEXT) as u8;
-    if ch < 32 {
-        format!("^{}", (ch + 64) as char)
-    } else if ch == 127 {
-        "^?".to_string()
-    } else {
-        (ch as char).to_string()
+        let mut h = Harness::new();
+        let mut input = h.input.try_clone().unwrap();
+        // SAFETY: pthread_self() has no preconditions.
+        let waiting = unsafe { libc::pthread_self() };
+        let sender = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            // SAFETY: the test thread is blocked in getch() until the write below.
+            unsafe { libc::pthread_kill(waiting, libc::SIGUSR1) };
+            std::thread::sleep(Duration::from_millis(50));
+            input.write_all(b"a").unwrap();
+        });
+
+        let start = Instant::now();
+        assert_eq!(h.screen.getch().unwrap(), 'a' as i32);
+        assert!(start.elapsed() >= Duration::from_millis(100));
+        sender.join().unwrap();
     }
-}
 
-/// Get printable representation of a wide character.
-#[cfg(feature = "wide")]
-pub fn wunctrl(wc: &crate::wide::CCharT) -> String {
-    let c = wc.spacing_char();
-    if c < ' ' {
-        format!("^{}", ((c as u8) + 64) as char)
-    } else if c as u8 == 127 {
-        "^?".to_string()
-    } else {
-        c.to_string()
+    #[test]
+    fn test_run_event_loop() {
+        let mut h = Harness::new();
+        h.screen.ungetch(KEY_RESIZE).unwrap();
+        h.feed(b"xq");
+
+        let mut events = Vec::new();
+        h.screen
+            .run(|_, event| {
+                let quit = event == Event::Key(Key::Char('q'));
+                events.push(event);
+                if quit {
+                    std::ops::ControlFlow::Break(())
+                } else {
+                    std::ops::ControlFlow::Continue(())
+                }
+            })
+            .unwrap();
+
+        let (lines, cols) = h.screen.getmaxyx();
+        assert_eq!(
+            events,
+            [
+                Event::Resize { lines, cols },
+                Event::Key(Key::Char('x')),
+                Event::Key(Key::Char('q')),
+            ]
+        );
+        assert!(!h.screen.has_key());
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn test_clearok_repaints_without_erasing() {
+        let mut h = Harness::new();
+        h.screen.mvaddstr(0, 0, "alpha").unwrap();
+        h.screen.mvaddstr(3, 2, "beta").unwrap();
+        h.screen.refresh().unwrap();
+
+        // Nothing changed, so a plain refresh sends no text
+        let start = h.output_len() as usize;
+        h.screen.refresh().unwrap();
+        assert!(!contains(&h.output()[start..], b"alpha"));
+
+        h.screen.stdscr_mut().clearok(true);
+        let start = h.output_len() as usize;
+        h.screen.refresh().unwrap();
+        let out = h.output()[start..].to_vec();
+        assert!(contains(&out, b"\x1b[2J"));
+        assert!(contains(&out, b"alpha"));
+        assert!(contains(&out, b"beta"));
+        assert_eq!(h.screen.stdscr_mut().mvinnstr(0, 0, 5).unwrap(), "alpha");
+        assert!(!h.screen.stdscr_mut().is_cleared());
+
+        // The same through another window
+        let mut win = Window::new(2, 10, 5, 0).unwrap();
+        win.mvaddstr(0, 0, "gamma").unwrap();
+        h.screen.wrefresh(&mut win).unwrap();
+        win.clearok(true);
+        let start = h.output_len() as usize;
+        h.screen.wrefresh(&mut win).unwrap();
+        let out = h.output()[start..].to_vec();
+        assert!(contains(&out, b"\x1b[2J"));
+        assert!(contains(&out, b"alpha") && contains(&out, b"gamma"));
+        assert!(!win.is_cleared());
+    }
 
-    // Note: Most Screen tests require a terminal, so they're integration tests
+    #[cfg(feature = "mouse")]
+    #[test]
+    fn test_getmouse_clamps_to_screen() {
+        use crate::mouse::BUTTON1_PRESSED;
+
+        // Clamped to the whole screen, not to stdscr below a ripped-off line
+        globals::ripoffline(1, None).unwrap();
+        let mut h = Harness::new();
+        h.screen.keypad(true);
+        h.screen.mousemask(BUTTON1_PRESSED);
+        let (lines, cols) = (h.screen.lines(), h.screen.cols());
+        assert_eq!(h.screen.getmaxyx().0, lines - 1);
+
+        let packet = format!("\x1b[<0;{};{}M", cols + 40, lines + 9);
+        h.feed(packet.as_bytes());
+        assert_eq!(h.screen.getch().unwrap(), KEY_MOUSE);
+        let event = h.screen.getmouse().unwrap();
+        assert_eq!((event.y, event.x), (lines - 1, cols - 1));
+
+        h.feed(packet.as_bytes());
+        assert_eq!(h.screen.getch().unwrap(), KEY_MOUSE);
+        let event = h.screen.getmouse_unclamped().unwrap();
+        assert_eq!((event.y, event.x), (lines + 8, cols + 39));
+
+        h.feed(format!("\x1b[<0;3;{}M", lines).as_bytes());
+        assert_eq!(h.screen.getch().unwrap(), KEY_MOUSE);
+        assert_eq!(h.screen.getmouse().map(|e| e.y), Some(lines - 1));
+    }
 
     #[test]
-    fn test_delay_conversion() {
-        assert_eq!(Delay::from_raw(0), Delay::NoDelay);
-        assert_eq!(Delay::from_raw(-1), Delay::Blocking);
-        assert_eq!(Delay::from_raw(100), Delay::Timeout(100));
+    fn test_synchronized_output() {
+        let mut h = Harness::new();
+        assert!(matches!(
+            h.screen.set_synchronized_output(true),
+            Err(Error::NotSupported(_))
+        ));
+
+        let mut h = Harness::with_term(Some("foot"));
+        h.screen.set_synchronized_output(true).unwrap();
+        h.screen.mvaddstr(2, 3, "frame").unwrap();
+        let start = h.output_len() as usize;
+        h.screen.refresh().unwrap();
+        let out = h.output()[start..].to_vec();
+        assert!(out.starts_with(b"\x1b[?2026h"));
+        assert!(out.ends_with(b"\x1b[?2026l"));
+        assert!(contains(&out, b"frame"));
+
+        // endwin releases the display even if a frame was left open
+        h.screen.terminal.begin_sync().unwrap();
+        let start = h.output_len() as usize;
+        h.screen.endwin().unwrap();
+        assert!(contains(&h.output()[start..], b"\x1b[?2026l"));
+        h.screen.refresh().unwrap();
+
+        // Nothing to draw, nothing sent
+        let start = h.output_len() as usize;
+        h.screen.refresh().unwrap();
+        assert!(!contains(&h.output()[start..], b"2026"));
+
+        h.screen.set_synchronized_output(false).unwrap();
+        h.screen.mvaddstr(0, 0, "plain").unwrap();
+        let start = h.output_len() as usize;
+        h.screen.refresh().unwrap();
+        assert!(!contains(&h.output()[start..], b"2026"));
+
+        // Support reported by the terminal itself
+        let mut h = Harness::with_term(Some("vt100"));
+        h.feed(b"\x1bP>|kitty(0.35.2)\x1b\\\x1b[?62;c");
+        h.screen.detect_capabilities().unwrap();
+        h.screen.set_synchronized_output(true).unwrap();
+    }
 
-        assert_eq!(Delay::NoDelay.to_raw(), 0);
-        assert_eq!(Delay::Blocking.to_raw(), -1);
-        assert_eq!(Delay::Timeout(100).to_raw(), 100);
+    #[test]
+    fn test_syncok_subwindow_refreshes_with_parent() {
+        let mut h = Harness::new();
+        let mut sub = h.screen.stdscr().derwin(2, 10, 3, 4).unwrap();
+        sub.syncok(true);
+        sub.mvaddstr(1, 0, "synced").unwrap();
+        h.screen.refresh().unwrap();
+        assert!(contains(&h.output(), b"\x1b[5;5Hsynced"));
+
+        // Without syncok the Screen wrapper syncs on request
+        sub.syncok(false);
+        sub.mvaddstr(0, 0, "later").unwrap();
+        h.screen.refresh().unwrap();
+        assert!(!contains(&h.output(), b"later"));
+        h.screen.wsyncup(&mut sub);
+        h.screen.refresh().unwrap();
+        assert!(contains(&h.output(), b"\x1b[4;5Hlater"));
     }
 }
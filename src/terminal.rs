@@ -18,6 +18,21 @@
 //! allowing applications to work in pipelines or non-interactive contexts
 //! where output is still useful even if interactive input is not available.
 //!
+//! # Headless Mode
+//!
+//! For tests and CI only: when `NCURSES_PURE_HEADLESS=1` is set, stdout is
+//! not a TTY and `LINES`/`COLUMNS` are set, [`Terminal::from_stdio()`]
+//! creates a terminal that discards all output and reports that size, so
+//! programs can run the full stack without a terminal.
+//!
+//! # Recording and Replay
+//!
+//! [`Terminal::record_input()`] logs every byte read, with the time it
+//! arrived, as one `milliseconds byte` pair per line. A terminal created
+//! with [`Terminal::replay_from()`] reads its input from such a log instead
+//! of a file descriptor, at the recorded pace or as fast as it is read, so
+//! a captured session can drive a test.
+//!
 //! # Safety
 //!
 //! This module contains unsafe code for interfacing with POSIX terminal APIs.
@@ -29,9 +44,141 @@
 //! - `libc::select` for input availability checking
 
 use crate::error::{Error, Result};
-use std::io;
+use crate::types::Size;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, Write};
 use std::mem::MaybeUninit;
-use std::os::unix::io::RawFd;
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/// Sequences the panic hook writes to undo program mode: reset attributes,
+/// stop mouse, paste and focus reporting, end any synchronized update, show
+/// the cursor and leave the alternate screen.
+const PANIC_RESET: &[u8] = b"\x1b[0m\x1b[?1000l\x1b[?1002l\x1b[?1003l\x1b[?1006l\
+\x1b[?2004l\x1b[?1004l\x1b[?2026l\x1b[?25h\x1b[?1049l";
+
+/// Environment variable enabling headless mode in [`Terminal::from_stdio()`].
+const HEADLESS_ENV: &str = "NCURSES_PURE_HEADLESS";
+
+/// Get the size for headless mode, if it is enabled and applies.
+fn headless_size() -> Option<Size> {
+    if std::env::var(HEADLESS_ENV).ok()? != "1" {
+        return None;
+    }
+    // SAFETY: `isatty` only inspects the file descriptor.
+    if unsafe { libc::isatty(libc::STDOUT_FILENO) } == 1 {
+        return None;
+    }
+    let dimension = |name| {
+        std::env::var(name)
+            .ok()?
+            .parse::<i32>()
+            .ok()
+            .filter(|&n| n > 0)
+    };
+    Some(Size::new(dimension("LINES")?, dimension("COLUMNS")?))
+}
+
+/// Pick the shortest sequence moving the cursor from `(from_y, from_x)` to
+/// `(y, x)` on a screen `columns` wide: an absolute `cup`, or relative
+/// vertical and horizontal moves.
+fn cursor_motion(from_y: i32, from_x: i32, y: i32, x: i32, columns: i32) -> String {
+    let absolute = format!("\x1b[{};{}H", y + 1, x + 1);
+    // A cursor past the last column is waiting to wrap, where relative
+    // moves behave differently between terminals
+    if from_y < 0 || from_x < 0 || from_x >= columns {
+        return absolute;
+    }
+
+    let csi = |n: i32, op: char| {
+        if n == 1 {
+            format!("\x1b[{op}")
+        } else {
+            format!("\x1b[{n}{op}")
+        }
+    };
+    let vertical = match y - from_y {
+        0 => String::new(),
+        dy if dy > 0 => csi(dy, 'B'),
+        dy => csi(-dy, 'A'),
+    };
+    let horizontal = match x - from_x {
+        0 => String::new(),
+        _ if x == 0 => "\r".to_string(),
+        -1 => "\x08".to_string(),
+        dx if dx > 0 => csi(dx, 'C'),
+        dx => csi(-dx, 'D'),
+    };
+
+    let relative = vertical + &horizontal;
+    if relative.len() < absolute.len() {
+        relative
+    } else {
+        absolute
+    }
+}
+
+/// SGR sequence resetting the rendition and turning on the video
+/// attributes in `attr`. Colors are left to [`sgr_color`].
+pub(crate) fn sgr_attributes(attr: crate::types::AttrT) -> String {
+    use crate::attr::*;
+
+    let mut codes = vec![0u8]; // Reset
+
+    if attr & A_BOLD != 0 {
+        codes.push(1);
+    }
+    if attr & A_DIM != 0 {
+        codes.push(2);
+    }
+    if attr & A_ITALIC != 0 {
+        codes.push(3);
+    }
+    if attr & A_UNDERLINE != 0 {
+        codes.push(4);
+    }
+    if attr & A_BLINK != 0 {
+        codes.push(5);
+    }
+    if attr & A_REVERSE != 0 {
+        codes.push(7);
+    }
+    if attr & A_INVIS != 0 {
+        codes.push(8);
+    }
+
+    let code_str: String = codes
+        .iter()
+        .map(|c| c.to_string())
+        .collect::<Vec<_>>()
+        .join(";");
+    format!("\x1b[{}m", code_str)
+}
+
+/// SGR sequence selecting palette `color` (negative for the default) as the
+/// foreground or background.
+pub(crate) fn sgr_color(color: i16, background: bool) -> String {
+    let base = if background { 40 } else { 30 };
+    if color < 0 {
+        format!("\x1b[{}m", base + 9)
+    } else if color < 8 {
+        format!("\x1b[{}m", base + color)
+    } else if color < 16 {
+        format!("\x1b[{}m", base + 60 + color - 8)
+    } else {
+        format!("\x1b[{};5;{}m", base + 8, color)
+    }
+}
+
+/// SGR sequence selecting a 24-bit foreground or background color.
+pub(crate) fn sgr_rgb((r, g, b): (u8, u8, u8), background: bool) -> String {
+    let base = if background { 48 } else { 38 };
+    format!("\x1b[{};2;{};{};{}m", base, r, g, b)
+}
 
 /// Terminal state flags for tracking initialization.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
@@ -151,8 +298,10 @@ impl Default for TermSettings {
 pub struct Terminal {
     /// Input file descriptor.
     input_fd: RawFd,
-    /// Output file descriptor.
+    /// Output file descriptor (-1 when writing to `output`).
     output_fd: RawFd,
+    /// Writer receiving output instead of `output_fd`, if any.
+    output: Option<Box<dyn Write + Send>>,
     /// Whether the terminal is operating without a real TTY.
     ///
     /// When true, `tcsetattr`/`tcgetattr` operations are skipped (they would
@@ -179,6 +328,8 @@ pub struct Terminal {
     color_pairs: i32,
     /// Whether terminal can change colors.
     can_change_color: bool,
+    /// Whether the terminal accepts 24-bit RGB color sequences.
+    truecolor: bool,
     /// Output buffer for batching writes.
     output_buffer: Vec<u8>,
     /// File descriptor for typeahead checking (-1 to disable).
@@ -187,16 +338,203 @@ pub struct Terminal {
     has_ic: bool,
     /// Whether terminal has insert/delete line capability.
     has_il: bool,
+    /// Log receiving every byte read, if recording.
+    ///
+    /// Behind a mutex so [`read_byte()`](Self::read_byte) can take `&self`.
+    recorder: Mutex<Option<InputRecorder>>,
+    /// Recorded input read instead of `input_fd`, if replaying.
+    replay: Option<Mutex<Replay>>,
+    /// Panic hook installed for this terminal, if any.
+    panic_hook: Mutex<Option<Arc<Mutex<PanicHookState>>>>,
+}
+
+/// How a replayed input log is paced.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ReplayTiming {
+    /// Deliver each byte as long after the start as it was recorded.
+    #[default]
+    Recorded,
+    /// Deliver every byte as soon as it is read.
+    Immediate,
+}
+
+/// What a terminal reported about itself when queried.
+///
+/// Built from the reply to the primary device attributes request (DA1,
+/// `ESC [ c`) and, if the terminal answers it, the XTVERSION request
+/// (`ESC [ > 0 q`).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TermCapabilities {
+    /// Conformance level from DA1: 1 or 6 for VT100-class terminals, 62 and
+    /// up for VT220 and later.
+    pub device_class: u32,
+    /// Extension codes listed after the class in the DA1 reply.
+    pub features: Vec<u32>,
+    /// Name and version from the XTVERSION reply, such as `XTerm(390)`.
+    pub version: Option<String>,
+}
+
+impl TermCapabilities {
+    /// DA1 extension code for sixel graphics.
+    pub const SIXEL: u32 = 4;
+    /// DA1 extension code for ANSI color.
+    pub const ANSI_COLOR: u32 = 22;
+
+    /// Parse a DA1 reply of the form `ESC [ ? class ; ext ; ... c`.
+    ///
+    /// Returns `None` if `seq` is not exactly one such reply.
+    pub fn parse_da1(seq: &[u8]) -> Option<Self> {
+        let params = seq.strip_prefix(b"\x1b[?")?.strip_suffix(b"c")?;
+        let mut codes = std::str::from_utf8(params)
+            .ok()?
+            .split(';')
+            .filter(|s| !s.is_empty())
+            .map(|s| {
+                if s.bytes().all(|b| b.is_ascii_digit()) {
+                    s.parse::<u32>().ok()
+                } else {
+                    None
+                }
+            });
+        let device_class = codes.next()??;
+        let features = codes.collect::<Option<Vec<_>>>()?;
+        Some(TermCapabilities {
+            device_class,
+            features,
+            version: None,
+        })
+    }
+
+    /// Parse an XTVERSION reply of the form `ESC P > | text ESC \`.
+    pub fn parse_xtversion(seq: &[u8]) -> Option<String> {
+        let text = seq.strip_prefix(b"\x1bP>|")?.strip_suffix(b"\x1b\\")?;
+        String::from_utf8(text.to_vec()).ok()
+    }
+
+    /// Check whether the DA1 reply listed an extension code.
+    pub fn has_feature(&self, code: u32) -> bool {
+        self.features.contains(&code)
+    }
+
+    /// Check whether the terminal is a VT220 or later, or answered
+    /// XTVERSION, which only current emulators do.
+    ///
+    /// Such terminals erase with the background color and are safe to move
+    /// in insert and standout mode.
+    pub fn is_modern(&self) -> bool {
+        self.device_class >= 62 || self.version.is_some()
+    }
+
+    /// Check whether the XTVERSION reply names an emulator that supports
+    /// synchronized updates (mode 2026).
+    pub fn has_synchronized_output(&self) -> bool {
+        const SYNC: [&str; 8] = [
+            "kitty",
+            "wezterm",
+            "foot",
+            "tmux",
+            "iterm2",
+            "alacritty",
+            "contour",
+            "ghostty",
+        ];
+        self.version.as_deref().is_some_and(|version| {
+            let version = version.to_ascii_lowercase();
+            SYNC.iter().any(|name| version.starts_with(name))
+        })
+    }
+
+    /// Check whether the XTVERSION reply names an emulator with 24-bit
+    /// color.
+    pub fn has_truecolor(&self) -> bool {
+        const TRUECOLOR: [&str; 9] = [
+            "xterm",
+            "kitty",
+            "wezterm",
+            "foot",
+            "tmux",
+            "iterm2",
+            "alacritty",
+            "contour",
+            "ghostty",
+        ];
+        self.version.as_deref().is_some_and(|version| {
+            let version = version.to_ascii_lowercase();
+            TRUECOLOR.iter().any(|name| version.starts_with(name))
+        })
+    }
+}
+
+/// Destination of [`Terminal::record_input()`].
+struct InputRecorder {
+    log: Box<dyn Write + Send>,
+    start: Instant,
+}
+
+/// Input log fed back by a terminal from [`Terminal::replay_from()`].
+struct Replay {
+    /// Remaining bytes with their offsets from the start of the recording.
+    events: VecDeque<(Duration, u8)>,
+    start: Instant,
+    timing: ReplayTiming,
+    /// Descriptor standing in for the input, kept open while replaying.
+    _input: File,
+}
+
+impl Replay {
+    /// Parse a log written by [`Terminal::record_input()`].
+    fn parse(log: &str, timing: ReplayTiming, input: File) -> Result<Self> {
+        let mut events = VecDeque::new();
+        for line in log.lines().filter(|line| !line.trim().is_empty()) {
+            let event = line
+                .split_once(' ')
+                .and_then(|(ms, byte)| Some((ms.parse().ok()?, byte.trim().parse().ok()?)));
+            let Some((ms, byte)) = event else {
+                return Err(Error::InvalidArgument(format!(
+                    "malformed input log entry: {:?}",
+                    line
+                )));
+            };
+            events.push_back((Duration::from_millis(ms), byte));
+        }
+        Ok(Self {
+            events,
+            start: Instant::now(),
+            timing,
+            _input: input,
+        })
+    }
+
+    /// Time until the next byte is due; `None` once the log is exhausted.
+    fn until_next(&self) -> Option<Duration> {
+        let &(offset, _) = self.events.front()?;
+        Some(match self.timing {
+            ReplayTiming::Recorded => offset.saturating_sub(self.start.elapsed()),
+            ReplayTiming::Immediate => Duration::ZERO,
+        })
+    }
 }
 
 impl Terminal {
     /// Create a new terminal with the given file descriptors.
     pub fn new(input_fd: RawFd, output_fd: RawFd) -> Result<Self> {
-        Self::new_internal(input_fd, output_fd)
+        Self::new_internal(input_fd, output_fd, None)
+    }
+
+    /// Create a new terminal of type `term_type` with the given file descriptors.
+    ///
+    /// The type selects the capabilities in place of the `TERM` environment
+    /// variable; `None` falls back to `TERM` as in [`new()`](Self::new).
+    pub fn with_term_type(
+        term_type: Option<&str>,
+        input_fd: RawFd,
+        output_fd: RawFd,
+    ) -> Result<Self> {
+        Self::new_internal(input_fd, output_fd, term_type)
     }
 
     /// Internal constructor.
-    fn new_internal(input_fd: RawFd, output_fd: RawFd) -> Result<Self> {
+    fn new_internal(input_fd: RawFd, output_fd: RawFd, term_type: Option<&str>) -> Result<Self> {
         // SAFETY: `libc::termios` is a C struct that can be safely zero-initialized.
         // All fields are primitive types that have valid zero representations.
         // The struct will be properly initialized by `tcgetattr` below.
@@ -208,6 +546,7 @@ impl Terminal {
         let mut term = Self {
             input_fd,
             output_fd,
+            output: None,
             no_tty: false,
             state: TermState::Unknown,
             shell_settings: TermSettings::new(),
@@ -219,10 +558,14 @@ impl Terminal {
             colors: 8,
             color_pairs: 64,
             can_change_color: false,
+            truecolor: false,
             output_buffer: Vec::with_capacity(4096),
             typeahead_fd: libc::STDIN_FILENO,
             has_ic: true, // Will be updated in detect_terminal
             has_il: true, // Will be updated in detect_terminal
+            recorder: Mutex::new(None),
+            replay: None,
+            panic_hook: Mutex::new(None),
         };
 
         // SAFETY: `tcgetattr` is a POSIX function that reads terminal attributes.
@@ -246,7 +589,7 @@ impl Terminal {
         term.shell_settings.save(input_fd)?;
 
         // Detect terminal type
-        term.detect_terminal()?;
+        term.detect_terminal(term_type)?;
 
         // Get terminal size
         term.update_size()?;
@@ -255,19 +598,85 @@ impl Terminal {
         Ok(term)
     }
 
+    /// Create a terminal that reads from `input_fd` and writes to `output`.
+    ///
+    /// This lets a program render somewhere other than a file descriptor it
+    /// owns, for example a PTY master it manages or an in-memory buffer.
+    /// Since the size cannot be queried from an arbitrary writer, it must be
+    /// supplied in `size`; it is also kept when the size would otherwise be
+    /// re-read, such as after `SIGWINCH`. Use [`set_size()`](Self::set_size)
+    /// to change it.
+    pub fn with_output(input_fd: RawFd, output: Box<dyn Write + Send>, size: Size) -> Result<Self> {
+        Self::with_output_term_type(None, input_fd, output, size)
+    }
+
+    /// Create a terminal of type `term_type` that reads from `input_fd` and
+    /// writes to `output`.
+    ///
+    /// This is [`with_output()`](Self::with_output) with the type chosen as
+    /// in [`with_term_type()`](Self::with_term_type).
+    pub fn with_output_term_type(
+        term_type: Option<&str>,
+        input_fd: RawFd,
+        output: Box<dyn Write + Send>,
+        size: Size,
+    ) -> Result<Self> {
+        if size.height <= 0 || size.width <= 0 {
+            return Err(Error::InvalidArgument(
+                "terminal size must be positive".into(),
+            ));
+        }
+        let mut term = Self::new_internal(input_fd, -1, term_type)?;
+        term.output = Some(output);
+        term.set_size(size.height, size.width);
+        Ok(term)
+    }
+
+    /// Create a terminal whose input is replayed from a log written by
+    /// [`record_input()`](Self::record_input).
+    ///
+    /// Output goes to `output` and the size is fixed as in
+    /// [`with_output()`](Self::with_output). Once the log is exhausted the
+    /// input reports end of file.
+    pub fn replay_from(
+        path: impl AsRef<Path>,
+        timing: ReplayTiming,
+        output: Box<dyn Write + Send>,
+        size: Size,
+    ) -> Result<Self> {
+        let log = std::fs::read_to_string(path)?;
+        let input = File::open("/dev/null")?;
+        let replay = Replay::parse(&log, timing, input)?;
+        let mut term = Self::with_output(replay._input.as_raw_fd(), output, size)?;
+        term.replay = Some(Mutex::new(replay));
+        Ok(term)
+    }
+
     /// Create a terminal using stdin/stdout.
     ///
     /// If stdin/stdout is not a TTY (e.g., when input is redirected), the terminal
     /// will operate in no-TTY mode where output still works but terminal attribute
     /// changes are no-ops.
+    ///
+    /// If `NCURSES_PURE_HEADLESS=1` is set, stdout is not a TTY and both
+    /// `LINES` and `COLUMNS` hold positive sizes, a headless terminal of that
+    /// size is created instead: output is discarded and input still comes
+    /// from stdin. This is meant for running tests and demos in CI only.
     pub fn from_stdio() -> Result<Self> {
+        if let Some(size) = headless_size() {
+            return Self::with_output(libc::STDIN_FILENO, Box::new(io::sink()), size);
+        }
         Self::new(libc::STDIN_FILENO, libc::STDOUT_FILENO)
     }
 
     /// Detect terminal type and capabilities.
-    fn detect_terminal(&mut self) -> Result<()> {
-        // Get TERM environment variable
-        self.term_type = std::env::var("TERM").unwrap_or_else(|_| "dumb".to_string());
+    ///
+    /// `term_type` overrides the `TERM` environment variable.
+    fn detect_terminal(&mut self, term_type: Option<&str>) -> Result<()> {
+        self.term_type = match term_type {
+            Some(t) => t.to_string(),
+            None => std::env::var("TERM").unwrap_or_else(|_| "dumb".to_string()),
+        };
 
         // Set capabilities based on terminal type
         // Start with defaults
@@ -501,12 +910,11 @@ impl Terminal {
                 "truecolor" | "24bit" => {
                     self.colors = 16777216; // 24-bit color
                     self.can_change_color = true;
+                    self.truecolor = true;
                 }
-                "256" => {
-                    if self.colors < 256 {
-                        self.colors = 256;
-                        self.color_pairs = 32767;
-                    }
+                "256" if self.colors < 256 => {
+                    self.colors = 256;
+                    self.color_pairs = 32767;
                 }
                 _ => {}
             }
@@ -523,12 +931,10 @@ impl Terminal {
                     }
                     self.can_change_color = true;
                 }
-                "Apple_Terminal" => {
-                    // macOS Terminal supports 256 colors but not true color modification
-                    if self.colors < 256 {
-                        self.colors = 256;
-                        self.color_pairs = 32767;
-                    }
+                // macOS Terminal supports 256 colors but not true color modification
+                "Apple_Terminal" if self.colors < 256 => {
+                    self.colors = 256;
+                    self.color_pairs = 32767;
                 }
                 "vscode" | "VSCode" => {
                     // VS Code integrated terminal supports true color
@@ -636,6 +1042,11 @@ impl Terminal {
             w.assume_init()
         };
 
+        // A custom writer has no window size; keep the one supplied
+        if self.output.is_some() {
+            return Ok(());
+        }
+
         // SAFETY: `ioctl` with `TIOCGWINSZ` reads the terminal window size.
         // - `self.output_fd` is a valid file descriptor (validated in `new()`)
         // - `&mut ws` is a valid pointer to a `libc::winsize` struct
@@ -663,6 +1074,12 @@ impl Terminal {
         Ok(())
     }
 
+    /// Set the terminal size explicitly.
+    pub fn set_size(&mut self, lines: i32, columns: i32) {
+        self.lines = lines;
+        self.columns = columns;
+    }
+
     /// Enter program mode (raw/cbreak).
     pub fn enter_program_mode(&mut self) -> Result<()> {
         // Save current as program settings
@@ -720,8 +1137,9 @@ impl Terminal {
         // Leave alternate screen buffer
         self.write_escape("\x1b[?1049l")?;
 
-        // Flush output
-        self.flush()?;
+        // Flush output, but restore the tty settings even if that fails so a
+        // broken output never leaves the terminal in raw mode
+        let flushed = self.flush();
 
         // Restore shell settings (no-op in no-TTY mode)
         self.shell_settings.restore(self.input_fd, self.no_tty)?;
@@ -730,7 +1148,7 @@ impl Terminal {
         }
         self.state = TermState::Suspend;
 
-        Ok(())
+        flushed
     }
 
     /// Set raw mode.
@@ -850,6 +1268,14 @@ impl Terminal {
             return Ok(());
         }
 
+        if let Some(output) = self.output.as_mut() {
+            let result = output
+                .write_all(&self.output_buffer)
+                .and_then(|()| output.flush());
+            self.output_buffer.clear();
+            return result.map_err(Error::from);
+        }
+
         // SAFETY: `libc::write` writes data to a file descriptor.
         // - `self.output_fd` is a valid file descriptor (validated in `new()`)
         // - `self.output_buffer.as_ptr()` returns a valid pointer to the buffer's data
@@ -874,32 +1300,83 @@ impl Terminal {
         }
     }
 
+    /// Log every byte read from now on to `log`.
+    ///
+    /// Each byte is written as a line holding the milliseconds since
+    /// recording started and the byte value, the format read by
+    /// [`replay_from()`](Self::replay_from). Replaces any earlier log.
+    pub fn record_input(&mut self, log: Box<dyn Write + Send>) {
+        *lock_ignoring_poison(&self.recorder) = Some(InputRecorder {
+            log,
+            start: Instant::now(),
+        });
+    }
+
+    /// Stop logging input, flushing the log.
+    pub fn stop_recording(&mut self) -> Result<()> {
+        if let Some(mut recorder) = lock_ignoring_poison(&self.recorder).take() {
+            recorder.log.flush()?;
+        }
+        Ok(())
+    }
+
     /// Read a single byte from the terminal.
     pub fn read_byte(&self) -> Result<Option<u8>> {
+        let byte = match &self.replay {
+            Some(replay) => {
+                let mut replay = lock_ignoring_poison(replay);
+                if let Some(wait) = replay.until_next() {
+                    std::thread::sleep(wait);
+                }
+                replay.events.pop_front().map(|(_, byte)| byte)
+            }
+            None => self.read_input_byte()?,
+        };
+        let mut recorder = lock_ignoring_poison(&self.recorder);
+        if let (Some(byte), Some(recorder)) = (byte, recorder.as_mut()) {
+            let ms = recorder.start.elapsed().as_millis();
+            writeln!(recorder.log, "{} {}", ms, byte)?;
+        }
+        Ok(byte)
+    }
+
+    /// Read a single byte from the input descriptor.
+    ///
+    /// A read interrupted by a signal is restarted.
+    fn read_input_byte(&self) -> Result<Option<u8>> {
         let mut buf = [0u8; 1];
-        // SAFETY: `libc::read` reads data from a file descriptor.
-        // - `self.input_fd` is a valid file descriptor (validated in `new()`)
-        // - `buf.as_mut_ptr()` returns a valid pointer to a 1-byte buffer
-        // - The size argument (1) matches the buffer size exactly
-        // - The buffer is stack-allocated and remains valid during the read call
-        let result = unsafe { libc::read(self.input_fd, buf.as_mut_ptr() as *mut libc::c_void, 1) };
-
-        if result > 0 {
-            Ok(Some(buf[0]))
-        } else if result == 0 {
-            Ok(None) // EOF
-        } else {
+        loop {
+            // SAFETY: `libc::read` reads data from a file descriptor.
+            // - `self.input_fd` is a valid file descriptor (validated in `new()`)
+            // - `buf.as_mut_ptr()` returns a valid pointer to a 1-byte buffer
+            // - The size argument (1) matches the buffer size exactly
+            // - The buffer is stack-allocated and remains valid during the read call
+            let result =
+                unsafe { libc::read(self.input_fd, buf.as_mut_ptr() as *mut libc::c_void, 1) };
+
+            if result > 0 {
+                return Ok(Some(buf[0]));
+            } else if result == 0 {
+                return Ok(None); // EOF
+            }
             let err = io::Error::last_os_error();
-            if err.kind() == io::ErrorKind::WouldBlock {
-                Ok(None)
-            } else {
-                Err(Error::SystemError(err.raw_os_error().unwrap_or(-1)))
+            match err.kind() {
+                io::ErrorKind::Interrupted => continue,
+                io::ErrorKind::WouldBlock => return Ok(None),
+                _ => return Err(Error::SystemError(err.raw_os_error().unwrap_or(-1))),
             }
         }
     }
 
     /// Check if input is available.
     pub fn has_input(&self) -> bool {
+        // An exhausted log is at end of file, which reads without blocking
+        if let Some(replay) = &self.replay {
+            return lock_ignoring_poison(replay)
+                .until_next()
+                .is_none_or(|wait| wait.is_zero());
+        }
+
         // SAFETY: This unsafe block uses `select` to check for available input.
         // - `libc::fd_set` is zero-initialized, which is the correct initial state
         // - `FD_ZERO` clears the set (redundant but safe)
@@ -932,6 +1409,43 @@ impl Terminal {
         }
     }
 
+    /// Wait until input is available or `timeout` elapses.
+    ///
+    /// A `timeout` of `None` waits indefinitely. Returns `true` if input is
+    /// ready. Unlike polling [`has_input()`](Self::has_input) in a sleep loop,
+    /// this wakes up as soon as input arrives and does not overshoot the
+    /// deadline by more than the system's timer granularity.
+    ///
+    /// A signal arriving while waiting ends the wait early with `false`, so
+    /// callers can look at state the handler changed before waiting again.
+    pub fn wait_for_input(&self, timeout: Option<std::time::Duration>) -> bool {
+        if let Some(replay) = &self.replay {
+            let Some(wait) = lock_ignoring_poison(replay).until_next() else {
+                return true;
+            };
+            std::thread::sleep(timeout.map_or(wait, |t| t.min(wait)));
+            return timeout.is_none_or(|t| wait <= t);
+        }
+
+        let timeout_ms = match timeout {
+            // Round up so a sub-millisecond remainder doesn't become a busy loop
+            Some(t) => t.as_micros().div_ceil(1000).min(libc::c_int::MAX as u128) as libc::c_int,
+            None => -1,
+        };
+        let mut pfd = libc::pollfd {
+            fd: self.input_fd,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+
+        // SAFETY: `poll` is called with a pointer to a single, valid, stack-allocated
+        // `pollfd` and a count of 1. `self.input_fd` is a valid file descriptor
+        // (validated in `new()`).
+        let result = unsafe { libc::poll(&mut pfd, 1, timeout_ms) };
+
+        result > 0
+    }
+
     // ========================================================================
     // Terminal output operations
     // ========================================================================
@@ -943,6 +1457,19 @@ impl Terminal {
         self.write_str(&seq)
     }
 
+    /// Move the cursor from `(from_y, from_x)` to `(y, x)` using the
+    /// shortest sequence available.
+    ///
+    /// Nothing is written if the cursor is already there. Pass a negative
+    /// `from_y` when the position is unknown to always move absolutely.
+    pub fn move_cursor_from(&mut self, from_y: i32, from_x: i32, y: i32, x: i32) -> Result<()> {
+        if (from_y, from_x) == (y, x) {
+            return Ok(());
+        }
+        let seq = cursor_motion(from_y, from_x, y, x, self.columns);
+        self.write_str(&seq)
+    }
+
     /// Clear the entire screen.
     pub fn clear_screen(&mut self) -> Result<()> {
         self.write_escape("\x1b[2J")
@@ -953,6 +1480,26 @@ impl Terminal {
         self.write_escape("\x1b[K")
     }
 
+    /// Switch to the VT100 line-drawing character set (`smacs`).
+    pub fn enter_acs(&mut self) -> Result<()> {
+        self.write_escape("\x1b(0")
+    }
+
+    /// Switch back to the normal character set (`rmacs`).
+    pub fn exit_acs(&mut self) -> Result<()> {
+        self.write_escape("\x1b(B")
+    }
+
+    /// Insert one blank at the cursor, shifting the rest of the line right.
+    pub fn insert_char(&mut self) -> Result<()> {
+        self.write_escape("\x1b[@")
+    }
+
+    /// Delete the character at the cursor, shifting the rest of the line left.
+    pub fn delete_char(&mut self) -> Result<()> {
+        self.write_escape("\x1b[P")
+    }
+
     /// Clear to end of screen.
     pub fn clear_to_eos(&mut self) -> Result<()> {
         self.write_escape("\x1b[J")
@@ -967,75 +1514,111 @@ impl Terminal {
         }
     }
 
-    /// Set text attributes.
-    pub fn set_attributes(&mut self, attr: crate::types::AttrT) -> Result<()> {
-        use crate::attr::*;
+    /// Set the cursor shape using DECSCUSR.
+    ///
+    /// A `style` of 0 restores the terminal's default cursor.
+    pub fn cursor_style(&mut self, style: i32) -> Result<()> {
+        self.write_escape(&format!("\x1b[{} q", style))
+    }
 
-        let mut codes = vec![0u8]; // Reset
+    /// Check whether the terminal understands DECSCUSR cursor shapes.
+    pub fn has_cursor_style(&self) -> bool {
+        let term = self.term_type.as_str();
+        term.starts_with("xterm")
+            || term.starts_with("screen")
+            || term.starts_with("tmux")
+            || term.starts_with("rxvt")
+            || term.starts_with("vte")
+            || term.starts_with("foot")
+            || matches!(term, "kitty" | "alacritty" | "wezterm" | "iterm2")
+    }
 
-        if attr & A_BOLD != 0 {
-            codes.push(1);
-        }
-        if attr & A_DIM != 0 {
-            codes.push(2);
-        }
-        if attr & A_ITALIC != 0 {
-            codes.push(3);
-        }
-        if attr & A_UNDERLINE != 0 {
-            codes.push(4);
-        }
-        if attr & A_BLINK != 0 {
-            codes.push(5);
-        }
-        if attr & A_REVERSE != 0 {
-            codes.push(7);
-        }
-        if attr & A_INVIS != 0 {
-            codes.push(8);
-        }
+    /// Set the window title with OSC 0.
+    ///
+    /// Control characters in `title` are dropped, since they would end the
+    /// sequence early.
+    pub fn set_title(&mut self, title: &str) -> Result<()> {
+        let title: String = title.chars().filter(|c| !c.is_control()).collect();
+        self.write_escape(&format!("\x1b]0;{}\x07", title))
+    }
 
-        let code_str: String = codes
-            .iter()
-            .map(|c| c.to_string())
-            .collect::<Vec<_>>()
-            .join(";");
-        let seq = format!("\x1b[{}m", code_str);
-        self.write_str(&seq)
+    /// Save the window title on the terminal's title stack (XTWINOPS 22).
+    pub fn push_title(&mut self) -> Result<()> {
+        self.write_escape("\x1b[22;0t")
+    }
+
+    /// Restore the window title saved by `push_title()` (XTWINOPS 23).
+    pub fn pop_title(&mut self) -> Result<()> {
+        self.write_escape("\x1b[23;0t")
+    }
+
+    /// Check whether the terminal accepts a window title through OSC 0.
+    pub fn has_title(&self) -> bool {
+        let term = self.term_type.as_str();
+        self.has_title_stack()
+            || term.starts_with("screen")
+            || term.starts_with("rxvt")
+            || term.starts_with("putty")
+            || term == "iterm2"
+    }
+
+    /// Check whether the terminal keeps a stack of window titles.
+    pub fn has_title_stack(&self) -> bool {
+        let term = self.term_type.as_str();
+        term.starts_with("xterm")
+            || term.starts_with("tmux")
+            || term.starts_with("vte")
+            || term.starts_with("foot")
+            || matches!(term, "kitty" | "alacritty" | "wezterm")
+    }
+
+    /// Start a synchronized update (mode 2026); the terminal holds the
+    /// display until [`end_sync()`](Self::end_sync).
+    pub fn begin_sync(&mut self) -> Result<()> {
+        self.write_escape("\x1b[?2026h")
+    }
+
+    /// End a synchronized update, showing everything since `begin_sync()`.
+    pub fn end_sync(&mut self) -> Result<()> {
+        self.write_escape("\x1b[?2026l")
+    }
+
+    /// Check whether the terminal type is known to support synchronized
+    /// updates.
+    pub fn has_synchronized_output(&self) -> bool {
+        let term = self.term_type.as_str();
+        term.starts_with("foot")
+            || term.starts_with("tmux")
+            || term.starts_with("contour")
+            || matches!(
+                term,
+                "kitty" | "xterm-kitty" | "wezterm" | "alacritty" | "iterm2" | "xterm-ghostty"
+            )
+    }
+
+    /// Set text attributes.
+    pub fn set_attributes(&mut self, attr: crate::types::AttrT) -> Result<()> {
+        self.write_str(&sgr_attributes(attr))
     }
 
     /// Set foreground color.
     pub fn set_fg_color(&mut self, color: i16) -> Result<()> {
-        if color < 0 {
-            // Default color
-            self.write_escape("\x1b[39m")
-        } else if color < 8 {
-            let seq = format!("\x1b[{}m", 30 + color);
-            self.write_str(&seq)
-        } else if color < 16 {
-            let seq = format!("\x1b[{}m", 90 + color - 8);
-            self.write_str(&seq)
-        } else {
-            let seq = format!("\x1b[38;5;{}m", color);
-            self.write_str(&seq)
-        }
+        self.write_str(&sgr_color(color, false))
     }
 
     /// Set background color.
     pub fn set_bg_color(&mut self, color: i16) -> Result<()> {
-        if color < 0 {
-            // Default color
-            self.write_escape("\x1b[49m")
-        } else if color < 8 {
-            let seq = format!("\x1b[{}m", 40 + color);
-            self.write_str(&seq)
-        } else if color < 16 {
-            let seq = format!("\x1b[{}m", 100 + color - 8);
-            self.write_str(&seq)
-        } else {
-            let seq = format!("\x1b[48;5;{}m", color);
-            self.write_str(&seq)
-        }
+        self.write_str(&sgr_color(color, true))
+    }
+
+    /// Set a 24-bit RGB foreground color.
+    pub fn set_fg_rgb(&mut self, rgb: (u8, u8, u8)) -> Result<()> {
+        self.write_str(&sgr_rgb(rgb, false))
+    }
+
+    /// Set a 24-bit RGB background color.
+    pub fn set_bg_rgb(&mut self, rgb: (u8, u8, u8)) -> Result<()> {
+        self.write_str(&sgr_rgb(rgb, true))
     }
 
     /// Ring the terminal bell.
@@ -1127,6 +1710,13 @@ impl Terminal {
         self.state
     }
 
+    /// Pretend to be a different terminal type without re-detecting
+    /// anything else.
+    #[cfg(test)]
+    pub(crate) fn set_term_type(&mut self, term_type: &str) {
+        self.term_type = term_type.to_string();
+    }
+
     /// Get the terminal type.
     pub fn term_type(&self) -> &str {
         &self.term_type
@@ -1157,12 +1747,26 @@ impl Terminal {
         self.can_change_color
     }
 
+    /// Check if the terminal accepts 24-bit RGB colors.
+    ///
+    /// Detected from `COLORTERM=truecolor` (or `24bit`).
+    pub fn has_truecolor(&self) -> bool {
+        self.truecolor
+    }
+
+    /// Override truecolor detection.
+    pub fn set_truecolor(&mut self, truecolor: bool) {
+        self.truecolor = truecolor;
+    }
+
     /// Get the input file descriptor.
     pub fn input_fd(&self) -> RawFd {
         self.input_fd
     }
 
     /// Get the output file descriptor.
+    ///
+    /// Returns -1 for a terminal created with [`with_output()`](Self::with_output).
     pub fn output_fd(&self) -> RawFd {
         self.output_fd
     }
@@ -1296,10 +1900,137 @@ impl Terminal {
     pub fn has_il(&self) -> bool {
         self.has_il
     }
+
+    /// Restore this terminal from a panic hook if the program panics.
+    ///
+    /// The hook runs before the previously installed hook prints the panic
+    /// message, so the message lands on a usable terminal even if the panic
+    /// aborts instead of unwinding. The hook is installed on top of the
+    /// existing one the first time this is called; later calls only refresh
+    /// the saved tty settings. It fires at most once per call. Reset
+    /// sequences are written straight to the output file descriptor and are
+    /// skipped for a terminal created with [`with_output()`](Self::with_output).
+    pub fn install_panic_hook(&self) {
+        let restore = PanicRestore {
+            input_fd: self.input_fd,
+            output_fd: self.output_fd,
+            shell_settings: self.shell_settings.clone(),
+            no_tty: self.no_tty,
+        };
+
+        let mut installed = lock_ignoring_poison(&self.panic_hook);
+        if let Some(state) = installed.as_ref() {
+            lock_ignoring_poison(state).restore = Some(restore);
+            return;
+        }
+
+        let state = Arc::new(Mutex::new(PanicHookState {
+            restore: Some(restore),
+            previous: Some(std::panic::take_hook()),
+            below: PANIC_HOOK_TOP.load(Ordering::SeqCst),
+        }));
+        let hook_state = Arc::clone(&state);
+        std::panic::set_hook(Box::new(move |info| {
+            let mut state = lock_ignoring_poison(&hook_state);
+            if let Some(restore) = state.restore.take() {
+                restore.run();
+            }
+            if let Some(previous) = state.previous.as_ref() {
+                previous(info);
+            }
+        }));
+        PANIC_HOOK_TOP.store(Arc::as_ptr(&state) as usize, Ordering::SeqCst);
+        *installed = Some(state);
+    }
+
+    /// Stop restoring this terminal on panic.
+    ///
+    /// If no other hook has been installed since, the hook that was in place
+    /// before [`install_panic_hook()`](Self::install_panic_hook) is put back.
+    /// Otherwise this terminal's hook stays in the chain so the later hook
+    /// keeps working, but it only passes the panic on. This is done
+    /// automatically when the terminal is dropped.
+    pub fn remove_panic_hook(&self) {
+        let Some(state) = lock_ignoring_poison(&self.panic_hook).take() else {
+            return;
+        };
+        let mut state_guard = lock_ignoring_poison(&state);
+        state_guard.restore = None;
+        let ours = Arc::as_ptr(&state) as usize;
+        if PANIC_HOOK_TOP
+            .compare_exchange(ours, state_guard.below, Ordering::SeqCst, Ordering::SeqCst)
+            .is_ok()
+        {
+            if let Some(previous) = state_guard.previous.take() {
+                drop(state_guard);
+                drop(std::panic::take_hook());
+                std::panic::set_hook(previous);
+            }
+        }
+    }
+}
+
+/// Signature of a process panic hook.
+type PanicHook = Box<dyn Fn(&std::panic::PanicHookInfo<'_>) + Sync + Send + 'static>;
+
+/// Address of the [`PanicHookState`] whose hook is currently installed, or
+/// 0 if the current hook was not installed by a terminal.
+static PANIC_HOOK_TOP: AtomicUsize = AtomicUsize::new(0);
+
+/// A terminal's installed panic hook, shared between the terminal and the
+/// hook closure.
+struct PanicHookState {
+    /// Settings to restore, until the hook fires or is removed.
+    restore: Option<PanicRestore>,
+    /// The hook that was installed before this one.
+    previous: Option<PanicHook>,
+    /// [`PANIC_HOOK_TOP`] from before this hook was installed.
+    below: usize,
+}
+
+/// What the panic hook needs to restore a terminal, copied out of it so the
+/// hook does not have to reach the `Terminal` itself.
+struct PanicRestore {
+    input_fd: RawFd,
+    output_fd: RawFd,
+    shell_settings: TermSettings,
+    no_tty: bool,
+}
+
+impl PanicRestore {
+    /// Write the reset sequences and put back the shell tty settings.
+    ///
+    /// Errors are ignored: there is nothing useful to do with them while
+    /// panicking.
+    fn run(&self) {
+        if self.output_fd >= 0 {
+            let mut rest = PANIC_RESET;
+            while !rest.is_empty() {
+                // SAFETY: `rest` is a valid buffer of `rest.len()` bytes and
+                // `write` does not retain the pointer. A closed or invalid fd
+                // makes the call fail, which ends the loop.
+                let n = unsafe { libc::write(self.output_fd, rest.as_ptr().cast(), rest.len()) };
+                if n <= 0 {
+                    break;
+                }
+                rest = &rest[n as usize..];
+            }
+        }
+        let _ = self.shell_settings.restore(self.input_fd, self.no_tty);
+    }
+}
+
+/// Lock a mutex, ignoring poisoning so the panic hook still works after a
+/// panic elsewhere.
+fn lock_ignoring_poison<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
 }
 
 impl Drop for Terminal {
     fn drop(&mut self) {
+        self.remove_panic_hook();
         // Try to restore terminal state
         if self.state == TermState::Running {
             let _ = self.leave_program_mode();
@@ -1403,8 +2134,60 @@ mod tests {
         assert!(!settings.is_saved());
     }
 
+    #[test]
+    fn test_with_output_size() {
+        let output: Box<dyn Write + Send> = Box::new(io::sink());
+        let mut term = Terminal::with_output(libc::STDIN_FILENO, output, Size::new(7, 33)).unwrap();
+        term.update_size().unwrap();
+        assert_eq!((term.lines(), term.columns()), (7, 33));
+        assert_eq!(term.output_fd(), -1);
+
+        let output: Box<dyn Write + Send> = Box::new(io::sink());
+        assert!(Terminal::with_output(libc::STDIN_FILENO, output, Size::new(0, 80)).is_err());
+    }
+
     #[test]
     fn test_term_state() {
         assert_eq!(TermState::default(), TermState::Unknown);
     }
+
+    #[test]
+    fn test_cursor_motion() {
+        // Unknown or pending-wrap positions always move absolutely
+        assert_eq!(cursor_motion(-1, -1, 2, 3, 80), "\x1b[3;4H");
+        assert_eq!(cursor_motion(2, 80, 2, 3, 80), "\x1b[3;4H");
+
+        assert_eq!(cursor_motion(2, 3, 2, 4, 80), "\x1b[C");
+        assert_eq!(cursor_motion(2, 3, 2, 20, 80), "\x1b[17C");
+        assert_eq!(cursor_motion(2, 3, 2, 2, 80), "\x08");
+        assert_eq!(cursor_motion(2, 30, 2, 20, 80), "\x1b[10D");
+        assert_eq!(cursor_motion(2, 30, 3, 0, 80), "\x1b[B\r");
+        assert_eq!(cursor_motion(9, 5, 2, 5, 80), "\x1b[7A");
+        assert_eq!(cursor_motion(0, 0, 40, 70, 80), "\x1b[41;71H");
+    }
+
+    #[test]
+    fn test_parse_device_attributes() {
+        let caps = TermCapabilities::parse_da1(b"\x1b[?64;1;2;4;22c").unwrap();
+        assert_eq!(caps.device_class, 64);
+        assert_eq!(caps.features, [1, 2, 4, 22]);
+        assert!(caps.has_feature(TermCapabilities::SIXEL));
+        assert!(caps.is_modern());
+        assert!(!caps.has_truecolor());
+
+        // Alacritty reports a VT102 with no extensions
+        let caps = TermCapabilities::parse_da1(b"\x1b[?6c").unwrap();
+        assert_eq!((caps.device_class, caps.features.len()), (6, 0));
+        assert!(!caps.is_modern());
+
+        assert!(TermCapabilities::parse_da1(b"\x1b[?c").is_none());
+        assert!(TermCapabilities::parse_da1(b"\x1b[?1;xc").is_none());
+        assert!(TermCapabilities::parse_da1(b"\x1b[12;34R").is_none());
+
+        assert_eq!(
+            TermCapabilities::parse_xtversion(b"\x1bP>|kitty(0.35.2)\x1b\\").as_deref(),
+            Some("kitty(0.35.2)")
+        );
+        assert!(TermCapabilities::parse_xtversion(b"\x1bP>|kitty").is_none());
+    }
 }
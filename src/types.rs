@@ -96,6 +96,14 @@ bitflags::bitflags! {
 #[cfg(feature = "wide")]
 pub const CCHARW_MAX: usize = 5;
 
+/// The number of combining characters a single cell can hold.
+///
+/// Marks beyond this are rejected by
+/// [`CCharT::push_combining`](crate::wide::CCharT::push_combining) and
+/// reported as errors by `addnwstr` and `setcchar`.
+#[cfg(feature = "wide")]
+pub const MAX_COMBINING: usize = CCHARW_MAX - 1;
+
 /// Timeout values for input operations.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 pub enum Delay {
@@ -187,6 +195,35 @@ impl From<CursorVisibility> for i32 {
     }
 }
 
+/// Hardware cursor shapes.
+///
+/// These map onto the DECSCUSR (`CSI Ps SP q`) control sequence understood by
+/// xterm and most modern terminal emulators.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(i32)]
+pub enum CursorStyle {
+    /// Blinking block cursor.
+    BlinkingBlock = 1,
+    /// Steady (non-blinking) block cursor.
+    SteadyBlock = 2,
+    /// Blinking underline cursor.
+    BlinkingUnderline = 3,
+    /// Steady underline cursor.
+    SteadyUnderline = 4,
+    /// Blinking vertical bar cursor.
+    BlinkingBar = 5,
+    /// Steady vertical bar cursor.
+    SteadyBar = 6,
+}
+
+impl CursorStyle {
+    /// Get the DECSCUSR parameter for this style.
+    #[must_use]
+    pub const fn to_raw(self) -> i32 {
+        self as i32
+    }
+}
+
 /// Border characters for drawing window borders.
 ///
 /// This struct provides a more ergonomic way to specify border characters
@@ -200,6 +237,9 @@ impl From<CursorVisibility> for i32 {
 /// // Use default box-drawing characters
 /// let border = BorderChars::default();
 ///
+/// // Use a preset style
+/// let border = BorderChars::double();
+///
 /// // Create a simple border with same chars for sides
 /// let border = BorderChars::simple('|' as u32, '-' as u32);
 ///
@@ -225,6 +265,10 @@ pub struct BorderChars {
     pub bottom_left: ChType,
     /// Bottom-right corner character.
     pub bottom_right: ChType,
+    /// Whether the characters are Unicode code points for line drawing
+    /// rather than `ChType` cells. Set by the presets in wide builds; the
+    /// values then carry no attributes.
+    pub line_drawing: bool,
 }
 
 impl Default for BorderChars {
@@ -240,6 +284,7 @@ impl Default for BorderChars {
             top_right: 0,
             bottom_left: 0,
             bottom_right: 0,
+            line_drawing: false,
         }
     }
 }
@@ -258,6 +303,7 @@ impl BorderChars {
             top_right: 0,
             bottom_left: 0,
             bottom_right: 0,
+            line_drawing: false,
         }
     }
 
@@ -273,9 +319,61 @@ impl BorderChars {
             top_right: ch,
             bottom_left: ch,
             bottom_right: ch,
+            line_drawing: false,
         }
     }
 
+    /// Light lines with rounded corners: `╭─╮`, `│ │`, `╰─╯`.
+    ///
+    /// Without the `wide` feature this is the default ACS border.
+    #[must_use]
+    pub const fn rounded() -> Self {
+        Self::line_drawing(['│', '─', '╭', '╮', '╰', '╯'])
+    }
+
+    /// Double lines: `╔═╗`, `║ ║`, `╚═╝`.
+    ///
+    /// Without the `wide` feature this is the default ACS border.
+    #[must_use]
+    pub const fn double() -> Self {
+        Self::line_drawing(['║', '═', '╔', '╗', '╚', '╝'])
+    }
+
+    /// Heavy lines: `┏━┓`, `┃ ┃`, `┗━┛`.
+    ///
+    /// Without the `wide` feature this is the default ACS border.
+    #[must_use]
+    pub const fn heavy() -> Self {
+        Self::line_drawing(['┃', '━', '┏', '┓', '┗', '┛'])
+    }
+
+    /// Plain ASCII: `+-+`, `| |`, `+-+`.
+    #[must_use]
+    pub const fn ascii() -> Self {
+        let corner = '+' as ChType;
+        Self::simple('|' as ChType, '-' as ChType).with_corners(corner, corner, corner, corner)
+    }
+
+    /// Border from line-drawing characters, given as the vertical and
+    /// horizontal sides followed by the corners in `with_corners()` order.
+    #[cfg(feature = "wide")]
+    const fn line_drawing([v, h, tl, tr, bl, br]: [char; 6]) -> Self {
+        let mut border = Self::simple(v as ChType, h as ChType).with_corners(
+            tl as ChType,
+            tr as ChType,
+            bl as ChType,
+            br as ChType,
+        );
+        border.line_drawing = true;
+        border
+    }
+
+    /// Border from line-drawing characters; narrow builds use ACS instead.
+    #[cfg(not(feature = "wide"))]
+    const fn line_drawing(_: [char; 6]) -> Self {
+        Self::uniform(0)
+    }
+
     /// Set corner characters.
     #[must_use]
     pub const fn with_corners(
@@ -13,7 +13,8 @@ use std::fmt;
 /// A `cchar_t` stores an array of wide characters (up to `CCHARW_MAX`).
 /// The first character is normally a spacing character, and the rest are
 /// combining (non-spacing) characters.
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CCharT {
     /// Attributes for this character cell.
     pub attr: AttrT,
@@ -78,17 +79,31 @@ impl CCharT {
         self.chars.iter().take_while(|&&c| c != '\0').count()
     }
 
-    /// Add a combining character.
+    /// Append a combining character.
     ///
-    /// Returns `true` if the character was added, `false` if there's no room.
-    pub fn add_combining(&mut self, ch: char) -> bool {
-        for i in 1..CCHARW_MAX {
-            if self.chars[i] == '\0' {
-                self.chars[i] = ch;
-                return true;
+    /// Returns `true` if the character was added, or `false` if the cell
+    /// already holds [`MAX_COMBINING`](crate::types::MAX_COMBINING) combining
+    /// characters. A full cell is left unchanged.
+    pub fn push_combining(&mut self, ch: char) -> bool {
+        match self.chars[1..].iter_mut().find(|c| **c == '\0') {
+            Some(slot) => {
+                *slot = ch;
+                true
             }
+            None => false,
         }
-        false
+    }
+
+    /// Add a combining character.
+    ///
+    /// This is the same as [`push_combining`](Self::push_combining).
+    pub fn add_combining(&mut self, ch: char) -> bool {
+        self.push_combining(ch)
+    }
+
+    /// Get the combining characters stored after the spacing character.
+    pub fn combining(&self) -> impl Iterator<Item = char> + '_ {
+        self.chars[1..].iter().copied().take_while(|&c| c != '\0')
     }
 
     /// Get the attributes.
@@ -287,6 +302,22 @@ mod tests {
         assert_eq!(ch.spacing_char(), 'e');
     }
 
+    #[test]
+    fn test_push_combining_capacity() {
+        use crate::types::MAX_COMBINING;
+
+        let marks = ['\u{0301}', '\u{0308}', '\u{0323}', '\u{0330}', '\u{0331}'];
+        let mut ch = CCharT::from_char('a');
+        for &m in &marks[..MAX_COMBINING] {
+            assert!(ch.push_combining(m));
+        }
+        assert_eq!(ch.char_count(), 1 + MAX_COMBINING);
+
+        // A full cell rejects further marks and keeps what it had
+        assert!(!ch.push_combining(marks[MAX_COMBINING]));
+        assert_eq!(ch.combining().collect::<Vec<_>>(), marks[..MAX_COMBINING]);
+    }
+
     #[test]
     fn test_cchar_width() {
         // ASCII character
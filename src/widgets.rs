@@ -0,0 +1,212 @@
+//! Simple widgets for ncurses-pure.
+//!
+//! Widgets draw into an existing [`Window`] using the ordinary output
+//! functions, so they combine freely with other drawing and are shown by
+//! the usual refresh.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use ncurses::widgets::Gauge;
+//! use ncurses::{Result, Screen};
+//!
+//! # fn main() -> Result<()> {
+//! let mut screen = Screen::init()?;
+//! let mut win = screen.newwin(1, 40, 0, 0)?;
+//! Gauge::new(0.42).label("Copying").draw(&mut win, 0)?;
+//! screen.wrefresh(&mut win)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::attr::{A_COLOR, A_NORMAL};
+use crate::error::Result;
+use crate::types::{AttrT, Coord};
+use crate::window::Window;
+
+/// Character used for the filled part of a gauge.
+#[cfg(feature = "wide")]
+const GAUGE_FILL: &str = "█";
+#[cfg(not(feature = "wide"))]
+const GAUGE_FILL: &str = "#";
+
+/// Width of the percentage shown at the right of a gauge, e.g. `" 42%"`.
+const PERCENT_WIDTH: Coord = 4;
+
+/// A horizontal progress bar with an optional label and a percentage.
+///
+/// The gauge takes a whole window row: the label on the left, then the bar,
+/// then the percentage right-aligned in the last four columns. Cells are
+/// drawn with the window's current attributes, plus the filled or empty
+/// attributes of the gauge; a color pair in those replaces the window's.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Gauge {
+    /// Progress from 0.0 to 1.0.
+    fraction: f64,
+    /// Text shown before the bar.
+    label: Option<String>,
+    /// Attributes added to the filled part of the bar.
+    filled_attr: AttrT,
+    /// Attributes added to the empty part of the bar.
+    empty_attr: AttrT,
+}
+
+impl Gauge {
+    /// Create a gauge showing `fraction`, clamped to `0.0..=1.0`.
+    pub fn new(fraction: f64) -> Self {
+        let mut gauge = Self {
+            fraction: 0.0,
+            label: None,
+            filled_attr: A_NORMAL,
+            empty_attr: A_NORMAL,
+        };
+        gauge.set_fraction(fraction);
+        gauge
+    }
+
+    /// Show `label` before the bar.
+    #[must_use]
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Add `attr` to the filled part of the bar.
+    #[must_use]
+    pub fn filled_attr(mut self, attr: AttrT) -> Self {
+        self.filled_attr = attr;
+        self
+    }
+
+    /// Add `attr` to the empty part of the bar.
+    #[must_use]
+    pub fn empty_attr(mut self, attr: AttrT) -> Self {
+        self.empty_attr = attr;
+        self
+    }
+
+    /// Get the progress shown.
+    pub fn fraction(&self) -> f64 {
+        self.fraction
+    }
+
+    /// Change the progress shown, clamped to `0.0..=1.0`.
+    ///
+    /// NaN counts as no progress.
+    pub fn set_fraction(&mut self, fraction: f64) {
+        self.fraction = if fraction.is_nan() {
+            0.0
+        } else {
+            fraction.clamp(0.0, 1.0)
+        };
+    }
+
+    /// Draw the gauge across row `y` of `win`.
+    ///
+    /// The window's attributes are left as they were, and the cursor ends
+    /// up after the percentage.
+    pub fn draw(&self, win: &mut Window, y: Coord) -> Result<()> {
+        let width = win.getmaxx();
+        let saved = win.getattrs();
+
+        win.mv(y, 0)?;
+        win.clrtoeol()?;
+
+        let mut x = 0;
+        if let Some(label) = &self.label {
+            x = win.mvaddstr_clipped(y, 0, label)? as Coord + 1;
+        }
+
+        // Leave a space between the bar and the percentage
+        let bar_width = (width - x - PERCENT_WIDTH - 1).max(0);
+        let filled = (self.fraction * bar_width as f64).floor() as Coord;
+        let result = self.draw_bar(win, y, x, filled, bar_width, saved);
+        win.attrset(saved)?;
+        result?;
+
+        let percent = format!("{:>3}%", (self.fraction * 100.0).floor() as i32);
+        win.mvaddstr_clipped(y, (width - PERCENT_WIDTH).max(x), &percent)?;
+        Ok(())
+    }
+
+    /// Draw `filled` of `bar_width` cells from column `x` as filled.
+    fn draw_bar(
+        &self,
+        win: &mut Window,
+        y: Coord,
+        x: Coord,
+        filled: Coord,
+        bar_width: Coord,
+        base: AttrT,
+    ) -> Result<()> {
+        win.attrset(with_attr(base, self.filled_attr))?;
+        win.mvaddstr_clipped(y, x, &GAUGE_FILL.repeat(filled as usize))?;
+        win.attrset(with_attr(base, self.empty_attr))?;
+        win.mvaddstr_clipped(y, x + filled, &" ".repeat((bar_width - filled) as usize))?;
+        Ok(())
+    }
+}
+
+/// Add `extra` to `base`, letting a color pair in `extra` replace the one in
+/// `base`.
+fn with_attr(base: AttrT, extra: AttrT) -> AttrT {
+    if extra & A_COLOR != 0 {
+        (base & !A_COLOR) | extra
+    } else {
+        base | extra
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attr::{color_pair, pair_number, A_REVERSE};
+
+    fn row(win: &mut Window) -> String {
+        win.mvinnstr(0, 0, 20).unwrap()
+    }
+
+    fn filled_cells(win: &mut Window) -> usize {
+        row(win).matches(GAUGE_FILL).count()
+    }
+
+    #[test]
+    fn test_gauge_fill() {
+        let mut win = Window::new(1, 20, 0, 0).unwrap();
+
+        Gauge::new(0.0).draw(&mut win, 0).unwrap();
+        assert_eq!(filled_cells(&mut win), 0);
+        assert!(row(&mut win).ends_with("  0%"));
+
+        // 15 bar cells: 20 columns less the percentage and a space
+        Gauge::new(0.5).draw(&mut win, 0).unwrap();
+        assert_eq!(filled_cells(&mut win), 7);
+        assert!(row(&mut win).ends_with(" 50%"));
+
+        Gauge::new(1.0).draw(&mut win, 0).unwrap();
+        assert_eq!(filled_cells(&mut win), 15);
+        assert!(row(&mut win).ends_with("100%"));
+
+        Gauge::new(f64::NAN).draw(&mut win, 0).unwrap();
+        assert_eq!(filled_cells(&mut win), 0);
+    }
+
+    #[test]
+    fn test_gauge_label_and_attrs() {
+        let mut win = Window::new(1, 20, 0, 0).unwrap();
+        win.attron(color_pair(2)).unwrap();
+        Gauge::new(2.0)
+            .label("io")
+            .filled_attr(A_REVERSE)
+            .draw(&mut win, 0)
+            .unwrap();
+
+        assert!(row(&mut win).starts_with("io "));
+        assert_eq!(filled_cells(&mut win), 12);
+        assert_eq!(win.getattrs(), color_pair(2));
+        let bar = win.mvinch(0, 3).unwrap();
+        assert_ne!(bar & A_REVERSE, 0);
+        assert_eq!(pair_number(bar), 2);
+        assert_eq!(win.mvinch(0, 0).unwrap() & A_REVERSE, 0);
+    }
+}
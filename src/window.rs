@@ -7,12 +7,24 @@ use crate::attr::{self, color_pair, A_CHARTEXT, A_NORMAL};
 use crate::error::{Error, Result};
 use crate::line::LineData;
 use crate::types::{AttrT, ChType, NcursesSize, WindowFlags};
+use std::collections::BTreeMap;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
 
 #[cfg(feature = "wide")]
 use crate::wide::CCharT;
 
+/// The contents of one window cell.
+#[cfg(not(feature = "wide"))]
+type Cell = ChType;
+/// The contents of one window cell.
+#[cfg(feature = "wide")]
+type Cell = CCharT;
+
 /// Pad-specific data for pad windows.
 #[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PadData {
     /// Pad Y position for refresh.
     pub pad_y: NcursesSize,
@@ -28,6 +40,259 @@ pub struct PadData {
     pub pad_right: NcursesSize,
 }
 
+/// A copy of a window's size, cursor and cells.
+///
+/// Take one with [`Window::snapshot()`] and apply it with
+/// [`Window::restore_snapshot()`]. With the `serde` feature it can be
+/// serialized, for example to JSON for snapshot tests.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WindowSnapshot {
+    /// Number of lines.
+    pub height: i32,
+    /// Number of columns.
+    pub width: i32,
+    /// Cursor position as `(y, x)`.
+    pub cursor: (i32, i32),
+    /// Cells, one row per line.
+    #[cfg(not(feature = "wide"))]
+    pub cells: Vec<Vec<ChType>>,
+    /// Cells, one row per line.
+    #[cfg(feature = "wide")]
+    pub cells: Vec<Vec<CCharT>>,
+}
+
+/// What [`Window::addstr_checked()`] managed to write.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AddResult {
+    /// Number of `char`s of the string written, including control and
+    /// combining characters.
+    pub written: usize,
+    /// Whether output ran past the last line. Without `scrollok` writing
+    /// stopped there and the rest of the string was dropped.
+    pub reached_bottom: bool,
+    /// Whether the window scrolled to make room.
+    pub scrolled: bool,
+}
+
+/// Output position while `addstr_wrapped` lays out text.
+struct WrapCursor {
+    /// Line being written.
+    row: NcursesSize,
+    /// Next free column; equal to the width once the line is full.
+    col: usize,
+    /// Whether reaching the bottom scrolls the window.
+    scroll: bool,
+}
+
+/// Attribute state saved by `push_attrs` and `standout`.
+#[derive(Clone, Copy, Debug)]
+struct SavedAttrs {
+    attrs: AttrT,
+    #[cfg(feature = "ext-colors")]
+    color: i32,
+}
+
+/// What the subwindows of a window have synced up to it.
+///
+/// Each window owns one and its subwindows reach it through their link,
+/// so the window can tell without locking any link whether there is
+/// anything to apply.
+struct SyncInbox {
+    /// Set when a subwindow queues cells for the window.
+    cells: AtomicBool,
+    /// Cursor position set by a subwindow's `cursyncup()`, packed by
+    /// `pack_cursor()`, or `NO_CURSOR`.
+    cursor: AtomicU64,
+}
+
+/// The packed value of an unset `SyncInbox::cursor`.
+const NO_CURSOR: u64 = u64::MAX;
+
+/// Pack a cursor position into one `u64`.
+fn pack_cursor(y: usize, x: usize) -> u64 {
+    ((y as u64) << 32) | (x as u64 & 0xffff_ffff)
+}
+
+impl SyncInbox {
+    fn new() -> Self {
+        Self {
+            cells: AtomicBool::new(false),
+            cursor: AtomicU64::new(NO_CURSOR),
+        }
+    }
+
+    /// The cursor position waiting to be taken, if any.
+    fn pending_cursor(&self) -> Option<(usize, usize)> {
+        match self.cursor.load(Ordering::Acquire) {
+            NO_CURSOR => None,
+            packed => Some(((packed >> 32) as usize, (packed & 0xffff_ffff) as usize)),
+        }
+    }
+}
+
+/// Changes passed between a subwindow and its parent.
+///
+/// A subwindow keeps its own storage, so the two share one of these and
+/// the sync functions copy cells through it. Cells queued for the parent
+/// are applied the next time the parent is written to or refreshed.
+struct SyncLink {
+    /// Position of the subwindow inside the parent.
+    offset: (usize, usize),
+    /// Cells changed in the subwindow, by position in the parent.
+    up: BTreeMap<(usize, usize), Cell>,
+    /// Cells changed in the parent, by position in the subwindow.
+    down: BTreeMap<(usize, usize), Cell>,
+    /// The parent's inbox.
+    inbox: Arc<SyncInbox>,
+}
+
+/// Lock a sync link, ignoring poisoning: the queued cells stay usable.
+fn lock_link(link: &Mutex<SyncLink>) -> MutexGuard<'_, SyncLink> {
+    link.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// The links a write is passed on to.
+struct SyncTargets<'a> {
+    /// The parent, when this is a subwindow with `syncok` set.
+    parent: Option<&'a Mutex<SyncLink>>,
+    /// The subwindows.
+    children: &'a [Arc<Mutex<SyncLink>>],
+}
+
+impl<'a> SyncTargets<'a> {
+    /// The targets of a window from its `syncok` setting and links.
+    fn new(
+        sync: bool,
+        parent: &'a Option<Arc<Mutex<SyncLink>>>,
+        children: &'a mut Mutex<Vec<Arc<Mutex<SyncLink>>>>,
+    ) -> Self {
+        Self {
+            parent: parent.as_deref().filter(|_| sync),
+            children: children.get_mut().unwrap_or_else(PoisonError::into_inner),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.parent.is_none() && self.children.is_empty()
+    }
+
+    /// Pass on the columns `first..=last` of line `y`, just written.
+    fn pass_on(&self, y: usize, line: &LineData, (first, last): (usize, usize)) {
+        if let Some(link) = self.parent {
+            let mut link = lock_link(link);
+            let (pary, parx) = link.offset;
+            for x in first..=last {
+                // The subwindow's own write is newer than anything from above
+                link.down.remove(&(y, x));
+                link.up.insert((pary + y, parx + x), line.text()[x]);
+            }
+            link.inbox.cells.store(true, Ordering::Release);
+        }
+        for link in self.children {
+            let mut link = lock_link(link);
+            let (pary, parx) = link.offset;
+            if let Some(y) = y.checked_sub(pary) {
+                for x in first.max(parx)..=last {
+                    link.down.insert((y, x - parx), line.text()[x]);
+                }
+            }
+        }
+    }
+}
+
+/// A line borrowed for writing by `Window::line_at()`.
+///
+/// The columns written through it are passed on to the window's sync
+/// targets as the writer is dropped.
+struct LineWriter<'a> {
+    line: &'a mut LineData,
+    y: usize,
+    targets: SyncTargets<'a>,
+}
+
+impl Deref for LineWriter<'_> {
+    type Target = LineData;
+
+    fn deref(&self) -> &LineData {
+        self.line
+    }
+}
+
+impl DerefMut for LineWriter<'_> {
+    fn deref_mut(&mut self) -> &mut LineData {
+        self.line
+    }
+}
+
+impl Drop for LineWriter<'_> {
+    fn drop(&mut self) {
+        if let Some(written) = self.line.take_written() {
+            if !self.targets.is_empty() {
+                self.targets.pass_on(self.y, self.line, written);
+            }
+        }
+    }
+}
+
+/// All lines borrowed for writing by `Window::lines_mut()`.
+struct LinesWriter<'a> {
+    lines: &'a mut [LineData],
+    targets: SyncTargets<'a>,
+}
+
+impl Deref for LinesWriter<'_> {
+    type Target = [LineData];
+
+    fn deref(&self) -> &[LineData] {
+        self.lines
+    }
+}
+
+impl DerefMut for LinesWriter<'_> {
+    fn deref_mut(&mut self) -> &mut [LineData] {
+        self.lines
+    }
+}
+
+impl Drop for LinesWriter<'_> {
+    fn drop(&mut self) {
+        let targets = !self.targets.is_empty();
+        for (y, line) in self.lines.iter_mut().enumerate() {
+            if let Some(written) = line.take_written() {
+                if targets {
+                    self.targets.pass_on(y, line, written);
+                }
+            }
+        }
+    }
+}
+
+/// Fold `bytes` into a 64-bit FNV-1a hash.
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, &b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// Bytes of a cell as fed to [`Window::content_digest()`].
+#[cfg(feature = "wide")]
+fn cell_digest_bytes(cell: &Cell) -> Vec<u8> {
+    let mut bytes = cell.attr.to_le_bytes().to_vec();
+    for c in cell.chars {
+        bytes.extend_from_slice(&u32::from(c).to_le_bytes());
+    }
+    #[cfg(feature = "ext-colors")]
+    bytes.extend_from_slice(&cell.ext_color.to_le_bytes());
+    bytes
+}
+
+/// Bytes of a cell as fed to [`Window::content_digest()`].
+#[cfg(not(feature = "wide"))]
+fn cell_digest_bytes(cell: &Cell) -> Vec<u8> {
+    cell.to_le_bytes().to_vec()
+}
+
 /// A curses window.
 ///
 /// Windows are the fundamental abstraction in curses. They represent a
@@ -61,6 +326,10 @@ pub struct Window {
     flags: WindowFlags,
     /// Current attributes for non-space characters.
     attrs: AttrT,
+    /// Attributes in effect before the outermost `standout()`.
+    standout_saved: Option<SavedAttrs>,
+    /// Attribute states saved by `push_attrs()`, innermost last.
+    attr_stack: Vec<SavedAttrs>,
     /// Current background character/attribute pair.
     #[cfg(not(feature = "wide"))]
     bkgd: ChType,
@@ -78,6 +347,12 @@ pub struct Window {
     leaveok: bool,
     /// OK to scroll this window.
     scroll: bool,
+    /// Wrap at the right margin rather than truncating.
+    wrap: bool,
+    /// Cursor position at which output stopped at the margin with `wrap`
+    /// off; characters written there are dropped until the cursor is moved
+    /// by `mv` or a carriage return, newline or backspace.
+    clipped_at: Option<(NcursesSize, NcursesSize)>,
     /// OK to use insert/delete line.
     idlok: bool,
     /// OK to use insert/delete char.
@@ -90,12 +365,17 @@ pub struct Window {
     use_keypad: bool,
     /// Input delay: 0=nodelay, <0=blocking, >0=delay ms.
     delay: i32,
+    /// Distance between tab stops.
+    tabsize: i32,
 
     // ========================================================================
     // Line data
     // ========================================================================
     /// The actual line data.
     lines: Vec<LineData>,
+    /// Lines touched since the window was last untouched, so refresh can
+    /// skip the rest. `None` means every line has to be checked.
+    dirty: Option<Vec<usize>>,
 
     // ========================================================================
     // Scrolling region
@@ -112,7 +392,12 @@ pub struct Window {
     parx: i32,
     /// Y coordinate of this window in parent.
     pary: i32,
-    // Note: parent pointer not stored directly due to ownership rules
+    /// Link to the parent, through which this subwindow syncs.
+    sync_parent: Option<Arc<Mutex<SyncLink>>>,
+    /// Links to the subwindows of this window.
+    sync_children: Mutex<Vec<Arc<Mutex<SyncLink>>>>,
+    /// Where the subwindows leave what they sync up.
+    sync_inbox: Arc<SyncInbox>,
 
     // ========================================================================
     // Pad data
@@ -320,6 +605,8 @@ impl Window {
             begx: begx as NcursesSize,
             flags: WindowFlags::empty(),
             attrs: A_NORMAL,
+            standout_saved: None,
+            attr_stack: Vec::new(),
             #[cfg(not(feature = "wide"))]
             bkgd: b' ' as ChType,
             #[cfg(feature = "wide")]
@@ -328,17 +615,24 @@ impl Window {
             clear: false,
             leaveok: false,
             scroll: false,
+            wrap: true,
+            clipped_at: None,
             idlok: false,
             idcok: true,
             immed: false,
             sync: false,
             use_keypad: false,
             delay: -1,
+            tabsize: 8,
             lines,
+            dirty: None,
             regtop: 0,
             regbottom: (height - 1) as NcursesSize,
             parx: 0,
             pary: 0,
+            sync_parent: None,
+            sync_children: Mutex::new(Vec::new()),
+            sync_inbox: Arc::new(SyncInbox::new()),
             pad: PadData::default(),
             yoffset: 0,
             #[cfg(feature = "ext-colors")]
@@ -348,7 +642,10 @@ impl Window {
 
     /// Create a new pad.
     ///
-    /// Pads are windows that are not constrained to the screen size.
+    /// Pads are windows that are not constrained to the screen size. As with
+    /// other windows, scrolling is off until `scrollok(true)` and the
+    /// scrolling region covers the whole pad, so scrolling moves the entire
+    /// pad buffer.
     pub fn new_pad(nlines: i32, ncols: i32) -> Result<Self> {
         let mut win = Self::new(nlines, ncols, 0, 0)?;
         win.flags |= WindowFlags::ISPAD;
@@ -397,6 +694,7 @@ impl Window {
     /// * `lines` - New number of lines (height).
     /// * `cols` - New number of columns (width).
     pub fn resize(&mut self, lines: i32, cols: i32) -> Result<()> {
+        self.sync_cursor();
         if lines <= 0 || cols <= 0 {
             return Err(Error::InvalidArgument(
                 "window dimensions must be positive".into(),
@@ -411,6 +709,8 @@ impl Window {
         #[cfg(feature = "wide")]
         let fill = self.bkgrnd;
 
+        let old_maxy = self.maxy;
+
         // Resize existing lines or add new ones
         if new_height > self.lines.len() {
             // Add new lines
@@ -423,7 +723,7 @@ impl Window {
         }
 
         // Resize each line's width
-        for line in &mut self.lines {
+        for line in self.lines_mut().iter_mut() {
             line.resize(new_width, fill);
         }
 
@@ -439,8 +739,8 @@ impl Window {
             self.curx = self.maxx;
         }
 
-        // Update scroll region
-        if self.regbottom >= new_height as NcursesSize {
+        // Update scroll region, keeping a full-window region full
+        if self.regbottom >= new_height as NcursesSize || self.regbottom == old_maxy {
             self.regbottom = self.maxy;
         }
 
@@ -467,8 +767,8 @@ impl Window {
     /// # Note
     ///
     /// Due to Rust's ownership model, this creates a new window with its own
-    /// storage. For true shared storage, use derwin and coordinate updates
-    /// between parent and child.
+    /// storage. Use [`syncup`](Self::syncup) and [`syncdown`](Self::syncdown),
+    /// or [`syncok`](Self::syncok), to copy changes between parent and child.
     pub fn subwin(&self, nlines: i32, ncols: i32, begy: i32, begx: i32) -> Result<Self> {
         if nlines < 0 || ncols < 0 || begy < 0 || begx < 0 {
             return Err(Error::InvalidArgument(
@@ -516,10 +816,12 @@ impl Window {
 
         let mut win = Self::new(height, width, begy, begx)?;
         win.flags |= WindowFlags::SUBWIN;
+        win.tabsize = self.tabsize;
 
         // Store parent offset for coordinate translation
         win.pary = begy - parent_begy;
         win.parx = begx - parent_begx;
+        self.link_subwindow(&mut win);
 
         Ok(win)
     }
@@ -539,8 +841,8 @@ impl Window {
     /// # Note
     ///
     /// Due to Rust's ownership model, this creates a new window with its own
-    /// storage. For true shared storage, coordinate updates between parent
-    /// and child manually.
+    /// storage. Use [`syncup`](Self::syncup) and [`syncdown`](Self::syncdown),
+    /// or [`syncok`](Self::syncok), to copy changes between parent and child.
     pub fn derwin(&self, nlines: i32, ncols: i32, begy: i32, begx: i32) -> Result<Self> {
         if nlines < 0 || ncols < 0 || begy < 0 || begx < 0 {
             return Err(Error::InvalidArgument("negative derwin dimensions".into()));
@@ -570,10 +872,12 @@ impl Window {
 
         let mut win = Self::new(height, width, screen_begy, screen_begx)?;
         win.flags |= WindowFlags::SUBWIN;
+        win.tabsize = self.tabsize;
 
         // Store parent-relative offset
         win.pary = begy;
         win.parx = begx;
+        self.link_subwindow(&mut win);
 
         Ok(win)
     }
@@ -603,6 +907,9 @@ impl Window {
         // Update parent-relative position
         self.pary = y;
         self.parx = x;
+        if let Some(link) = &self.sync_parent {
+            lock_link(link).offset = (y as usize, x as usize);
+        }
 
         // Touch the window to ensure it gets refreshed
         self.touchwin();
@@ -610,10 +917,115 @@ impl Window {
         Ok(())
     }
 
+    /// Turn a subwindow into an independent window.
+    ///
+    /// The contents and screen position are kept, but the window is no
+    /// longer tied to its parent: it can be moved with `mvwin()` and outlives
+    /// the parent like any window from `dupwin()`. Returns an error if the
+    /// window is not a subwindow.
+    pub fn detach(&mut self) -> Result<()> {
+        if !self.flags.contains(WindowFlags::SUBWIN) {
+            return Err(Error::InvalidArgument(
+                "only subwindows can be detached".into(),
+            ));
+        }
+
+        self.flags.remove(WindowFlags::SUBWIN);
+        self.pary = 0;
+        self.parx = 0;
+        self.sync_parent = None;
+        Ok(())
+    }
+
+    /// Link a new subwindow of this window for the sync functions.
+    fn link_subwindow(&self, win: &mut Window) {
+        let link = Arc::new(Mutex::new(SyncLink {
+            offset: (win.pary as usize, win.parx as usize),
+            up: BTreeMap::new(),
+            down: BTreeMap::new(),
+            inbox: Arc::clone(&self.sync_inbox),
+        }));
+        let mut children = self
+            .sync_children
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        // Links only this window still holds belong to dropped subwindows
+        children.retain(|link| Arc::strong_count(link) > 1);
+        children.push(Arc::clone(&link));
+        win.sync_parent = Some(link);
+    }
+
+    /// Copy the changes made in this subwindow into its parent.
+    ///
+    /// This is the equivalent of `wsyncup()`. Subwindows do not share
+    /// storage with their parent, so the changed cells of every touched line
+    /// are queued for the parent at the subwindow's offset. The parent
+    /// applies them, marking its corresponding lines touched, the next time
+    /// it is written to or refreshed. With [`syncok`](Self::syncok) set this
+    /// happens after every change. Returns an error if the window is not a
+    /// subwindow.
+    pub fn syncup(&self) -> Result<()> {
+        let targets = SyncTargets {
+            parent: Some(self.parent_link()?),
+            children: &[],
+        };
+        for y in self.touched_lines() {
+            if let Some(changed) = self.lines[y].changed_range() {
+                targets.pass_on(y, &self.lines[y], changed);
+            }
+        }
+        Ok(())
+    }
+
+    /// Copy the changes made in the parent into this subwindow.
+    ///
+    /// This is the equivalent of `wsyncdown()`: cells the parent changed
+    /// inside the area this subwindow covers, since the subwindow was created
+    /// or last synced down, are copied in, which marks the corresponding
+    /// lines of the subwindow touched. Cells the subwindow has written since
+    /// are kept. Returns an error if the window is not a subwindow.
+    pub fn syncdown(&mut self) -> Result<()> {
+        let cells = std::mem::take(&mut lock_link(self.parent_link()?).down);
+        for ((y, x), cell) in cells {
+            if y < self.lines.len() && x < self.lines[y].width() {
+                self.mark_dirty(y);
+                self.lines[y].set(x, cell);
+                // Came from the parent, so not to be passed back up
+                self.lines[y].take_written();
+            }
+        }
+        Ok(())
+    }
+
+    /// Move the parent's cursor to where this subwindow's cursor is.
+    ///
+    /// This is the equivalent of `wcursyncup()`. The parent's cursor moves
+    /// at once: its next read or write of the cursor sees the new position.
+    /// Returns an error if the window is not a subwindow.
+    pub fn cursyncup(&self) -> Result<()> {
+        let link = lock_link(self.parent_link()?);
+        let (pary, parx) = link.offset;
+        let (cury, curx) = self.cur();
+        link.inbox.cursor.store(
+            pack_cursor(pary + cury as usize, parx + curx as usize),
+            Ordering::Release,
+        );
+        Ok(())
+    }
+
+    /// Get the link to the parent, or an error if this is not a subwindow.
+    fn parent_link(&self) -> Result<&Mutex<SyncLink>> {
+        match &self.sync_parent {
+            Some(link) if self.flags.contains(WindowFlags::SUBWIN) => Ok(link),
+            _ => Err(Error::InvalidArgument("window is not a subwindow".into())),
+        }
+    }
+
     /// Duplicate this window.
     ///
     /// Creates an exact copy of this window with its own storage.
     pub fn dupwin(&self) -> Result<Self> {
+        let (cury, curx) = self.cur();
         let mut win = Self::new(
             self.getmaxy(),
             self.getmaxx(),
@@ -622,11 +1034,13 @@ impl Window {
         )?;
 
         // Copy all state
-        win.cury = self.cury;
-        win.curx = self.curx;
+        win.cury = cury;
+        win.curx = curx;
         win.flags = self.flags;
         win.flags.remove(WindowFlags::SUBWIN); // Duplicated window is not a subwindow
         win.attrs = self.attrs;
+        win.standout_saved = self.standout_saved;
+        win.attr_stack = self.attr_stack.clone();
         #[cfg(not(feature = "wide"))]
         {
             win.bkgd = self.bkgd;
@@ -639,12 +1053,14 @@ impl Window {
         win.clear = self.clear;
         win.leaveok = self.leaveok;
         win.scroll = self.scroll;
+        win.wrap = self.wrap;
         win.idlok = self.idlok;
         win.idcok = self.idcok;
         win.immed = self.immed;
         win.sync = self.sync;
         win.use_keypad = self.use_keypad;
         win.delay = self.delay;
+        win.tabsize = self.tabsize;
         win.regtop = self.regtop;
         win.regbottom = self.regbottom;
         win.parx = 0;
@@ -665,6 +1081,47 @@ impl Window {
         Ok(win)
     }
 
+    /// Capture the size, cursor and cells of this window.
+    #[must_use]
+    pub fn snapshot(&self) -> WindowSnapshot {
+        WindowSnapshot {
+            height: self.getmaxy(),
+            width: self.getmaxx(),
+            cursor: self.cursor(),
+            cells: self.lines.iter().map(|line| line.text().to_vec()).collect(),
+        }
+    }
+
+    /// Resize this window to a snapshot and copy in its cells and cursor.
+    ///
+    /// Returns an error, leaving the window unchanged, if the rows of the
+    /// snapshot do not match its dimensions.
+    pub fn restore_snapshot(&mut self, snapshot: &WindowSnapshot) -> Result<()> {
+        self.sync_cursor();
+        let (y, x) = snapshot.cursor;
+        if snapshot.cells.len() != snapshot.height.max(0) as usize
+            || snapshot
+                .cells
+                .iter()
+                .any(|row| row.len() != snapshot.width.max(0) as usize)
+            || !(0..snapshot.height).contains(&y)
+            || !(0..snapshot.width).contains(&x)
+        {
+            return Err(Error::InvalidArgument(
+                "snapshot does not match its dimensions".into(),
+            ));
+        }
+
+        self.resize(snapshot.height, snapshot.width)?;
+        for (line, row) in self.lines_mut().iter_mut().zip(&snapshot.cells) {
+            line.text_mut().copy_from_slice(row);
+        }
+        self.cury = y as NcursesSize;
+        self.curx = x as NcursesSize;
+        self.touchwin();
+        Ok(())
+    }
+
     // ========================================================================
     // Dimension getters
     // ========================================================================
@@ -701,14 +1158,14 @@ impl Window {
     #[inline]
     #[must_use]
     pub fn getcury(&self) -> i32 {
-        self.cury as i32
+        self.cur().0 as i32
     }
 
     /// Get the current cursor X position.
     #[inline]
     #[must_use]
     pub fn getcurx(&self) -> i32 {
-        self.curx as i32
+        self.cur().1 as i32
     }
 
     /// Get the parent X coordinate (for subwindows).
@@ -725,6 +1182,32 @@ impl Window {
         self.pary
     }
 
+    /// Get the current cursor position as `(y, x)`.
+    #[inline]
+    #[must_use]
+    pub fn cursor(&self) -> (i32, i32) {
+        (self.getcury(), self.getcurx())
+    }
+
+    /// Get the screen position of the upper-left corner as `(y, x)`.
+    #[inline]
+    #[must_use]
+    pub fn origin(&self) -> (i32, i32) {
+        (self.getbegy(), self.getbegx())
+    }
+
+    /// Translate a screen position to a position within this window.
+    ///
+    /// `begy`/`begx` hold the screen origin even for derived windows, so
+    /// this works at any depth of a `derwin` chain. Returns `None` if the
+    /// position is outside the window.
+    #[must_use]
+    pub fn screen_to_local(&self, y: i32, x: i32) -> Option<(i32, i32)> {
+        let (y, x) = (y - self.getbegy(), x - self.getbegx());
+        let inside = (0..self.getmaxy()).contains(&y) && (0..self.getmaxx()).contains(&x);
+        inside.then_some((y, x))
+    }
+
     // ========================================================================
     // Cursor movement
     // ========================================================================
@@ -736,6 +1219,7 @@ impl Window {
     /// * `y` - New Y coordinate (row).
     /// * `x` - New X coordinate (column).
     pub fn mv(&mut self, y: i32, x: i32) -> Result<()> {
+        self.sync_cursor();
         if y < 0 || y > self.maxy as i32 || x < 0 || x > self.maxx as i32 {
             return Err(Error::OutOfBounds {
                 y,
@@ -744,8 +1228,10 @@ impl Window {
                 max_x: self.maxx as i32,
             });
         }
+        self.apply_synced();
         self.cury = y as NcursesSize;
         self.curx = x as NcursesSize;
+        self.clipped_at = None;
         self.flags |= WindowFlags::HASMOVED;
         Ok(())
     }
@@ -788,6 +1274,7 @@ impl Window {
     /// Internal character addition for non-wide mode.
     #[cfg(not(feature = "wide"))]
     fn add_ch_internal(&mut self, ch: ChType) -> Result<()> {
+        self.sync_cursor();
         let x = self.curx as usize;
         let y = self.cury as usize;
 
@@ -796,6 +1283,7 @@ impl Window {
         match c {
             b'\n' => {
                 // Newline: clear to end of line and move to next line
+                self.clipped_at = None;
                 self.clrtoeol()?;
                 if self.cury < self.maxy {
                     self.cury += 1;
@@ -807,12 +1295,14 @@ impl Window {
                 return Ok(());
             }
             b'\r' => {
+                self.clipped_at = None;
                 self.curx = 0;
                 return Ok(());
             }
             b'\t' => {
-                // Tab: move to next tab stop (every 8 columns)
-                let next_tab = ((self.curx / 8) + 1) * 8;
+                // Tab: move to next tab stop
+                let tabsize = self.tabsize as NcursesSize;
+                let next_tab = ((self.curx / tabsize) + 1) * tabsize;
                 let spaces = (next_tab - self.curx) as usize;
                 for _ in 0..spaces {
                     self.add_ch_internal(b' ' as ChType | (ch & !A_CHARTEXT))?;
@@ -821,6 +1311,7 @@ impl Window {
             }
             b'\x08' => {
                 // Backspace
+                self.clipped_at = None;
                 if self.curx > 0 {
                     self.curx -= 1;
                 }
@@ -830,13 +1321,13 @@ impl Window {
         }
 
         // Check bounds
-        if y > self.maxy as usize {
+        if y > self.maxy as usize || self.past_margin() {
             return Ok(());
         }
 
         // Write the character
         if x <= self.maxx as usize {
-            self.lines[y].set(x, ch);
+            self.line_at(y).set(x, ch);
         }
 
         // Advance cursor
@@ -848,6 +1339,7 @@ impl Window {
     /// Internal wide character addition.
     #[cfg(feature = "wide")]
     fn add_wch_internal(&mut self, ch: CCharT) -> Result<()> {
+        self.sync_cursor();
         let x = self.curx as usize;
         let y = self.cury as usize;
 
@@ -855,6 +1347,7 @@ impl Window {
         let c = ch.spacing_char();
         match c {
             '\n' => {
+                self.clipped_at = None;
                 self.clrtoeol()?;
                 if self.cury < self.maxy {
                     self.cury += 1;
@@ -866,11 +1359,13 @@ impl Window {
                 return Ok(());
             }
             '\r' => {
+                self.clipped_at = None;
                 self.curx = 0;
                 return Ok(());
             }
             '\t' => {
-                let next_tab = ((self.curx / 8) + 1) * 8;
+                let tabsize = self.tabsize as NcursesSize;
+                let next_tab = ((self.curx / tabsize) + 1) * tabsize;
                 let spaces = (next_tab - self.curx) as usize;
                 for _ in 0..spaces {
                     let space = CCharT::from_char_attr(' ', ch.attr);
@@ -879,6 +1374,7 @@ impl Window {
                 return Ok(());
             }
             '\x08' => {
+                self.clipped_at = None;
                 if self.curx > 0 {
                     self.curx -= 1;
                 }
@@ -887,7 +1383,7 @@ impl Window {
             _ => {}
         }
 
-        if y > self.maxy as usize {
+        if y > self.maxy as usize || self.past_margin() {
             return Ok(());
         }
 
@@ -904,13 +1400,13 @@ impl Window {
         }
 
         // Write the character
-        self.lines[y].set(x, ch);
+        self.line_at(y).set(x, ch);
 
         // For wide characters, fill the second cell with a placeholder
         if width > 1 && x < self.maxx as usize {
             // Use a special marker for the second cell
             let placeholder = CCharT::new();
-            self.lines[y].set(x + 1, placeholder);
+            self.line_at(y).set(x + 1, placeholder);
         }
 
         // Advance cursor by character width
@@ -923,8 +1419,12 @@ impl Window {
 
     /// Advance the cursor after character output.
     fn advance_cursor(&mut self) -> Result<()> {
+        self.sync_cursor();
         self.curx += 1;
-        if self.curx > self.maxx {
+        if self.curx > self.maxx && !self.wrap {
+            self.curx = self.maxx;
+            self.clipped_at = Some((self.cury, self.curx));
+        } else if self.curx > self.maxx {
             self.curx = 0;
             self.flags |= WindowFlags::WRAPPED;
             if self.cury < self.maxy {
@@ -939,11 +1439,61 @@ impl Window {
         Ok(())
     }
 
+    /// Whether output has run into the right margin with wrapping off.
+    fn past_margin(&self) -> bool {
+        self.clipped_at == Some(self.cur())
+    }
+
     /// Add a string at the current position.
     pub fn addstr(&mut self, s: &str) -> Result<()> {
         self.addnstr(s, -1)
     }
 
+    /// Add a string, reporting whether it ran past the bottom of the window.
+    ///
+    /// Behaves like [`addstr()`](Self::addstr), except that without
+    /// `scrollok` it stops at the bottom-right cell instead of overwriting
+    /// it with each remaining character. The returned [`AddResult`] says how
+    /// much of `s` was written and whether the bottom margin was reached.
+    pub fn addstr_checked(&mut self, s: &str) -> Result<AddResult> {
+        self.sync_cursor();
+        let mut result = AddResult::default();
+        let mut buf = [0u8; 4];
+        for c in s.chars() {
+            let (y, x) = (self.cury as usize, self.curx as usize);
+            let cells = match c {
+                '\t' => {
+                    let tabsize = self.tabsize as usize;
+                    (x / tabsize + 1) * tabsize - x
+                }
+                '\n' => 0,
+                c if c.is_control() => 0,
+                c => Self::char_cells(c),
+            };
+            // Whether this character moves output past the last line
+            let overflows = y == self.maxy as usize
+                && (c == '\n' || (self.wrap && x + cells > self.maxx as usize));
+            if overflows && !self.scroll {
+                result.reached_bottom = true;
+                // The last cell can still be filled; a newline has nowhere to go
+                if c == '\n' || x + cells > self.maxx as usize + 1 {
+                    break;
+                }
+            }
+
+            self.addstr(c.encode_utf8(&mut buf))?;
+            result.written += 1;
+            if overflows && self.scroll {
+                result.reached_bottom = true;
+                result.scrolled = true;
+            }
+            if result.reached_bottom && !self.scroll {
+                break;
+            }
+        }
+        Ok(result)
+    }
+
     /// Move to position and add a string.
     pub fn mvaddstr(&mut self, y: i32, x: i32, s: &str) -> Result<()> {
         self.mv(y, x)?;
@@ -957,29 +1507,205 @@ impl Window {
     }
 
     /// Add a string with a maximum length.
+    ///
+    /// In the wide build this behaves like [`addnwstr`](Self::addnwstr):
+    /// combining characters join the preceding cell.
+    #[cfg(not(feature = "wide"))]
     pub fn addnstr(&mut self, s: &str, n: i32) -> Result<()> {
         let max_chars = if n < 0 { usize::MAX } else { n as usize };
-        for (i, c) in s.chars().enumerate() {
-            if i >= max_chars {
+        for c in s.chars().take(max_chars) {
+            if c.is_ascii() {
+                self.addch(c as ChType)?;
+            } else {
+                self.addch(b'?' as ChType)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Add a string with a maximum length.
+    ///
+    /// In the wide build this behaves like [`addnwstr`](Self::addnwstr):
+    /// combining characters join the preceding cell.
+    #[cfg(feature = "wide")]
+    pub fn addnstr(&mut self, s: &str, n: i32) -> Result<()> {
+        self.addnwstr(s, n)
+    }
+
+    /// Move to a position and add a string, clipping instead of failing.
+    ///
+    /// The position is clamped into the window, and the string is cut off at
+    /// the right margin instead of wrapping or scrolling. The cursor is left
+    /// after the last character written (on the last column if the string
+    /// reached the margin). Returns the number of `char`s of `s` written,
+    /// including combining characters. `s` is treated as a single line:
+    /// output stops at the first control character other than `'\t'`, and
+    /// a tab counts as the cells up to the next tab stop.
+    pub fn mvaddstr_clipped(&mut self, y: i32, x: i32, s: &str) -> Result<usize> {
+        self.sync_cursor();
+        let y = y.clamp(0, self.maxy as i32);
+        let x = x.clamp(0, self.maxx as i32);
+        let room = (self.maxx as i32 + 1 - x) as usize;
+
+        let mut used = 0;
+        let mut count = 0;
+        let mut end = 0;
+        let tabsize = self.tabsize.max(1) as usize;
+        for (i, c) in s.char_indices() {
+            let width = match c {
+                '\t' => tabsize - (x as usize + used) % tabsize,
+                c if c.is_control() => break,
+                c => Self::char_cells(c),
+            };
+            if used + width > room {
                 break;
             }
-            #[cfg(not(feature = "wide"))]
-            {
-                if c.is_ascii() {
-                    self.addch(c as ChType)?;
-                } else {
-                    self.addch(b'?' as ChType)?;
+            used += width;
+            count += 1;
+            end = i + c.len_utf8();
+        }
+
+        self.mv(y, x)?;
+        let scroll = self.scroll;
+        self.scroll = false;
+        let result = self.addstr(&s[..end]);
+        self.scroll = scroll;
+        result?;
+
+        self.cury = y as NcursesSize;
+        self.curx = (x + used as i32).min(self.maxx as i32) as NcursesSize;
+        Ok(count)
+    }
+
+    /// Add a string, wrapping at word boundaries instead of mid-word.
+    ///
+    /// Words are separated by spaces; a word that does not fit on the rest
+    /// of the line starts the next one, and the spaces at the break are
+    /// dropped. Words wider than the window are split where they must be.
+    /// `'\n'` starts a new line as in [`addstr()`](Self::addstr). Writing
+    /// stops at the bottom of the window unless `scrollok` is set, in which
+    /// case the window scrolls. The current attributes are applied.
+    pub fn addstr_wrapped(&mut self, s: &str) -> Result<()> {
+        self.sync_cursor();
+        // Breaks are placed here, so keep addstr from scrolling on its own
+        let scroll = self.scroll;
+        self.scroll = false;
+        let mut wrap = WrapCursor {
+            row: self.cury,
+            col: self.curx as usize,
+            scroll,
+        };
+        let result = self.add_wrapped(s, &mut wrap);
+        self.scroll = scroll;
+        self.cury = wrap.row;
+        self.curx = wrap.col.min(self.maxx as usize) as NcursesSize;
+        result
+    }
+
+    /// Write `s` for [`addstr_wrapped()`](Self::addstr_wrapped), tracking
+    /// the position in `wrap`.
+    fn add_wrapped(&mut self, s: &str, wrap: &mut WrapCursor) -> Result<()> {
+        let width = self.maxx as usize + 1;
+
+        for (i, line) in s.split('\n').enumerate() {
+            if i > 0 {
+                if wrap.col < width {
+                    self.mv(wrap.row as i32, wrap.col as i32)?;
+                    self.clrtoeol()?;
+                }
+                if !self.wrap_line(wrap)? {
+                    return Ok(());
                 }
             }
-            #[cfg(feature = "wide")]
-            {
-                let cchar = CCharT::from_char_attr(c, self.attrs);
-                self.add_wch_internal(cchar)?;
+
+            let mut gap = 0;
+            for (j, word) in line.split(' ').enumerate() {
+                if j > 0 {
+                    gap += 1;
+                }
+                if word.is_empty() {
+                    continue;
+                }
+
+                let word_width: usize = word.chars().map(Self::char_cells).sum();
+                if wrap.col > 0 && wrap.col + gap + word_width > width {
+                    if !self.wrap_line(wrap)? {
+                        return Ok(());
+                    }
+                    gap = 0;
+                }
+                let spaces = gap.min(width - wrap.col);
+                self.put_wrapped(&" ".repeat(spaces), spaces, wrap)?;
+                gap = 0;
+
+                // Split words wider than the remaining line
+                let mut start = 0;
+                let mut used = 0;
+                for (k, c) in word.char_indices() {
+                    let w = Self::char_cells(c);
+                    if wrap.col + used + w > width {
+                        self.put_wrapped(&word[start..k], used, wrap)?;
+                        if !self.wrap_line(wrap)? {
+                            return Ok(());
+                        }
+                        start = k;
+                        used = 0;
+                    }
+                    used += w;
+                }
+                self.put_wrapped(&word[start..], used, wrap)?;
             }
+
+            // Keep trailing spaces that still fit, so pieces can be joined
+            let spaces = gap.min(width - wrap.col);
+            self.put_wrapped(&" ".repeat(spaces), spaces, wrap)?;
+        }
+        Ok(())
+    }
+
+    /// Write `text`, known to be `cells` wide and to fit, at the wrap
+    /// position.
+    fn put_wrapped(&mut self, text: &str, cells: usize, wrap: &mut WrapCursor) -> Result<()> {
+        if text.is_empty() {
+            return Ok(());
         }
+        self.mv(wrap.row as i32, wrap.col as i32)?;
+        self.addstr(text)?;
+        wrap.col += cells;
         Ok(())
     }
 
+    /// Move the wrap position to the start of the next line, scrolling if
+    /// allowed. Returns false if the bottom of the window was reached.
+    fn wrap_line(&mut self, wrap: &mut WrapCursor) -> Result<bool> {
+        if wrap.row < self.maxy {
+            wrap.row += 1;
+        } else if wrap.scroll {
+            self.scroll_up(1)?;
+        } else {
+            return Ok(false);
+        }
+        wrap.col = 0;
+        Ok(true)
+    }
+
+    /// Number of cells `c` takes up when written.
+    fn char_cells(c: char) -> usize {
+        #[cfg(feature = "wide")]
+        {
+            if crate::wide::is_combining(c) {
+                0
+            } else {
+                crate::wide::char_width(c)
+            }
+        }
+        #[cfg(not(feature = "wide"))]
+        {
+            let _ = c;
+            1
+        }
+    }
+
     /// Add a chtype string at the current position.
     pub fn addchstr(&mut self, chstr: &[ChType]) -> Result<()> {
         self.addchnstr(chstr, -1)
@@ -987,23 +1713,26 @@ impl Window {
 
     /// Add a chtype string with a maximum length.
     pub fn addchnstr(&mut self, chstr: &[ChType], n: i32) -> Result<()> {
+        self.sync_cursor();
         let max_chars = if n < 0 { chstr.len() } else { n as usize };
         let y = self.cury as usize;
-        let mut x = self.curx as usize;
+        let start = self.curx as usize;
 
-        for &ch in chstr.iter().take(max_chars) {
+        for (x, &ch) in (start..).zip(chstr.iter().take(max_chars)) {
             if x > self.maxx as usize {
                 break;
             }
             #[cfg(not(feature = "wide"))]
-            self.lines[y].set(x, ch | self.attrs);
+            {
+                let ch = ch | self.attrs;
+                self.line_at(y).set(x, ch);
+            }
             #[cfg(feature = "wide")]
             {
                 let c = (ch & A_CHARTEXT) as u8 as char;
                 let attr = (ch & !A_CHARTEXT) | self.attrs;
-                self.lines[y].set(x, CCharT::from_char_attr(c, attr));
+                self.line_at(y).set(x, CCharT::from_char_attr(c, attr));
             }
-            x += 1;
         }
 
         Ok(())
@@ -1025,16 +1754,55 @@ impl Window {
     /// Add a wide string with a maximum length.
     ///
     /// This is the Rust equivalent of `addnwstr()` / `waddnwstr()`.
-    /// At most n characters are written. If n is negative, the entire string is written.
+    /// At most n spacing characters are written. If n is negative, the entire
+    /// string is written. Combining characters (zero display width) do not
+    /// count towards n; they are stored in the combining slots of the cell
+    /// written just before them, and are dropped if there is no such cell.
+    ///
+    /// Returns an error if a cell would need more than
+    /// [`MAX_COMBINING`](crate::types::MAX_COMBINING) combining characters.
+    /// Everything before the rejected mark has been written by then.
     #[cfg(feature = "wide")]
     pub fn addnwstr(&mut self, s: &str, n: i32) -> Result<()> {
+        self.sync_cursor();
         let max_chars = if n < 0 { usize::MAX } else { n as usize };
-        for (i, c) in s.chars().enumerate() {
-            if i >= max_chars {
+        let mut written = 0;
+        // Cell that received the last spacing character, for combining marks
+        let mut base: Option<(usize, usize)> = None;
+
+        for c in s.chars() {
+            if crate::wide::is_combining(c) {
+                if let Some((y, x)) = base {
+                    let mut cell = self.lines[y].get(x);
+                    if !cell.push_combining(c) {
+                        return Err(Error::InvalidArgument(format!(
+                            "cell already holds {} combining characters",
+                            crate::types::MAX_COMBINING
+                        )));
+                    }
+                    self.line_at(y).set(x, cell);
+                }
+                continue;
+            }
+
+            if written >= max_chars {
                 break;
             }
+            written += 1;
+
             let cchar = CCharT::from_char_attr(c, self.attrs);
+            let (y, x) = (self.cury as usize, self.curx as usize);
+            let fits = y <= self.maxy as usize
+                && x + cchar.width() <= (self.maxx + 1) as usize
+                && !self.past_margin();
+            base = (fits && !c.is_control()).then_some((y, x));
             self.add_wch_internal(cchar)?;
+
+            // Wrapping off the bottom line scrolls the base cell up one row
+            let scrolled = self.scroll && self.cury as usize == y && (self.curx as usize) <= x;
+            if scrolled {
+                base = base.and_then(|(by, bx)| Some((by.checked_sub(1)?, bx)));
+            }
         }
         Ok(())
     }
@@ -1074,19 +1842,16 @@ impl Window {
     /// Writing stops at the right margin without wrapping.
     #[cfg(feature = "wide")]
     pub fn add_wchnstr(&mut self, wchstr: &[CCharT], n: i32) -> Result<()> {
+        self.sync_cursor();
         let y = self.cury as usize;
-        let mut x = self.curx as usize;
+        let start = self.curx as usize;
         let max_chars = if n < 0 { wchstr.len() } else { n as usize };
 
-        for (i, wch) in wchstr.iter().enumerate() {
-            if i >= max_chars {
-                break;
-            }
+        for (x, wch) in (start..).zip(wchstr.iter().take(max_chars)) {
             if x > self.maxx as usize {
                 break;
             }
-            self.lines[y].set(x, *wch);
-            x += 1;
+            self.line_at(y).set(x, *wch);
         }
 
         Ok(())
@@ -1127,8 +1892,9 @@ impl Window {
     #[cfg(not(feature = "wide"))]
     #[must_use]
     pub fn inch(&self) -> ChType {
-        let y = self.cury as usize;
-        let x = self.curx as usize;
+        let (cury, curx) = self.cur();
+        let y = cury as usize;
+        let x = curx as usize;
         if y <= self.maxy as usize && x <= self.maxx as usize {
             self.lines[y].get(x)
         } else {
@@ -1140,8 +1906,9 @@ impl Window {
     #[cfg(feature = "wide")]
     #[must_use]
     pub fn inch(&self) -> ChType {
-        let y = self.cury as usize;
-        let x = self.curx as usize;
+        let (cury, curx) = self.cur();
+        let y = cury as usize;
+        let x = curx as usize;
         if y <= self.maxy as usize && x <= self.maxx as usize {
             let cchar = self.lines[y].get(x);
             let c = cchar.spacing_char() as u8 as ChType;
@@ -1154,15 +1921,16 @@ impl Window {
     /// Get a string of characters from the current position.
     #[must_use]
     pub fn instr(&self, n: i32) -> String {
+        let (cury, curx) = self.cur();
         let mut result = String::new();
-        let y = self.cury as usize;
+        let y = cury as usize;
         let max_x = if n < 0 {
             self.maxx as usize + 1
         } else {
-            (self.curx as usize + n as usize).min(self.maxx as usize + 1)
+            (curx as usize + n as usize).min(self.maxx as usize + 1)
         };
 
-        for x in (self.curx as usize)..max_x {
+        for x in (curx as usize)..max_x {
             #[cfg(not(feature = "wide"))]
             {
                 let ch = self.lines[y].get(x);
@@ -1199,21 +1967,22 @@ impl Window {
     ///
     /// If `n` is negative, reads to the end of the line.
     pub fn inchnstr(&self, chstr: &mut [ChType], n: i32) -> i32 {
-        let y = self.cury as usize;
+        let (cury, curx) = self.cur();
+        let y = cury as usize;
         let max_chars = if n < 0 {
             chstr
                 .len()
-                .min((self.maxx as usize + 1).saturating_sub(self.curx as usize))
+                .min((self.maxx as usize + 1).saturating_sub(curx as usize))
         } else {
             chstr
                 .len()
                 .min(n as usize)
-                .min((self.maxx as usize + 1).saturating_sub(self.curx as usize))
+                .min((self.maxx as usize + 1).saturating_sub(curx as usize))
         };
 
         let mut count = 0;
         for (i, ch) in chstr.iter_mut().take(max_chars).enumerate() {
-            let x = self.curx as usize + i;
+            let x = curx as usize + i;
             if x > self.maxx as usize {
                 break;
             }
@@ -1267,12 +2036,13 @@ impl Window {
 
     /// Erase the entire window (fill with background).
     pub fn erase(&mut self) -> Result<()> {
+        self.sync_cursor();
         #[cfg(not(feature = "wide"))]
         let fill = self.bkgd;
         #[cfg(feature = "wide")]
         let fill = self.bkgrnd;
 
-        for line in &mut self.lines {
+        for line in self.lines_mut().iter_mut() {
             line.fill(fill);
         }
         self.cury = 0;
@@ -1282,6 +2052,7 @@ impl Window {
 
     /// Clear to end of line.
     pub fn clrtoeol(&mut self) -> Result<()> {
+        self.sync_cursor();
         let y = self.cury as usize;
         let x = self.curx as usize;
 
@@ -1291,13 +2062,15 @@ impl Window {
         let fill = self.bkgrnd;
 
         if y <= self.maxy as usize {
-            self.lines[y].fill_range(x, (self.maxx + 1) as usize, fill);
+            let end = (self.maxx + 1) as usize;
+            self.line_at(y).fill_range(x, end, fill);
         }
         Ok(())
     }
 
     /// Clear to end of window.
     pub fn clrtobot(&mut self) -> Result<()> {
+        self.sync_cursor();
         self.clrtoeol()?;
 
         #[cfg(not(feature = "wide"))]
@@ -1306,7 +2079,7 @@ impl Window {
         let fill = self.bkgrnd;
 
         for y in (self.cury as usize + 1)..=(self.maxy as usize) {
-            self.lines[y].fill(fill);
+            self.line_at(y).fill(fill);
         }
         Ok(())
     }
@@ -1317,60 +2090,53 @@ impl Window {
 
     /// Scroll the window up by n lines.
     pub fn scroll_up(&mut self, n: i32) -> Result<()> {
-        if n <= 0 {
-            return Ok(());
+        if n > 0 {
+            self.shift_lines(self.regtop as usize, self.regbottom as usize, -n);
         }
+        Ok(())
+    }
 
-        let n = n.min((self.regbottom - self.regtop + 1) as i32) as usize;
-        let top = self.regtop as usize;
-        let bottom = self.regbottom as usize;
-
-        #[cfg(not(feature = "wide"))]
-        let fill = self.bkgd;
-        #[cfg(feature = "wide")]
-        let fill = self.bkgrnd;
-
-        // Shift lines up - clone source lines first to avoid borrow issues
-        for y in top..=(bottom - n) {
-            let src_line = self.lines[y + n].clone();
-            self.lines[y].copy_from(&src_line);
-        }
-
-        // Clear the bottom lines
-        for y in (bottom - n + 1)..=bottom {
-            self.lines[y].fill(fill);
+    /// Scroll the window down by n lines.
+    pub fn scroll_down(&mut self, n: i32) -> Result<()> {
+        if n > 0 {
+            self.shift_lines(self.regtop as usize, self.regbottom as usize, n);
         }
-
         Ok(())
     }
 
-    /// Scroll the window down by n lines.
-    pub fn scroll_down(&mut self, n: i32) -> Result<()> {
-        if n <= 0 {
-            return Ok(());
+    /// Shift lines `top..=bottom` down by `n` (up if negative).
+    ///
+    /// Lines shifted out of the range are lost and vacated lines are filled
+    /// with the background. Lines outside the range are not touched.
+    fn shift_lines(&mut self, top: usize, bottom: usize, n: i32) {
+        let n_abs = (n.unsigned_abs() as usize).min(bottom + 1 - top);
+        if n_abs == 0 {
+            return;
         }
 
-        let n = n.min((self.regbottom - self.regtop + 1) as i32) as usize;
-        let top = self.regtop as usize;
-        let bottom = self.regbottom as usize;
-
         #[cfg(not(feature = "wide"))]
         let fill = self.bkgd;
         #[cfg(feature = "wide")]
         let fill = self.bkgrnd;
 
-        // Shift lines down - clone source lines first to avoid borrow issues
-        for y in ((top + n)..=bottom).rev() {
-            let src_line = self.lines[y - n].clone();
-            self.lines[y].copy_from(&src_line);
-        }
+        // Clone source lines first to avoid borrow issues
+        let vacated = if n > 0 {
+            for y in ((top + n_abs)..=bottom).rev() {
+                let src_line = self.lines[y - n_abs].clone();
+                self.line_at(y).copy_from(&src_line);
+            }
+            top..(top + n_abs)
+        } else {
+            for y in top..=(bottom - n_abs) {
+                let src_line = self.lines[y + n_abs].clone();
+                self.line_at(y).copy_from(&src_line);
+            }
+            (bottom + 1 - n_abs)..(bottom + 1)
+        };
 
-        // Clear the top lines
-        for y in top..(top + n) {
-            self.lines[y].fill(fill);
+        for y in vacated {
+            self.line_at(y).fill(fill);
         }
-
-        Ok(())
     }
 
     /// Scroll the scrolling region (wscrl).
@@ -1398,14 +2164,22 @@ impl Window {
     }
 
     /// Turn off attributes.
+    ///
+    /// This ends any pending [`standout()`](Self::standout), so a later
+    /// `standend()` no longer restores the attributes it saved.
     pub fn attroff(&mut self, attr: AttrT) -> Result<()> {
         self.attrs &= !attr;
+        self.standout_saved = None;
         Ok(())
     }
 
     /// Set attributes.
+    ///
+    /// This ends any pending [`standout()`](Self::standout), so a later
+    /// `standend()` no longer restores the attributes it saved.
     pub fn attrset(&mut self, attr: AttrT) -> Result<()> {
         self.attrs = attr;
+        self.standout_saved = None;
         Ok(())
     }
 
@@ -1416,13 +2190,73 @@ impl Window {
     }
 
     /// Turn on standout mode (typically reverse video).
+    ///
+    /// The current attributes are remembered so that the matching
+    /// [`standend()`](Self::standend) can restore them. Nested calls keep the
+    /// attributes from before the outermost `standout()`.
     pub fn standout(&mut self) -> Result<()> {
+        if self.standout_saved.is_none() {
+            self.standout_saved = Some(self.saved_attrs());
+        }
         self.attron(crate::attr::A_STANDOUT)
     }
 
     /// Turn off standout mode.
+    ///
+    /// Restores the attributes saved by `standout()`, including any color
+    /// pair. Without a preceding `standout()`, or if the attributes were set
+    /// or turned off since, this resets the attributes to `A_NORMAL`, as in
+    /// traditional curses.
     pub fn standend(&mut self) -> Result<()> {
-        self.attrset(A_NORMAL)
+        let saved = self.standout_saved.take();
+        self.attrset(saved.map_or(A_NORMAL, |saved| saved.attrs))?;
+        #[cfg(feature = "ext-colors")]
+        if let Some(saved) = saved {
+            self.color = saved.color;
+        }
+        Ok(())
+    }
+
+    /// Save the current attributes and color pair.
+    ///
+    /// The matching [`pop_attrs()`](Self::pop_attrs) restores them, so a
+    /// temporary change can be undone without knowing what was set before.
+    pub fn push_attrs(&mut self) {
+        let saved = self.saved_attrs();
+        self.attr_stack.push(saved);
+    }
+
+    /// The current attributes and color pair, for restoring later.
+    fn saved_attrs(&self) -> SavedAttrs {
+        SavedAttrs {
+            attrs: self.attrs,
+            #[cfg(feature = "ext-colors")]
+            color: self.color,
+        }
+    }
+
+    /// Restore the attributes saved by the most recent `push_attrs()`.
+    ///
+    /// Returns an error, leaving the attributes untouched, if nothing has
+    /// been pushed.
+    pub fn pop_attrs(&mut self) -> Result<()> {
+        let saved = self
+            .attr_stack
+            .pop()
+            .ok_or_else(|| Error::WindowError("attribute stack is empty".into()))?;
+        self.attrs = saved.attrs;
+        self.standout_saved = None;
+        #[cfg(feature = "ext-colors")]
+        {
+            self.color = saved.color;
+        }
+        Ok(())
+    }
+
+    /// Number of attribute states currently saved by `push_attrs()`.
+    #[must_use]
+    pub fn attr_stack_depth(&self) -> usize {
+        self.attr_stack.len()
     }
 
     /// Set color pair.
@@ -1435,6 +2269,29 @@ impl Window {
         Ok(())
     }
 
+    /// Set an extended color pair.
+    ///
+    /// Unlike [`color_set()`](Self::color_set) the pair may exceed the range
+    /// that fits in the attribute bits; those bits then hold the largest pair
+    /// they can represent and the full number is kept separately.
+    #[cfg(feature = "ext-colors")]
+    pub fn extended_color_set(&mut self, pair: i32) -> Result<()> {
+        if pair < 0 {
+            return Err(Error::InvalidColorPair(pair.max(i16::MIN as i32) as i16));
+        }
+        let attr_pair = pair.min(attr::MAX_ATTR_COLOR_PAIR as i32) as i16;
+        self.attrs = (self.attrs & !attr::A_COLOR) | color_pair(attr_pair);
+        self.color = pair;
+        Ok(())
+    }
+
+    /// Get the current extended color pair.
+    #[cfg(feature = "ext-colors")]
+    #[must_use]
+    pub fn extended_color(&self) -> i32 {
+        self.color
+    }
+
     // ========================================================================
     // Background
     // ========================================================================
@@ -1480,7 +2337,7 @@ impl Window {
         let new_attr = ch & !A_CHARTEXT;
 
         // Update all cells
-        for line in &mut self.lines {
+        for line in self.lines_mut().iter_mut() {
             for x in 0..line.width() {
                 let cell = line.get(x);
                 let cell_char = (cell & A_CHARTEXT) as u8;
@@ -1532,11 +2389,39 @@ impl Window {
 
     /// Change attributes of characters starting at current position (window version).
     pub fn wchgat(&mut self, n: i32, attr: AttrT, color: i16) -> Result<()> {
+        // Build the combined attribute with color
+        let combined_attr = if color != 0 {
+            attr | color_pair(color)
+        } else {
+            attr
+        };
+
+        self.map_attrs(n, |_| combined_attr);
+        Ok(())
+    }
+
+    /// Change only the color pair of characters starting at current position.
+    ///
+    /// Unlike [`wchgat()`](Self::wchgat), which replaces the attributes
+    /// wholesale, this keeps bold, underline and the other attributes of each
+    /// cell. A negative `n` changes the rest of the line.
+    pub fn chgat_color_only(&mut self, n: i32, pair: i16) -> Result<()> {
+        let color = color_pair(pair);
+        self.map_attrs(n, |attrs| (attrs & !attr::A_COLOR) | color);
+        Ok(())
+    }
+
+    /// Replace the attributes of up to `n` characters from the cursor with
+    /// `f` applied to their current attributes. A negative `n` means the
+    /// rest of the line. Both halves of a wide character at either end of
+    /// the range are changed together.
+    fn map_attrs(&mut self, n: i32, f: impl Fn(AttrT) -> AttrT) {
+        self.sync_cursor();
         let y = self.cury as usize;
         let x = self.curx as usize;
 
         if y > self.maxy as usize {
-            return Ok(());
+            return;
         }
 
         // Determine end position
@@ -1545,34 +2430,43 @@ impl Window {
         } else {
             (x + n as usize).min(self.maxx as usize + 1)
         };
+        let (x, end_x) = self.whole_chars(y, x, end_x);
 
-        // Build the combined attribute with color
-        let combined_attr = if color != 0 {
-            attr | color_pair(color)
-        } else {
-            attr
-        };
-
-        // Change attributes for each character
-        #[cfg(not(feature = "wide"))]
-        {
-            for cx in x..end_x {
+        for cx in x..end_x {
+            #[cfg(not(feature = "wide"))]
+            {
                 let cell = self.lines[y].get(cx);
-                let ch = cell & A_CHARTEXT;
-                self.lines[y].set(cx, ch | combined_attr);
+                self.line_at(y)
+                    .set(cx, (cell & A_CHARTEXT) | f(cell & !A_CHARTEXT));
             }
-        }
-
-        #[cfg(feature = "wide")]
-        {
-            for cx in x..end_x {
+            #[cfg(feature = "wide")]
+            {
                 let mut cell = self.lines[y].get(cx);
-                cell.set_attrs(combined_attr);
-                self.lines[y].set(cx, cell);
+                cell.set_attrs(f(cell.attrs()));
+                self.line_at(y).set(cx, cell);
             }
         }
+    }
 
-        Ok(())
+    /// Widen the columns `x..end_x` of line `y` so they do not split a wide
+    /// character.
+    #[cfg(feature = "wide")]
+    fn whole_chars(&self, y: usize, mut x: usize, mut end_x: usize) -> (usize, usize) {
+        let line = &self.lines[y];
+        if x > 0 && line.get(x).is_null() && line.get(x - 1).is_wide() {
+            x -= 1;
+        }
+        if end_x > x && end_x < line.width() && line.get(end_x - 1).is_wide() {
+            end_x += 1;
+        }
+        (x, end_x)
+    }
+
+    /// Widen the columns `x..end_x` of line `y` so they do not split a wide
+    /// character.
+    #[cfg(not(feature = "wide"))]
+    fn whole_chars(&self, _y: usize, x: usize, end_x: usize) -> (usize, usize) {
+        (x, end_x)
     }
 
     /// Move cursor and change attributes.
@@ -1612,6 +2506,33 @@ impl Window {
     /// win.draw_border(BorderChars::simple('|' as u32, '-' as u32))?;
     /// ```
     pub fn draw_border(&mut self, chars: crate::types::BorderChars) -> Result<()> {
+        #[cfg(feature = "wide")]
+        if chars.line_drawing {
+            let cell = |ch: ChType| {
+                (ch != 0).then(|| CCharT::from_char(char::from_u32(ch).unwrap_or(' ')))
+            };
+            let [ls, rs, ts, bs, tl, tr, bl, br] = [
+                chars.left,
+                chars.right,
+                chars.top,
+                chars.bottom,
+                chars.top_left,
+                chars.top_right,
+                chars.bottom_left,
+                chars.bottom_right,
+            ]
+            .map(cell);
+            return self.wborder_set(
+                ls.as_ref(),
+                rs.as_ref(),
+                ts.as_ref(),
+                bs.as_ref(),
+                tl.as_ref(),
+                tr.as_ref(),
+                bl.as_ref(),
+                br.as_ref(),
+            );
+        }
         self.border(
             chars.left,
             chars.right,
@@ -1638,94 +2559,84 @@ impl Window {
         br: ChType,
     ) -> Result<()> {
         // Default characters if 0
-        let ls = if ls == 0 { self.acs_vline() } else { ls };
-        let rs = if rs == 0 { self.acs_vline() } else { rs };
-        let ts = if ts == 0 { self.acs_hline() } else { ts };
-        let bs = if bs == 0 { self.acs_hline() } else { bs };
-        let tl = if tl == 0 { self.acs_ulcorner() } else { tl };
-        let tr = if tr == 0 { self.acs_urcorner() } else { tr };
-        let bl = if bl == 0 { self.acs_llcorner() } else { bl };
-        let br = if br == 0 { self.acs_lrcorner() } else { br };
+        #[cfg(not(feature = "wide"))]
+        let [ls, rs, ts, bs, tl, tr, bl, br] = [
+            if ls == 0 { self.acs_vline() } else { ls },
+            if rs == 0 { self.acs_vline() } else { rs },
+            if ts == 0 { self.acs_hline() } else { ts },
+            if bs == 0 { self.acs_hline() } else { bs },
+            if tl == 0 { self.acs_ulcorner() } else { tl },
+            if tr == 0 { self.acs_urcorner() } else { tr },
+            if bl == 0 { self.acs_llcorner() } else { bl },
+            if br == 0 { self.acs_lrcorner() } else { br },
+        ];
+        #[cfg(feature = "wide")]
+        let [ls, rs, ts, bs, tl, tr, bl, br] = {
+            use crate::acs::*;
+            [
+                Self::acs_cchar(ls, ACS_VLINE),
+                Self::acs_cchar(rs, ACS_VLINE),
+                Self::acs_cchar(ts, ACS_HLINE),
+                Self::acs_cchar(bs, ACS_HLINE),
+                Self::acs_cchar(tl, ACS_ULCORNER),
+                Self::acs_cchar(tr, ACS_URCORNER),
+                Self::acs_cchar(bl, ACS_LLCORNER),
+                Self::acs_cchar(br, ACS_LRCORNER),
+            ]
+        };
 
         let maxy = self.maxy as usize;
         let maxx = self.maxx as usize;
 
         // Corners
-        #[cfg(not(feature = "wide"))]
-        {
-            self.lines[0].set(0, tl);
-            self.lines[0].set(maxx, tr);
-            self.lines[maxy].set(0, bl);
-            self.lines[maxy].set(maxx, br);
-        }
-        #[cfg(feature = "wide")]
-        {
-            self.set_ch_at(0, 0, tl);
-            self.set_ch_at(0, maxx as i32, tr);
-            self.set_ch_at(maxy as i32, 0, bl);
-            self.set_ch_at(maxy as i32, maxx as i32, br);
-        }
+        self.line_at(0).set(0, tl);
+        self.line_at(0).set(maxx, tr);
+        self.line_at(maxy).set(0, bl);
+        self.line_at(maxy).set(maxx, br);
 
         // Top and bottom edges
         for x in 1..maxx {
-            #[cfg(not(feature = "wide"))]
-            {
-                self.lines[0].set(x, ts);
-                self.lines[maxy].set(x, bs);
-            }
-            #[cfg(feature = "wide")]
-            {
-                self.set_ch_at(0, x as i32, ts);
-                self.set_ch_at(maxy as i32, x as i32, bs);
-            }
+            self.line_at(0).set(x, ts);
+            self.line_at(maxy).set(x, bs);
         }
 
         // Left and right edges
         for y in 1..maxy {
-            #[cfg(not(feature = "wide"))]
-            {
-                self.lines[y].set(0, ls);
-                self.lines[y].set(maxx, rs);
-            }
-            #[cfg(feature = "wide")]
-            {
-                self.set_ch_at(y as i32, 0, ls);
-                self.set_ch_at(y as i32, maxx as i32, rs);
-            }
+            self.line_at(y).set(0, ls);
+            self.line_at(y).set(maxx, rs);
         }
 
         Ok(())
     }
 
-    #[cfg(feature = "wide")]
-    fn set_ch_at(&mut self, y: i32, x: i32, ch: ChType) {
-        let c = (ch & A_CHARTEXT) as u8 as char;
-        let attr = ch & !A_CHARTEXT;
-        self.lines[y as usize].set(x as usize, CCharT::from_char_attr(c, attr));
-    }
-
     /// Draw a horizontal line.
+    ///
+    /// The line starts at the cursor and is clipped at the right margin;
+    /// nothing is drawn if the cursor is past it. The window's attributes are
+    /// merged into `ch`, with a color pair in `ch` taking precedence.
     pub fn hline(&mut self, ch: ChType, n: i32) -> Result<()> {
-        let ch = if ch == 0 { self.acs_hline() } else { ch };
-        let n = n.min((self.maxx - self.curx + 1) as i32) as usize;
+        self.sync_cursor();
+        if self.cury > self.maxy {
+            return Ok(());
+        }
+        let n = Self::line_len(n, self.curx, self.maxx);
         let y = self.cury as usize;
         let x = self.curx as usize;
 
         #[cfg(not(feature = "wide"))]
         {
-            // Combine character with window attributes (like addch does)
+            let ch = if ch == 0 { self.acs_hline() } else { ch };
+            let ch = (ch & A_CHARTEXT) | self.merge_line_attrs(ch & !A_CHARTEXT);
             for i in 0..n {
-                self.lines[y].set(x + i, ch | self.attrs);
+                self.line_at(y).set(x + i, ch);
             }
         }
         #[cfg(feature = "wide")]
         {
-            // Extract character and attributes, combining with window attributes
-            let c = (ch & A_CHARTEXT) as u8 as char;
-            let attr = (ch & !A_CHARTEXT) | self.attrs;
-            let cchar = CCharT::from_char_attr(c, attr);
+            let mut cchar = Self::acs_cchar(ch, crate::acs::ACS_HLINE);
+            cchar.set_attrs(self.merge_line_attrs(cchar.attrs()));
             for i in 0..n {
-                self.lines[y].set(x + i, cchar);
+                self.line_at(y).set(x + i, cchar);
             }
         }
 
@@ -1733,27 +2644,33 @@ impl Window {
     }
 
     /// Draw a vertical line.
+    ///
+    /// The line starts at the cursor and is clipped at the bottom margin;
+    /// nothing is drawn if the cursor is past it. Attributes are merged as
+    /// in [`hline()`](Self::hline).
     pub fn vline(&mut self, ch: ChType, n: i32) -> Result<()> {
-        let ch = if ch == 0 { self.acs_vline() } else { ch };
-        let n = n.min((self.maxy - self.cury + 1) as i32) as usize;
+        self.sync_cursor();
+        if self.curx > self.maxx {
+            return Ok(());
+        }
+        let n = Self::line_len(n, self.cury, self.maxy);
         let y = self.cury as usize;
         let x = self.curx as usize;
 
         #[cfg(not(feature = "wide"))]
         {
-            // Combine character with window attributes (like addch does)
+            let ch = if ch == 0 { self.acs_vline() } else { ch };
+            let ch = (ch & A_CHARTEXT) | self.merge_line_attrs(ch & !A_CHARTEXT);
             for i in 0..n {
-                self.lines[y + i].set(x, ch | self.attrs);
+                self.line_at(y + i).set(x, ch);
             }
         }
         #[cfg(feature = "wide")]
         {
-            // Extract character and attributes, combining with window attributes
-            let c = (ch & A_CHARTEXT) as u8 as char;
-            let attr = (ch & !A_CHARTEXT) | self.attrs;
-            let cchar = CCharT::from_char_attr(c, attr);
+            let mut cchar = Self::acs_cchar(ch, crate::acs::ACS_VLINE);
+            cchar.set_attrs(self.merge_line_attrs(cchar.attrs()));
             for i in 0..n {
-                self.lines[y + i].set(x, cchar);
+                self.line_at(y + i).set(x, cchar);
             }
         }
 
@@ -1763,17 +2680,21 @@ impl Window {
     /// Draw a horizontal line using a complex character.
     #[cfg(feature = "wide")]
     pub fn hline_set(&mut self, wch: &CCharT, n: i32) -> Result<()> {
+        self.sync_cursor();
+        if self.cury > self.maxy {
+            return Ok(());
+        }
         let wch = if wch.spacing_char() == '\0' {
             CCharT::from_char('─')
         } else {
             *wch
         };
-        let n = n.min((self.maxx - self.curx + 1) as i32) as usize;
+        let n = Self::line_len(n, self.curx, self.maxx);
         let y = self.cury as usize;
         let x = self.curx as usize;
 
         for i in 0..n {
-            self.lines[y].set(x + i, wch);
+            self.line_at(y).set(x + i, wch);
         }
 
         Ok(())
@@ -1782,82 +2703,83 @@ impl Window {
     /// Draw a vertical line using a complex character.
     #[cfg(feature = "wide")]
     pub fn vline_set(&mut self, wch: &CCharT, n: i32) -> Result<()> {
+        self.sync_cursor();
+        if self.curx > self.maxx {
+            return Ok(());
+        }
         let wch = if wch.spacing_char() == '\0' {
             CCharT::from_char('│')
         } else {
             *wch
         };
-        let n = n.min((self.maxy - self.cury + 1) as i32) as usize;
+        let n = Self::line_len(n, self.cury, self.maxy);
         let y = self.cury as usize;
         let x = self.curx as usize;
 
         for i in 0..n {
-            self.lines[y + i].set(x, wch);
+            self.line_at(y + i).set(x, wch);
         }
 
         Ok(())
     }
 
-    // ACS character helpers using Unicode box-drawing characters
-    fn acs_hline(&self) -> ChType {
-        #[cfg(feature = "wide")]
-        {
-            crate::acs::ACS_HLINE as ChType
-        }
-        #[cfg(not(feature = "wide"))]
-        {
-            crate::acs::acs_char(crate::acs::ACS_HLINE)
+    /// Number of cells a line of length `n` starting at `pos` can draw
+    /// before passing `max`; zero if `n` is negative or `pos` is past `max`.
+    fn line_len(n: i32, pos: NcursesSize, max: NcursesSize) -> usize {
+        let room = (max as i32).saturating_sub(pos as i32).saturating_add(1);
+        n.min(room).max(0) as usize
+    }
+
+    /// Merge the window's attributes into a line character's attributes.
+    ///
+    /// Video attributes are combined; a color pair in `attr` wins over the
+    /// window's, since OR-ing two pair numbers would yield a third.
+    fn merge_line_attrs(&self, attr: AttrT) -> AttrT {
+        let pair = if attr & attr::A_COLOR != 0 {
+            attr & attr::A_COLOR
+        } else {
+            self.attrs & attr::A_COLOR
+        };
+        ((attr | self.attrs) & !attr::A_COLOR) | pair
+    }
+
+    /// Build a line-drawing cell in wide mode.
+    ///
+    /// `ch == 0` selects the `default` line character; otherwise the low
+    /// byte of `ch` is the character and the rest its attributes.
+    #[cfg(feature = "wide")]
+    fn acs_cchar(ch: ChType, default: char) -> CCharT {
+        if ch == 0 {
+            CCharT::from_char(default)
+        } else {
+            CCharT::from_char_attr((ch & A_CHARTEXT) as u8 as char, ch & !A_CHARTEXT)
         }
     }
+
+    // ACS character helpers for the default border and line characters
+    #[cfg(not(feature = "wide"))]
+    fn acs_hline(&self) -> ChType {
+        crate::acs::acs_char(crate::acs::ACS_HLINE)
+    }
+    #[cfg(not(feature = "wide"))]
     fn acs_vline(&self) -> ChType {
-        #[cfg(feature = "wide")]
-        {
-            crate::acs::ACS_VLINE as ChType
-        }
-        #[cfg(not(feature = "wide"))]
-        {
-            crate::acs::acs_char(crate::acs::ACS_VLINE)
-        }
+        crate::acs::acs_char(crate::acs::ACS_VLINE)
     }
+    #[cfg(not(feature = "wide"))]
     fn acs_ulcorner(&self) -> ChType {
-        #[cfg(feature = "wide")]
-        {
-            crate::acs::ACS_ULCORNER as ChType
-        }
-        #[cfg(not(feature = "wide"))]
-        {
-            crate::acs::acs_char(crate::acs::ACS_ULCORNER)
-        }
+        crate::acs::acs_char(crate::acs::ACS_ULCORNER)
     }
+    #[cfg(not(feature = "wide"))]
     fn acs_urcorner(&self) -> ChType {
-        #[cfg(feature = "wide")]
-        {
-            crate::acs::ACS_URCORNER as ChType
-        }
-        #[cfg(not(feature = "wide"))]
-        {
-            crate::acs::acs_char(crate::acs::ACS_URCORNER)
-        }
+        crate::acs::acs_char(crate::acs::ACS_URCORNER)
     }
+    #[cfg(not(feature = "wide"))]
     fn acs_llcorner(&self) -> ChType {
-        #[cfg(feature = "wide")]
-        {
-            crate::acs::ACS_LLCORNER as ChType
-        }
-        #[cfg(not(feature = "wide"))]
-        {
-            crate::acs::acs_char(crate::acs::ACS_LLCORNER)
-        }
+        crate::acs::acs_char(crate::acs::ACS_LLCORNER)
     }
+    #[cfg(not(feature = "wide"))]
     fn acs_lrcorner(&self) -> ChType {
-        #[cfg(feature = "wide")]
-        {
-            crate::acs::ACS_LRCORNER as ChType
-        }
-        #[cfg(not(feature = "wide"))]
-        {
-            crate::acs::acs_char(crate::acs::ACS_LRCORNER)
-        }
+        crate::acs::acs_char(crate::acs::ACS_LRCORNER)
     }
 
     // ========================================================================
@@ -1866,16 +2788,21 @@ impl Window {
 
     /// Insert a character at the current position.
     pub fn insch(&mut self, ch: ChType) -> Result<()> {
+        self.sync_cursor();
         let y = self.cury as usize;
         let x = self.curx as usize;
 
         #[cfg(not(feature = "wide"))]
-        self.lines[y].insert(x, ch | self.attrs, 1);
+        {
+            let ch = ch | self.attrs;
+            self.line_at(y).insert(x, ch, 1);
+        }
         #[cfg(feature = "wide")]
         {
             let c = (ch & A_CHARTEXT) as u8 as char;
             let attr = (ch & !A_CHARTEXT) | self.attrs;
-            self.lines[y].insert(x, CCharT::from_char_attr(c, attr), 1);
+            self.line_at(y)
+                .insert_char(x, CCharT::from_char_attr(c, attr));
         }
 
         Ok(())
@@ -1898,6 +2825,7 @@ impl Window {
     ///
     /// If n is negative, the entire string is inserted.
     pub fn insnstr(&mut self, s: &str, n: i32) -> Result<()> {
+        self.sync_cursor();
         let y = self.cury as usize;
         let x = self.curx as usize;
 
@@ -1915,12 +2843,13 @@ impl Window {
             #[cfg(not(feature = "wide"))]
             {
                 let ch = chars[i] as ChType | self.attrs;
-                self.lines[y].insert(x, ch, 1);
+                self.line_at(y).insert(x, ch, 1);
             }
             #[cfg(feature = "wide")]
             {
                 let attr = self.attrs;
-                self.lines[y].insert(x, CCharT::from_char_attr(chars[i], attr), 1);
+                self.line_at(y)
+                    .insert_char(x, CCharT::from_char_attr(chars[i], attr));
             }
         }
 
@@ -1941,13 +2870,15 @@ impl Window {
 
     /// Delete the character at the current position.
     pub fn delch(&mut self) -> Result<()> {
+        self.sync_cursor();
         let y = self.cury as usize;
         let x = self.curx as usize;
 
         #[cfg(not(feature = "wide"))]
-        self.lines[y].delete(x, 1, self.bkgd);
+        let fill = self.bkgd;
         #[cfg(feature = "wide")]
-        self.lines[y].delete(x, 1, self.bkgrnd);
+        let fill = self.bkgrnd;
+        self.line_at(y).delete(x, 1, fill);
 
         Ok(())
     }
@@ -1963,16 +2894,21 @@ impl Window {
     }
 
     /// Insert or delete lines.
+    ///
+    /// A positive `n` inserts blank lines above the cursor line, a negative
+    /// `n` deletes lines starting at it. Only lines from the cursor row to
+    /// the bottom of the scrolling region move (to the bottom of the window
+    /// if the cursor is below the region); lines above the cursor stay put.
     pub fn insdelln(&mut self, n: i32) -> Result<()> {
-        if n > 0 {
-            // Insert lines (scroll down)
-            self.scroll_down(n)
-        } else if n < 0 {
-            // Delete lines (scroll up)
-            self.scroll_up(-n)
+        self.sync_cursor();
+        let top = self.cury as usize;
+        let bottom = if self.cury <= self.regbottom {
+            self.regbottom
         } else {
-            Ok(())
-        }
+            self.maxy
+        };
+        self.shift_lines(top, bottom as usize, n);
+        Ok(())
     }
 
     // ========================================================================
@@ -1992,6 +2928,7 @@ impl Window {
     /// Insert a wide character at the current position (window version).
     #[cfg(feature = "wide")]
     pub fn wins_wch(&mut self, wch: &CCharT) -> Result<()> {
+        self.sync_cursor();
         let y = self.cury as usize;
         let x = self.curx as usize;
 
@@ -2010,8 +2947,7 @@ impl Window {
             cchar.attr |= self.attrs;
         }
 
-        let width = cchar.width().max(1);
-        self.lines[y].insert(x, cchar, width);
+        self.line_at(y).insert_char(x, cchar);
 
         Ok(())
     }
@@ -2043,8 +2979,9 @@ impl Window {
     #[cfg(feature = "wide")]
     #[must_use]
     pub fn win_wch(&self) -> CCharT {
-        let y = self.cury as usize;
-        let x = self.curx as usize;
+        let (cury, curx) = self.cur();
+        let y = cury as usize;
+        let x = curx as usize;
 
         if y <= self.maxy as usize && x <= self.maxx as usize {
             self.lines[y].get(x)
@@ -2075,21 +3012,22 @@ impl Window {
     /// If `n` is negative, reads to the end of the line.
     #[cfg(feature = "wide")]
     pub fn in_wchnstr(&self, wchstr: &mut [CCharT], n: i32) -> i32 {
-        let y = self.cury as usize;
+        let (cury, curx) = self.cur();
+        let y = cury as usize;
         let max_chars = if n < 0 {
             wchstr
                 .len()
-                .min((self.maxx as usize + 1).saturating_sub(self.curx as usize))
+                .min((self.maxx as usize + 1).saturating_sub(curx as usize))
         } else {
             wchstr
                 .len()
                 .min(n as usize)
-                .min((self.maxx as usize + 1).saturating_sub(self.curx as usize))
+                .min((self.maxx as usize + 1).saturating_sub(curx as usize))
         };
 
         let mut count = 0;
         for (i, wch) in wchstr.iter_mut().take(max_chars).enumerate() {
-            let x = self.curx as usize + i;
+            let x = curx as usize + i;
             if x > self.maxx as usize {
                 break;
             }
@@ -2133,6 +3071,9 @@ impl Window {
     }
 
     /// Draw a border using wide characters (window version).
+    ///
+    /// Each character is stored as given, attributes and color included.
+    /// Returns an error if the window has no cells.
     #[cfg(feature = "wide")]
     #[allow(clippy::too_many_arguments)]
     pub fn wborder_set(
@@ -2158,25 +3099,30 @@ impl Window {
         let bl = bl.unwrap_or(&wacs::LLCORNER);
         let br = br.unwrap_or(&wacs::LRCORNER);
 
+        if self.maxy < 0 || self.maxx < 0 {
+            return Err(Error::WindowError(
+                "cannot draw a border on an empty window".into(),
+            ));
+        }
         let maxy = self.maxy as usize;
         let maxx = self.maxx as usize;
 
         // Corners
-        self.lines[0].set(0, *tl);
-        self.lines[0].set(maxx, *tr);
-        self.lines[maxy].set(0, *bl);
-        self.lines[maxy].set(maxx, *br);
+        self.line_at(0).set(0, *tl);
+        self.line_at(0).set(maxx, *tr);
+        self.line_at(maxy).set(0, *bl);
+        self.line_at(maxy).set(maxx, *br);
 
         // Top and bottom edges
         for x in 1..maxx {
-            self.lines[0].set(x, *ts);
-            self.lines[maxy].set(x, *bs);
+            self.line_at(0).set(x, *ts);
+            self.line_at(maxy).set(x, *bs);
         }
 
         // Left and right edges
         for y in 1..maxy {
-            self.lines[y].set(0, *ls);
-            self.lines[y].set(maxx, *rs);
+            self.line_at(y).set(0, *ls);
+            self.line_at(y).set(maxx, *rs);
         }
 
         self.touchwin();
@@ -2232,7 +3178,7 @@ impl Window {
         let new_attr = wch.attrs();
 
         // Update all cells
-        for line in &mut self.lines {
+        for line in self.lines_mut().iter_mut() {
             for x in 0..line.width() {
                 let cell = line.get(x);
                 let cell_char = cell.spacing_char();
@@ -2276,7 +3222,7 @@ impl Window {
 
     /// Mark the entire window as changed.
     pub fn touchwin(&mut self) {
-        for line in &mut self.lines {
+        for line in self.lines_mut().iter_mut() {
             line.touch();
         }
     }
@@ -2286,36 +3232,215 @@ impl Window {
         for line in &mut self.lines {
             line.untouch();
         }
+        self.dirty = Some(Vec::new());
     }
 
     /// Mark a range of lines as changed.
+    ///
+    /// With `changed` false the lines are marked unchanged instead, so the
+    /// next refresh skips them. Lines outside the window are ignored.
     pub fn touchln(&mut self, start: i32, count: i32, changed: bool) {
-        let start = start.max(0) as usize;
-        let end = (start + count as usize).min(self.lines.len());
+        let len = self.lines.len() as i64;
+        let end = (start as i64 + count as i64).clamp(0, len) as usize;
+        let start = (start as i64).clamp(0, len) as usize;
+        if start >= end {
+            return;
+        }
 
-        for line in &mut self.lines[start..end] {
+        for y in start..end {
             if changed {
-                line.touch();
+                self.line_at(y).touch();
             } else {
-                line.untouch();
+                self.lines[y].untouch();
             }
         }
     }
 
+    /// Get the indices of all touched lines in ascending order.
+    ///
+    /// Only the lines changed since the last [`untouchwin()`](Self::untouchwin)
+    /// are examined, so this is cheap for large windows with few changes.
+    /// Lines with changes synced up from a subwindow but not yet applied
+    /// are included.
+    pub fn touched_lines(&self) -> Vec<usize> {
+        let mut touched: Vec<usize> = match &self.dirty {
+            Some(dirty) => dirty
+                .iter()
+                .copied()
+                .filter(|&y| self.lines[y].is_touched())
+                .collect(),
+            None => (0..self.lines.len())
+                .filter(|&y| self.lines[y].is_touched())
+                .collect(),
+        };
+        touched.extend(self.synced_cells().into_iter().map(|(y, _, _)| y));
+        touched.sort_unstable();
+        touched.dedup();
+        touched
+    }
+
+    /// Get line `y` for writing, recording it as possibly changed.
+    ///
+    /// All writes to a line go through here (or [`lines_mut()`](Self::lines_mut))
+    /// so the touched-line list cannot miss one, and so the changes can be
+    /// passed on to the parent and subwindows.
+    fn line_at(&mut self, y: usize) -> LineWriter<'_> {
+        self.apply_synced();
+        self.mark_dirty(y);
+        let targets = SyncTargets::new(self.sync, &self.sync_parent, &mut self.sync_children);
+        LineWriter {
+            line: &mut self.lines[y],
+            y,
+            targets,
+        }
+    }
+
+    /// Get every line for writing; refresh goes back to checking them all.
+    fn lines_mut(&mut self) -> LinesWriter<'_> {
+        self.apply_synced();
+        self.dirty = None;
+        let targets = SyncTargets::new(self.sync, &self.sync_parent, &mut self.sync_children);
+        LinesWriter {
+            lines: &mut self.lines[..],
+            targets,
+        }
+    }
+
+    /// Apply the cells and cursor position synced up from subwindows.
+    ///
+    /// With `syncok` set the cells are passed on to this window's parent in
+    /// turn, as ancestors share what their subwindows show. The subwindow
+    /// links are only visited when one of them has queued cells.
+    pub(crate) fn apply_synced(&mut self) {
+        self.sync_cursor();
+        if !self.sync_inbox.cells.swap(false, Ordering::Acquire) {
+            return;
+        }
+        let children = self
+            .sync_children
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner);
+        let mut cells = Vec::new();
+        for link in children.iter() {
+            cells.extend(std::mem::take(&mut lock_link(link).up));
+        }
+        // Links only this window still holds belong to dropped subwindows
+        children.retain(|link| Arc::strong_count(link) > 1);
+
+        for ((y, x), cell) in cells {
+            if y < self.lines.len() && x < self.lines[y].width() {
+                self.mark_dirty(y);
+                self.lines[y].set(x, cell);
+            }
+        }
+        let targets = SyncTargets {
+            parent: self.sync_parent.as_deref().filter(|_| self.sync),
+            children: &[],
+        };
+        for (y, line) in self.lines.iter_mut().enumerate() {
+            if let Some(written) = line.take_written() {
+                if !targets.is_empty() {
+                    targets.pass_on(y, line, written);
+                }
+            }
+        }
+    }
+
+    /// Move the cursor to a position set by a subwindow's `cursyncup()`.
+    ///
+    /// Called before the cursor is read or written through `&mut self`, so
+    /// a position set later by this window is never overwritten.
+    fn sync_cursor(&mut self) {
+        if self.sync_inbox.pending_cursor().is_none() {
+            return;
+        }
+        let packed = self.sync_inbox.cursor.swap(NO_CURSOR, Ordering::AcqRel);
+        let y = (packed >> 32) as usize;
+        let x = (packed & 0xffff_ffff) as usize;
+        if packed != NO_CURSOR && y <= self.maxy as usize && x <= self.maxx as usize {
+            self.cury = y as NcursesSize;
+            self.curx = x as NcursesSize;
+        }
+    }
+
+    /// The cursor position, including one set by a subwindow's
+    /// `cursyncup()` that [`sync_cursor()`](Self::sync_cursor) has not
+    /// taken yet.
+    fn cur(&self) -> (NcursesSize, NcursesSize) {
+        match self.sync_inbox.pending_cursor() {
+            Some((y, x)) if y <= self.maxy as usize && x <= self.maxx as usize => {
+                (y as NcursesSize, x as NcursesSize)
+            }
+            _ => (self.cury, self.curx),
+        }
+    }
+
+    /// Cells synced up from subwindows that have not been applied yet.
+    pub(crate) fn synced_cells(&self) -> Vec<(usize, usize, Cell)> {
+        if !self.sync_inbox.cells.load(Ordering::Acquire) {
+            return Vec::new();
+        }
+        let children = self
+            .sync_children
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        children
+            .iter()
+            .flat_map(|link| {
+                let link = lock_link(link);
+                link.up
+                    .iter()
+                    .map(|(&(y, x), &cell)| (y, x, cell))
+                    .collect::<Vec<_>>()
+            })
+            .filter(|&(y, x, _)| y < self.lines.len() && x < self.lines[y].width())
+            .collect()
+    }
+
+    /// Record that line `y` is about to change.
+    ///
+    /// A line that is already touched is in the list, so only untouched
+    /// lines are added. Lines untouched one at a time can leave stale
+    /// entries behind; they are dropped once the list outgrows the window.
+    fn mark_dirty(&mut self, y: usize) {
+        let Some(dirty) = &mut self.dirty else {
+            return;
+        };
+        let Some(line) = self.lines.get(y) else {
+            return;
+        };
+        if line.is_touched() {
+            return;
+        }
+        if dirty.len() >= self.lines.len() {
+            let lines = &self.lines;
+            dirty.sort_unstable();
+            dirty.dedup();
+            dirty.retain(|&y| lines[y].is_touched());
+        }
+        dirty.push(y);
+    }
+
     /// Check if a line has been touched.
+    ///
+    /// Returns `false` for lines outside the window.
     #[must_use]
     pub fn is_linetouched(&self, line: i32) -> bool {
         if line < 0 || line > self.maxy as i32 {
             false
         } else {
             self.lines[line as usize].is_touched()
+                || self
+                    .synced_cells()
+                    .iter()
+                    .any(|&(y, _, _)| y == line as usize)
         }
     }
 
     /// Check if any line in the window has been touched.
     #[must_use]
     pub fn is_wintouched(&self) -> bool {
-        self.lines.iter().any(|l| l.is_touched())
+        self.lines.iter().any(|l| l.is_touched()) || !self.synced_cells().is_empty()
     }
 
     // ========================================================================
@@ -2333,6 +3458,23 @@ impl Window {
         self.scroll
     }
 
+    /// Choose between wrapping and truncating at the right margin.
+    ///
+    /// With wrapping on (the default) output continues on the next line.
+    /// With it off the cursor stops on the last column, and characters
+    /// written once that column is filled are dropped until the cursor is
+    /// moved, which suits status lines and table cells.
+    pub fn set_wrap(&mut self, bf: bool) {
+        self.wrap = bf;
+        self.clipped_at = None;
+    }
+
+    /// Check if output wraps at the right margin.
+    #[must_use]
+    pub fn is_wrap(&self) -> bool {
+        self.wrap
+    }
+
     /// Enable/disable keypad mode.
     pub fn keypad(&mut self, bf: bool) {
         self.use_keypad = bf;
@@ -2366,6 +3508,35 @@ impl Window {
         self.delay
     }
 
+    /// Run `f` with the input timeout set to `delay`.
+    ///
+    /// The previous delay is restored once `f` returns, whether it succeeds
+    /// or fails.
+    pub fn with_timeout<T, F>(&mut self, delay: i32, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut Self) -> Result<T>,
+    {
+        let saved = self.getdelay();
+        self.timeout(delay);
+        let result = f(self);
+        self.timeout(saved);
+        result
+    }
+
+    /// Set the distance between tab stops used when adding a tab.
+    ///
+    /// Values below 1 are treated as 1. Windows start with the screen's
+    /// TABSIZE, and subwindows inherit their parent's setting.
+    pub fn set_tabsize(&mut self, size: i32) {
+        self.tabsize = size.max(1);
+    }
+
+    /// Get the distance between tab stops.
+    #[must_use]
+    pub fn get_tabsize(&self) -> i32 {
+        self.tabsize
+    }
+
     /// Enable/disable leaveok mode.
     pub fn leaveok(&mut self, bf: bool) {
         self.leaveok = bf;
@@ -2411,6 +3582,11 @@ impl Window {
     }
 
     /// Enable/disable immedok mode.
+    ///
+    /// With immedok set, output through `Screen` (such as `Screen::waddch`
+    /// or `Screen::waddstr`) refreshes the window right away. Calling output
+    /// methods on the `Window` directly cannot reach the screen, so those
+    /// changes still need an explicit refresh.
     pub fn immedok(&mut self, bf: bool) {
         self.immed = bf;
     }
@@ -2422,6 +3598,9 @@ impl Window {
     }
 
     /// Enable/disable syncok mode.
+    ///
+    /// With it on, every change to a subwindow is synced up to its parent
+    /// as if [`syncup`](Self::syncup) were called after it.
     pub fn syncok(&mut self, bf: bool) {
         self.sync = bf;
     }
@@ -2585,8 +3764,10 @@ impl Window {
 
         let mut win = Self::new_pad(height, width)?;
         win.flags |= WindowFlags::SUBWIN;
+        win.tabsize = self.tabsize;
         win.pary = begy;
         win.parx = begx;
+        self.link_subwindow(&mut win);
 
         Ok(win)
     }
@@ -2602,6 +3783,7 @@ impl Window {
 
     /// Get a mutable reference to a line.
     pub fn line_mut(&mut self, y: usize) -> Option<&mut LineData> {
+        self.mark_dirty(y);
         self.lines.get_mut(y)
     }
 
@@ -2616,14 +3798,55 @@ impl Window {
         self.clear = false;
         was_clear
     }
+
+    /// Hash the window's dimensions and cell contents.
+    ///
+    /// Covers the same data as `==` on windows: equal windows have equal
+    /// digests. Useful for cheaply checking whether a window's rendering
+    /// changed, or for comparing against a digest recorded in a test.
+    ///
+    /// The digest is 64-bit FNV-1a over a fixed little-endian encoding of
+    /// the cells, so it is the same across runs, processes and platforms.
+    /// It does depend on the crate features, since `wide` and `ext-colors`
+    /// change what a cell holds.
+    pub fn content_digest(&self) -> u64 {
+        let mut hash = fnv1a(0xcbf2_9ce4_8422_2325, &self.maxy.to_le_bytes());
+        hash = fnv1a(hash, &self.maxx.to_le_bytes());
+        for line in &self.lines {
+            for cell in line.text() {
+                hash = fnv1a(hash, &cell_digest_bytes(cell));
+            }
+        }
+        hash
+    }
+}
+
+/// Windows compare equal when they have the same size and the same cells.
+///
+/// Each cell's character (including combining characters), attributes and
+/// color pair are compared. The window's position on screen, cursor
+/// position, current attributes, background, option flags (scrolling,
+/// keypad, delay, ...) and change tracking are not.
+impl PartialEq for Window {
+    fn eq(&self, other: &Self) -> bool {
+        self.maxy == other.maxy
+            && self.maxx == other.maxx
+            && self
+                .lines
+                .iter()
+                .zip(&other.lines)
+                .all(|(a, b)| a.text() == b.text())
+    }
 }
 
+impl Eq for Window {}
+
 impl std::fmt::Debug for Window {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Window")
             .field("size", &(self.getmaxy(), self.getmaxx()))
             .field("position", &(self.begy, self.begx))
-            .field("cursor", &(self.cury, self.curx))
+            .field("cursor", &self.cur())
             .field("flags", &self.flags)
             .finish()
     }
@@ -2650,6 +3873,66 @@ mod tests {
         assert_eq!(win.getcurx(), 20);
     }
 
+    #[test]
+    fn test_cursor_and_origin() {
+        let mut win = Window::new(5, 10, 3, 7).unwrap();
+        assert_eq!(win.origin(), (3, 7));
+        assert_eq!(win.cursor(), (0, 0));
+        win.mv(2, 4).unwrap();
+        assert_eq!(win.cursor(), (2, 4));
+    }
+
+    #[cfg(feature = "ext-colors")]
+    #[test]
+    fn test_extended_color_set() {
+        let mut win = Window::new(5, 10, 0, 0).unwrap();
+        win.extended_color_set(500).unwrap();
+        assert_eq!(win.extended_color(), 500);
+        assert_eq!(attr::pair_number(win.getattrs()), attr::MAX_ATTR_COLOR_PAIR);
+
+        win.color_set(3).unwrap();
+        assert_eq!(win.extended_color(), 3);
+        assert!(win.extended_color_set(-1).is_err());
+    }
+
+    #[test]
+    fn test_touchln_ranges() {
+        let mut win = Window::new(6, 10, 0, 0).unwrap();
+        win.touchln(2, 3, true);
+        win.touchln(3, 1, false);
+        let touched: Vec<bool> = (0..6).map(|y| win.is_linetouched(y)).collect();
+        assert_eq!(touched, [false, false, true, false, true, false]);
+
+        assert!(!win.is_linetouched(-1));
+        assert!(!win.is_linetouched(6));
+
+        // Out-of-range and empty requests are no-ops
+        win.touchln(10, 2, true);
+        win.touchln(0, -1, true);
+        win.touchln(-3, 4, true);
+        assert!(win.is_linetouched(0));
+        assert!(!win.is_linetouched(1));
+    }
+
+    #[test]
+    fn test_touched_lines_follow_writes() {
+        let mut win = Window::new(6, 10, 0, 0).unwrap();
+        win.untouchwin();
+        win.mvaddch(1, 0, b'x' as ChType).unwrap();
+        win.mv(3, 2).unwrap();
+        win.delch().unwrap();
+        win.mvchgat(4, 0, 2, attr::A_BOLD, 0).unwrap();
+        assert_eq!(win.touched_lines(), [1, 3, 4]);
+
+        // Changing the background rewrites every cell
+        win.untouchwin();
+        #[cfg(not(feature = "wide"))]
+        win.bkgd(b'.' as ChType).unwrap();
+        #[cfg(feature = "wide")]
+        win.wbkgrnd(&CCharT::from_char('.')).unwrap();
+        assert_eq!(win.touched_lines(), [0, 1, 2, 3, 4, 5]);
+    }
+
     #[test]
     fn test_addstr() {
         let mut win = Window::new(24, 80, 0, 0).unwrap();
@@ -2657,12 +3940,722 @@ mod tests {
         assert_eq!(win.getcurx(), 5);
     }
 
+    #[cfg(feature = "wide")]
+    #[test]
+    fn test_addnstr_combining() {
+        let mut win = Window::new(2, 10, 0, 0).unwrap();
+        win.addnstr("a\u{0301}b", -1).unwrap();
+        assert_eq!(win.getcurx(), 2);
+        assert_eq!(win.lines[0].get(0).chars[..2], ['a', '\u{0301}']);
+        assert_eq!(win.lines[0].get(1).spacing_char(), 'b');
+        assert!(win.lines[0].get(2).spacing_char() == ' ');
+
+        // Combining marks do not count towards n
+        win.mv(1, 0).unwrap();
+        win.addnwstr("e\u{0301}\u{0302}xy", 2).unwrap();
+        assert_eq!(win.getcurx(), 2);
+        assert_eq!(win.lines[1].get(0).char_count(), 3);
+        assert_eq!(win.lines[1].get(1).spacing_char(), 'x');
+
+        // A mark after a character that scrolled the window follows it up
+        win.scrollok(true);
+        win.mv(1, 9).unwrap();
+        win.addnwstr("z\u{0301}", -1).unwrap();
+        assert_eq!(win.lines[0].get(9).chars[..2], ['z', '\u{0301}']);
+    }
+
+    #[test]
+    fn test_insdelln_from_cursor() {
+        let mut win = Window::new(6, 4, 0, 0).unwrap();
+        for (y, s) in ["a", "b", "c", "d", "e", "f"].iter().enumerate() {
+            win.mvaddstr(y as i32, 0, s).unwrap();
+        }
+        let rows = |win: &Window| -> String {
+            (0..6).map(|y| win.lines[y].get(0).spacing_char()).collect()
+        };
+
+        win.mv(2, 0).unwrap();
+        win.insdelln(2).unwrap();
+        assert_eq!(rows(&win), "ab  cd");
+
+        win.insdelln(-3).unwrap();
+        assert_eq!(rows(&win), "abd   ");
+
+        // With a scrolling region, lines below it stay put
+        win.setscrreg(0, 3).unwrap();
+        win.mvaddstr(4, 0, "x").unwrap();
+        win.mv(1, 0).unwrap();
+        win.deleteln().unwrap();
+        assert_eq!(rows(&win), "ad  x ");
+    }
+
+    #[test]
+    fn test_mvaddstr_clipped() {
+        let mut win = Window::new(3, 10, 0, 0).unwrap();
+        win.scrollok(true);
+        let text = |win: &Window, y: usize| -> String {
+            (0..10)
+                .map(|x| win.lines[y].get(x).spacing_char())
+                .collect()
+        };
+
+        assert_eq!(win.mvaddstr_clipped(2, 6, "status line").unwrap(), 4);
+        assert_eq!(text(&win, 2), "      stat");
+        assert_eq!(win.cursor(), (2, 9));
+        assert_eq!(text(&win, 1), "          ");
+
+        // Out-of-range positions are clamped
+        assert_eq!(win.mvaddstr_clipped(-4, 30, "xy").unwrap(), 1);
+        assert_eq!(text(&win, 0), "         x");
+
+        assert_eq!(win.mvaddstr_clipped(1, 2, "ok").unwrap(), 2);
+        assert_eq!(win.cursor(), (1, 4));
+
+        // Control characters end the line; tabs are measured to the stop
+        win.erase().unwrap();
+        assert_eq!(win.mvaddstr_clipped(0, 0, "ab\ncd").unwrap(), 2);
+        assert_eq!(text(&win, 0), "ab        ");
+        assert_eq!(text(&win, 1), "          ");
+        win.set_tabsize(4);
+        assert_eq!(win.mvaddstr_clipped(1, 3, "a\tb\tc").unwrap(), 3);
+        assert_eq!(text(&win, 1), "   a    b ");
+        assert_eq!(text(&win, 2), "          ");
+        assert_eq!(win.cursor(), (1, 9));
+    }
+
+    #[cfg(feature = "wide")]
+    #[test]
+    fn test_mvaddstr_clipped_wide() {
+        let mut win = Window::new(1, 6, 0, 0).unwrap();
+        // The second wide char would straddle the margin, so it is dropped
+        assert_eq!(win.mvaddstr_clipped(0, 2, "a\u{0301}日本").unwrap(), 3);
+        assert_eq!(win.lines[0].get(2).chars[..2], ['a', '\u{0301}']);
+        assert_eq!(win.lines[0].get(3).spacing_char(), '日');
+        assert_eq!(win.lines[0].get(5).spacing_char(), ' ');
+        assert_eq!(win.cursor(), (0, 5));
+    }
+
+    #[test]
+    fn test_tabsize() {
+        let mut win = Window::new(2, 20, 0, 0).unwrap();
+        win.mvaddstr(0, 1, "\t").unwrap();
+        assert_eq!(win.getcurx(), 8);
+
+        win.set_tabsize(4);
+        win.mvaddstr(0, 1, "\tx\t").unwrap();
+        assert_eq!(win.getcurx(), 8);
+        assert_eq!(win.mvinch(0, 4).unwrap() & A_CHARTEXT, 'x' as ChType);
+
+        let sub = win.derwin(1, 10, 1, 0).unwrap();
+        assert_eq!(sub.get_tabsize(), 4);
+    }
+
+    #[test]
+    fn test_content_equality() {
+        let build = |y| {
+            let mut win = Window::new(3, 10, y, 0).unwrap();
+            win.mvaddstr(0, 0, "plain").unwrap();
+            win.attron(attr::A_BOLD).unwrap();
+            win.mvaddstr(1, 2, "bold").unwrap();
+            win
+        };
+        let a = build(0);
+        let mut b = build(5);
+        b.mv(2, 9).unwrap();
+        b.scrollok(true);
+        assert_eq!(a, b);
+        assert_eq!(a.content_digest(), b.content_digest());
+        // Reference FNV-1a value, so the digest cannot silently change
+        assert_eq!(fnv1a(0xcbf2_9ce4_8422_2325, b"a"), 0xaf63_dc4c_8601_ec8c);
+
+        b.mvchgat(1, 2, 1, attr::A_UNDERLINE, 0).unwrap();
+        assert_ne!(a, b);
+        assert_ne!(a.content_digest(), b.content_digest());
+
+        assert_ne!(a, Window::new(3, 11, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn test_standend_restores_attrs() {
+        let mut win = Window::new(3, 10, 0, 0).unwrap();
+        win.attrset(attr::A_BOLD | color_pair(3)).unwrap();
+        win.standout().unwrap();
+        assert_ne!(win.getattrs() & attr::A_STANDOUT, 0);
+        win.standout().unwrap();
+        win.standend().unwrap();
+        assert_eq!(win.getattrs(), attr::A_BOLD | color_pair(3));
+
+        // Unmatched standend keeps the traditional reset
+        win.standend().unwrap();
+        assert_eq!(win.getattrs(), A_NORMAL);
+
+        // Setting or clearing attributes in between forgets the saved ones
+        win.attrset(attr::A_BOLD).unwrap();
+        win.standout().unwrap();
+        win.attrset(attr::A_UNDERLINE).unwrap();
+        win.standend().unwrap();
+        assert_eq!(win.getattrs(), A_NORMAL);
+
+        win.attrset(attr::A_BOLD).unwrap();
+        win.standout().unwrap();
+        win.attroff(attr::A_STANDOUT).unwrap();
+        win.standend().unwrap();
+        assert_eq!(win.getattrs(), A_NORMAL);
+    }
+
+    #[test]
+    fn test_push_pop_attrs() {
+        let mut win = Window::new(3, 10, 0, 0).unwrap();
+        win.attrset(attr::A_BOLD | color_pair(2)).unwrap();
+        assert_eq!(win.attr_stack_depth(), 0);
+
+        win.push_attrs();
+        win.attron(attr::A_UNDERLINE).unwrap();
+        win.push_attrs();
+        win.attrset(attr::A_REVERSE).unwrap();
+        win.color_set(5).unwrap();
+        assert_eq!(win.attr_stack_depth(), 2);
+
+        win.pop_attrs().unwrap();
+        assert_eq!(
+            win.getattrs(),
+            attr::A_BOLD | attr::A_UNDERLINE | color_pair(2)
+        );
+        win.pop_attrs().unwrap();
+        assert_eq!(win.getattrs(), attr::A_BOLD | color_pair(2));
+        assert_eq!(win.attr_stack_depth(), 0);
+
+        // Underflow is an error and leaves the attributes alone
+        assert!(win.pop_attrs().is_err());
+        assert_eq!(win.getattrs(), attr::A_BOLD | color_pair(2));
+    }
+
+    #[cfg(feature = "ext-colors")]
+    #[test]
+    fn test_pop_attrs_restores_extended_color() {
+        let mut win = Window::new(3, 10, 0, 0).unwrap();
+        win.extended_color_set(40000).unwrap();
+        win.push_attrs();
+        win.color_set(1).unwrap();
+        win.pop_attrs().unwrap();
+        assert_eq!(win.extended_color(), 40000);
+    }
+
+    #[cfg(feature = "ext-colors")]
+    #[test]
+    fn test_standend_restores_extended_color() {
+        let mut win = Window::new(3, 10, 0, 0).unwrap();
+        win.extended_color_set(40000).unwrap();
+        win.standout().unwrap();
+        win.color_set(1).unwrap();
+        win.standend().unwrap();
+        assert_eq!(win.extended_color(), 40000);
+        assert_eq!(win.getattrs(), color_pair(attr::MAX_ATTR_COLOR_PAIR));
+    }
+
+    #[cfg(feature = "wide")]
+    #[test]
+    fn test_insert_wide_shifts_two_columns() {
+        let mut win = Window::new(2, 8, 0, 0).unwrap();
+        let row = |win: &Window, y: usize| -> String {
+            win.line(y)
+                .unwrap()
+                .text()
+                .iter()
+                .filter(|c| !c.is_null())
+                .map(|c| c.spacing_char())
+                .collect()
+        };
+
+        win.mvaddstr(0, 0, "abcdef").unwrap();
+        win.mvinsstr(0, 2, "中").unwrap();
+        assert_eq!(row(&win, 0), "ab中cdef");
+        assert!(win.line(0).unwrap().text()[3].is_null());
+        assert_eq!(win.line(0).unwrap().text()[4].spacing_char(), 'c');
+
+        win.mvaddstr(1, 0, "abcdef").unwrap();
+        win.mv(1, 3).unwrap();
+        win.ins_wch(&CCharT::from_char('文')).unwrap();
+        assert_eq!(row(&win, 1), "abc文def");
+        assert_eq!(win.line(1).unwrap().text()[5].spacing_char(), 'd');
+    }
+
+    #[test]
+    fn test_set_wrap() {
+        let mut win = Window::new(3, 5, 0, 0).unwrap();
+        assert!(win.is_wrap());
+        win.mvaddstr(0, 2, "abcdef").unwrap();
+        assert_eq!(win.cursor(), (1, 3));
+        assert_eq!(win.mvinch(1, 0).unwrap() & A_CHARTEXT, b'd' as ChType);
+
+        win.erase().unwrap();
+        win.set_wrap(false);
+        win.mvaddstr(0, 2, "abcdef").unwrap();
+        assert_eq!(win.cursor(), (0, 4));
+        assert_eq!(win.mvinch(0, 4).unwrap() & A_CHARTEXT, b'c' as ChType);
+        assert_eq!(win.mvinch(1, 0).unwrap() & A_CHARTEXT, b' ' as ChType);
+
+        // Moving the cursor allows writing again
+        win.mvaddstr(1, 0, "xy").unwrap();
+        win.mvaddch(0, 4, b'z' as ChType).unwrap();
+        assert_eq!(win.mvinch(0, 4).unwrap() & A_CHARTEXT, b'z' as ChType);
+        assert_eq!(win.mvinch(1, 1).unwrap() & A_CHARTEXT, b'y' as ChType);
+
+        // So do carriage return, newline and backspace
+        win.mvaddstr(2, 0, "abcdefg\rvwxyz").unwrap();
+        assert_eq!(win.mvinnstr(2, 0, 5).unwrap(), "vwxyz");
+        win.mvaddstr(0, 0, "abcdefg\x08q").unwrap();
+        assert_eq!(win.mvinnstr(0, 0, 5).unwrap(), "abcqe");
+        win.mvaddstr(0, 0, "abcdefg\nvwxyz").unwrap();
+        assert_eq!(win.mvinnstr(1, 0, 5).unwrap(), "vwxyz");
+    }
+
+    #[test]
+    fn test_addstr_checked() {
+        let text = |win: &mut Window| {
+            let top = win.mvinnstr(0, 0, 4).unwrap();
+            top + "|" + &win.mvinnstr(1, 0, 4).unwrap()
+        };
+        let mut win = Window::new(2, 4, 0, 0).unwrap();
+
+        let result = win.addstr_checked("abc").unwrap();
+        assert_eq!(
+            result,
+            AddResult {
+                written: 3,
+                reached_bottom: false,
+                scrolled: false
+            }
+        );
+
+        // Exactly filling the window reaches the bottom
+        win.mv(0, 0).unwrap();
+        let result = win.addstr_checked("abcdefgh").unwrap();
+        assert_eq!(
+            result,
+            AddResult {
+                written: 8,
+                reached_bottom: true,
+                scrolled: false
+            }
+        );
+
+        // Overfilling stops at the last cell
+        win.erase().unwrap();
+        let result = win.addstr_checked("abcdefghij").unwrap();
+        assert_eq!(
+            result,
+            AddResult {
+                written: 8,
+                reached_bottom: true,
+                scrolled: false
+            }
+        );
+        assert_eq!(text(&mut win), "abcd|efgh");
+        win.mv(1, 0).unwrap();
+        assert_eq!(win.addstr_checked("x\ny").unwrap().written, 1);
+
+        win.scrollok(true);
+        win.erase().unwrap();
+        let result = win.addstr_checked("abcdefghij").unwrap();
+        assert_eq!(
+            result,
+            AddResult {
+                written: 10,
+                reached_bottom: true,
+                scrolled: true
+            }
+        );
+        assert_eq!(text(&mut win), "efgh|ij  ");
+
+        win.erase().unwrap();
+        let result = win.addstr_checked("ab\ncd").unwrap();
+        assert_eq!(
+            result,
+            AddResult {
+                written: 5,
+                reached_bottom: false,
+                scrolled: false
+            }
+        );
+    }
+
+    #[test]
+    fn test_line_clipping() {
+        let mut win = Window::new(3, 10, 0, 0).unwrap();
+        let blank = win.lines[0].get(9);
+
+        win.mv(0, 9).unwrap();
+        win.hline('-' as ChType, 5).unwrap();
+        assert_ne!(win.lines[0].get(9), blank);
+        assert_eq!(win.lines[0].get(8), blank);
+
+        // A cursor left past the margin by a wrap draws nothing
+        win.mv(1, 0).unwrap();
+        win.curx = win.maxx + 1;
+        win.hline('-' as ChType, 5).unwrap();
+        win.hline('-' as ChType, -1).unwrap();
+        assert!(win.lines[1].text().iter().all(|&c| c == blank));
+        win.cury = win.maxy + 1;
+        win.curx = 0;
+        win.vline('|' as ChType, 5).unwrap();
+        assert!(win.lines[2].text().iter().all(|&c| c == blank));
+    }
+
+    #[test]
+    fn test_line_attrs() {
+        let mut win = Window::new(3, 10, 0, 0).unwrap();
+        win.attrset(attr::A_BOLD | color_pair(2)).unwrap();
+        win.mv(0, 0).unwrap();
+        win.hline(0, 3).unwrap();
+        win.mv(1, 0).unwrap();
+        win.hline('=' as ChType | attr::A_UNDERLINE | color_pair(5), 3)
+            .unwrap();
+
+        #[cfg(feature = "wide")]
+        {
+            let cell = win.lines[0].get(2);
+            assert_eq!(cell.spacing_char(), crate::acs::ACS_HLINE);
+            assert_eq!(cell.attrs(), attr::A_BOLD | color_pair(2));
+            let cell = win.lines[1].get(0);
+            assert_eq!(cell.spacing_char(), '=');
+            assert_eq!(
+                cell.attrs(),
+                attr::A_BOLD | attr::A_UNDERLINE | color_pair(5)
+            );
+        }
+        #[cfg(not(feature = "wide"))]
+        assert_eq!(
+            win.lines[1].get(0),
+            '=' as ChType | attr::A_BOLD | attr::A_UNDERLINE | color_pair(5)
+        );
+    }
+
+    #[test]
+    fn test_chgat_color_only() {
+        let mut win = Window::new(2, 10, 0, 0).unwrap();
+        win.mvaddstr(0, 0, "abcdef").unwrap();
+        win.mvchgat(0, 1, 4, attr::A_BOLD | attr::A_UNDERLINE, 1)
+            .unwrap();
+        win.mvchgat(0, 0, 1, attr::A_REVERSE, 0).unwrap();
+
+        win.mv(0, 0).unwrap();
+        win.chgat_color_only(3, 2).unwrap();
+        assert_eq!(
+            win.mvinch(0, 0).unwrap() & !A_CHARTEXT,
+            attr::A_REVERSE | color_pair(2)
+        );
+        for x in 1..3 {
+            let cell = win.mvinch(0, x).unwrap();
+            assert_eq!(cell & A_CHARTEXT, (b'a' + x as u8) as ChType);
+            assert_eq!(
+                cell & !A_CHARTEXT,
+                attr::A_BOLD | attr::A_UNDERLINE | color_pair(2)
+            );
+        }
+        // Cells past the run keep their original pair
+        assert_eq!(
+            win.mvinch(0, 3).unwrap() & !A_CHARTEXT,
+            attr::A_BOLD | attr::A_UNDERLINE | color_pair(1)
+        );
+
+        // Pair 0 clears the color but nothing else
+        win.mv(0, 1).unwrap();
+        win.chgat_color_only(-1, 0).unwrap();
+        assert_eq!(
+            win.mvinch(0, 4).unwrap() & !A_CHARTEXT,
+            attr::A_BOLD | attr::A_UNDERLINE
+        );
+    }
+
+    #[test]
+    fn test_subwin_delete_and_detach() {
+        let mut parent = Window::new(10, 20, 2, 4).unwrap();
+        parent.mvaddstr(1, 1, "parent").unwrap();
+
+        let mut sub = parent.derwin(3, 5, 1, 1).unwrap();
+        sub.mvaddstr(0, 0, "sub").unwrap();
+        crate::screen::delwin(sub);
+        parent.mvaddstr(5, 0, "still here").unwrap();
+        assert_eq!(parent.mvinnstr(1, 1, 6).unwrap(), "parent");
+        assert_eq!(parent.mvinnstr(5, 0, 10).unwrap(), "still here");
+
+        let mut sub = parent.derwin(3, 5, 1, 1).unwrap();
+        sub.mvaddstr(0, 0, "kept").unwrap();
+        assert!(sub.mvwin(0, 0).is_err());
+        sub.detach().unwrap();
+        drop(parent);
+
+        assert!(!sub.is_subwin());
+        assert_eq!((sub.getbegy(), sub.getbegx()), (3, 5));
+        assert_eq!(sub.mvinnstr(0, 0, 4).unwrap(), "kept");
+        assert!(sub.mvwin(0, 0).is_ok());
+        assert!(sub.mvderwin(1, 1).is_err());
+        assert!(sub.detach().is_err());
+    }
+
+    #[test]
+    fn test_addstr_wrapped() {
+        let text = "The quick brown fox jumps over the lazy dog while \
+                    an extraordinarily patient tortoise watches";
+        let mut win = Window::new(6, 20, 0, 0).unwrap();
+        win.attron(attr::A_BOLD).unwrap();
+        win.addstr_wrapped(text).unwrap();
+
+        let rows: Vec<String> = (0..6)
+            .map(|y| win.mvinnstr(y, 0, 20).unwrap().trim_end().to_string())
+            .collect();
+        assert_eq!(rows[0], "The quick brown fox");
+        let words: Vec<&str> = rows.iter().flat_map(|r| r.split_whitespace()).collect();
+        assert_eq!(words, text.split_whitespace().collect::<Vec<_>>());
+        assert!(rows.iter().all(|r| !r.starts_with(' ')));
+        assert_ne!(win.mvinch(0, 0).unwrap() & attr::A_BOLD, 0);
+
+        // Stops at the bottom, splitting only words wider than the window
+        let mut win = Window::new(2, 8, 0, 0).unwrap();
+        win.addstr_wrapped("ab cdefghijklmn op qr").unwrap();
+        assert_eq!(win.mvinnstr(0, 0, 8).unwrap(), "ab      ");
+        assert_eq!(win.mvinnstr(1, 0, 8).unwrap(), "cdefghij");
+
+        // With scrollok the window scrolls instead
+        let mut win = Window::new(2, 8, 0, 0).unwrap();
+        win.scrollok(true);
+        win.addstr_wrapped("one two three\nfour").unwrap();
+        assert_eq!(win.mvinnstr(0, 0, 8).unwrap(), "three   ");
+        assert_eq!(win.mvinnstr(1, 0, 8).unwrap(), "four    ");
+    }
+
+    #[test]
+    #[cfg(feature = "wide")]
+    fn test_addwstr_combining_capacity() {
+        use crate::types::MAX_COMBINING;
+
+        let mut win = Window::new(2, 10, 0, 0).unwrap();
+        let full: String = std::iter::once('a')
+            .chain(std::iter::repeat_n('\u{0301}', MAX_COMBINING))
+            .collect();
+        win.addwstr(&full).unwrap();
+        assert_eq!(win.mvin_wch(0, 0).unwrap().char_count(), 1 + MAX_COMBINING);
+
+        // One mark too many is reported and the cell keeps what fit
+        let over = format!("b{}", "\u{0308}".repeat(MAX_COMBINING + 1));
+        win.mv(1, 0).unwrap();
+        assert!(matches!(win.addwstr(&over), Err(Error::InvalidArgument(_))));
+        assert_eq!(win.mvin_wch(1, 0).unwrap().char_count(), 1 + MAX_COMBINING);
+    }
+
+    #[test]
+    fn test_syncup_and_syncdown() {
+        let mut parent = Window::new(10, 20, 0, 0).unwrap();
+        let mut child = parent.derwin(3, 5, 4, 6).unwrap();
+        parent.untouchwin();
+        child.untouchwin();
+
+        // Without syncok the parent only sees changes synced explicitly
+        child.mvaddstr(0, 0, "xy").unwrap();
+        assert!(!parent.is_wintouched());
+        child.syncup().unwrap();
+        assert_eq!(parent.touched_lines(), [4]);
+        assert_eq!(parent.mvinnstr(4, 6, 2).unwrap(), "xy");
+
+        // With syncok every change goes up
+        parent.untouchwin();
+        child.syncok(true);
+        child.mvaddstr(1, 1, "ab").unwrap();
+        assert_eq!(parent.touched_lines(), [5]);
+        assert_eq!(parent.mvinnstr(5, 7, 2).unwrap(), "ab");
+
+        // A later write to the parent wins over an earlier one synced up
+        child.mvaddstr(2, 0, "c").unwrap();
+        parent.mvaddstr(6, 6, "P").unwrap();
+        assert_eq!(parent.mvinnstr(6, 6, 1).unwrap(), "P");
+
+        // Parent changes inside the child's area are copied down
+        child.untouchwin();
+        parent.mvaddstr(6, 0, "0123456789").unwrap();
+        parent.mvaddstr(0, 0, "outside").unwrap();
+        child.syncdown().unwrap();
+        assert_eq!(child.touched_lines(), [2]);
+        assert_eq!(child.mvinnstr(2, 0, 4).unwrap(), "6789");
+
+        child.mv(2, 3).unwrap();
+        child.cursyncup().unwrap();
+        assert_eq!((parent.getcury(), parent.getcurx()), (6, 9));
+        parent.addstr("Q").unwrap();
+        assert_eq!(parent.mvinnstr(6, 9, 1).unwrap(), "Q");
+
+        // Changes queued by a dropped subwindow still arrive
+        child.mvaddstr(0, 0, "z").unwrap();
+        drop(child);
+        assert_eq!(parent.mvinnstr(4, 6, 1).unwrap(), "z");
+        assert!(parent.syncup().is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "wide")]
+    fn test_chgat_wide_char_halves() {
+        let mut win = Window::new(2, 10, 0, 0).unwrap();
+        let attrs_at = |win: &Window, x: usize| win.line(0).unwrap().get(x).attrs();
+        win.mvaddwstr(0, 0, "a漢b").unwrap();
+
+        // Starting on the right half still changes the left half
+        win.mvchgat(0, 2, 1, attr::A_BOLD, 0).unwrap();
+        assert_eq!(attrs_at(&win, 1), attr::A_BOLD);
+        assert_eq!(attrs_at(&win, 2), attr::A_BOLD);
+        assert_eq!(attrs_at(&win, 3), attr::A_NORMAL);
+
+        // Ending on the left half still changes the right half
+        win.mvchgat(0, 0, 2, attr::A_REVERSE, 0).unwrap();
+        assert_eq!(attrs_at(&win, 0), attr::A_REVERSE);
+        assert_eq!(attrs_at(&win, 2), attr::A_REVERSE);
+        assert_eq!(attrs_at(&win, 3), attr::A_NORMAL);
+
+        win.mvchgat(0, 2, -1, attr::A_UNDERLINE, 0).unwrap();
+        assert!((1..10).all(|x| attrs_at(&win, x) == attr::A_UNDERLINE));
+        assert_eq!(attrs_at(&win, 0), attr::A_REVERSE);
+    }
+
+    #[test]
+    fn test_screen_to_local_derwin_chain() {
+        let stdscr = Window::new(24, 80, 0, 0).unwrap();
+        let win = stdscr.derwin(10, 30, 2, 5).unwrap();
+        let inner = win.derwin(4, 10, 3, 4).unwrap();
+
+        assert_eq!(inner.origin(), (5, 9));
+        assert_eq!(inner.screen_to_local(5, 9), Some((0, 0)));
+        assert_eq!(inner.screen_to_local(8, 18), Some((3, 9)));
+        assert_eq!(inner.screen_to_local(9, 9), None);
+        assert_eq!(inner.screen_to_local(5, 19), None);
+        assert_eq!(inner.screen_to_local(4, 9), None);
+        assert_eq!(win.screen_to_local(5, 9), Some((3, 4)));
+        assert_eq!(stdscr.screen_to_local(5, 9), Some((5, 9)));
+    }
+
+    #[test]
+    fn test_pad_scrolls_whole_buffer() {
+        let mut pad = Window::new_pad(50, 10).unwrap();
+        assert!(!pad.is_scrollok());
+        assert_eq!(pad.getscrreg(), (0, 49));
+
+        // Without scrollok, writing past the bottom leaves the buffer alone
+        pad.mvaddstr(0, 0, "first").unwrap();
+        pad.mvaddstr(49, 0, "last\n").unwrap();
+        assert_eq!(pad.mvinnstr(0, 0, 5).unwrap(), "first");
+
+        pad.scrollok(true);
+        pad.mvaddstr(49, 0, "last\nnext").unwrap();
+        assert_eq!(pad.mvinnstr(0, 0, 5).unwrap(), "     ");
+        assert_eq!(pad.mvinnstr(48, 0, 4).unwrap(), "last");
+        assert_eq!(pad.mvinnstr(49, 0, 4).unwrap(), "next");
+
+        pad.mvaddstr(10, 0, "middle").unwrap();
+        pad.scrl(3).unwrap();
+        assert_eq!(pad.mvinnstr(7, 0, 6).unwrap(), "middle");
+        assert_eq!(pad.mvinnstr(45, 0, 4).unwrap(), "last");
+        pad.scrl(-3).unwrap();
+        assert_eq!(pad.mvinnstr(10, 0, 6).unwrap(), "middle");
+        assert_eq!(pad.mvinnstr(0, 0, 5).unwrap(), "     ");
+    }
+
+    #[test]
+    fn test_draw_border_presets() {
+        use crate::types::BorderChars;
+
+        let mut win = Window::new(4, 6, 0, 0).unwrap();
+        win.draw_border(BorderChars::ascii()).unwrap();
+        assert_eq!(win.mvinnstr(0, 0, 6).unwrap(), "+----+");
+        assert_eq!(win.mvinnstr(1, 0, 6).unwrap(), "|    |");
+        assert_eq!(win.mvinnstr(3, 0, 6).unwrap(), "+----+");
+
+        #[cfg(feature = "wide")]
+        {
+            let cell =
+                |win: &Window, y: usize, x: usize| win.line(y).unwrap().get(x).spacing_char();
+            win.draw_border(BorderChars::double()).unwrap();
+            assert_eq!(cell(&win, 0, 0), '╔');
+            assert_eq!(cell(&win, 0, 5), '╗');
+            assert_eq!(cell(&win, 3, 0), '╚');
+            assert_eq!(cell(&win, 3, 5), '╝');
+            assert_eq!(cell(&win, 0, 2), '═');
+            assert_eq!(cell(&win, 2, 5), '║');
+            assert_eq!(win.line(0).unwrap().get(0).attrs(), A_NORMAL);
+
+            win.draw_border(BorderChars::rounded()).unwrap();
+            assert_eq!(cell(&win, 3, 5), '╯');
+            win.draw_border(BorderChars::heavy()).unwrap();
+            assert_eq!(cell(&win, 1, 0), '┃');
+        }
+
+        // A colored ASCII character is not taken for a line-drawing one
+        let side = b'|' as ChType | crate::attr::color_pair(37);
+        win.draw_border(BorderChars::simple(side, 0)).unwrap();
+        assert_eq!(win.mvinch(1, 0).unwrap(), side);
+        win.border(side, side, 0, 0, 0, 0, 0, 0).unwrap();
+        assert_eq!(win.mvinch(2, 5).unwrap(), side);
+    }
+
+    #[test]
+    fn test_with_timeout_restores_delay() {
+        let mut win = Window::new(5, 10, 0, 0).unwrap();
+        win.nodelay(true);
+
+        let delay = win.with_timeout(100, |win| Ok(win.getdelay())).unwrap();
+        assert_eq!(delay, 100);
+        assert!(win.is_nodelay());
+
+        let result: Result<()> = win.with_timeout(-1, |_| Err(Error::Timeout));
+        assert!(matches!(result, Err(Error::Timeout)));
+        assert_eq!(win.getdelay(), 0);
+    }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let mut win = Window::new(2, 5, 0, 0).unwrap();
+        win.attron(crate::attr::A_BOLD).unwrap();
+        win.mvaddstr(1, 1, "hey").unwrap();
+        let snapshot = win.snapshot();
+        assert_eq!((snapshot.height, snapshot.width), (2, 5));
+        assert_eq!(snapshot.cursor, (1, 4));
+
+        let mut copy = Window::new(1, 1, 0, 0).unwrap();
+        copy.restore_snapshot(&snapshot).unwrap();
+        assert_eq!(copy.snapshot(), snapshot);
+        assert_eq!(copy.mvinnstr(1, 1, 3).unwrap(), "hey");
+
+        let mut bad = snapshot.clone();
+        bad.cells.pop();
+        assert!(copy.restore_snapshot(&bad).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_snapshot_json_round_trip() {
+        let mut win = Window::new(2, 4, 0, 0).unwrap();
+        win.mvaddstr(0, 1, "ok").unwrap();
+        let snapshot = win.snapshot();
+
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let back: WindowSnapshot = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, snapshot);
+    }
+
     #[test]
     fn test_border() {
         let mut win = Window::new(10, 20, 0, 0).unwrap();
         win.box_(0, 0).unwrap();
         // Check corners are set
         assert!(win.is_wintouched());
+        #[cfg(feature = "wide")]
+        {
+            assert_eq!(
+                win.lines[0].get(0),
+                CCharT::from_char(crate::acs::ACS_ULCORNER)
+            );
+            assert_eq!(
+                win.lines[9].get(5),
+                CCharT::from_char(crate::acs::ACS_HLINE)
+            );
+        }
     }
 
     #[test]
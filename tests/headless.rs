@@ -0,0 +1,59 @@
+//! Headless mode tests for ncurses-pure
+//!
+//! Headless mode only applies when stdout is not a terminal, so the checks
+//! run in a child copy of this test binary with stdout redirected to a
+//! pipe. That keeps them meaningful under `cargo test` from a terminal.
+
+use ncurses::*;
+use std::process::{Command, Stdio};
+
+/// Set in the child process to run the checks instead of spawning
+const CHILD_VAR: &str = "NCURSES_PURE_HEADLESS_TEST_CHILD";
+
+/// Run this test again in a child process with stdout piped and `env`
+/// added, returning its output. Returns `None` in the child itself.
+fn run_in_child(test: &str, env: &[(&str, &str)]) -> Option<std::process::Output> {
+    if std::env::var_os(CHILD_VAR).is_some() {
+        return None;
+    }
+    let output = Command::new(std::env::current_exe().unwrap())
+        .args([test, "--exact", "--nocapture", "--test-threads=1"])
+        .env(CHILD_VAR, "1")
+        .envs(env.iter().copied())
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()
+        .unwrap();
+    Some(output)
+}
+
+/// Test that `Screen::init` runs without a terminal in headless mode
+#[test]
+fn test_headless_init() {
+    let env = [
+        ("NCURSES_PURE_HEADLESS", "1"),
+        ("LINES", "13"),
+        ("COLUMNS", "47"),
+    ];
+    if let Some(output) = run_in_child("test_headless_init", &env) {
+        assert!(
+            output.status.success(),
+            "child failed:\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        // The child must actually have run the test, not filtered it out
+        assert!(String::from_utf8_lossy(&output.stdout).contains("1 passed"));
+        return;
+    }
+
+    // SAFETY: `isatty` only inspects the file descriptor.
+    assert_eq!(unsafe { libc::isatty(libc::STDOUT_FILENO) }, 0);
+
+    let mut screen = Screen::init().unwrap();
+    assert_eq!((screen.lines(), screen.cols()), (13, 47));
+    assert_eq!(screen.stdscr().getmaxy(), 13);
+    assert_eq!(screen.stdscr().getmaxx(), 47);
+    screen.mvaddstr(12, 40, "bottom").unwrap();
+    screen.refresh().unwrap();
+}
@@ -370,6 +370,7 @@ fn test_instr() {
 
 /// Test key constants are defined
 #[test]
+#[allow(clippy::assertions_on_constants)]
 fn test_key_constants() {
     assert!(key::KEY_UP > 0x100);
     assert!(key::KEY_DOWN > 0x100);
@@ -0,0 +1,67 @@
+//! Panic hook tests for ncurses-pure
+//!
+//! These live in their own test binary because they replace the
+//! process-wide panic hook, which tests running alongside would see.
+
+use ncurses::terminal::Terminal;
+use ncurses::*;
+use std::fs::File;
+use std::os::unix::fs::FileExt;
+use std::os::unix::io::{AsRawFd, FromRawFd};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// Everything written to `output` so far.
+fn written(output: &File) -> Vec<u8> {
+    let mut buf = vec![0; output.metadata().unwrap().len() as usize];
+    output.read_exact_at(&mut buf, 0).unwrap();
+    buf
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack.windows(needle.len()).any(|w| w == needle)
+}
+
+/// Test that the hook restores the terminal and keeps the hook chain intact
+#[test]
+fn test_panic_hook_restores_terminal() {
+    let mut fds = [0; 2];
+    // SAFETY: `fds` is a valid two-element array for `pipe` to fill.
+    assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+    // SAFETY: both descriptors were just created by `pipe` and are owned here.
+    let (reader, _writer) = unsafe { (File::from_raw_fd(fds[0]), File::from_raw_fd(fds[1])) };
+    let output = tempfile::tempfile().unwrap();
+    let terminal = Terminal::with_term_type(
+        Some("xterm-256color"),
+        reader.as_raw_fd(),
+        output.as_raw_fd(),
+    )
+    .unwrap();
+    let screen = Screen::with_terminal(terminal).unwrap();
+
+    screen.install_panic_hook();
+    screen.install_panic_hook();
+    let before = written(&output).len();
+    assert!(std::panic::catch_unwind(|| panic!("test panic")).is_err());
+    let out = written(&output);
+    assert!(contains(&out[before..], b"\x1b[?25h"));
+    assert!(contains(&out[before..], b"\x1b[?1049l"));
+
+    // The hook fires once per installation, and not at all once removed
+    screen.install_panic_hook();
+    screen.remove_panic_hook();
+    let before = written(&output).len();
+    assert!(std::panic::catch_unwind(|| panic!("test panic")).is_err());
+    assert_eq!(written(&output).len(), before);
+
+    // Removing the hook puts back the one it was installed over
+    static CALLS: AtomicU32 = AtomicU32::new(0);
+    let original = std::panic::take_hook();
+    std::panic::set_hook(Box::new(|_| {
+        CALLS.fetch_add(1, Ordering::SeqCst);
+    }));
+    screen.install_panic_hook();
+    screen.remove_panic_hook();
+    assert!(std::panic::catch_unwind(|| panic!("test panic")).is_err());
+    assert_eq!(CALLS.load(Ordering::SeqCst), 1);
+    std::panic::set_hook(original);
+}